@@ -46,11 +46,17 @@ pub trait AdkT: Send + Sync {
     fn saf_info(&self, saf_info_input: ()) -> ExternResult<SafInfo>;
     fn zome_info(&self, zome_info_input: ()) -> ExternResult<ZomeInfo>;
     fn call_info(&self, call_info_input: ()) -> ExternResult<CallInfo>;
+    // Capability
+    fn capability_claims(&self, capability_claims_input: ()) -> ExternResult<Vec<CapClaim>>;
+    fn capability_grants(&self, capability_grants_input: ())
+        -> ExternResult<Vec<ZomeCallCapGrant>>;
+    fn capability_info(&self, capability_info_input: ()) -> ExternResult<CapGrant>;
     // Link
     fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<HeaderHash>;
     fn delete_link(&self, add_link_header: HeaderHash) -> ExternResult<HeaderHash>;
     fn get_links(&self, get_links_input: GetLinksInput) -> ExternResult<Links>;
     fn get_link_details(&self, get_links_input: GetLinksInput) -> ExternResult<LinkDetails>;
+    fn count_links(&self, get_links_input: GetLinksInput) -> ExternResult<usize>;
     // P2P
     fn call(&self, call: Call) -> ExternResult<ZomeCallResponse>;
     fn call_remote(&self, call_remote: CallRemote) -> ExternResult<ZomeCallResponse>;
@@ -60,7 +66,7 @@ pub trait AdkT: Send + Sync {
     fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes>;
     // Time
     fn sys_time(&self, sys_time_input: ()) -> ExternResult<core::time::Duration>;
-    fn schedule(&self, execute_after: std::time::Duration) -> ExternResult<()>;
+    fn schedule(&self, schedule: Schedule) -> ExternResult<()>;
     fn sleep(&self, wake_after: std::time::Duration) -> ExternResult<()>;
     // Trace
     fn trace(&self, trace_msg: TraceMsg) -> ExternResult<()>;
@@ -144,6 +150,16 @@ impl AdkT for ErrAdk {
     fn call_info(&self, _: ()) -> ExternResult<CallInfo> {
         Self::err()
     }
+    // Capability
+    fn capability_claims(&self, _: ()) -> ExternResult<Vec<CapClaim>> {
+        Self::err()
+    }
+    fn capability_grants(&self, _: ()) -> ExternResult<Vec<ZomeCallCapGrant>> {
+        Self::err()
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<CapGrant> {
+        Self::err()
+    }
     // Link
     fn create_link(&self, _: CreateLinkInput) -> ExternResult<HeaderHash> {
         Self::err()
@@ -157,6 +173,9 @@ impl AdkT for ErrAdk {
     fn get_link_details(&self, _: GetLinksInput) -> ExternResult<LinkDetails> {
         Self::err()
     }
+    fn count_links(&self, _: GetLinksInput) -> ExternResult<usize> {
+        Self::err()
+    }
     // P2P
     fn call(&self, _: Call) -> ExternResult<ZomeCallResponse> {
         Self::err()
@@ -178,7 +197,7 @@ impl AdkT for ErrAdk {
     fn sys_time(&self, _: ()) -> ExternResult<core::time::Duration> {
         Self::err()
     }
-    fn schedule(&self, _: std::time::Duration) -> ExternResult<()> {
+    fn schedule(&self, _: Schedule) -> ExternResult<()> {
         Self::err()
     }
     fn sleep(&self, _: std::time::Duration) -> ExternResult<()> {
@@ -285,6 +304,16 @@ impl AdkT for HostAdk {
         host_call::<(), CallInfo>(__call_info, ())
     }
 
+    fn capability_claims(&self, _: ()) -> ExternResult<Vec<CapClaim>> {
+        host_call::<(), Vec<CapClaim>>(__capability_claims, ())
+    }
+    fn capability_grants(&self, _: ()) -> ExternResult<Vec<ZomeCallCapGrant>> {
+        host_call::<(), Vec<ZomeCallCapGrant>>(__capability_grants, ())
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<CapGrant> {
+        host_call::<(), CapGrant>(__capability_info, ())
+    }
+
     fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<HeaderHash> {
         host_call::<CreateLinkInput, HeaderHash>(__create_link, create_link_input)
     }
@@ -297,6 +326,9 @@ impl AdkT for HostAdk {
     fn get_link_details(&self, get_links_input: GetLinksInput) -> ExternResult<LinkDetails> {
         host_call::<GetLinksInput, LinkDetails>(__get_link_details, get_links_input)
     }
+    fn count_links(&self, get_links_input: GetLinksInput) -> ExternResult<usize> {
+        host_call::<GetLinksInput, usize>(__count_links, get_links_input)
+    }
 
     fn call(&self, call: Call) -> ExternResult<ZomeCallResponse> {
         host_call::<Call, ZomeCallResponse>(__call, call)
@@ -318,8 +350,8 @@ impl AdkT for HostAdk {
     fn sys_time(&self, _: ()) -> ExternResult<core::time::Duration> {
         host_call::<(), core::time::Duration>(__sys_time, ())
     }
-    fn schedule(&self, execute_after: std::time::Duration) -> ExternResult<()> {
-        host_call::<std::time::Duration, ()>(__schedule, execute_after)
+    fn schedule(&self, schedule: Schedule) -> ExternResult<()> {
+        host_call::<Schedule, ()>(__schedule, schedule)
     }
     fn sleep(&self, wake_after: std::time::Duration) -> ExternResult<()> {
         host_call::<std::time::Duration, ()>(__sleep, wake_after)
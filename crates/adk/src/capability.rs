@@ -103,7 +103,7 @@ pub fn create_cap_claim(cap_claim_entry: CapClaimEntry) -> ExternResult<HeaderHa
 /// extern at one time, or may apply in addition to a stricter grant. In this case, revoking a
 /// stricter grant, or failing to revoke all Unrestricted grants will leave the function open.
 ///
-/// @todo administration functions to query active grants
+/// Use [ `capability_grants` ] to list the grants that are currently live.
 ///
 /// There is an apparent "chicken or the egg" situation where [ `CapGrant` ] are required for remote
 /// agents to call externs, so how does an agent request a grant in the first place?
@@ -186,3 +186,24 @@ pub fn update_cap_grant(
         EntryWithDefId::new(EntryDefId::CapGrant, Entry::CapGrant(new_grant_value)),
     )
 }
+
+/// List the capability grants on the local source chain that haven't been updated or deleted.
+///
+/// Filter by the grant tag to administer a particular category of grants.
+pub fn capability_grants() -> ExternResult<Vec<CapGrantEntry>> {
+    ADK.with(|h| h.borrow().capability_grants(()))
+}
+
+/// List the capability claims on the local source chain.
+///
+/// There is no guarantee that any of these claims are still usable without trying them.
+pub fn capability_claims() -> ExternResult<Vec<CapClaimEntry>> {
+    ADK.with(|h| h.borrow().capability_claims(()))
+}
+
+/// Get the [ `CapGrant` ] that authorized the current zome call.
+///
+/// This is [ `CapGrant::ChainAuthor` ] when the agent is calling its own externs.
+pub fn capability_info() -> ExternResult<CapGrant> {
+    ADK.with(|h| h.borrow().capability_info(()))
+}
@@ -13,12 +13,21 @@ pub fn agent_info() -> ExternResult<AgentInfo> {
     ADK.with(|h| h.borrow().agent_info(()))
 }
 
-/// @todo Not implemented
+/// Get the information about the installed app the current cell belongs to.
+/// There are no inputs to [ `app_info` ].
+///
+/// App information includes the installed app id and the cells provisioned for each slot,
+/// so a zome can find the other cells of its app e.g. to [ `crate::p2p::call` ] them.
+///
+/// Only available during zome calls, as it depends on the local conductor.
 pub fn app_info() -> ExternResult<AppInfo> {
     ADK.with(|h| h.borrow().app_info(()))
 }
 
-/// @todo Not implemented
+/// Get the saf information.
+/// There are no inputs to [ `saf_info` ].
+///
+/// Saf information includes the saf name, hash, properties and uid.
 pub fn saf_info() -> ExternResult<SafInfo> {
     ADK.with(|h| h.borrow().saf_info(()))
 }
@@ -34,7 +43,13 @@ pub fn zome_info() -> ExternResult<ZomeInfo> {
     ADK.with(|h| h.borrow().zome_info(()))
 }
 
-/// @todo Not implemented
+/// Get the information about the current call.
+/// There are no inputs to [ `call_info` ].
+///
+/// Call information includes the provenance of the caller, the function name, the chain head
+/// as seen by the call and the [ `CapGrant` ] that authorized it.
+/// Callbacks invoked by the host, e.g. `init` or `validate`, are made by the current agent under
+/// their implicit [ `CapGrant::ChainAuthor` ] grant.
 pub fn call_info() -> ExternResult<CallInfo> {
     ADK.with(|h| h.borrow().call_info(()))
 }
@@ -119,6 +119,35 @@ pub fn get_links(base: EntryHash, link_tag: Option<LinkTag>) -> ExternResult<Lin
     ADK.with(|h| h.borrow().get_links(GetLinksInput::new(base, link_tag)))
 }
 
+/// Like [ `get_links` ] but only returns the links which pass a filter.
+///
+/// The filter can narrow links down by author and creation time, choose whether the oldest or
+/// newest links come first, and page through a base with many links using a limit and a cursor
+/// taken from the last link of the previous page.
+/// The authorities for the base apply the filter, so only the requested page is sent back.
+///
+/// See [ `GetLinksFilter` ].
+pub fn get_links_with_filter(
+    base: EntryHash,
+    link_tag: Option<LinkTag>,
+    filter: GetLinksFilter,
+) -> ExternResult<Links> {
+    ADK.with(|h| {
+        h.borrow()
+            .get_links(GetLinksInput::new(base, link_tag).with_filter(filter))
+    })
+}
+
+/// Count the live links on a base entry hash, optionally filtered by tag.
+///
+/// This is the same as the length of [ `get_links` ] but the authorities only send back the
+/// live links, without any deleted links or their deletes.
+///
+/// See [ `get_links` ].
+pub fn count_links(base: EntryHash, link_tag: Option<LinkTag>) -> ExternResult<usize> {
+    ADK.with(|h| h.borrow().count_links(GetLinksInput::new(base, link_tag)))
+}
+
 /// Get all link creates and deletes that reference a base entry hash, optionally filtered by tag
 ///
 /// Tag filtering is a simple bytes prefix.
@@ -1,4 +1,7 @@
 pub use crate::app_entry;
+pub use crate::capability::capability_claims;
+pub use crate::capability::capability_grants;
+pub use crate::capability::capability_info;
 pub use crate::capability::create_cap_claim;
 pub use crate::capability::create_cap_grant;
 pub use crate::capability::delete_cap_grant;
@@ -35,11 +38,16 @@ pub use crate::hash_path::anchor::Anchor;
 pub use crate::hash_path::path::Path;
 pub use crate::adk::*;
 pub use crate::info::agent_info;
+pub use crate::info::app_info;
+pub use crate::info::call_info;
+pub use crate::info::saf_info;
 pub use crate::info::zome_info;
+pub use crate::link::count_links;
 pub use crate::link::create_link;
 pub use crate::link::delete_link;
 pub use crate::link::get_link_details;
 pub use crate::link::get_links;
+pub use crate::link::get_links_with_filter;
 pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
 pub use crate::p2p::call;
@@ -105,6 +113,7 @@ macro_rules! aingle_externs {
             __get_details,
             __get_links,
             __get_link_details,
+            __count_links,
             __get_agent_activity,
             __query,
             __call_remote,
@@ -65,12 +65,33 @@ pub fn sys_time() -> ExternResult<core::time::Duration> {
     ADK.with(|h| h.borrow().sys_time(()))
 }
 
-/// @todo Not implemented
-pub fn schedule(execute_after: std::time::Duration) -> ExternResult<()> {
-    ADK.with(|h| h.borrow().schedule(execute_after))
+/// Schedule an extern in the current zome to be called by the host in the future.
+///
+/// The host persists the schedule in the cell database so it survives restarts of the
+/// conductor. Scheduled functions are called with `()` as input, as the agent of the cell,
+/// so they are always authorized.
+///
+/// ```ignore
+/// // Call `tick` in 10 seconds and then every minute after that.
+/// schedule(Schedule::recurring("tick", Duration::from_secs(10), Duration::from_secs(60)))?;
+/// ```
+///
+/// Scheduling a function that is already scheduled replaces the existing schedule, so a
+/// one-off schedule can be used to cancel a recurring one.
+///
+/// Note: schedules are only written when the calling zome call completes successfully.
+pub fn schedule(schedule: Schedule) -> ExternResult<()> {
+    ADK.with(|h| h.borrow().schedule(schedule))
 }
 
-/// @todo Not implemented
+/// Suspend the current zome call for `wake_after`.
+///
+/// The calling zome call keeps its host thread while it sleeps, so the conductor sets a
+/// maximum for a single sleep (5 seconds by default) and longer sleeps return an error
+/// without sleeping at all.
+///
+/// Note: the zome call still counts against any timeout of the caller, e.g. a `call_remote`
+/// from another agent, so prefer [`schedule`] for anything that can happen later.
 pub fn sleep(wake_after: std::time::Duration) -> ExternResult<()> {
     ADK.with(|h| h.borrow().sleep(wake_after))
 }
@@ -1,7 +1,7 @@
 #![forbid(missing_docs)]
 //! Binary `ai-saf` command executable.
 
-use aingle_types::prelude::{AppManifest, SafManifest};
+use aingle_types::prelude::{AgentPubKeyB64, AppManifest, SafManifest};
 use aingle_util::ffs;
 use mr_bundle::Manifest;
 use std::path::Path;
@@ -9,6 +9,7 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 
 use crate::error::AinBundleResult;
+use crate::packing::BundleSigner;
 
 /// The file extension to use for SAF bundles
 pub const SAF_BUNDLE_EXT: &str = "saf";
@@ -47,6 +48,17 @@ pub enum AinSafBundle {
         /// provided working directory.
         #[structopt(short = "o", long)]
         output: Option<PathBuf>,
+
+        /// Sign the bundle as this publisher, an agent key held in the keystore
+        /// (base64 encoded)
+        #[structopt(long, parse(try_from_str = AgentPubKeyB64::from_b64_str))]
+        sign: Option<AgentPubKeyB64>,
+
+        /// The lair keystore directory holding the publisher key
+        ///
+        /// If not specified, the default lair directory is used.
+        #[structopt(long, requires = "sign")]
+        keystore_path: Option<PathBuf>,
     },
 
     /// Unpack parts of the `.saf` bundle file into a specific directory.
@@ -105,6 +117,17 @@ pub enum AinAppBundle {
         /// provided working directory.
         #[structopt(short = "o", long)]
         output: Option<PathBuf>,
+
+        /// Sign the bundle as this publisher, an agent key held in the keystore
+        /// (base64 encoded)
+        #[structopt(long, parse(try_from_str = AgentPubKeyB64::from_b64_str))]
+        sign: Option<AgentPubKeyB64>,
+
+        /// The lair keystore directory holding the publisher key
+        ///
+        /// If not specified, the default lair directory is used.
+        #[structopt(long, requires = "sign")]
+        keystore_path: Option<PathBuf>,
     },
 
     /// Unpack parts of the `.happ` bundle file into a specific directory.
@@ -140,10 +163,17 @@ impl AinSafBundle {
             Self::Init { path } => {
                 crate::init::init_saf(path).await?;
             }
-            Self::Pack { path, output } => {
+            Self::Pack {
+                path,
+                output,
+                sign,
+                keystore_path,
+            } => {
                 let name = get_saf_name(&path).await?;
+                let signer = bundle_signer(sign, keystore_path).await?;
                 let (bundle_path, _) =
-                    crate::packing::pack::<SafManifest>(&path, output, name).await?;
+                    crate::packing::pack::<SafManifest>(&path, output, name, signer.as_ref())
+                        .await?;
                 println!("Wrote bundle {}", bundle_path.to_string_lossy());
             }
             Self::Unpack {
@@ -168,10 +198,17 @@ impl AinAppBundle {
             Self::Init { path } => {
                 crate::init::init_app(path).await?;
             }
-            Self::Pack { path, output } => {
+            Self::Pack {
+                path,
+                output,
+                sign,
+                keystore_path,
+            } => {
                 let name = get_app_name(&path).await?;
+                let signer = bundle_signer(sign, keystore_path).await?;
                 let (bundle_path, _) =
-                    crate::packing::pack::<AppManifest>(&path, output, name).await?;
+                    crate::packing::pack::<AppManifest>(&path, output, name, signer.as_ref())
+                        .await?;
                 println!("Wrote bundle {}", bundle_path.to_string_lossy());
             }
             Self::Unpack {
@@ -189,6 +226,24 @@ impl AinAppBundle {
     }
 }
 
+async fn bundle_signer(
+    publisher: Option<AgentPubKeyB64>,
+    keystore_path: Option<PathBuf>,
+) -> AinBundleResult<Option<BundleSigner>> {
+    match publisher {
+        Some(publisher) => {
+            let keystore =
+                aingle_keystore::lair_keystore::spawn_lair_keystore(keystore_path.as_deref())
+                    .await?;
+            Ok(Some(BundleSigner {
+                keystore,
+                publisher: publisher.into(),
+            }))
+        }
+        None => Ok(None),
+    }
+}
+
 async fn get_saf_name(manifest_path: &Path) -> AinBundleResult<String> {
     let manifest_path = manifest_path.to_path_buf();
     let manifest_path = manifest_path.join(&SafManifest::path());
@@ -21,6 +21,14 @@ pub enum AinBundleError {
     #[error(transparent)]
     MrBundleError(#[from] mr_bundle::error::MrBundleError),
 
+    /// KeystoreError
+    #[error("Keystore error: {0}")]
+    KeystoreError(#[from] aingle_keystore::KeystoreError),
+
+    /// BundleSignatureError
+    #[error("Bundle signing error: {0}")]
+    BundleSignatureError(#[from] aingle_types::bundle_signature::BundleSignatureError),
+
     /// SerializedBytesError
     #[error("Internal serialization error: {0}")]
     SerializedBytesError(#[from] SerializedBytesError),
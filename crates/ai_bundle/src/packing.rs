@@ -3,6 +3,7 @@
 //! Defines the CLI commands for packing/unpacking both SAF and hApp bundles
 
 use crate::error::{AinBundleError, AinBundleResult};
+use aingle_types::prelude::{sign_bundle, AgentPubKey, KeystoreSender};
 use aingle_util::ffs;
 use mr_bundle::{Bundle, Manifest};
 use std::path::Path;
@@ -45,16 +46,34 @@ fn bundle_path_to_dir(path: &Path, extension: &'static str) -> AinBundleResult<P
         .join(stem))
 }
 
+/// The keystore and publisher key with which to sign a bundle while packing
+#[derive(Clone)]
+pub struct BundleSigner {
+    /// The keystore holding the publisher's private key
+    pub keystore: KeystoreSender,
+    /// The publisher key to sign with
+    pub publisher: AgentPubKey,
+}
+
 /// Pack a directory containing a SAF manifest into a SafBundle, returning
-/// the path to which the bundle file was written
+/// the path to which the bundle file was written.
+/// If a signer is given, the bundle is signed before being written.
 pub async fn pack<M: Manifest>(
     dir_path: &std::path::Path,
     target_path: Option<PathBuf>,
     name: String,
+    signer: Option<&BundleSigner>,
 ) -> AinBundleResult<(PathBuf, Bundle<M>)> {
     let dir_path = ffs::canonicalize(dir_path).await?;
     let manifest_path = dir_path.join(&M::path());
-    let bundle: Bundle<M> = Bundle::pack_yaml(&manifest_path).await?;
+    let mut bundle: Bundle<M> = Bundle::pack_yaml(&manifest_path).await?;
+    if let Some(BundleSigner {
+        keystore,
+        publisher,
+    }) = signer
+    {
+        bundle = sign_bundle(bundle, keystore, publisher).await?;
+    }
     let target_path = match target_path {
         Some(target_path) => {
             if target_path.is_dir() {
@@ -75,7 +94,7 @@ fn dir_to_bundle_path(dir_path: &Path, name: String, extension: &str) -> AinBund
 
 #[cfg(test)]
 mod tests {
-    use aingle_types::prelude::SafManifest;
+    use aingle_types::prelude::{AgentPubKeyExt, SafManifest};
     use mr_bundle::error::{MrBundleError, UnpackingError};
 
     use super::*;
@@ -113,7 +132,7 @@ zomes:
         // in the parent directory
         std::fs::write(tmpdir.path().join("zome-3.wasm"), &[7, 8, 9]).unwrap();
 
-        let (bundle_path, bundle) = pack::<SafManifest>(&dir, None, "test_saf".to_string())
+        let (bundle_path, bundle) = pack::<SafManifest>(&dir, None, "test_saf".to_string(), None)
             .await
             .unwrap();
 
@@ -151,6 +170,7 @@ zomes:
             &dir,
             Some(dir.parent().unwrap().to_path_buf()),
             "test_saf".to_string(),
+            None,
         )
         .await
         .unwrap();
@@ -169,7 +189,30 @@ zomes:
         assert_eq!(dir.read_dir().unwrap().collect::<Vec<_>>().len(), 3);
 
         // Ensure that we get the same bundle after the roundtrip
-        let (_, bundle2) = pack(&dir, None, "test_saf".to_string()).await.unwrap();
+        let (_, bundle2) = pack(&dir, None, "test_saf".to_string(), None)
+            .await
+            .unwrap();
         assert_eq!(bundle, bundle2);
+
+        // Pack again with a signature, which survives writing to file
+        let keystore = aingle_keystore::test_keystore::spawn_test_keystore()
+            .await
+            .unwrap();
+        let publisher = AgentPubKey::new_from_pure_entropy(&keystore).await.unwrap();
+        let signer = BundleSigner {
+            keystore,
+            publisher: publisher.clone(),
+        };
+        let (bundle_path, _) =
+            pack::<SafManifest>(&dir, None, "test_saf".to_string(), Some(&signer))
+                .await
+                .unwrap();
+        let signed: Bundle<SafManifest> = Bundle::read_from_file(&bundle_path).await.unwrap();
+        assert_eq!(
+            aingle_types::prelude::verified_publishers(&signed)
+                .await
+                .unwrap(),
+            vec![publisher]
+        );
     }
 }
@@ -35,7 +35,12 @@ async fn main() -> anyhow::Result<()> {
         let app_id = app_id.clone();
 
         // Create a conductor config with the network.
-        let path = ai_sandbox::generate::generate(Some(network.clone()), None, None)?;
+        let path = ai_sandbox::generate::generate(
+            Some(network.clone()),
+            Default::default(),
+            None,
+            None,
+        )?;
 
         // Create a command runner to run admin commands.
         // This runs the conductor in the background and cleans
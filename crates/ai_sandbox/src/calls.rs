@@ -12,23 +12,31 @@ use anyhow::bail;
 use anyhow::ensure;
 use aingle_conductor_api::AdminRequest;
 use aingle_conductor_api::AdminResponse;
+use aingle_conductor_api::AppAuthenticationTokenIssued;
 use aingle_conductor_api::AppStatusFilter;
+use aingle_conductor_api::CellMetrics;
 use aingle_conductor_api::InterfaceDriver;
+use aingle_conductor_api::IssueAppAuthenticationTokenPayload;
 use aingle_conductor_api::{AdminInterfaceConfig, InstalledAppInfo};
 use aingle_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
+use aingle_types::prelude::ArchiveCloneCellPayload;
+use aingle_types::prelude::DeleteArchivedCloneCellsPayload;
 use aingle_types::prelude::SafHash;
 use aingle_types::prelude::InstallAppSafPayload;
 use aingle_types::prelude::InstallAppPayload;
+use aingle_types::prelude::MigrateAgentPayload;
 use aingle_types::prelude::RegisterSafPayload;
 use aingle_types::prelude::YamlProperties;
 use aingle_types::prelude::{AgentPubKey, AppBundleSource};
 use aingle_types::prelude::{CellId, InstallAppBundlePayload};
 use aingle_types::prelude::{SafSource, Uid};
+use aingle_types::prelude::{SerializedBytes, SourceChainArchive, UnsafeBytes};
 use std::convert::TryFrom;
 
 use crate::cmds::Existing;
 use crate::expect_match;
 use crate::ports::get_admin_ports;
+use crate::ports::get_admin_socket;
 use crate::run::run_async;
 use crate::CmdRunner;
 use structopt::StructOpt;
@@ -41,6 +49,10 @@ pub struct Call {
     /// If this is empty existing sandboxes will be used.
     /// Cannot be combined with existing sandboxes.
     pub running: Vec<u16>,
+    #[structopt(long, conflicts_with_all = &["existing_paths", "indices", "running"], value_delimiter = ",")]
+    /// Paths to the Unix domain sockets of running conductor admin interfaces.
+    /// Cannot be combined with existing sandboxes or running ports.
+    pub running_socket: Vec<PathBuf>,
     #[structopt(flatten)]
     pub existing: Existing,
     #[structopt(subcommand)]
@@ -55,6 +67,9 @@ pub struct Call {
 pub enum AdminRequestCli {
     AddAdminWs(AddAdminWs),
     AddAppWs(AddAppWs),
+    AddAdminUnixSocket(AddAdminUnixSocket),
+    AddAppUnixSocket(AddAppUnixSocket),
+    IssueAppToken(IssueAppToken),
     RegisterSaf(RegisterSaf),
     InstallApp(InstallApp),
     InstallAppBundle(InstallAppBundle),
@@ -72,7 +87,14 @@ pub enum AdminRequestCli {
     ListApps(ListApps),
     ActivateApp(ActivateApp),
     DeactivateApp(DeactivateApp),
+    UninstallApp(UninstallApp),
+    ArchiveCloneCell(ArchiveCloneCell),
+    DeleteArchivedCloneCells(DeleteArchivedCloneCells),
+    MigrateAgent(MigrateAgent),
     DumpState(DumpState),
+    GetCellMetrics(GetCellMetrics),
+    ExportSourceChain(ExportSourceChain),
+    ImportSourceChain(ImportSourceChain),
     /// Calls AdminRequest::AddAgentInfo.
     /// [Unimplemented].
     AddAgents,
@@ -96,6 +118,43 @@ pub struct AddAppWs {
     pub port: Option<u16>,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::AddAdminInterfaces
+/// and adds another admin interface on a Unix domain socket.
+pub struct AddAdminUnixSocket {
+    /// Path of the socket file to create.
+    /// Only the conductor's user will be able to connect to it,
+    /// unless `--mode` says otherwise.
+    pub path: PathBuf,
+    #[structopt(short, long, parse(try_from_str = parse_socket_mode))]
+    /// Optional permissions of the socket file in octal, e.g. 660.
+    pub mode: Option<u32>,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::AttachAppUnixSocketInterface
+/// and adds another app interface on a Unix domain socket.
+pub struct AddAppUnixSocket {
+    /// Path of the socket file to create.
+    /// Only the conductor's user will be able to connect to it,
+    /// unless `--mode` says otherwise.
+    pub path: PathBuf,
+    #[structopt(short, long, parse(try_from_str = parse_socket_mode))]
+    /// Optional permissions of the socket file in octal, e.g. 660.
+    pub mode: Option<u32>,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::IssueAppAuthenticationToken
+/// and prints a token an app interface client can authenticate with.
+pub struct IssueAppToken {
+    /// The InstalledAppId the token gives access to.
+    pub app_id: String,
+    #[structopt(short, long, default_value = "30")]
+    /// Seconds until the token expires.
+    pub expiry_seconds: u64,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::RegisterSaf
 /// and registers a Saf. You can only use a path or a hash not both.
@@ -177,6 +236,55 @@ pub struct DeactivateApp {
     pub app_id: String,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::UninstallApp
+/// and uninstalls the app, deleting any data no other app uses.
+pub struct UninstallApp {
+    /// The InstalledAppId to uninstall.
+    pub app_id: String,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::ArchiveCloneCell
+/// and stops and archives a clone cell.
+pub struct ArchiveCloneCell {
+    /// The InstalledAppId the clone belongs to.
+    pub app_id: String,
+    /// The SlotId the clone was created in.
+    pub slot_id: String,
+    #[structopt(parse(try_from_str = parse_saf_hash))]
+    /// The saf hash half of the clone's cell id.
+    pub saf: SafHash,
+    #[structopt(parse(try_from_str = parse_agent_key))]
+    /// The agent half of the clone's cell id.
+    pub agent_key: AgentPubKey,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::DeleteArchivedCloneCells
+/// and deletes the archived clone cells of a slot
+/// along with their data.
+pub struct DeleteArchivedCloneCells {
+    /// The InstalledAppId the clones belong to.
+    pub app_id: String,
+    /// The SlotId the clones were created in.
+    pub slot_id: String,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::MigrateAgent
+/// and moves the agent of a slot to a new saf,
+/// closing its chain in the slot's current cell.
+pub struct MigrateAgent {
+    /// The InstalledAppId the slot belongs to.
+    pub app_id: String,
+    /// The SlotId whose agent migrates.
+    pub slot_id: String,
+    #[structopt(parse(try_from_str = parse_saf_hash))]
+    /// The already registered saf to migrate to.
+    pub saf: SafHash,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::DumpState
 /// and dumps the current cell's state.
@@ -191,6 +299,45 @@ pub struct DumpState {
     pub agent_key: AgentPubKey,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::GetCellMetrics
+/// and pretty prints the metrics of the running cells.
+pub struct GetCellMetrics {
+    #[structopt(short, long, parse(try_from_str = parse_agent_key), requires = "saf")]
+    /// Optionally request metrics for a particular cell id.
+    pub agent_key: Option<AgentPubKey>,
+    #[structopt(short, long, parse(try_from_str = parse_saf_hash), requires = "agent_key")]
+    /// Optionally request metrics for a particular cell id.
+    pub saf: Option<SafHash>,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::ExportSourceChain
+/// and writes a signed archive of a cell's source chain to a file.
+pub struct ExportSourceChain {
+    #[structopt(parse(try_from_str = parse_saf_hash))]
+    /// The saf hash half of the cell id to export.
+    pub saf: SafHash,
+    #[structopt(parse(try_from_str = parse_agent_key))]
+    /// The agent half of the cell id to export.
+    pub agent_key: AgentPubKey,
+    #[structopt(short, long)]
+    /// Only export the elements from this sequence number onwards.
+    /// Defaults to the whole chain.
+    pub from_seq: Option<u32>,
+    #[structopt(short, long)]
+    /// The file to write the archive to.
+    pub output: PathBuf,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::ImportSourceChain
+/// and restores a source chain from an archive file.
+pub struct ImportSourceChain {
+    /// Path to an archive written by `export-source-chain`.
+    pub path: PathBuf,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::RequestAgentInfo
 /// and pretty prints the agent info on
@@ -219,16 +366,34 @@ pub async fn call(aingle_path: &Path, req: Call) -> anyhow::Result<()> {
     let Call {
         existing,
         running,
+        running_socket,
         call,
     } = req;
-    let cmds = if running.is_empty() {
+    let cmds = if !running_socket.is_empty() {
+        connect_running_sockets(running_socket).await?
+    } else if running.is_empty() {
         let paths = if existing.is_empty() {
             crate::save::load(std::env::current_dir()?)?
         } else {
             existing.load()?
         };
+        // Sandboxes with an admin socket are connected to directly,
+        // so only the others are matched up with their admin ports.
+        let mut sockets = Vec::new();
+        let mut port_paths = Vec::with_capacity(paths.len());
+        for path in paths {
+            match get_admin_socket(path.clone())? {
+                Some(socket) => sockets.push(socket),
+                None => port_paths.push(path),
+            }
+        }
+        let paths = port_paths;
+        let mut cmds = if sockets.is_empty() {
+            Vec::with_capacity(paths.len())
+        } else {
+            connect_running_sockets(sockets).await?
+        };
         let ports = get_admin_ports(paths.clone()).await?;
-        let mut cmds = Vec::with_capacity(ports.len());
         for (port, path) in ports.into_iter().zip(paths.into_iter()) {
             match CmdRunner::try_new(port).await {
                 Ok(cmd) => cmds.push((cmd, None)),
@@ -261,6 +426,24 @@ pub async fn call(aingle_path: &Path, req: Call) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
+async fn connect_running_sockets(
+    paths: Vec<PathBuf>,
+) -> anyhow::Result<Vec<(CmdRunner, Option<tokio::process::Child>)>> {
+    let mut cmds = Vec::with_capacity(paths.len());
+    for path in paths {
+        cmds.push((CmdRunner::try_new_unix(&path).await?, None));
+    }
+    Ok(cmds)
+}
+
+#[cfg(not(unix))]
+async fn connect_running_sockets(
+    _paths: Vec<PathBuf>,
+) -> anyhow::Result<Vec<(CmdRunner, Option<tokio::process::Child>)>> {
+    bail!("Unix domain sockets are not supported on this platform")
+}
+
 async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Result<()> {
     match call {
         AdminRequestCli::AddAdminWs(args) => {
@@ -271,9 +454,25 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             let port = attach_app_interface(cmd, args).await?;
             msg!("Added App port {}", port);
         }
+        AdminRequestCli::AddAdminUnixSocket(args) => {
+            let path = add_admin_unix_socket_interface(cmd, args).await?;
+            msg!("Added Admin socket {}", path.display());
+        }
+        AdminRequestCli::AddAppUnixSocket(args) => {
+            let path = attach_app_unix_socket_interface(cmd, args).await?;
+            msg!("Added App socket {}", path.display());
+        }
+        AdminRequestCli::IssueAppToken(args) => {
+            let issued = issue_app_authentication_token(cmd, args).await?;
+            msg!(
+                "Issued App token {:?}, expires at {}",
+                issued.token,
+                issued.expires_at
+            );
+        }
         AdminRequestCli::ListAppWs => {
-            let ports = list_app_ws(cmd).await?;
-            msg!("Attached App Interfaces {:?}", ports);
+            let drivers = list_app_ws(cmd).await?;
+            msg!("Attached App Interfaces {:?}", drivers);
         }
         AdminRequestCli::RegisterSaf(args) => {
             let safs = register_saf(cmd, args).await?;
@@ -318,10 +517,47 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             deactivate_app(cmd, args).await?;
             msg!("Deactivated app: {:?}", app_id);
         }
+        AdminRequestCli::UninstallApp(args) => {
+            let app_id = args.app_id.clone();
+            uninstall_app(cmd, args).await?;
+            msg!("Uninstalled app: {:?}", app_id);
+        }
+        AdminRequestCli::ArchiveCloneCell(args) => {
+            let cell_id = CellId::new(args.saf.clone(), args.agent_key.clone());
+            archive_clone_cell(cmd, args).await?;
+            msg!("Archived clone cell: {:?}", cell_id);
+        }
+        AdminRequestCli::DeleteArchivedCloneCells(args) => {
+            let slot_id = args.slot_id.clone();
+            delete_archived_clone_cells(cmd, args).await?;
+            msg!("Deleted archived clone cells in slot: {:?}", slot_id);
+        }
+        AdminRequestCli::MigrateAgent(args) => {
+            let cell_id = migrate_agent(cmd, args).await?;
+            msg!("Migrated agent to cell: {:?}", cell_id);
+        }
         AdminRequestCli::DumpState(args) => {
             let state = dump_state(cmd, args).await?;
             msg!("DUMP STATE \n{}", state);
         }
+        AdminRequestCli::GetCellMetrics(args) => {
+            let metrics = get_cell_metrics(cmd, args).await?;
+            msg!("Cell metrics: {:#?}", metrics);
+        }
+        AdminRequestCli::ExportSourceChain(args) => {
+            let path = args.output.clone();
+            let archive = export_source_chain(cmd, args).await?;
+            let count = archive.elements.len();
+            std::fs::write(&path, SerializedBytes::try_from(archive)?.bytes())?;
+            msg!("Exported {} elements to {}", count, path.display());
+        }
+        AdminRequestCli::ImportSourceChain(args) => {
+            let bytes = UnsafeBytes::from(std::fs::read(&args.path)?);
+            let archive = SourceChainArchive::try_from(SerializedBytes::from(bytes))?;
+            let cell_id = archive.cell_id.clone();
+            import_source_chain(cmd, archive).await?;
+            msg!("Imported source chain for cell: {:?}", cell_id);
+        }
         AdminRequestCli::AddAgents => todo!("Adding agent info via cli is coming soon"),
         AdminRequestCli::ListAgents(args) => {
             use std::fmt::Write;
@@ -512,7 +748,7 @@ pub async fn install_app_bundle(
 }
 
 /// Calls [`AdminRequest::ListAppInterfaces`].
-pub async fn list_app_ws(cmd: &mut CmdRunner) -> anyhow::Result<Vec<u16>> {
+pub async fn list_app_ws(cmd: &mut CmdRunner) -> anyhow::Result<Vec<InterfaceDriver>> {
     let resp = cmd.command(AdminRequest::ListAppInterfaces).await?;
     Ok(expect_match!(resp => AdminResponse::AppInterfacesListed, "Failed to list app interfaces"))
 }
@@ -582,6 +818,60 @@ pub async fn deactivate_app(cmd: &mut CmdRunner, args: DeactivateApp) -> anyhow:
     Ok(())
 }
 
+/// Calls [`AdminRequest::UninstallApp`] and uninstalls the app.
+pub async fn uninstall_app(cmd: &mut CmdRunner, args: UninstallApp) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::UninstallApp {
+            installed_app_id: args.app_id,
+        })
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::AppUninstalled),
+        "Failed to uninstall app, got: {:?}",
+        resp
+    );
+    Ok(())
+}
+
+/// Calls [`AdminRequest::ArchiveCloneCell`] and archives the clone cell.
+pub async fn archive_clone_cell(cmd: &mut CmdRunner, args: ArchiveCloneCell) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::ArchiveCloneCell(Box::new(args.into())))
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::CloneCellArchived),
+        "Failed to archive clone cell, got: {:?}",
+        resp
+    );
+    Ok(())
+}
+
+/// Calls [`AdminRequest::DeleteArchivedCloneCells`] and deletes the archived clone cells.
+pub async fn delete_archived_clone_cells(
+    cmd: &mut CmdRunner,
+    args: DeleteArchivedCloneCells,
+) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::DeleteArchivedCloneCells(Box::new(
+            args.into(),
+        )))
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::ArchivedCloneCellsDeleted),
+        "Failed to delete archived clone cells, got: {:?}",
+        resp
+    );
+    Ok(())
+}
+
+/// Calls [`AdminRequest::MigrateAgent`] and returns the cell the agent migrated to.
+pub async fn migrate_agent(cmd: &mut CmdRunner, args: MigrateAgent) -> anyhow::Result<CellId> {
+    let resp = cmd
+        .command(AdminRequest::MigrateAgent(Box::new(args.into())))
+        .await?;
+    Ok(expect_match!(resp => AdminResponse::AgentMigrated, "Failed to migrate agent"))
+}
+
 /// Calls [`AdminRequest::AttachAppInterface`] and adds another app interface.
 pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow::Result<u16> {
     let resp = cmd
@@ -598,6 +888,67 @@ pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow
     }
 }
 
+/// Calls [`AdminRequest::AddAdminInterfaces`] and adds another admin interface
+/// on a Unix domain socket.
+pub async fn add_admin_unix_socket_interface(
+    cmd: &mut CmdRunner,
+    args: AddAdminUnixSocket,
+) -> anyhow::Result<PathBuf> {
+    let resp = cmd
+        .command(AdminRequest::AddAdminInterfaces(vec![
+            AdminInterfaceConfig {
+                driver: InterfaceDriver::UnixSocket {
+                    path: args.path.clone(),
+                    mode: args.mode,
+                },
+            },
+        ]))
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::AdminInterfacesAdded),
+        "Failed to add admin interface, got: {:?}",
+        resp
+    );
+    Ok(args.path)
+}
+
+/// Calls [`AdminRequest::AttachAppUnixSocketInterface`] and adds another app interface
+/// on a Unix domain socket.
+pub async fn attach_app_unix_socket_interface(
+    cmd: &mut CmdRunner,
+    args: AddAppUnixSocket,
+) -> anyhow::Result<PathBuf> {
+    let resp = cmd
+        .command(AdminRequest::AttachAppUnixSocketInterface {
+            path: args.path.clone(),
+            mode: args.mode,
+        })
+        .await?;
+    tracing::debug!(?resp);
+    match resp {
+        AdminResponse::AppUnixSocketInterfaceAttached { path } => Ok(path),
+        _ => Err(anyhow!(
+            "Failed to attach app interface {:?}, got: {:?}",
+            args.path,
+            resp
+        )),
+    }
+}
+
+/// Calls [`AdminRequest::IssueAppAuthenticationToken`] and issues a token
+/// for connecting to an app interface.
+pub async fn issue_app_authentication_token(
+    cmd: &mut CmdRunner,
+    args: IssueAppToken,
+) -> anyhow::Result<AppAuthenticationTokenIssued> {
+    let resp = cmd
+        .command(AdminRequest::IssueAppAuthenticationToken(args.into()))
+        .await?;
+    Ok(
+        expect_match!(resp => AdminResponse::AppAuthenticationTokenIssued, "Failed to issue app authentication token"),
+    )
+}
+
 /// Calls [`AdminRequest::DumpState`] and dumps the current cell's state.
 // TODO: Add pretty print.
 // TODO: Default to dumping all cell state.
@@ -610,6 +961,51 @@ pub async fn dump_state(cmd: &mut CmdRunner, args: DumpState) -> anyhow::Result<
     Ok(expect_match!(resp => AdminResponse::StateDumped, "Failed to dump state"))
 }
 
+/// Calls [`AdminRequest::GetCellMetrics`] and returns the metrics of the running cells.
+pub async fn get_cell_metrics(
+    cmd: &mut CmdRunner,
+    args: GetCellMetrics,
+) -> anyhow::Result<Vec<CellMetrics>> {
+    let resp = cmd
+        .command(AdminRequest::GetCellMetrics {
+            cell_id: args.into(),
+        })
+        .await?;
+    Ok(expect_match!(resp => AdminResponse::CellMetricsReported, "Failed to get cell metrics"))
+}
+
+/// Calls [`AdminRequest::ExportSourceChain`] and returns the signed archive.
+pub async fn export_source_chain(
+    cmd: &mut CmdRunner,
+    args: ExportSourceChain,
+) -> anyhow::Result<SourceChainArchive> {
+    let resp = cmd
+        .command(AdminRequest::ExportSourceChain {
+            cell_id: Box::new(CellId::new(args.saf, args.agent_key)),
+            from_seq: args.from_seq,
+        })
+        .await?;
+    Ok(*expect_match!(resp => AdminResponse::SourceChainExported, "Failed to export source chain"))
+}
+
+/// Calls [`AdminRequest::ImportSourceChain`] and restores the archive.
+pub async fn import_source_chain(
+    cmd: &mut CmdRunner,
+    archive: SourceChainArchive,
+) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::ImportSourceChain {
+            archive: Box::new(archive),
+        })
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::SourceChainImported),
+        "Failed to import source chain, got: {:?}",
+        resp
+    );
+    Ok(())
+}
+
 /// Calls [`AdminRequest::AddAgentInfo`] with and adds the list of agent info.
 pub async fn add_agent_info(cmd: &mut CmdRunner, args: Vec<AgentInfoSigned>) -> anyhow::Result<()> {
     let resp = cmd
@@ -644,6 +1040,12 @@ fn parse_saf_hash(arg: &str) -> anyhow::Result<SafHash> {
     SafHash::try_from(arg).map_err(|e| anyhow::anyhow!("{:?}", e))
 }
 
+fn parse_socket_mode(arg: &str) -> anyhow::Result<u32> {
+    let mode = arg.trim_start_matches("0o");
+    u32::from_str_radix(mode, 8)
+        .map_err(|e| anyhow::anyhow!("Bad socket mode: {}, expected octal like 660: {}", arg, e))
+}
+
 fn parse_status_filter(arg: &str) -> anyhow::Result<AppStatusFilter> {
     match arg {
         "active" => Ok(AppStatusFilter::Active),
@@ -668,6 +1070,56 @@ impl From<DumpState> for CellId {
     }
 }
 
+impl From<ArchiveCloneCell> for ArchiveCloneCellPayload {
+    fn from(a: ArchiveCloneCell) -> Self {
+        Self {
+            installed_app_id: a.app_id,
+            slot_id: a.slot_id,
+            cell_id: CellId::new(a.saf, a.agent_key),
+        }
+    }
+}
+
+impl From<DeleteArchivedCloneCells> for DeleteArchivedCloneCellsPayload {
+    fn from(d: DeleteArchivedCloneCells) -> Self {
+        Self {
+            installed_app_id: d.app_id,
+            slot_id: d.slot_id,
+        }
+    }
+}
+
+impl From<MigrateAgent> for MigrateAgentPayload {
+    fn from(m: MigrateAgent) -> Self {
+        Self {
+            installed_app_id: m.app_id,
+            slot_id: m.slot_id,
+            saf_hash: m.saf,
+            membrane_proof: None,
+        }
+    }
+}
+
+impl From<IssueAppToken> for IssueAppAuthenticationTokenPayload {
+    fn from(i: IssueAppToken) -> Self {
+        Self {
+            installed_app_id: i.app_id,
+            expiry_seconds: i.expiry_seconds,
+        }
+    }
+}
+
+impl From<GetCellMetrics> for Option<CellId> {
+    fn from(m: GetCellMetrics) -> Self {
+        let GetCellMetrics {
+            agent_key: a,
+            saf: d,
+        } = m;
+        d.and_then(|d| a.map(|a| (d, a)))
+            .map(|(d, a)| CellId::new(d, a))
+    }
+}
+
 impl From<ListAgents> for Option<CellId> {
     fn from(la: ListAgents) -> Self {
         let ListAgents {
@@ -154,12 +154,15 @@ impl AinSandbox {
                 crate::save::list(std::env::current_dir()?, verbose)?
             }
             AinSandboxSubcommand::Clean => crate::save::clean(std::env::current_dir()?, Vec::new())?,
-            AinSandboxSubcommand::Create(Create {
-                num_sandboxes,
-                network,
-                root,
-                directories,
-            }) => {
+            AinSandboxSubcommand::Create(create) => {
+                let bundle_resources = create.bundle_resources();
+                let Create {
+                    num_sandboxes,
+                    network,
+                    root,
+                    directories,
+                    ..
+                } = create;
                 let mut paths = Vec::with_capacity(num_sandboxes);
                 msg!(
                     "Creating {} conductor sandboxes with same settings",
@@ -168,6 +171,7 @@ impl AinSandbox {
                 for i in 0..num_sandboxes {
                     let path = crate::generate::generate(
                         network.clone().map(|n| n.into_inner().into()),
+                        bundle_resources.clone(),
                         root.clone(),
                         directories.get(i).cloned(),
                     )?;
@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use aingle_conductor_api::config::conductor::RemoteConfig;
 use aingle_p2p::kitsune_p2p::KitsuneP2pConfig;
 use aingle_p2p::kitsune_p2p::TransportConfig;
 use structopt::StructOpt;
@@ -33,6 +34,24 @@ pub struct Create {
     /// For example `ai gen -r path/to/my/chains -n 3 -d=first,second,third`
     /// will create three sandboxes with directories named "first", "second", and "third".
     pub directories: Vec<PathBuf>,
+    /// Cache bundle resources fetched from a `url` in this directory,
+    /// by the hash they are pinned to.
+    #[structopt(long)]
+    pub bundle_cache: Option<PathBuf>,
+    /// Only resolve bundle resources at a `url` from the `--bundle-cache`
+    /// directory, never fetching them.
+    #[structopt(long, requires = "bundle-cache")]
+    pub offline: bool,
+}
+
+impl Create {
+    /// The [`RemoteConfig`] set by `--bundle-cache` and `--offline`.
+    pub fn bundle_resources(&self) -> RemoteConfig {
+        RemoteConfig {
+            cache_dir: self.bundle_cache.clone(),
+            offline: self.offline,
+        }
+    }
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -227,6 +246,8 @@ impl Default for Create {
             network: None,
             root: None,
             directories: Vec::with_capacity(0),
+            bundle_cache: None,
+            offline: false,
         }
     }
 }
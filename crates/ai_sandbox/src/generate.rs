@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use aingle_conductor_api::conductor::ConductorConfig;
+use aingle_conductor_api::conductor::RemoteConfig;
 use aingle_p2p::kitsune_p2p::KitsuneP2pConfig;
 
 use crate::config::create_config;
@@ -11,18 +12,20 @@ use crate::ports::random_admin_port;
 
 /// Generate a new sandbox.
 /// This creates a directory and a [`ConductorConfig`]
-/// from an optional network.
+/// from an optional network and how to fetch bundle resources at a `url`.
 /// The root directory and inner directory
 /// (where this sandbox will be created) can be overridden.
 /// For example `my_root_dir/this_sandbox_dir/`
 pub fn generate(
     network: Option<KitsuneP2pConfig>,
+    bundle_resources: RemoteConfig,
     root: Option<PathBuf>,
     directory: Option<PathBuf>,
 ) -> anyhow::Result<PathBuf> {
     let dir = generate_directory(root, directory)?;
     let mut config = create_config(dir.clone());
     config.network = network;
+    config.bundle_resources = bundle_resources;
     random_admin_port(&mut config);
     let path = write_config(dir.clone(), &config);
     msg!("Config {:?}", config);
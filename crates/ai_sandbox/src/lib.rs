@@ -117,6 +117,8 @@ use aingle_conductor_api::{AdminRequest, AdminResponse};
 use aingle_websocket::WebsocketResult;
 use aingle_websocket::WebsocketSender;
 use ports::get_admin_api;
+#[cfg(unix)]
+use ports::get_admin_api_unix;
 
 pub use ports::force_admin_port;
 
@@ -165,6 +167,14 @@ impl CmdRunner {
         Ok(Self { client })
     }
 
+    /// Create a new connection for calling admin interface commands
+    /// over the Unix domain socket at `path`.
+    #[cfg(unix)]
+    pub async fn try_new_unix(path: &Path) -> WebsocketResult<Self> {
+        let client = get_admin_api_unix(path).await?;
+        Ok(Self { client })
+    }
+
     /// Create a command runner from a sandbox path.
     /// This expects aingle to be on the path.
     pub async fn from_sandbox(
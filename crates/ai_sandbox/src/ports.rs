@@ -1,4 +1,6 @@
 //! Helpers for working with websockets and ports.
+#[cfg(unix)]
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -43,11 +45,32 @@ pub async fn get_admin_ports(paths: Vec<PathBuf>) -> anyhow::Result<Vec<u16>> {
     Ok(ports)
 }
 
+/// Get the Unix domain socket of a sandbox's admin interface,
+/// if that's what its first admin interface listens on.
+pub(crate) fn get_admin_socket(path: PathBuf) -> anyhow::Result<Option<PathBuf>> {
+    Ok(read_config(path)?
+        .and_then(|config| config.admin_interfaces)
+        .and_then(|ai| match ai.into_iter().next() {
+            Some(AdminInterfaceConfig {
+                driver: InterfaceDriver::UnixSocket { path, .. },
+            }) => Some(path),
+            _ => None,
+        }))
+}
+
 pub(crate) async fn get_admin_api(port: u16) -> WebsocketResult<WebsocketSender> {
     tracing::debug!(port);
     websocket_client_by_port(port).await.map(|p| p.0)
 }
 
+#[cfg(unix)]
+pub(crate) async fn get_admin_api_unix(path: &Path) -> WebsocketResult<WebsocketSender> {
+    tracing::debug!(?path);
+    ws::connect_unix(path, Arc::new(WebsocketConfig::default()))
+        .await
+        .map(|p| p.0)
+}
+
 async fn websocket_client_by_port(
     port: u16,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
@@ -67,6 +90,10 @@ pub(crate) fn random_admin_port(config: &mut ConductorConfig) {
                 *port = 0;
             }
         }
+        // A socket path is chosen by the user, so leave it as it is.
+        Some(AdminInterfaceConfig {
+            driver: InterfaceDriver::UnixSocket { .. },
+        }) => (),
         None => {
             let port = 0;
             config.admin_interfaces = Some(vec![AdminInterfaceConfig {
@@ -2,6 +2,8 @@
 use std::path::Path;
 use std::{path::PathBuf, process::Stdio};
 
+use aingle_conductor_api::AdminInterfaceConfig;
+use aingle_conductor_api::InterfaceDriver;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::BufReader;
 use tokio::process::{Child, Command};
@@ -10,6 +12,8 @@ use tokio::sync::oneshot;
 use crate::calls::attach_app_interface;
 use crate::calls::AddAppWs;
 use crate::config::*;
+#[cfg(unix)]
+use crate::ports::get_admin_socket;
 use crate::ports::random_admin_port;
 use crate::ports::set_admin_port;
 use crate::CmdRunner;
@@ -23,20 +27,84 @@ use crate::CmdRunner;
 /// Can optionally force the admin port used. Otherwise
 /// the port in the config will be used if it's free or
 /// a random free port will be chosen.
+/// If the config's admin interface is a Unix domain socket
+/// and no admin port is forced, the socket is used instead.
 pub async fn run(
     aingle_path: &Path,
     sandbox_path: PathBuf,
     app_ports: Vec<u16>,
     force_admin_port: Option<u16>,
 ) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        if let (None, Some(socket)) = (force_admin_port, get_admin_socket(sandbox_path.clone())?) {
+            return run_unix(aingle_path, sandbox_path, socket, app_ports).await;
+        }
+    }
     let (port, mut aingle) =
         run_async(aingle_path, sandbox_path.clone(), force_admin_port).await?;
     msg!("Running conductor on admin port {}", port);
+    attach_app_ports(&mut CmdRunner::try_new(port).await?, app_ports).await?;
+    crate::save::lock_live(std::env::current_dir()?, &sandbox_path, port).await?;
+    msg!("Connected successfully to a running aingle");
+    let e = format!("Failed to run aingle at {}", sandbox_path.display());
+
+    aingle.wait().await.expect(&e);
+    Ok(())
+}
+
+/// Run a conductor whose admin interface is the Unix domain socket
+/// at `socket` and wait for it to finish.
+/// Nothing is recorded for `ai sandbox call` to find the conductor by,
+/// as it connects to the socket in the sandbox's config.
+#[cfg(unix)]
+async fn run_unix(
+    aingle_path: &Path,
+    sandbox_path: PathBuf,
+    socket: PathBuf,
+    app_ports: Vec<u16>,
+) -> anyhow::Result<()> {
+    let config = read_config(sandbox_path.clone())?
+        .expect("Failed to find the config with the admin socket");
+    let config_path = write_config(sandbox_path.clone(), &config);
+    let mut aingle = start_aingle(aingle_path, config_path, None).await;
+    check_started(&mut aingle).await;
+    let mut cmd = connect_when_listening(&mut aingle, &socket).await?;
+    msg!("Running conductor on admin socket {}", socket.display());
+    attach_app_ports(&mut cmd, app_ports).await?;
+    msg!("Connected successfully to a running aingle");
+    let e = format!("Failed to run aingle at {}", sandbox_path.display());
+
+    aingle.wait().await.expect(&e);
+    Ok(())
+}
+
+/// Connect to the admin socket of a conductor once it is listening on it.
+/// A socket left behind by an earlier run refuses connections
+/// until the conductor replaces it.
+#[cfg(unix)]
+async fn connect_when_listening(aingle: &mut Child, socket: &Path) -> anyhow::Result<CmdRunner> {
+    loop {
+        if let Ok(cmd) = CmdRunner::try_new_unix(socket).await {
+            return Ok(cmd);
+        }
+        if let Some(status) = aingle.try_wait()? {
+            anyhow::bail!(
+                "AIngle exited before listening on {}. status: {:?}",
+                socket.display(),
+                status
+            );
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+}
+
+/// Attach an app interface on each of the `app_ports`.
+async fn attach_app_ports(cmd: &mut CmdRunner, app_ports: Vec<u16>) -> anyhow::Result<()> {
     for app_port in app_ports {
         msg!("Attaching app port {}", app_port);
-        let mut cmd = CmdRunner::try_new(port).await?;
         let port = attach_app_interface(
-            &mut cmd,
+            cmd,
             AddAppWs {
                 port: Some(app_port),
             },
@@ -44,11 +112,6 @@ pub async fn run(
         .await?;
         msg!("App port attached at {}", port);
     }
-    crate::save::lock_live(std::env::current_dir()?, &sandbox_path, port).await?;
-    msg!("Connected successfully to a running aingle");
-    let e = format!("Failed to run aingle at {}", sandbox_path.display());
-
-    aingle.wait().await.expect(&e);
     Ok(())
 }
 
@@ -74,9 +137,21 @@ pub async fn run_async(
         }
         None => random_admin_port(&mut config),
     }
+    // The conductor only reports an admin port, so it would never be found.
+    if let Some(AdminInterfaceConfig {
+        driver: InterfaceDriver::UnixSocket { path, .. },
+    }) = config.admin_interfaces.as_ref().and_then(|ai| ai.first())
+    {
+        anyhow::bail!(
+            "The sandbox at {} has an admin socket at {} so it can't be run with an admin port. \
+            Start it with `ai sandbox run` and use --running-socket to call it",
+            sandbox_path.display(),
+            path.display()
+        );
+    }
     let config_path = write_config(sandbox_path.clone(), &config);
     let (tx_config, rx_config) = oneshot::channel();
-    let mut child = start_aingle(aingle_path, config_path, tx_config).await;
+    let mut child = start_aingle(aingle_path, config_path, Some(tx_config)).await;
     check_started(&mut child).await;
     let port = rx_config
         .await
@@ -87,7 +162,7 @@ pub async fn run_async(
 async fn start_aingle(
     aingle_path: &Path,
     config_path: PathBuf,
-    tx_config: oneshot::Sender<u16>,
+    tx_config: Option<oneshot::Sender<u16>>,
 ) -> Child {
     tracing::info!("\n\n----\nstarting aingle\n----\n\n");
     let mut cmd = Command::new(aingle_path);
@@ -114,12 +189,12 @@ async fn check_started(aingle: &mut Child) {
     }
 }
 
-fn spawn_output(aingle: &mut Child, config: oneshot::Sender<u16>) {
+fn spawn_output(aingle: &mut Child, config: Option<oneshot::Sender<u16>>) {
     let stdout = aingle.stdout.take();
     let stderr = aingle.stderr.take();
     tokio::task::spawn(async move {
         let mut needs_setup = true;
-        let mut config = Some(config);
+        let mut config = config;
         if let Some(stdout) = stdout {
             let mut reader = BufReader::new(stdout).lines();
             while let Ok(Some(line)) = reader.next_line().await {
@@ -19,8 +19,14 @@ pub async fn default_with_network(
     happ: PathBuf,
     app_id: InstalledAppId,
 ) -> anyhow::Result<PathBuf> {
+    let bundle_resources = create.bundle_resources();
     let Create { network, root, .. } = create;
-    let path = crate::generate::generate(network.map(|n| n.into_inner().into()), root, directory)?;
+    let path = crate::generate::generate(
+        network.map(|n| n.into_inner().into()),
+        bundle_resources,
+        root,
+        directory,
+    )?;
     let conductor = run_async(aingle_path, path.clone(), None).await?;
     let mut cmd = CmdRunner::new(conductor.0).await;
     let install_bundle = InstallAppBundle {
@@ -9,6 +9,7 @@ use std::sync::Arc;
 use assert_cmd::prelude::*;
 use aingle_conductor_api::AppRequest;
 use aingle_conductor_api::AppResponse;
+use aingle_conductor_api::ExternalApiWireError;
 use aingle_websocket::{self as ws, WebsocketConfig, WebsocketReceiver, WebsocketSender};
 use matches::assert_matches;
 use portpicker::pick_unused_port;
@@ -37,7 +38,11 @@ async fn call_app_interface(port: u16) {
     };
     let response = app_tx.request(request);
     let r: AppResponse = check_timeout(response).await;
-    assert_matches!(r, AppResponse::AppInfo(None));
+    // The interface is up, but refuses connections which haven't authenticated
+    assert_matches!(
+        r,
+        AppResponse::Error(ExternalApiWireError::AuthenticationFailed(_))
+    );
 }
 
 async fn check_timeout<T>(response: impl Future<Output = Result<T, ws::WebsocketError>>) -> T {
@@ -4,11 +4,13 @@ use std::sync::Arc;
 
 use super::error::ConductorApiError;
 use super::error::ConductorApiResult;
+use crate::conductor::dpki::KeyRegistryRef;
 use crate::conductor::interface::SignalBroadcaster;
 use crate::conductor::ConductorHandle;
 use crate::core::workflow::ZomeCallResult;
 use async_trait::async_trait;
 use ai_hash::SafHash;
+use aingle_conductor_api::InstalledAppInfo;
 use aingle_conductor_api::ZomeCall;
 use aingle_keystore::KeystoreSender;
 use aingle_state::host_fn_workspace::HostFnWorkspace;
@@ -100,6 +102,18 @@ impl CellConductorApiT for CellConductorApi {
         self.conductor_handle.get_entry_def(key).await
     }
 
+    fn block_warranted_agents(&self) -> bool {
+        self.conductor_handle.block_warranted_agents()
+    }
+
+    fn key_registry(&self) -> Option<KeyRegistryRef> {
+        self.conductor_handle.key_registry()
+    }
+
+    fn max_sleep_per_call(&self) -> std::time::Duration {
+        self.conductor_handle.max_sleep_per_call()
+    }
+
     fn into_call_zome_handle(self) -> CellConductorReadHandle {
         Arc::new(self)
     }
@@ -142,6 +156,15 @@ pub trait CellConductorApiT: Clone + Send + Sync + Sized {
     /// Get a [EntryDef] from the [EntryDefBuf]
     async fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
 
+    /// Whether incoming ops from agents with a warrant against them are dropped
+    fn block_warranted_agents(&self) -> bool;
+
+    /// The registry used to check whether author keys were valid, if any
+    fn key_registry(&self) -> Option<KeyRegistryRef>;
+
+    /// The longest a single call to the `sleep` host function may suspend a callback for
+    fn max_sleep_per_call(&self) -> std::time::Duration;
+
     /// Turn this into a call zome handle
     fn into_call_zome_handle(self) -> CellConductorReadHandle;
 }
@@ -162,6 +185,15 @@ pub trait CellConductorReadHandleT: Send + Sync {
 
     /// Get a zome from this cell's Saf
     async fn get_zome(&self, saf_hash: &SafHash, zome_name: &ZomeName) -> ConductorApiResult<Zome>;
+
+    /// The longest a single call to the `sleep` host function may suspend a zome call for
+    fn max_sleep_per_call(&self) -> std::time::Duration;
+
+    /// Get info about the installed app this cell belongs to
+    async fn installed_app_info(&self) -> ConductorApiResult<Option<InstalledAppInfo>>;
+
+    /// Record that an x25519 keypair in the keystore was created for this cell's agent
+    async fn add_x25519_keypair(&self, key: X25519PubKey) -> ConductorApiResult<()>;
 }
 
 #[async_trait]
@@ -187,4 +219,22 @@ impl CellConductorReadHandleT for CellConductorApi {
     async fn get_zome(&self, saf_hash: &SafHash, zome_name: &ZomeName) -> ConductorApiResult<Zome> {
         CellConductorApiT::get_zome(self, saf_hash, zome_name).await
     }
+
+    fn max_sleep_per_call(&self) -> std::time::Duration {
+        self.conductor_handle.max_sleep_per_call()
+    }
+
+    async fn installed_app_info(&self) -> ConductorApiResult<Option<InstalledAppInfo>> {
+        Ok(self
+            .conductor_handle
+            .find_app_containing_cell(&self.cell_id)
+            .await?)
+    }
+
+    async fn add_x25519_keypair(&self, key: X25519PubKey) -> ConductorApiResult<()> {
+        Ok(self
+            .conductor_handle
+            .add_x25519_key_agent(key, self.cell_id.agent_pubkey().clone())
+            .await?)
+    }
 }
@@ -4,13 +4,14 @@ use crate::conductor::api::error::ConductorApiResult;
 
 use crate::conductor::api::error::SerializationError;
 
+use crate::conductor::error::ConductorError;
 use crate::conductor::error::CreateAppError;
+use crate::conductor::interface::AdminConnection;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
 use crate::conductor::ConductorHandle;
 use aingle_keystore::KeystoreSenderExt;
 use aingle_middleware_bytes::prelude::*;
-use aingle_types::saf::SafBundle;
 use aingle_types::prelude::*;
 use mr_bundle::Bundle;
 
@@ -30,6 +31,10 @@ pub trait AdminInterfaceApi: 'static + Send + Sync + Clone {
         request: AdminRequest,
     ) -> ConductorApiResult<AdminResponse>;
 
+    /// A copy of this api for a single connection, which keeps the
+    /// connection's metrics subscription in `connection`
+    fn for_connection(&self, connection: AdminConnection) -> Self;
+
     // -- provided -- //
 
     /// Deal with error cases produced by `handle_admin_request_inner`
@@ -50,11 +55,15 @@ pub trait AdminInterfaceApi: 'static + Send + Sync + Clone {
 pub struct RealAdminInterfaceApi {
     /// Mutable access to the Conductor
     conductor_handle: ConductorHandle,
+    connection: AdminConnection,
 }
 
 impl RealAdminInterfaceApi {
     pub(crate) fn new(conductor_handle: ConductorHandle) -> Self {
-        RealAdminInterfaceApi { conductor_handle }
+        RealAdminInterfaceApi {
+            conductor_handle,
+            connection: AdminConnection::default(),
+        }
     }
 }
 
@@ -83,6 +92,12 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 // uid and properties from the register call will override any in the bundle
                 let saf = match source {
                     SafSource::Hash(ref hash) => {
+                        if self.conductor_handle.requires_trusted_publisher() {
+                            return Err(ConductorError::UnsignedSourceRefused(
+                                "A SAF derived from a hash".to_string(),
+                            )
+                            .into());
+                        }
                         if properties.is_none() && uid.is_none() {
                             return Err(ConductorApiError::SafReadError(
                                 "Hash Saf source requires properties or uid to create a derived Saf"
@@ -108,12 +123,16 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     }
                     SafSource::Path(ref path) => {
                         let bundle = Bundle::read_from_file(path).await?;
-                        let bundle: SafBundle = bundle.into();
+                        let bundle = self
+                            .conductor_handle
+                            .verify_saf_bundle(bundle.into())
+                            .await?;
                         let (saf_file, _original_hash) =
                             bundle.into_saf_file(uid, properties).await?;
                         saf_file
                     }
                     SafSource::Bundle(bundle) => {
+                        let bundle = self.conductor_handle.verify_saf_bundle(bundle).await?;
                         let (saf_file, _original_hash) =
                             bundle.into_saf_file(uid, properties).await?;
                         saf_file
@@ -135,6 +154,24 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::CloneCellCreated(cell_id))
             }
+            ArchiveCloneCell(payload) => {
+                self.conductor_handle.archive_clone_cell(*payload).await?;
+                Ok(AdminResponse::CloneCellArchived)
+            }
+            DeleteArchivedCloneCells(payload) => {
+                self.conductor_handle
+                    .delete_archived_clone_cells(*payload)
+                    .await?;
+                Ok(AdminResponse::ArchivedCloneCellsDeleted)
+            }
+            MigrateAgent(payload) => {
+                let cell_id = self
+                    .conductor_handle
+                    .clone()
+                    .migrate_agent(*payload)
+                    .await?;
+                Ok(AdminResponse::AgentMigrated(cell_id))
+            }
             InstallApp(payload) => {
                 trace!(?payload.safs);
                 let InstallAppPayload {
@@ -262,6 +299,12 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppDeactivated)
             }
+            UninstallApp { installed_app_id } => {
+                self.conductor_handle
+                    .uninstall_app(&installed_app_id)
+                    .await?;
+                Ok(AdminResponse::AppUninstalled)
+            }
             AttachAppInterface { port } => {
                 let port = port.unwrap_or(0);
                 let port = self
@@ -271,6 +314,20 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppInterfaceAttached { port })
             }
+            AttachAppUnixSocketInterface { path, mode } => {
+                self.conductor_handle
+                    .clone()
+                    .add_app_unix_socket_interface(path.clone(), mode)
+                    .await?;
+                Ok(AdminResponse::AppUnixSocketInterfaceAttached { path })
+            }
+            IssueAppAuthenticationToken(payload) => {
+                let issued = self
+                    .conductor_handle
+                    .issue_app_authentication_token(payload)
+                    .await?;
+                Ok(AdminResponse::AppAuthenticationTokenIssued(issued))
+            }
             ListAppInterfaces => {
                 let interfaces = self.conductor_handle.list_app_interfaces().await?;
                 Ok(AdminResponse::AppInterfacesListed(interfaces))
@@ -279,6 +336,44 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let state = self.conductor_handle.dump_cell_state(&cell_id).await?;
                 Ok(AdminResponse::StateDumped(state))
             }
+            GetCellMetrics { cell_id } => {
+                let metrics = self.conductor_handle.get_cell_metrics(cell_id).await?;
+                Ok(AdminResponse::CellMetricsReported(metrics))
+            }
+            SubscribeCellMetrics { interval_ms } => {
+                if interval_ms == 0 {
+                    return Err(ConductorApiError::Other(
+                        "Cell metrics interval must be greater than zero".into(),
+                    ));
+                }
+                let task = self
+                    .conductor_handle
+                    .subscribe_cell_metrics(
+                        std::time::Duration::from_millis(interval_ms),
+                        self.connection.signal_tx().clone(),
+                    )
+                    .await?;
+                self.connection.set_cell_metrics_task(Some(task));
+                Ok(AdminResponse::CellMetricsSubscribed)
+            }
+            UnsubscribeCellMetrics => {
+                self.connection.set_cell_metrics_task(None);
+                Ok(AdminResponse::CellMetricsUnsubscribed)
+            }
+            ExportSourceChain { cell_id, from_seq } => {
+                let archive = self
+                    .conductor_handle
+                    .export_source_chain(&cell_id, from_seq.unwrap_or(0))
+                    .await?;
+                Ok(AdminResponse::SourceChainExported(Box::new(archive)))
+            }
+            ImportSourceChain { archive } => {
+                self.conductor_handle
+                    .clone()
+                    .import_source_chain(*archive)
+                    .await?;
+                Ok(AdminResponse::SourceChainImported)
+            }
             AddAgentInfo { agent_infos } => {
                 self.conductor_handle.add_agent_infos(agent_infos).await?;
                 Ok(AdminResponse::AgentInfoAdded)
@@ -289,6 +384,13 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
             }
         }
     }
+
+    fn for_connection(&self, connection: AdminConnection) -> Self {
+        Self {
+            connection,
+            ..self.clone()
+        }
+    }
 }
 
 /// Return the proper phenotype for a Saf, given a manifest and some optional
@@ -599,6 +701,43 @@ mod test {
         assert_matches!(res, AdminResponse::ActiveAppsListed(v) if v.contains(&"test-by-path".to_string()) && v.contains(&"test-by-hash".to_string())
         );
 
+        // The SAF is kept while another app still uses it
+        let res = admin_api
+            .handle_admin_request(AdminRequest::UninstallApp {
+                installed_app_id: "test-by-path".to_string(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AppUninstalled);
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ListActiveApps)
+            .await;
+        assert_matches!(res, AdminResponse::ActiveAppsListed(v) if v == vec!["test-by-hash".to_string()]);
+        let saf_list = admin_api.handle_admin_request(AdminRequest::ListSafs).await;
+        assert_matches!(saf_list, AdminResponse::SafsListed(a) if a == vec![saf_hash.clone()]);
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::UninstallApp {
+                installed_app_id: "test-by-hash".to_string(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AppUninstalled);
+        let saf_list = admin_api.handle_admin_request(AdminRequest::ListSafs).await;
+        assert_matches!(saf_list, AdminResponse::SafsListed(a) if a.is_empty());
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ListCellIds)
+            .await;
+        assert_matches!(res, AdminResponse::CellIdsListed(v) if v.is_empty());
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::UninstallApp {
+                installed_app_id: "test-by-hash".to_string(),
+            })
+            .await;
+        assert_matches!(
+            res,
+            AdminResponse::Error(ExternalApiWireError::InternalError(_))
+        );
+
         handle.shutdown().await;
         tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
             .await
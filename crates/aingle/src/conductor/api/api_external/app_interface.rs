@@ -2,14 +2,19 @@ use super::InterfaceApi;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::AppConnection;
+use crate::conductor::interface::AuthenticatedApp;
 use crate::conductor::state::AppInterfaceId;
 use crate::conductor::ConductorHandle;
+use aingle_keystore::KeystoreSenderExt;
 
 use aingle_middleware_bytes::prelude::*;
 
 use aingle_types::prelude::*;
+use std::collections::HashSet;
 
 pub use aingle_conductor_api::*;
 
@@ -22,6 +27,14 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse>;
 
+    /// A copy of this api for a single connection, which keeps the App the
+    /// connection authenticated as and its signal subscriptions in `connection`
+    fn for_connection(&self, connection: AppConnection) -> Self;
+
+    /// Should this signal be emitted to the connection,
+    /// given the App it authenticated as and its signal subscriptions?
+    async fn allows_signal(&self, signal: &Signal) -> bool;
+
     // -- provided -- //
 
     /// Deal with error cases produced by `handle_app_request_inner`
@@ -41,15 +54,106 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
     interface_id: AppInterfaceId,
+    connection: AppConnection,
 }
 
 impl RealAppInterfaceApi {
-    /// Create a new instance from a shared Conductor reference
+    /// Create a new instance from a shared Conductor reference.
+    /// The instance is not authenticated as any App.
     pub fn new(conductor_handle: ConductorHandle, interface_id: AppInterfaceId) -> Self {
         Self {
             conductor_handle,
             interface_id,
+            connection: AppConnection::default(),
+        }
+    }
+
+    /// Use up an issued token to authenticate the connection as its App
+    async fn authenticate(&self, token: AppAuthenticationToken) -> ConductorApiResult<AppResponse> {
+        let installed_app_id = self.conductor_handle.authenticate_app_token(&token).await?;
+        let state_generation = self.conductor_handle.state_generation();
+        let app_info = self
+            .conductor_handle
+            .get_app_info(&installed_app_id)
+            .await?
+            .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?;
+        self.connection.authenticate(
+            installed_app_id.clone(),
+            app_cells(app_info),
+            state_generation,
+        );
+        Ok(AppResponse::Authenticated(installed_app_id))
+    }
+
+    /// The App the connection authenticated as, with its Cells looked up
+    /// again if the conductor state has been written since they last were.
+    /// Comparing generations can't miss a change the way waiting for
+    /// status signals could, because signals can be dropped when the
+    /// connection lags behind.
+    async fn authenticated_app(&self) -> ConductorApiResult<Option<AuthenticatedApp>> {
+        let app = match self.connection.authenticated_app() {
+            Some(app) => app,
+            None => return Ok(None),
+        };
+        let state_generation = self.conductor_handle.state_generation();
+        if app.state_generation == state_generation {
+            return Ok(Some(app));
         }
+        let app_info = self
+            .conductor_handle
+            .get_app_info(&app.installed_app_id)
+            .await?;
+        // An uninstalled App has no Cells left
+        self.connection.set_app_cells(
+            &app.installed_app_id,
+            app_info.map(app_cells).unwrap_or_default(),
+            state_generation,
+        );
+        Ok(self.connection.authenticated_app())
+    }
+
+    /// Perform a crypto operation with the keystore, as long as the key
+    /// whose private half is used belongs to one of the App's agents
+    async fn handle_crypto_request(
+        &self,
+        app: &AuthenticatedApp,
+        request: CryptoRequest,
+    ) -> ConductorApiResult<AppResponse> {
+        let installed_app_id = request.installed_app_id();
+        let agent = match &request {
+            CryptoRequest::Sign { sign, .. } => Some(sign.key.clone()),
+            CryptoRequest::Encrypt { encrypt, .. } => {
+                self.conductor_handle
+                    .get_x25519_key_agent(encrypt.as_sender_ref())
+                    .await?
+            }
+            CryptoRequest::Decrypt { decrypt, .. } => {
+                self.conductor_handle
+                    .get_x25519_key_agent(decrypt.as_recipient_ref())
+                    .await?
+            }
+        };
+        let is_app_agent =
+            |agent: &AgentPubKey| app.cells.iter().any(|cell| cell.agent_pubkey() == agent);
+        if !agent.as_ref().map_or(false, is_app_agent) {
+            return Ok(AppResponse::Error(
+                ExternalApiWireError::CryptoUnauthorized(format!(
+                    "The key does not belong to an agent of the app {}",
+                    installed_app_id
+                )),
+            ));
+        }
+        let keystore = self.conductor_handle.keystore();
+        let response = match request {
+            CryptoRequest::Sign { sign, .. } => CryptoResponse::Signed(keystore.sign(sign).await?),
+            CryptoRequest::Encrypt { encrypt, .. } => CryptoResponse::Encrypted(
+                keystore.x_25519_x_salsa20_poly1305_encrypt(encrypt).await?,
+            ),
+            CryptoRequest::Decrypt { decrypt, .. } => CryptoResponse::Decrypted(
+                keystore.x_25519_x_salsa20_poly1305_decrypt(decrypt).await?,
+            ),
+        };
+        Ok(AppResponse::Crypto(Box::new(response)))
     }
 }
 
@@ -60,12 +164,26 @@ impl AppInterfaceApi for RealAppInterfaceApi {
         &self,
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse> {
+        let request = match request {
+            AppRequest::Authenticate { token } => return self.authenticate(token).await,
+            request => request,
+        };
+        let app = match self.authenticated_app().await? {
+            Some(app) => app,
+            None => return Ok(not_authenticated()),
+        };
         match request {
-            AppRequest::AppInfo { installed_app_id } => Ok(AppResponse::AppInfo(
-                self.conductor_handle
-                    .get_app_info(&installed_app_id)
-                    .await?,
-            )),
+            AppRequest::Authenticate { .. } => unreachable!("handled above"),
+            AppRequest::AppInfo { installed_app_id } => {
+                if installed_app_id != app.installed_app_id {
+                    return Ok(app_unauthorized(&app, &installed_app_id));
+                }
+                Ok(AppResponse::AppInfo(
+                    self.conductor_handle
+                        .get_app_info(&installed_app_id)
+                        .await?,
+                ))
+            }
             AppRequest::ZomeCallInvocation(call) => {
                 tracing::warn!(
                     "AppRequest::ZomeCallInvocation is deprecated, use AppRequest::ZomeCall (TODO: update conductor-api)"
@@ -82,6 +200,14 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     })
             }
             AppRequest::ZomeCall(call) => {
+                if !app.cells.contains(&call.cell_id) {
+                    return Ok(AppResponse::Error(ExternalApiWireError::AppUnauthorized(
+                        format!(
+                            "The cell {:?} is not part of the app {} this connection authenticated as",
+                            call.cell_id, app.installed_app_id
+                        ),
+                    )));
+                }
                 match self.conductor_handle.call_zome(*call.clone()).await? {
                     Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCall(Box::new(output))),
                     Ok(ZomeCallResponse::Unauthorized(_, _, _, _)) => Ok(AppResponse::Error(
@@ -97,10 +223,65 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     Err(e) => Ok(AppResponse::Error(e.into())),
                 }
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
-            AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
+            AppRequest::SignalSubscription(subscription) => {
+                let installed_app_id = subscription.installed_app_id();
+                if *installed_app_id != app.installed_app_id {
+                    return Ok(app_unauthorized(&app, installed_app_id));
+                }
+                self.connection.signal_filters().subscribe(subscription);
+                Ok(AppResponse::SignalSubscriptionUpdated)
+            }
+            AppRequest::Crypto(request) => {
+                if *request.installed_app_id() != app.installed_app_id {
+                    return Ok(app_unauthorized(&app, request.installed_app_id()));
+                }
+                self.handle_crypto_request(&app, *request).await
+            }
         }
     }
+
+    fn for_connection(&self, connection: AppConnection) -> Self {
+        Self {
+            connection,
+            ..self.clone()
+        }
+    }
+
+    async fn allows_signal(&self, signal: &Signal) -> bool {
+        match self.authenticated_app().await {
+            Ok(app) => app.is_some() && self.connection.allows_signal(signal),
+            // The signal isn't sent when the App's Cells can't be checked
+            Err(err) => {
+                tracing::debug!(?err, "could not look up the cells of the app");
+                false
+            }
+        }
+    }
+}
+
+/// The ids of the Cells of an installed App
+fn app_cells(app_info: InstalledAppInfo) -> HashSet<CellId> {
+    app_info
+        .cell_data
+        .into_iter()
+        .map(|cell| cell.into_id())
+        .collect()
+}
+
+/// The response to a request made before the connection authenticated
+fn not_authenticated() -> AppResponse {
+    AppResponse::Error(ExternalApiWireError::AuthenticationFailed(
+        "The connection must send AppRequest::Authenticate before any other request".to_string(),
+    ))
+}
+
+/// The response to a request about an App other than
+/// the one the connection authenticated as
+fn app_unauthorized(app: &AuthenticatedApp, installed_app_id: &InstalledAppId) -> AppResponse {
+    AppResponse::Error(ExternalApiWireError::AppUnauthorized(format!(
+        "This connection authenticated as the app {}, and cannot make requests for the app {}",
+        app.installed_app_id, installed_app_id
+    )))
 }
 
 #[async_trait::async_trait]
@@ -141,6 +141,9 @@ impl From<ConductorApiError> for ExternalApiWireError {
     fn from(err: ConductorApiError) -> Self {
         match err {
             ConductorApiError::SafReadError(e) => ExternalApiWireError::SafReadError(e),
+            ConductorApiError::ConductorError(ConductorError::AppAuthenticationFailed(e)) => {
+                ExternalApiWireError::AuthenticationFailed(e)
+            }
             e => ExternalApiWireError::internal(e),
         }
     }
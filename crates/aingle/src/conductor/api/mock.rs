@@ -3,6 +3,7 @@
 use super::CellConductorApiT;
 use super::ZomeCall;
 use crate::conductor::api::error::ConductorApiResult;
+use crate::conductor::dpki::KeyRegistryRef;
 use crate::conductor::interface::SignalBroadcaster;
 use crate::core::workflow::ZomeCallResult;
 use async_trait::async_trait;
@@ -34,6 +35,9 @@ mock! {
         fn sync_get_this_saf(&self) -> ConductorApiResult<SafFile>;
         fn sync_get_zome(&self, saf_hash: &SafHash, zome_name: &ZomeName) -> ConductorApiResult<Zome>;
         fn sync_get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
+        fn block_warranted_agents(&self) -> bool;
+        fn key_registry(&self) -> Option<KeyRegistryRef>;
+        fn max_sleep_per_call(&self) -> std::time::Duration;
         fn into_call_zome_handle(self) -> super::CellConductorReadHandle;
     }
 
@@ -84,6 +88,18 @@ impl CellConductorApiT for MockCellConductorApi {
         self.sync_get_entry_def(key)
     }
 
+    fn block_warranted_agents(&self) -> bool {
+        self.block_warranted_agents()
+    }
+
+    fn key_registry(&self) -> Option<KeyRegistryRef> {
+        self.key_registry()
+    }
+
+    fn max_sleep_per_call(&self) -> std::time::Duration {
+        self.max_sleep_per_call()
+    }
+
     fn into_call_zome_handle(self) -> super::CellConductorReadHandle {
         self.into_call_zome_handle()
     }
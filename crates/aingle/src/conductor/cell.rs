@@ -14,14 +14,17 @@ use crate::conductor::entry_def_store::get_entry_def_from_ids;
 use crate::conductor::handle::ConductorHandle;
 use crate::core::queue_consumer::spawn_queue_consumer_tasks;
 use crate::core::queue_consumer::InitialQueueTriggers;
+use crate::core::queue_consumer::QueueConsumersJoined;
 use crate::core::queue_consumer::QueueTriggers;
 use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::workflow::call_zome_workflow;
 use crate::core::workflow::genesis_workflow::genesis_workflow;
+use crate::core::workflow::incoming_sgd_ops_workflow::drop_ops_from_warranted_authors;
 use crate::core::workflow::incoming_sgd_ops_workflow::incoming_sgd_ops_workflow;
 use crate::core::workflow::initialize_zomes_workflow;
+use crate::core::workflow::publish_sgd_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE;
 use crate::core::workflow::CallZomeWorkflowArgs;
 use crate::core::workflow::GenesisWorkflowArgs;
 use crate::core::workflow::GenesisWorkspace;
@@ -36,6 +39,7 @@ use aingle_cascade::authority;
 use aingle_cascade::Cascade;
 use aingle_middleware_bytes::SerializedBytes;
 use aingle_sqlite::prelude::*;
+use aingle_sqlite::rusqlite::OptionalExtension;
 use aingle_state::host_fn_workspace::HostFnWorkspace;
 use aingle_state::prelude::*;
 use aingle_types::prelude::*;
@@ -59,6 +63,10 @@ mod op_query_test;
 #[cfg(test)]
 mod test;
 
+/// How long [`Cell::cleanup`] waits for the queue consumers to finish the
+/// workflows they are running
+pub const QUEUE_CONSUMER_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
 impl Hash for Cell {
     fn hash<H>(&self, state: &mut H)
     where
@@ -97,6 +105,10 @@ where
     cache: EnvWrite,
     aingle_p2p_cell: P2pCell,
     queue_triggers: QueueTriggers,
+    /// Stops this Cell's queue consumers.
+    queue_consumer_stop: sync::broadcast::Sender<()>,
+    /// Resolves once the queue consumers have finished.
+    queue_consumers_joined: QueueConsumersJoined,
 }
 
 impl Cell {
@@ -126,16 +138,33 @@ impl Cell {
         };
 
         if has_genesis {
-            let (queue_triggers, initial_queue_triggers) = spawn_queue_consumer_tasks(
-                env.clone(),
-                cache.clone(),
-                aingle_p2p_cell.clone(),
-                conductor_handle.clone(),
-                conductor_api.clone(),
-                managed_task_add_sender,
-                managed_task_stop_broadcaster,
-            )
-            .await;
+            // The queue consumers stop when either the conductor shuts down
+            // or this Cell is removed.
+            let (queue_consumer_stop, _) = sync::broadcast::channel(1);
+            tokio::spawn({
+                let mut conductor_stop = managed_task_stop_broadcaster.subscribe();
+                let mut cell_stop = queue_consumer_stop.subscribe();
+                let queue_consumer_stop = queue_consumer_stop.clone();
+                async move {
+                    tokio::select! {
+                        _ = conductor_stop.recv() => {
+                            queue_consumer_stop.send(()).ok();
+                        }
+                        _ = cell_stop.recv() => {}
+                    }
+                }
+            });
+            let (queue_triggers, initial_queue_triggers, queue_consumers_joined) =
+                spawn_queue_consumer_tasks(
+                    env.clone(),
+                    cache.clone(),
+                    aingle_p2p_cell.clone(),
+                    conductor_handle.clone(),
+                    conductor_api.clone(),
+                    managed_task_add_sender,
+                    queue_consumer_stop.clone(),
+                )
+                .await;
 
             Ok((
                 Self {
@@ -145,6 +174,8 @@ impl Cell {
                     cache,
                     aingle_p2p_cell,
                     queue_triggers,
+                    queue_consumer_stop,
+                    queue_consumers_joined,
                 },
                 initial_queue_triggers,
             ))
@@ -437,6 +468,15 @@ impl Cell {
         _sgd_hash: ai_hash::AnySgdHash,
         ops: Vec<(ai_hash::SgdOpHash, aingle_types::sgd_op::SgdOp)>,
     ) -> CellResult<()> {
+        let ops = if self.conductor_api.block_warranted_agents() {
+            drop_ops_from_warranted_authors(&self.env, ops)
+                .await
+                .map_err(Box::new)
+                .map_err(ConductorApiError::from)
+                .map_err(Box::new)?
+        } else {
+            ops
+        };
         incoming_sgd_ops_workflow(
             &self.env,
             self.queue_triggers.sys_validation.clone(),
@@ -546,14 +586,17 @@ impl Cell {
             .map_err(Into::into)
     }
 
-    #[instrument(skip(self, _sgd_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _sgd_hash: ai_hash::AnySgdHash,
-        _options: aingle_p2p::event::GetMetaOptions,
+        sgd_hash: ai_hash::AnySgdHash,
+        options: aingle_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        let env = self.env.clone();
+        authority::handle_get_meta(env.into(), sgd_hash, options)
+            .await
+            .map_err(Into::into)
     }
 
     #[instrument(skip(self, options))]
@@ -590,16 +633,36 @@ impl Cell {
     #[tracing::instrument(skip(self))]
     async fn handle_validation_receipt(&self, receipt: SerializedBytes) -> CellResult<()> {
         let receipt: SignedValidationReceipt = receipt.try_into()?;
+        let op_hash = receipt.receipt.sgd_op_hash.clone();
 
-        self.env
+        let receipt_count = self
+            .env
             .async_commit(move |txn| {
                 // Update receipt count.
                 add_one_receipt_count(txn, &receipt.receipt.sgd_op_hash)?;
+                let receipt_count: Option<u32> = txn
+                    .query_row(
+                        "SELECT receipt_count FROM SgdOp WHERE hash = :hash",
+                        named_params! { ":hash": receipt.receipt.sgd_op_hash },
+                        |row| row.get(0),
+                    )
+                    .optional()?;
                 // Add to receipts db
-                validation_receipts::add_if_unique(txn, receipt)
+                validation_receipts::add_if_unique(txn, receipt)?;
+                StateMutationResult::Ok(receipt_count)
             })
             .await?;
 
+        if receipt_count == Some(DEFAULT_RECEIPT_BUNDLE_SIZE) {
+            self.signal_broadcaster()
+                .await
+                .send_system(SystemSignal::ReceiptThresholdReached {
+                    cell_id: self.id().clone(),
+                    op_hash,
+                    receipt_count: DEFAULT_RECEIPT_BUNDLE_SIZE,
+                });
+        }
+
         Ok(())
     }
 
@@ -696,10 +759,12 @@ impl Cell {
                 let sql = format!(
                     "
                 SELECT SgdOp.hash, SgdOp.basis_hash, SgdOp.type AS sgd_type,
-                Header.blob AS header_blob, Entry.blob AS entry_blob
+                Header.blob AS header_blob, Entry.blob AS entry_blob,
+                Warrant.blob AS warrant_blob
                 FROM SGdOp
                 JOIN Header ON SgdOp.header_hash = Header.hash
                 LEFT JOIN Entry ON Header.entry_hash = Entry.hash
+                LEFT JOIN Warrant ON Warrant.hash = SgdOp.hash
                 WHERE
                 SgdOp.when_integrated IS NOT NULL
                 AND
@@ -728,7 +793,12 @@ impl Cell {
                                 None => None,
                             };
                         }
-                        let op = SgdOp::from_type(op_type, header, entry)?;
+                        let warrant: Option<Vec<u8>> = row.get("warrant_blob")?;
+                        let warrant = match warrant {
+                            Some(warrant) => Some(from_blob::<SignedWarrant>(warrant)?),
+                            None => None,
+                        };
+                        let op = SgdOp::from_type(op_type, header, entry, warrant)?;
                         StateQueryResult::Ok((basis_hash, hash, op))
                     })?
                     .collect::<StateQueryResult<Vec<_>>>()?;
@@ -807,7 +877,7 @@ impl Cell {
             conductor_api,
             is_root_zome_call,
         };
-        Ok(call_zome_workflow(
+        let result = call_zome_workflow(
             workspace_lock,
             self.aingle_p2p_cell.clone(),
             keystore,
@@ -815,7 +885,13 @@ impl Cell {
             self.queue_triggers.publish_sgd_ops.clone(),
         )
         .await
-        .map_err(Box::new)?)
+        .map_err(Box::new)?;
+        // The root call has flushed the workspace
+        // so pick up any functions it scheduled.
+        if is_root_zome_call {
+            self.queue_triggers.schedule.clone().trigger();
+        }
+        Ok(result)
     }
 
     /// Check if each Zome's init callback has been run, and if not, run it.
@@ -868,22 +944,31 @@ impl Cell {
         Ok(())
     }
 
-    /// Clean up long-running managed tasks.
-    //
-    // FIXME: this should ensure that the long-running managed tasks,
-    //        i.e. the queue consumers, are stopped. Currently, they
-    //        will continue running because we have no way to target a specific
-    //        Cell's tasks for shutdown.
-    //
-    //        Consider using a separate TaskManager for each Cell, so that all
-    //        of a Cell's tasks can be shut down at once. Perhaps the Conductor
-    //        TaskManager can have these Cell TaskManagers as children.
-    //        [ B-04176 ]
+    /// Stop the long-running queue consumers and leave the network.
+    /// Once this returns successfully the consumers no longer hold the
+    /// Cell's database.
     pub async fn cleanup(&self) -> CellResult<()> {
-        tracing::info!("Cell removed, but cleanup is not yet implemented.");
+        // The consumers are stopped first so that a failure to leave the
+        // network doesn't leave them running.
+        let stopped = self.stop_queue_consumers().await;
+        if let Err(e) = self.aingle_p2p_cell.clone().leave().await {
+            warn!(cell_id = ?self.id, ?e, "Failed to leave the network");
+        }
+        stopped?;
         Ok(())
     }
 
+    /// Ask the queue consumers to stop and wait until they have.
+    /// A consumer finishes the workflow it is running before it stops, so
+    /// this gives up after [`QUEUE_CONSUMER_STOP_TIMEOUT`].
+    async fn stop_queue_consumers(&self) -> CellResult<()> {
+        // There is nothing to send to if they have already stopped
+        self.queue_consumer_stop.send(()).ok();
+        tokio::time::timeout(QUEUE_CONSUMER_STOP_TIMEOUT, self.queue_consumers_joined.wait())
+            .await
+            .map_err(|_| CellError::QueueConsumersStillRunning(self.id.clone()))
+    }
+
     /// Delete all data associated with this Cell by DELETING the associated
     /// LMDB environment. Completely reverses Cell creation.
     /// NB: This is NOT meant to be a Drop impl! This destroys all data
@@ -919,6 +1004,12 @@ impl Cell {
         &self.cache
     }
 
+    /// Publish any authored ops which were written directly to the
+    /// database, e.g. by importing a source chain archive
+    pub(crate) fn trigger_publish(&self) {
+        self.queue_triggers.publish_sgd_ops.clone().trigger();
+    }
+
     #[cfg(any(test, feature = "test_utils"))]
     /// Get the triggers for the cell
     /// Useful for testing when you want to
@@ -31,6 +31,8 @@ pub enum CellError {
         "The cell failed to cleanup its environment because: {0}. Recommend manually deleting the database at: {1}"
     )]
     Cleanup(String, PathBuf),
+    #[error("The queue consumers of cell {0:?} did not stop in time")]
+    QueueConsumersStillRunning(CellId),
     #[error(transparent)]
     SafError(#[from] SafError),
     #[error(transparent)]
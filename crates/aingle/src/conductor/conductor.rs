@@ -10,15 +10,18 @@
 //! users in a testing environment.
 use super::config::AdminInterfaceConfig;
 use super::config::InterfaceDriver;
+use super::dpki::DpkiKeyRegistry;
+use super::dpki::KeyRegistryRef;
 use super::saf_store::RealSafStore;
 use super::entry_def_store::get_entry_defs;
 use super::error::ConductorError;
 use super::error::CreateAppError;
 use super::handle::ConductorHandleImpl;
+use super::interface::error::InterfaceError;
 use super::interface::error::InterfaceResult;
 use super::interface::websocket::spawn_admin_interface_task;
 use super::interface::websocket::spawn_app_interface_task;
-use super::interface::websocket::spawn_websocket_listener;
+use super::interface::websocket::spawn_interface_listener;
 use super::interface::websocket::SIGNAL_BUFFER_SIZE;
 use super::interface::SignalBroadcaster;
 use super::manager::keep_alive_task;
@@ -42,6 +45,8 @@ use crate::conductor::config::ConductorConfig;
 use crate::conductor::error::ConductorResult;
 use crate::conductor::handle::ConductorHandle;
 use crate::core::queue_consumer::InitialQueueTriggers;
+use crate::core::workflow::migrate_agent_workflow::migrate_agent_workflow;
+use crate::core::workflow::migrate_agent_workflow::MigrateAgentWorkflowArgs;
 use crate::{
     conductor::api::error::ConductorApiResult, core::ribosome::real_ribosome::RealRibosome,
 };
@@ -50,10 +55,14 @@ use futures::future;
 use futures::future::TryFutureExt;
 use futures::stream::StreamExt;
 use ai_hash::SafHash;
+use aingle_conductor_api::AppAuthenticationToken;
+use aingle_conductor_api::AppAuthenticationTokenIssued;
 use aingle_conductor_api::AppStatusFilter;
+use aingle_conductor_api::CellMetrics;
 use aingle_conductor_api::InstalledAppInfo;
 use aingle_conductor_api::IntegrationStateDump;
 use aingle_conductor_api::JsonDump;
+use aingle_conductor_api::PublishMetrics;
 use aingle_keystore::lair_keystore::spawn_lair_keystore;
 use aingle_keystore::test_keystore::spawn_test_keystore;
 use aingle_keystore::KeystoreSender;
@@ -61,6 +70,7 @@ use aingle_keystore::KeystoreSenderExt;
 use aingle_p2p::SafHashExt;
 use aingle_sqlite::db::DbKind;
 use aingle_sqlite::prelude::*;
+use aingle_state::host_fn_workspace::HostFnWorkspace;
 use aingle_state::mutations;
 use aingle_state::prelude::from_blob;
 use aingle_state::prelude::StateMutationResult;
@@ -69,7 +79,8 @@ use aingle_types::prelude::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p::KitsuneSpace;
 use rusqlite::OptionalExtension;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::*;
@@ -84,6 +95,8 @@ use super::handle::MockConductorHandleT;
 pub struct CellState {
     /// Whether or not we should call any methods on the cell
     _active: bool,
+    /// Whether the cell has joined its network space
+    joined: AtomicBool,
 }
 
 /// An [Cell] tracked by a Conductor, along with some [CellState]
@@ -92,7 +105,7 @@ where
     CA: CellConductorApiT,
 {
     cell: Arc<Cell<CA>>,
-    _state: CellState,
+    state: CellState,
 }
 
 pub(crate) type StopBroadcaster = tokio::sync::broadcast::Sender<()>;
@@ -107,6 +120,10 @@ where
     /// The collection of cells associated with this Conductor
     cells: HashMap<CellId, CellItem<CA>>,
 
+    /// Cells which were removed but whose queue consumers didn't stop in time.
+    /// They are stopped again before their databases are deleted.
+    stopping_cells: HashMap<CellId, Arc<Cell<CA>>>,
+
     /// The database for persisting state related to this Conductor
     env: EnvWrite,
 
@@ -152,6 +169,19 @@ where
 
     /// Handle to the network actor.
     aingle_p2p: aingle_p2p::AIngleP2pRef,
+
+    /// The databases of the running cells, shared with the tasks pushing
+    /// [CellMetrics] snapshots so they don't need the conductor lock.
+    cell_envs: CellEnvs,
+
+    /// Bumped every time the [ConductorState] is written, and shared with
+    /// the handle so app interface connections can tell when the Cells of
+    /// their App may have changed without taking the conductor lock.
+    state_generation: StateGeneration,
+
+    /// Tokens issued for authenticating app interface connections,
+    /// with the App each one is for and when it expires.
+    app_authentication_tokens: HashMap<AppAuthenticationToken, (InstalledAppId, Timestamp)>,
 }
 
 impl Conductor {
@@ -258,19 +288,19 @@ where
             let admin_api = admin_api.clone();
             let stop_tx = stop_tx.clone();
             async move {
-                match driver {
-                    InterfaceDriver::Websocket { port } => {
-                        let (listener_handle, listener) = spawn_websocket_listener(port).await?;
-                        let port = listener_handle.local_addr().port().unwrap_or(port);
-                        let handle: ManagedTaskHandle = spawn_admin_interface_task(
-                            listener_handle,
-                            listener,
-                            admin_api.clone(),
-                            stop_tx.subscribe(),
-                        )?;
-                        InterfaceResult::Ok((port, handle))
-                    }
-                }
+                let (listener_handle, listener) = spawn_interface_listener(&driver).await?;
+                let local_addr = listener_handle.local_addr().clone();
+                let port = match driver {
+                    InterfaceDriver::Websocket { port } => Some(local_addr.port().unwrap_or(port)),
+                    InterfaceDriver::UnixSocket { .. } => None,
+                };
+                let handle: ManagedTaskHandle = spawn_admin_interface_task(
+                    listener_handle,
+                    listener,
+                    admin_api.clone(),
+                    stop_tx.subscribe(),
+                )?;
+                InterfaceResult::Ok((port, local_addr, handle))
             }
         };
 
@@ -296,11 +326,12 @@ where
             .await?;
 
             // Now that tasks are spawned, register them with the TaskManager
-            for (port, handle) in handles {
-                ports.push(port);
+            for (port, local_addr, handle) in handles {
+                // Only websocket interfaces have a port to report
+                ports.extend(port);
                 self.manage_task(ManagedTaskAdd::ignore(
                     handle,
-                    &format!("admin interface, port {}", port),
+                    &format!("admin interface, {}", local_addr),
                 ))
                 .await?
             }
@@ -313,15 +344,14 @@ where
 
     pub(super) async fn add_app_interface_via_handle(
         &mut self,
-        port: either::Either<u16, AppInterfaceId>,
+        interface_id: AppInterfaceId,
+        driver: InterfaceDriver,
         handle: ConductorHandle,
-    ) -> ConductorResult<u16> {
-        let interface_id = match port {
-            either::Either::Left(port) => AppInterfaceId::new(port),
-            either::Either::Right(id) => id,
-        };
-        let port = interface_id.port();
-        tracing::debug!("Attaching interface {}", port);
+    ) -> ConductorResult<InterfaceDriver> {
+        tracing::debug!("Attaching interface {:?}", driver);
+        if self.app_interfaces.contains_key(&interface_id) {
+            return Err(ConductorError::AppInterfaceIdCollision(interface_id));
+        }
         let app_api = RealAppInterfaceApi::new(handle, interface_id.clone());
         // This receiver is thrown away because we can produce infinite new
         // receivers from the Sender
@@ -332,39 +362,54 @@ where
             .expect("Task manager not initialized")
             .task_stop_broadcaster()
             .subscribe();
-        let (port, task) = spawn_app_interface_task(port, app_api, signal_tx.clone(), stop_rx)
-            .await
-            .map_err(Box::new)?;
+        let (listener_handle, listener) =
+            spawn_interface_listener(&driver).await.map_err(Box::new)?;
+        let local_addr = listener_handle.local_addr().clone();
+        let config = match driver {
+            InterfaceDriver::Websocket { .. } => AppInterfaceConfig::websocket(
+                local_addr
+                    .port()
+                    .ok_or(InterfaceError::PortError)
+                    .map_err(Box::new)?,
+            ),
+            InterfaceDriver::UnixSocket { path, mode } => {
+                AppInterfaceConfig::unix_socket(path, mode)
+            }
+        };
+        let task = spawn_app_interface_task(
+            listener_handle,
+            listener,
+            app_api,
+            signal_tx.clone(),
+            stop_rx,
+        )
+        .map_err(Box::new)?;
         // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
         self.manage_task(ManagedTaskAdd::ignore(
             task,
-            &format!("app interface, port {}", port),
+            &format!("app interface, {}", local_addr),
         ))
         .await?;
         let interface = AppInterfaceRuntime::Websocket { signal_tx };
 
-        if self.app_interfaces.contains_key(&interface_id) {
-            return Err(ConductorError::AppInterfaceIdCollision(interface_id));
-        }
-
         self.app_interfaces.insert(interface_id.clone(), interface);
-        let config = AppInterfaceConfig::websocket(port);
+        let driver = config.driver.clone();
         self.update_state(|mut state| {
             state.app_interfaces.insert(interface_id, config);
             Ok(state)
         })
         .await?;
-        tracing::debug!("App interface added at port: {}", port);
-        Ok(port)
+        tracing::debug!("App interface added at: {}", local_addr);
+        Ok(driver)
     }
 
-    pub(super) async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>> {
+    pub(super) async fn list_app_interfaces(&self) -> ConductorResult<Vec<InterfaceDriver>> {
         Ok(self
             .get_state()
             .await?
             .app_interfaces
             .values()
-            .map(|config| config.driver.port())
+            .map(|config| config.driver.clone())
             .collect())
     }
 
@@ -406,10 +451,15 @@ where
         &mut self,
         handle: ConductorHandle,
     ) -> ConductorResult<()> {
-        for id in self.get_state().await?.app_interfaces.keys().cloned() {
+        for (id, config) in self.get_state().await?.app_interfaces {
             tracing::debug!("Starting up app interface: {:?}", id);
+            let driver = match config.driver {
+                // Rebind on the port which was asked for, which may be 0
+                InterfaceDriver::Websocket { .. } => InterfaceDriver::Websocket { port: id.port() },
+                driver => driver,
+            };
             let _ = self
-                .add_app_interface_via_handle(either::Right(id), handle.clone())
+                .add_app_interface_via_handle(id, driver, handle.clone())
                 .await?;
         }
         Ok(())
@@ -700,43 +750,79 @@ where
             .collect())
     }
 
-    /// Entirely remove an app from the database
+    /// Entirely remove an app from the database, returning the removed app
+    /// along with the state it was removed from.
+    /// The x25519 keypairs of agents no other app uses are forgotten.
     pub(super) async fn remove_app_from_db(
         &mut self,
         installed_app_id: &InstalledAppId,
-    ) -> ConductorResult<Option<Vec<CellId>>> {
-        let (_state, cells_to_remove) = self
+    ) -> ConductorResult<(ConductorState, InstalledAppCommon)> {
+        let (state, app) = self
             .update_state_prime({
                 let installed_app_id = installed_app_id.clone();
                 move |mut state| {
                     let active = state.active_apps.remove(&installed_app_id);
                     let inactive = state.inactive_apps.remove(&installed_app_id);
-                    let cells = active
+                    let app = active
                         .map(|a| a.into_common())
                         .or_else(|| inactive.map(|a| a.into_common()))
-                        .map(|app| app.all_cells().cloned().collect());
-                    Ok((state, cells))
+                        .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?;
+                    Ok((state, app))
+                }
+            })
+            .await?;
+        let agents_in_use: HashSet<_> = state
+            .all_cells()
+            .into_iter()
+            .map(|cell_id| cell_id.agent_pubkey().clone())
+            .collect();
+        let agents: HashSet<_> = app
+            .all_cells()
+            .chain(app.archived_cells())
+            .map(|cell_id| cell_id.agent_pubkey().clone())
+            .filter(|agent| !agents_in_use.contains(agent))
+            .collect();
+        self.env
+            .async_commit(move |txn| {
+                for agent in agents.iter() {
+                    aingle_state::x25519_key_agent::delete_for_agent(txn, agent)?;
                 }
+                ConductorResult::Ok(())
             })
             .await?;
-        Ok(cells_to_remove)
+        Ok((state, app))
     }
 
-    /// Add fully constructed cells to the cell map in the Conductor
+    /// Add fully constructed cells to the cell map in the Conductor.
+    /// They are only reported active once they have joined the network.
     pub(super) fn add_cells(&mut self, cells: Vec<Cell>) {
         for cell in cells {
             let cell_id = cell.id().clone();
             tracing::info!(?cell_id, "ADD CELL");
+            self.cell_envs
+                .lock()
+                .insert(cell_id.clone(), cell.env().clone());
             self.cells.insert(
-                cell_id,
+                cell_id.clone(),
                 CellItem {
                     cell: Arc::new(cell),
-                    _state: CellState { _active: false },
+                    state: CellState {
+                        _active: false,
+                        joined: AtomicBool::new(false),
+                    },
                 },
             );
         }
     }
 
+    /// Record that a Cell has joined its network space.
+    /// Returns true only the first time, so the Cell is reported active once.
+    pub(super) fn mark_cell_joined(&self, cell_id: &CellId) -> bool {
+        self.cells.get(cell_id).map_or(false, |item| {
+            !item.state.joined.swap(true, Ordering::AcqRel)
+        })
+    }
+
     /// Associate a Cell with an existing App
     pub(super) async fn add_clone_cell_to_app(
         &mut self,
@@ -780,6 +866,216 @@ where
         Ok(cell_id)
     }
 
+    /// Move a clone Cell into its slot's archive
+    pub(super) async fn archive_clone_cell_in_db(
+        &mut self,
+        installed_app_id: &InstalledAppId,
+        slot_id: &SlotId,
+        cell_id: &CellId,
+    ) -> ConductorResult<()> {
+        self.update_state_prime(move |mut state| {
+            let app = state
+                .active_apps
+                .get_mut(installed_app_id)
+                .ok_or_else(|| ConductorError::AppNotActive(installed_app_id.clone()))?;
+            app.archive_clone(slot_id, cell_id)?;
+            Ok((state, ()))
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Remove all archived clone Cells from a slot, returning their ids
+    pub(super) async fn delete_archived_clone_cells_in_db(
+        &mut self,
+        installed_app_id: &InstalledAppId,
+        slot_id: &SlotId,
+    ) -> ConductorResult<Vec<CellId>> {
+        let (_, cell_ids) = self
+            .update_state_prime(move |mut state| {
+                let app = state
+                    .active_apps
+                    .get_mut(installed_app_id)
+                    .ok_or_else(|| ConductorError::AppNotActive(installed_app_id.clone()))?;
+                let cell_ids = app.delete_archived_clones(slot_id)?;
+                Ok((state, cell_ids))
+            })
+            .await?;
+        Ok(cell_ids)
+    }
+
+    /// Run genesis for the agent of an active app's slot in a new SAF, then
+    /// migrate its chain there from the slot's provisioned cell.
+    /// The new cell's database is removed again if the migration fails.
+    pub(super) async fn migrate_agent_chain(
+        &self,
+        installed_app_id: &InstalledAppId,
+        slot_id: &SlotId,
+        saf_hash: SafHash,
+        membrane_proof: Option<MembraneProof>,
+        conductor_handle: ConductorHandle,
+    ) -> ConductorResult<CellId> {
+        let state = self.get_state().await?;
+        let old_cell_id = state
+            .active_apps
+            .get(installed_app_id)
+            .ok_or_else(|| ConductorError::AppNotActive(installed_app_id.clone()))?
+            .slot(slot_id)?
+            .provisioned_cell()
+            .cloned()
+            .ok_or_else(|| AppError::SlotNotProvisioned(slot_id.clone()))?;
+        let agent_key = old_cell_id.agent_pubkey().clone();
+        let new_cell_id = CellId::new(saf_hash.clone(), agent_key.clone());
+        // An existing chain can't be opened again
+        if state.all_cells().contains(&new_cell_id) {
+            return Err(ConductorError::MigrationTargetExists(new_cell_id));
+        }
+        let old_cell = self.cell_by_id(&old_cell_id)?;
+        let old_ribosome = self.get_ribosome(old_cell_id.saf_hash())?;
+        let new_ribosome = self.get_ribosome(&saf_hash)?;
+
+        self.genesis_cells(
+            vec![(new_cell_id.clone(), membrane_proof)],
+            conductor_handle,
+        )
+        .await?;
+        let new_env = EnvWrite::open(
+            self.root_env_dir.as_ref(),
+            DbKind::Cell(new_cell_id.clone()),
+            self.keystore.clone(),
+        )?;
+        let result = async {
+            let old_workspace = HostFnWorkspace::new(
+                old_cell.env().clone(),
+                old_cell.cache().clone(),
+                agent_key.clone(),
+            )
+            .await?;
+            let new_workspace = HostFnWorkspace::new(
+                new_env.clone(),
+                self.get_or_create_cache(&saf_hash)?,
+                agent_key,
+            )
+            .await?;
+            migrate_agent_workflow(
+                old_workspace,
+                new_workspace,
+                MigrateAgentWorkflowArgs::new(old_ribosome, new_ribosome),
+            )
+            .await?;
+            ConductorResult::Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            let db = DbWrite::open(self.root_env_dir.as_ref(), DbKind::Cell(new_cell_id))?;
+            db.remove().await?;
+            return Err(e);
+        }
+        // Publish the CloseChain header
+        old_cell.trigger_publish();
+        Ok(new_cell_id)
+    }
+
+    /// Point an active app's slot at the cell its agent migrated to,
+    /// returning the previously provisioned cell
+    pub(super) async fn migrate_slot_in_db(
+        &mut self,
+        installed_app_id: &InstalledAppId,
+        slot_id: &SlotId,
+        cell_id: CellId,
+    ) -> ConductorResult<CellId> {
+        let (_, old_cell_id) = self
+            .update_state_prime(move |mut state| {
+                let app = state
+                    .active_apps
+                    .get_mut(installed_app_id)
+                    .ok_or_else(|| ConductorError::AppNotActive(installed_app_id.clone()))?;
+                let old_cell_id = app.migrate_slot(slot_id, cell_id)?;
+                Ok((state, old_cell_id))
+            })
+            .await?;
+        Ok(old_cell_id)
+    }
+
+    /// Record the agent an x25519 keypair was created for
+    pub(super) async fn add_x25519_key_agent_to_db(
+        &self,
+        key: X25519PubKey,
+        agent: AgentPubKey,
+    ) -> ConductorResult<()> {
+        self.env
+            .async_commit(move |txn| {
+                aingle_state::x25519_key_agent::put(txn, &key, &agent)?;
+                ConductorResult::Ok(())
+            })
+            .await
+    }
+
+    /// The agent an x25519 keypair was created for, if it was recorded
+    pub(super) async fn get_x25519_key_agent_from_db(
+        &self,
+        key: X25519PubKey,
+    ) -> ConductorResult<Option<AgentPubKey>> {
+        self.env
+            .conn()?
+            .with_reader(|txn| Ok(aingle_state::x25519_key_agent::get(&txn, &key)?))
+    }
+
+    /// Remove SAFs which are no longer used by any app from the SAF store
+    /// and the wasm database, along with any wasm and entry defs which no
+    /// remaining SAF uses
+    pub(super) async fn remove_safs(&mut self, saf_hashes: Vec<SafHash>) -> ConductorResult<()> {
+        if saf_hashes.is_empty() {
+            return Ok(());
+        }
+        let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
+        for saf_hash in saf_hashes.iter() {
+            self.saf_store_mut().remove_saf(saf_hash);
+            // The cache is shared by the SAF's cells, which are all gone
+            self.caches.lock().remove(saf_hash);
+            DbWrite::open(&root_env_dir, DbKind::Cache(saf_hash.clone()))?
+                .remove()
+                .await?;
+        }
+        self.wasm_env
+            .async_commit(move |txn| {
+                let mut wasm_hashes = HashSet::new();
+                for saf_hash in saf_hashes.iter() {
+                    if let Some(saf_def) = aingle_state::saf_def::get(txn, saf_hash)? {
+                        for (zome_name, zome) in saf_def.zomes.iter() {
+                            if let Ok(wasm_hash) = zome.wasm_hash(zome_name) {
+                                wasm_hashes.insert(wasm_hash);
+                            }
+                        }
+                        aingle_state::saf_def::delete(txn, saf_hash)?;
+                    }
+                }
+
+                // Wasm can be shared between SAFs, for example with clones
+                for saf_def in aingle_state::saf_def::get_all(txn)? {
+                    for (zome_name, zome) in saf_def.zomes.iter() {
+                        if let Ok(wasm_hash) = zome.wasm_hash(zome_name) {
+                            wasm_hashes.remove(&wasm_hash);
+                        }
+                    }
+                }
+                for wasm_hash in wasm_hashes.iter() {
+                    aingle_state::wasm::delete(txn, wasm_hash)?;
+                }
+                for (key, _) in aingle_state::entry_def::get_all(txn)? {
+                    let unused = matches!(
+                        &key.zome,
+                        ZomeDef::Wasm(WasmZome { wasm_hash }) if wasm_hashes.contains(wasm_hash)
+                    );
+                    if unused {
+                        aingle_state::entry_def::delete(txn, key)?;
+                    }
+                }
+                ConductorResult::Ok(())
+            })
+            .await
+    }
+
     pub(super) async fn load_wasms_into_saf_files(
         &self,
     ) -> ConductorResult<(
@@ -818,15 +1114,43 @@ where
         Ok((safs, defs))
     }
 
-    /// Remove cells from the cell map in the Conductor
-    pub(super) async fn remove_cells(&mut self, cell_ids: Vec<CellId>) {
+    /// Take cells out of the cell map in the Conductor, along with any
+    /// which were taken out before but didn't stop in time.
+    /// The cells keep running until they are cleaned up, which must happen
+    /// without holding the conductor lock because their queue consumers may
+    /// need it to finish.
+    pub(super) fn take_cells(&mut self, cell_ids: &[CellId]) -> Vec<Arc<Cell>> {
+        let mut signal_tx = self.signal_broadcaster();
+        let mut cells = Vec::new();
         for cell_id in cell_ids {
-            if let Some(item) = self.cells.remove(&cell_id) {
-                if let Err(err) = item.cell.cleanup().await {
-                    tracing::error!("Error cleaning up Cell: {:?}\nCellId: {}", err, cell_id);
-                }
+            self.cell_envs.lock().remove(cell_id);
+            if let Some(item) = self.cells.remove(cell_id) {
+                signal_tx.send_system(SystemSignal::CellStatusChanged {
+                    cell_id: cell_id.clone(),
+                    status: CellStatus::Inactive,
+                });
+                cells.push(item.cell);
+            } else if let Some(cell) = self.stopping_cells.remove(cell_id) {
+                cells.push(cell);
             }
         }
+        cells
+    }
+
+    /// Keep cells whose queue consumers didn't stop in time
+    pub(super) fn put_stopping_cells(&mut self, cells: Vec<Arc<Cell>>) {
+        self.stopping_cells
+            .extend(cells.into_iter().map(|cell| (cell.id().clone(), cell)));
+    }
+
+    /// Delete the databases of Cells which are no longer part of any app
+    pub(super) async fn remove_cell_envs(&self, cell_ids: Vec<CellId>) -> ConductorResult<()> {
+        let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
+        for cell_id in cell_ids {
+            let db = DbWrite::open(&root_env_dir, DbKind::Cell(cell_id))?;
+            db.remove().await?;
+        }
+        Ok(())
     }
 
     pub(super) async fn add_agent_infos(
@@ -987,6 +1311,90 @@ where
         Ok(serde_json::to_string_pretty(&out)?)
     }
 
+    pub(super) async fn get_cell_metrics(
+        &self,
+        cell_id: Option<CellId>,
+    ) -> ConductorApiResult<Vec<CellMetrics>> {
+        match cell_id {
+            Some(cell_id) => {
+                let env = self.cell_by_id(&cell_id)?.env().clone();
+                Ok(vec![cell_metrics(cell_id, &env.into()).await?])
+            }
+            None => running_cell_metrics(&self.cell_envs).await,
+        }
+    }
+
+    /// The databases of the running cells, which stay up to date as cells
+    /// are added and removed
+    pub(super) fn cell_envs(&self) -> CellEnvs {
+        self.cell_envs.clone()
+    }
+
+    pub(super) fn state_generation(&self) -> StateGeneration {
+        self.state_generation.clone()
+    }
+
+    /// Write a verified archive to its cell's database, whether or not the
+    /// cell is running, and let a running cell publish the new ops
+    pub(super) async fn import_source_chain(
+        &self,
+        archive: SourceChainArchive,
+    ) -> ConductorResult<()> {
+        match self.cell_by_id(&archive.cell_id) {
+            Ok(cell) => {
+                source_chain::import_archive(cell.env().clone(), archive).await?;
+                cell.trigger_publish();
+            }
+            Err(_) => {
+                let env = EnvWrite::open(
+                    self.root_env_dir.as_ref(),
+                    DbKind::Cell(archive.cell_id.clone()),
+                    self.keystore.clone(),
+                )?;
+                source_chain::import_archive(env, archive).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Issue a single use token for authenticating an app interface
+    /// connection as the given App, forgetting any tokens which have expired.
+    pub(super) fn issue_app_authentication_token(
+        &mut self,
+        installed_app_id: InstalledAppId,
+        expiry_seconds: u64,
+    ) -> ConductorResult<AppAuthenticationTokenIssued> {
+        use rand::Rng;
+        let now = timestamp::now();
+        let expires_at = (now + std::time::Duration::from_secs(expiry_seconds))
+            .map_err(|e| ConductorError::AppAuthenticationFailed(e.to_string()))?;
+        self.app_authentication_tokens
+            .retain(|_, (_, expires_at)| *expires_at > now);
+        let token = rand::thread_rng().gen::<[u8; 32]>().to_vec();
+        self.app_authentication_tokens
+            .insert(token.clone(), (installed_app_id, expires_at));
+        Ok(AppAuthenticationTokenIssued { token, expires_at })
+    }
+
+    /// Use up a token issued by [Conductor::issue_app_authentication_token],
+    /// returning the App it was issued for if it has not expired.
+    pub(super) fn authenticate_app_token(
+        &mut self,
+        token: &AppAuthenticationToken,
+    ) -> ConductorResult<InstalledAppId> {
+        match self.app_authentication_tokens.remove(token) {
+            Some((installed_app_id, expires_at)) if expires_at > timestamp::now() => {
+                Ok(installed_app_id)
+            }
+            Some(_) => Err(ConductorError::AppAuthenticationFailed(
+                "token has expired".to_string(),
+            )),
+            None => Err(ConductorError::AppAuthenticationFailed(
+                "token is unknown or has already been used".to_string(),
+            )),
+        }
+    }
+
     pub(super) fn p2p_env(&self, space: Arc<KitsuneSpace>) -> EnvWrite {
         let mut p2p_env = self.p2p_env.lock();
         p2p_env
@@ -1080,6 +1488,113 @@ pub async fn integration_dump(vault: &EnvRead) -> ConductorApiResult<Integration
         .await
 }
 
+/// Export the elements of a cell's source chain from `from_seq` onwards
+/// as an archive signed by the cell's agent.
+pub async fn export_source_chain(
+    env: EnvWrite,
+    keystore: &KeystoreSender,
+    cell_id: &CellId,
+    from_seq: u32,
+) -> ConductorResult<SourceChainArchive> {
+    let chain = source_chain::SourceChain::new(env, cell_id.agent_pubkey().clone()).await?;
+    let elements = chain
+        .query(
+            ChainQueryFilter::new()
+                .sequence_range(from_seq..)
+                .include_entries(true),
+        )
+        .await?;
+    if elements.is_empty() {
+        return Err(SourceChainArchiveError::StartsPastHead(from_seq).into());
+    }
+    Ok(SourceChainArchive::new(keystore, cell_id.clone(), from_seq, elements).await?)
+}
+
+/// The databases of the running cells, keyed by cell
+pub type CellEnvs = Arc<parking_lot::Mutex<HashMap<CellId, EnvWrite>>>;
+
+/// How many times the [ConductorState] has been written
+pub type StateGeneration = Arc<AtomicU64>;
+
+/// Collect the structured [`CellMetrics`] of every running cell.
+pub async fn running_cell_metrics(cell_envs: &CellEnvs) -> ConductorApiResult<Vec<CellMetrics>> {
+    let envs: Vec<_> = cell_envs
+        .lock()
+        .iter()
+        .map(|(cell_id, env)| (cell_id.clone(), env.clone()))
+        .collect();
+    let mut metrics = Vec::with_capacity(envs.len());
+    for (cell_id, env) in envs {
+        metrics.push(cell_metrics(cell_id, &env.into()).await?);
+    }
+    Ok(metrics)
+}
+
+/// Collect the structured [`CellMetrics`] for a cell's vault.
+pub async fn cell_metrics(cell_id: CellId, vault: &EnvRead) -> ConductorApiResult<CellMetrics> {
+    let integration = integration_dump(vault).await?;
+    vault
+        .async_reader(move |txn| {
+            let op_counts = txn
+                .prepare("SELECT type, count(hash) FROM SgdOp GROUP BY type")?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<HashMap<SgdOpType, usize>, _>>()?;
+            let validation_attempts = txn
+                .prepare(
+                    "
+                    SELECT IFNULL(num_validation_attempts, 0) AS attempts, count(hash)
+                    FROM SgdOp
+                    WHERE when_integrated IS NULL
+                    AND (
+                        (is_authored = 1 AND validation_stage IS NOT NULL AND validation_stage < 3)
+                        OR
+                        (is_authored = 0 AND (validation_stage IS NULL OR validation_stage < 3))
+                    )
+                    GROUP BY attempts
+                    ",
+                )?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<BTreeMap<u32, usize>, _>>()?;
+            let publish = txn.query_row(
+                "
+                SELECT
+                count(hash),
+                IFNULL(SUM(last_publish_time IS NULL), 0),
+                IFNULL(SUM(receipt_count), 0),
+                MAX(last_publish_time)
+                FROM SgdOp
+                WHERE is_authored = 1
+                ",
+                [],
+                |row| {
+                    Ok(PublishMetrics {
+                        authored_ops: row.get(0)?,
+                        awaiting_publish: row.get(1)?,
+                        receipts_received: row.get(2)?,
+                        last_publish_time: row.get(3)?,
+                        ..Default::default()
+                    })
+                },
+            )?;
+            let receipts_to_send = txn.query_row(
+                "SELECT count(hash) FROM SgdOp WHERE require_receipt = 1",
+                [],
+                |row| row.get(0),
+            )?;
+            ConductorApiResult::Ok(CellMetrics {
+                cell_id,
+                integration,
+                op_counts,
+                validation_attempts,
+                publish: PublishMetrics {
+                    receipts_to_send,
+                    ..publish
+                },
+            })
+        })
+        .await
+}
+
 //-----------------------------------------------------------------------------
 // Private methods
 //-----------------------------------------------------------------------------
@@ -1106,6 +1621,7 @@ where
             p2p_metrics_env,
             caches: parking_lot::Mutex::new(HashMap::new()),
             cells: HashMap::new(),
+            stopping_cells: HashMap::new(),
             shutting_down: false,
             app_interfaces: HashMap::new(),
             task_manager: None,
@@ -1114,6 +1630,9 @@ where
             keystore,
             root_env_dir,
             aingle_p2p,
+            cell_envs: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            state_generation: Arc::new(AtomicU64::new(0)),
+            app_authentication_tokens: HashMap::new(),
         })
     }
 
@@ -1184,6 +1703,7 @@ where
                 Result::<_, ConductorError>::Ok((new_state, output))
             })
             .await?;
+        self.state_generation.fetch_add(1, Ordering::SeqCst);
         Ok(output)
     }
 
@@ -1220,6 +1740,8 @@ mod builder {
         pub saf_store: DS,
         /// Optional keystore override
         pub keystore: Option<KeystoreSender>,
+        /// Optional registry used to check author keys during validation
+        pub key_registry: Option<KeyRegistryRef>,
         #[cfg(any(test, feature = "test_utils"))]
         /// Optional state override (for testing)
         pub state: Option<ConductorState>,
@@ -1301,7 +1823,10 @@ mod builder {
             let state = self.state;
 
             let Self {
-                saf_store, config, ..
+                saf_store,
+                config,
+                key_registry,
+                ..
             } = self;
 
             let network_config = match &config.network {
@@ -1334,24 +1859,47 @@ mod builder {
             #[cfg(any(test, feature = "test_utils"))]
             let conductor = Self::update_fake_state(state, conductor).await?;
 
-            Self::finish(conductor, config, p2p_evt).await
+            Self::finish(conductor, config, key_registry, p2p_evt).await
         }
 
         async fn finish(
             conductor: Conductor<DS>,
             conductor_config: ConductorConfig,
+            key_registry: Option<KeyRegistryRef>,
             p2p_evt: aingle_p2p::event::AIngleP2pEventReceiver,
         ) -> ConductorResult<ConductorHandle> {
             // Get data before handle
             let keystore = conductor.keystore.clone();
             let aingle_p2p = conductor.aingle_p2p.clone();
 
+            // A registry passed to the builder takes precedence over the DPKI app
+            let dpki_key_registry = match (&conductor_config.dpki, &key_registry) {
+                (Some(dpki), None) => Some(Arc::new(DpkiKeyRegistry::new(dpki))),
+                _ => None,
+            };
+            let key_registry = key_registry.or_else(|| {
+                dpki_key_registry
+                    .clone()
+                    .map(|registry| registry as KeyRegistryRef)
+            });
+
             // Create handle
             let handle: ConductorHandle = Arc::new(ConductorHandleImpl {
+                state_generation: conductor.state_generation(),
                 conductor: RwLock::new(conductor),
                 keystore,
                 aingle_p2p,
+                max_sleep_per_call: conductor_config.max_sleep_per_call(),
+                block_warranted_agents: conductor_config.block_warranted_agents,
+                trusted_publishers: conductor_config
+                    .trusted_publishers
+                    .map(|keys| keys.into_iter().map(AgentPubKey::from).collect()),
+                bundle_resources: conductor_config.bundle_resources,
+                key_registry,
             });
+            if let Some(dpki_key_registry) = dpki_key_registry {
+                dpki_key_registry.set_conductor_handle(&handle);
+            }
 
             let configs = conductor_config.admin_interfaces.unwrap_or_default();
             handle.clone().initialize_conductor(configs).await?;
@@ -1382,6 +1930,13 @@ mod builder {
             self
         }
 
+        /// Pass in the registry which sys validation uses to reject headers
+        /// signed by keys that were revoked or rotated before they were authored
+        pub fn with_key_registry(mut self, key_registry: KeyRegistryRef) -> Self {
+            self.key_registry = Some(key_registry);
+            self
+        }
+
         #[cfg(any(test, feature = "test_utils"))]
         /// Sets some fake conductor state for tests
         pub fn fake_state(mut self, state: ConductorState) -> Self {
@@ -1431,7 +1986,7 @@ mod builder {
 
             let conductor = Self::update_fake_state(self.state, conductor).await?;
 
-            Self::finish(conductor, self.config, p2p_evt).await
+            Self::finish(conductor, self.config, self.key_registry, p2p_evt).await
         }
     }
 }
@@ -1,15 +1,20 @@
 use super::Conductor;
 use super::ConductorState;
 use super::*;
+use crate::conductor::api::AppInterfaceApi;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
+use crate::core::workflow::error::WorkflowError;
 use crate::sweettest::*;
 use crate::test_utils::fake_valid_saf_file;
 use crate::{
     assert_eq_retry_10s, core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult,
 };
 use ::fixt::prelude::*;
+use aingle_conductor_api::IssueAppAuthenticationTokenPayload;
 use aingle_conductor_api::{AdminRequest, AdminResponse, AppRequest, AppResponse, ZomeCall};
+use aingle_conductor_api::{CryptoRequest, CryptoResponse, ExternalApiWireError};
 use aingle_keystore::crude_mock_keystore::spawn_crude_mock_keystore;
+use aingle_keystore::AgentPubKeyExt;
 use aingle_state::prelude::*;
 use aingle_types::test_utils::fake_cell_id;
 use aingle_wasm_test_utils::TestWasm;
@@ -17,6 +22,7 @@ use aingle_websocket::WebsocketSender;
 use kitsune_p2p_types::dependencies::lair_keystore_api::LairError;
 use maplit::hashset;
 use matches::assert_matches;
+use mr_bundle::error::{BundleError, MrBundleError};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_update_state() {
@@ -127,6 +133,101 @@ async fn can_add_clone_cell_to_app() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_archive_and_delete_clone_cell() {
+    let envs = test_environments();
+    let keystore = envs.conductor().keystore().clone();
+    let aingle_p2p = aingle_p2p::stub_network().await;
+
+    let agent = fixt!(AgentPubKey);
+    let saf = fake_valid_saf_file("");
+    let cell_id = CellId::new(saf.saf_hash().to_owned(), agent.clone());
+
+    let mut conductor = Conductor::new(
+        envs.conductor(),
+        envs.wasm(),
+        envs.p2p(),
+        envs.p2p_metrics(),
+        RealSafStore::new(),
+        keystore,
+        envs.tempdir().path().to_path_buf().into(),
+        aingle_p2p,
+    )
+    .await
+    .unwrap();
+
+    let app_id = "app".to_string();
+    let slot_id = "nick".to_string();
+    let slot = AppSlot::new(cell_id.clone(), true, 1);
+    let app = InstalledAppCommon::new(app_id.clone(), agent, vec![(slot_id.clone(), slot)]);
+
+    conductor.register_phenotype(saf).await.unwrap();
+    conductor
+        .update_state(move |mut state| {
+            state.active_apps.insert(app.into());
+            Ok(state)
+        })
+        .await
+        .unwrap();
+
+    let cloned_cell_id = conductor
+        .add_clone_cell_to_app(&app_id, &slot_id, ().into())
+        .await
+        .unwrap();
+
+    // Only clones can be archived
+    matches::assert_matches!(
+        conductor
+            .archive_clone_cell_in_db(&app_id, &slot_id, &cell_id)
+            .await,
+        Err(ConductorError::AppError(AppError::CloneCellMissing(_, _)))
+    );
+
+    conductor
+        .archive_clone_cell_in_db(&app_id, &slot_id, &cloned_cell_id)
+        .await
+        .unwrap();
+    let state = conductor.get_state().await.unwrap();
+    let app = state.active_apps.get(&app_id).unwrap();
+    assert_eq!(app.cloned_cells().count(), 0);
+    assert_eq!(
+        app.archived_cells().cloned().collect::<Vec<_>>(),
+        vec![cloned_cell_id.clone()]
+    );
+
+    // Archiving frees up the slot's clone limit
+    conductor
+        .add_clone_cell_to_app(&app_id, &slot_id, ().into())
+        .await
+        .unwrap();
+
+    let env_path = DbWrite::open(envs.tempdir().path(), DbKind::Cell(cloned_cell_id.clone()))
+        .unwrap()
+        .path()
+        .clone();
+    assert!(env_path.exists());
+    let base_env_path = DbWrite::open(envs.tempdir().path(), DbKind::Cell(cell_id.clone()))
+        .unwrap()
+        .path()
+        .clone();
+    assert!(base_env_path.exists());
+
+    let deleted = conductor
+        .delete_archived_clone_cells_in_db(&app_id, &slot_id)
+        .await
+        .unwrap();
+    assert_eq!(deleted, vec![cloned_cell_id]);
+    conductor.remove_cell_envs(deleted).await.unwrap();
+    assert!(!env_path.exists());
+    // Only the deleted cell's database is removed
+    assert!(base_env_path.exists());
+
+    let state = conductor.get_state().await.unwrap();
+    let app = state.active_apps.get(&app_id).unwrap();
+    assert_eq!(app.archived_cells().count(), 0);
+    assert_eq!(app.cloned_cells().count(), 1);
+}
+
 /// App can't be installed if another app is already installed under the
 /// same InstalledAppId
 #[tokio::test(flavor = "multi_thread")]
@@ -390,7 +491,20 @@ async fn test_uninstall_app() {
     observability::test_run().ok();
     let zome = InlineZome::new_unique(Vec::new());
 
-    let (conductor, _) = common_genesis_test_app(zome).await.unwrap();
+    let (conductor, app) = common_genesis_test_app(zome).await.unwrap();
+    let env_dir = conductor.envs().tempdir();
+    let env_path = |kind| DbWrite::open(env_dir.path(), kind).unwrap().path().clone();
+    let env_paths: Vec<_> = app
+        .cells()
+        .iter()
+        .flat_map(|cell| {
+            vec![
+                env_path(DbKind::Cell(cell.cell_id().clone())),
+                env_path(DbKind::Cache(cell.cell_id().saf_hash().clone())),
+            ]
+        })
+        .collect();
+    assert!(env_paths.iter().all(|path| path.exists()));
 
     // - Ensure that the app is active
     assert_eq_retry_10s!(
@@ -403,6 +517,9 @@ async fn test_uninstall_app() {
 
     conductor.uninstall_app(&"app".to_string()).await.unwrap();
 
+    // - The cell and cache databases are deleted
+    assert!(env_paths.iter().all(|path| !path.exists()));
+
     // - Ensure that the app is removed
     assert_eq_retry_10s!(
         {
@@ -426,6 +543,958 @@ async fn test_setup_cells_idempotency() {
     assert_eq_retry_10s!(conductor.list_active_apps().await.unwrap().len(), 1);
 }
 
+fn publisher_test_manifest(location: SafLocation) -> AppManifest {
+    let slot = AppSlotManifest {
+        saf: AppSlotSafManifest {
+            location: Some(location),
+            ..AppSlotSafManifest::sample()
+        },
+        ..AppSlotManifest::sample("slot".into())
+    };
+    AppManifestCurrentBuilder::default()
+        .name("published".into())
+        .description(None)
+        .slots(vec![slot])
+        .build()
+        .unwrap()
+        .into()
+}
+
+async fn publisher_test_bundle(saf: SafFile) -> AppBundle {
+    let saf_path = std::path::PathBuf::from("published.saf");
+    let manifest = publisher_test_manifest(SafLocation::Bundled(saf_path.clone()));
+    let resources = vec![(saf_path, SafBundle::from_saf_file(saf).await.unwrap())];
+    AppBundle::new(manifest, resources, std::path::PathBuf::from("."))
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_install_app_bundle_requires_trusted_publisher() {
+    observability::test_run().ok();
+    let publisher_keystore = aingle_keystore::test_keystore::spawn_test_keystore()
+        .await
+        .unwrap();
+    let trusted = AgentPubKey::new_from_pure_entropy(&publisher_keystore)
+        .await
+        .unwrap();
+    let untrusted = AgentPubKey::new_from_pure_entropy(&publisher_keystore)
+        .await
+        .unwrap();
+
+    let conductor = SweetConductor::from_config(ConductorConfig {
+        trusted_publishers: Some(vec![trusted.clone().into()]),
+        ..standard_config()
+    })
+    .await;
+    let agent_key = AgentPubKey::new_from_pure_entropy(&conductor.keystore())
+        .await
+        .unwrap();
+    let (saf, _) = SweetSafFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+
+    let install = |bundle: AppBundle| {
+        conductor
+            .inner_handle()
+            .install_app_bundle(InstallAppBundlePayload {
+                source: AppBundleSource::Bundle(bundle),
+                agent_key: agent_key.clone(),
+                installed_app_id: None,
+                membrane_proofs: HashMap::new(),
+                uid: None,
+            })
+    };
+
+    // - An unsigned bundle is refused
+    let bundle = publisher_test_bundle(saf.clone()).await;
+    assert_matches!(
+        install(bundle).await,
+        Err(ConductorError::AppBundleError(
+            AppBundleError::UntrustedPublisher
+        ))
+    );
+
+    // - A bundle signed by an unknown publisher is refused
+    let bundle = sign_bundle(
+        publisher_test_bundle(saf.clone()).await.into_inner(),
+        &publisher_keystore,
+        &untrusted,
+    )
+    .await
+    .unwrap();
+    assert_matches!(
+        install(bundle.into()).await,
+        Err(ConductorError::AppBundleError(
+            AppBundleError::UntrustedPublisher
+        ))
+    );
+
+    // - A signed bundle can't use a SAF from outside of it, which the
+    //   signature doesn't cover
+    let tmpdir = tempdir::TempDir::new("published").unwrap();
+    let saf_path = tmpdir.path().join("published.saf");
+    SafBundle::from_saf_file(saf.clone())
+        .await
+        .unwrap()
+        .write_to_file(&saf_path)
+        .await
+        .unwrap();
+    let manifest = publisher_test_manifest(SafLocation::Path(saf_path));
+    let bundle = AppBundle::new(manifest, vec![], tmpdir.path().to_path_buf())
+        .await
+        .unwrap();
+    let bundle = sign_bundle(bundle.into_inner(), &publisher_keystore, &trusted)
+        .await
+        .unwrap();
+    assert_matches!(
+        install(bundle.into()).await,
+        Err(ConductorError::AppBundleError(AppBundleError::MrBundleError(
+            MrBundleError::BundleError(BundleError::NotContentAddressed(_))
+        )))
+    );
+
+    // - A bundle signed by a trusted publisher is installed
+    let bundle = sign_bundle(
+        publisher_test_bundle(saf).await.into_inner(),
+        &publisher_keystore,
+        &trusted,
+    )
+    .await
+    .unwrap();
+    install(bundle.into()).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_saf_registration_requires_trusted_publisher() {
+    observability::test_run().ok();
+    let publisher_keystore = aingle_keystore::test_keystore::spawn_test_keystore()
+        .await
+        .unwrap();
+    let trusted = AgentPubKey::new_from_pure_entropy(&publisher_keystore)
+        .await
+        .unwrap();
+
+    let conductor = SweetConductor::from_config(ConductorConfig {
+        trusted_publishers: Some(vec![trusted.clone().into()]),
+        ..standard_config()
+    })
+    .await;
+    let handle = conductor.inner_handle();
+    let (saf, _) = SweetSafFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+
+    // - An unsigned SAF bundle is refused
+    let bundle = SafBundle::from_saf_file(saf.clone()).await.unwrap();
+    assert_matches!(
+        handle.verify_saf_bundle(bundle).await,
+        Err(ConductorError::UntrustedSafPublisher)
+    );
+
+    // - A SAF bundle signed by a trusted publisher is accepted
+    let bundle = SafBundle::from_saf_file(saf.clone()).await.unwrap();
+    let signed = sign_bundle(bundle.into_inner(), &publisher_keystore, &trusted)
+        .await
+        .unwrap();
+    let (saf_file, _) = handle
+        .verify_saf_bundle(signed.into())
+        .await
+        .unwrap()
+        .into_saf_file(None, None)
+        .await
+        .unwrap();
+    assert_eq!(saf_file.saf_hash(), saf.saf_hash());
+
+    // - Apps can't be installed from bare SAF hashes
+    let cell_id = CellId::new(saf.saf_hash().clone(), fixt!(AgentPubKey));
+    let installed_cell = InstalledCell::new(cell_id, "nick".into());
+    assert_matches!(
+        handle
+            .clone()
+            .install_app("app".to_string(), vec![(installed_cell, None)])
+            .await,
+        Err(ConductorError::UnsignedSourceRefused(_))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn key_registry_asks_the_dpki_app() {
+    use crate::conductor::config::DpkiConfig;
+    use crate::conductor::dpki::*;
+
+    let revoked_at = Timestamp(1_000, 0);
+    let (saf, _) = mk_saf(
+        DPKI_ZOME_NAME,
+        InlineZome::new_unique(Vec::new())
+            .callback(DPKI_KEY_STATE_FN, move |_api, _key: AgentPubKey| {
+                Ok(KeyState::Revoked(revoked_at))
+            }),
+    )
+    .await
+    .unwrap();
+    let mut conductor = SweetConductor::from_config(ConductorConfig {
+        dpki: Some(DpkiConfig {
+            instance_id: "dpki".to_string(),
+            init_params: String::new(),
+        }),
+        ..standard_config()
+    })
+    .await;
+    let key_registry = conductor.inner_handle().key_registry().unwrap();
+
+    // The app isn't installed yet.
+    assert_matches!(
+        key_registry.key_state(&fixt!(AgentPubKey)).await,
+        Err(KeyRegistryError::Unavailable(_))
+    );
+
+    let app = conductor.setup_app("dpki", &[saf]).await.unwrap();
+    assert_eq!(
+        key_registry.key_state(&fixt!(AgentPubKey)).await.unwrap(),
+        KeyState::Revoked(revoked_at)
+    );
+
+    // The DPKI cell's own ops aren't checked against itself.
+    assert!(!key_registry
+        .checks_cell(app.cells()[0].cell_id())
+        .await
+        .unwrap());
+    assert!(key_registry.checks_cell(&fixt!(CellId)).await.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cell_metrics_over_admin_interface() {
+    use aingle_conductor_api::AdminSignal;
+    use tokio_stream::StreamExt;
+
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (saf, _) = mk_saf("saf", InlineZome::new_unique(Vec::new()))
+        .await
+        .unwrap();
+    let app = conductor.setup_app("app", &[saf]).await.unwrap();
+    let cell_id = app.cells()[0].cell_id().clone();
+    let (mut admin_client, admin_rx) = conductor.admin_ws_client().await;
+
+    let response: AdminResponse = admin_client
+        .request(AdminRequest::GetCellMetrics {
+            cell_id: Some(cell_id.clone()),
+        })
+        .await
+        .unwrap();
+    let metrics = unwrap_to::unwrap_to!(response => AdminResponse::CellMetricsReported).clone();
+    assert_eq!(metrics.len(), 1);
+    assert_eq!(metrics[0].cell_id, cell_id);
+    // - Genesis authored ops for the three genesis elements
+    assert!(metrics[0].publish.authored_ops > 0);
+    assert!(metrics[0].op_counts.values().sum::<usize>() >= metrics[0].publish.authored_ops);
+
+    let response: AdminResponse = admin_client
+        .request(AdminRequest::SubscribeCellMetrics { interval_ms: 0 })
+        .await
+        .unwrap();
+    assert_matches!(response, AdminResponse::Error(_));
+
+    let response: AdminResponse = admin_client
+        .request(AdminRequest::SubscribeCellMetrics { interval_ms: 100 })
+        .await
+        .unwrap();
+    assert_matches!(response, AdminResponse::CellMetricsSubscribed);
+
+    // - Snapshots arrive as signals on the admin connection
+    let (bytes, msg) = Box::pin(admin_rx.timeout(std::time::Duration::from_secs(5)))
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!msg.is_request());
+    let signal = AdminSignal::try_from(bytes).unwrap();
+    let metrics = unwrap_to::unwrap_to!(signal => AdminSignal::CellMetrics).clone();
+    assert_eq!(metrics.len(), 1);
+    assert_eq!(metrics[0].cell_id, cell_id);
+
+    // - Other admin connections don't receive the snapshots
+    let (_other_client, other_rx) = conductor.admin_ws_client().await;
+    let next = Box::pin(other_rx.timeout(std::time::Duration::from_millis(500)))
+        .next()
+        .await;
+    assert_matches!(next, Some(Err(_)));
+
+    let response: AdminResponse = admin_client
+        .request(AdminRequest::UnsubscribeCellMetrics)
+        .await
+        .unwrap();
+    assert_matches!(response, AdminResponse::CellMetricsUnsubscribed);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_export_and_import_source_chain() {
+    observability::test_run().ok();
+    let (saf, _) = SweetSafFile::unique_from_test_wasms(vec![TestWasm::Create])
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &[saf.clone()]).await.unwrap();
+    let cell_id = app.cells()[0].cell_id().clone();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+
+    // - Back up the whole chain, then only what was committed since
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+    let full = conductor.export_source_chain(&cell_id, 0).await.unwrap();
+    let next_seq = full.elements.len() as u32;
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+    let diff = conductor
+        .export_source_chain(&cell_id, next_seq)
+        .await
+        .unwrap();
+    assert_eq!(diff.from_seq, next_seq);
+    assert!(!diff.elements.is_empty());
+
+    // - There is nothing to export past the chain head
+    let past_head = next_seq + diff.elements.len() as u32;
+    assert_matches!(
+        conductor.export_source_chain(&cell_id, past_head).await,
+        Err(ConductorError::SourceChainArchiveError(
+            SourceChainArchiveError::StartsPastHead(seq)
+        )) if seq == past_head
+    );
+
+    // - Restore into a fresh conductor which has the agent's key
+    let envs = test_envs_with_keystore(conductor.keystore());
+    let config = standard_config();
+    let mut restored = SweetConductor::new(
+        SweetConductor::handle_from_existing(&envs, &config).await,
+        envs,
+        config,
+    )
+    .await;
+    restored.register_saf(saf.clone()).await.unwrap();
+
+    // - A tampered archive is refused
+    let mut tampered = full.clone();
+    tampered.elements.pop();
+    assert_matches!(
+        restored.inner_handle().import_source_chain(tampered).await,
+        Err(ConductorError::SourceChainArchiveError(
+            SourceChainArchiveError::InvalidSignature
+        ))
+    );
+
+    // - The differential archive can't be restored without the rest of the chain
+    assert_matches!(
+        restored
+            .inner_handle()
+            .import_source_chain(diff.clone())
+            .await,
+        Err(ConductorError::SourceChainError(
+            SourceChainError::SourceChainArchiveError(
+                SourceChainArchiveError::DoesNotExtendChain { .. }
+            )
+        ))
+    );
+    restored
+        .inner_handle()
+        .import_source_chain(full.clone())
+        .await
+        .unwrap();
+    // - A whole chain can't be restored twice
+    assert_matches!(
+        restored
+            .inner_handle()
+            .import_source_chain(full.clone())
+            .await,
+        Err(ConductorError::SourceChainError(
+            SourceChainError::SourceChainArchiveError(
+                SourceChainArchiveError::DoesNotExtendChain { .. }
+            )
+        ))
+    );
+    restored
+        .inner_handle()
+        .import_source_chain(diff.clone())
+        .await
+        .unwrap();
+
+    // - Installing the app reuses the restored chain instead of running genesis
+    restored
+        .setup_app_for_agents("app", &[cell_id.agent_pubkey().clone()], &[saf])
+        .await
+        .unwrap();
+    let restored_chain = restored.export_source_chain(&cell_id, 0).await.unwrap();
+    assert_eq!(
+        restored_chain.elements,
+        [full.elements, diff.elements].concat()
+    );
+}
+
+/// Read the whole chain of a cell which may no longer be running
+async fn read_chain(conductor: &SweetConductor, cell_id: &CellId) -> Vec<Element> {
+    let env = EnvWrite::open(
+        conductor.envs().tempdir().path(),
+        DbKind::Cell(cell_id.clone()),
+        conductor.keystore(),
+    )
+    .unwrap();
+    source_chain::SourceChain::new(env, cell_id.agent_pubkey().clone())
+        .await
+        .unwrap()
+        .query(ChainQueryFilter::new().include_entries(true))
+        .await
+        .unwrap()
+}
+
+fn migrate_payload(old_saf: &SafFile, new_saf: &SafFile) -> MigrateAgentPayload {
+    MigrateAgentPayload {
+        installed_app_id: "app".into(),
+        slot_id: old_saf.saf_hash().to_string(),
+        saf_hash: new_saf.saf_hash().clone(),
+        membrane_proof: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_migrate_agent() {
+    observability::test_run().ok();
+    let zomes = vec![TestWasm::Create, TestWasm::MigrateAgentPass];
+    let (old_saf, _) = SweetSafFile::unique_from_test_wasms(zomes.clone())
+        .await
+        .unwrap();
+    let (new_saf, _) = SweetSafFile::unique_from_test_wasms(zomes).await.unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor
+        .setup_app("app", &[old_saf.clone()])
+        .await
+        .unwrap();
+    let old_cell_id = app.cells()[0].cell_id().clone();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+    let _: HeaderHash = conductor.call(&zome, "create_priv_msg", ()).await;
+    conductor.register_saf(new_saf.clone()).await.unwrap();
+
+    let new_cell_id = conductor
+        .inner_handle()
+        .migrate_agent(migrate_payload(&old_saf, &new_saf))
+        .await
+        .unwrap();
+    assert_eq!(
+        new_cell_id,
+        CellId::new(
+            new_saf.saf_hash().clone(),
+            old_cell_id.agent_pubkey().clone()
+        )
+    );
+
+    // - The old chain is closed and points at the new SAF
+    let old_chain = read_chain(&conductor, &old_cell_id).await;
+    assert_matches!(
+        old_chain.last().unwrap().header(),
+        Header::CloseChain(close) if close.new_saf_hash == *new_saf.saf_hash()
+    );
+
+    // - The new chain opens after genesis, pointing back at the old SAF,
+    //   and carries the app entries forward
+    let new_chain = read_chain(&conductor, &new_cell_id).await;
+    assert_matches!(
+        new_chain[3].header(),
+        Header::OpenChain(open) if open.prev_saf_hash == *old_saf.saf_hash()
+    );
+    let app_entries = |chain: &[Element]| -> Vec<Entry> {
+        chain
+            .iter()
+            .filter(|el| matches!(el.header().entry_type(), Some(EntryType::App(_))))
+            .filter_map(|el| el.entry().as_option().cloned())
+            .collect()
+    };
+    assert_eq!(app_entries(&new_chain).len(), 2);
+    assert_eq!(app_entries(&new_chain), app_entries(&old_chain));
+
+    // - The slot runs the new cell instead of the old one
+    let cell_ids = conductor.list_cell_ids().await.unwrap();
+    assert!(cell_ids.contains(&new_cell_id));
+    assert!(!cell_ids.contains(&old_cell_id));
+    let zome = SweetZome::new(new_cell_id.clone(), TestWasm::Create.into());
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+
+    // - The old cell is kept until the app is uninstalled
+    let state = conductor.get_state_from_handle().await.unwrap();
+    assert!(state.all_cells().contains(&old_cell_id));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_migrate_agent_refused() {
+    observability::test_run().ok();
+    let (old_saf, _) =
+        SweetSafFile::unique_from_test_wasms(vec![TestWasm::Create, TestWasm::MigrateAgentPass])
+            .await
+            .unwrap();
+    let (refusing_saf, _) =
+        SweetSafFile::unique_from_test_wasms(vec![TestWasm::Create, TestWasm::MigrateAgentFail])
+            .await
+            .unwrap();
+    let (new_saf, _) = SweetSafFile::unique_from_test_wasms(vec![TestWasm::Create])
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor
+        .setup_app("app", &[old_saf.clone()])
+        .await
+        .unwrap();
+    let old_cell_id = app.cells()[0].cell_id().clone();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+    conductor.register_saf(refusing_saf.clone()).await.unwrap();
+    conductor.register_saf(new_saf.clone()).await.unwrap();
+    let chain_before = read_chain(&conductor, &old_cell_id).await;
+
+    // - An agent can't migrate into its own chain
+    assert_matches!(
+        conductor
+            .inner_handle()
+            .migrate_agent(migrate_payload(&old_saf, &old_saf))
+            .await,
+        Err(ConductorError::MigrationTargetExists(_))
+    );
+
+    // - The open callback of the new SAF refuses the agent,
+    //   so neither chain is written to
+    assert_matches!(
+        conductor
+            .inner_handle()
+            .migrate_agent(migrate_payload(&old_saf, &refusing_saf))
+            .await,
+        Err(ConductorError::WorkflowError(
+            WorkflowError::MigrateAgentFailure(_, _)
+        ))
+    );
+    assert_eq!(read_chain(&conductor, &old_cell_id).await, chain_before);
+    assert!(conductor
+        .list_cell_ids()
+        .await
+        .unwrap()
+        .contains(&old_cell_id));
+
+    // - A SAF without migrate_agent callbacks lets the agent in
+    conductor
+        .inner_handle()
+        .migrate_agent(migrate_payload(&old_saf, &new_saf))
+        .await
+        .unwrap();
+
+    // - The close callback of the old SAF keeps the agent where it is
+    let app = conductor
+        .setup_app("refused", &[refusing_saf.clone()])
+        .await
+        .unwrap();
+    let refused_cell_id = app.cells()[0].cell_id().clone();
+    let chain_before = read_chain(&conductor, &refused_cell_id).await;
+    let payload = MigrateAgentPayload {
+        installed_app_id: "refused".into(),
+        ..migrate_payload(&refusing_saf, &new_saf)
+    };
+    assert_matches!(
+        conductor.inner_handle().migrate_agent(payload).await,
+        Err(ConductorError::WorkflowError(
+            WorkflowError::MigrateAgentFailure(_, _)
+        ))
+    );
+    assert_eq!(read_chain(&conductor, &refused_cell_id).await, chain_before);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_app_crypto_requests() {
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (saf, _) = mk_saf("saf", InlineZome::new_unique(Vec::new()))
+        .await
+        .unwrap();
+    let app = conductor.setup_app("app", &[saf]).await.unwrap();
+    let alice = app.agent().clone();
+    let api = RealAppInterfaceApi::new(conductor.inner_handle(), Default::default());
+    let token = conductor
+        .issue_app_authentication_token(IssueAppAuthenticationTokenPayload::for_installed_app_id(
+            "app".into(),
+        ))
+        .await
+        .unwrap()
+        .token;
+    assert_matches!(
+        api.handle_app_request(AppRequest::Authenticate { token })
+            .await,
+        AppResponse::Authenticated(_)
+    );
+    let crypto = |request| AppRequest::Crypto(Box::new(request));
+
+    // - An agent of the app can sign
+    let data = vec![1, 2, 3];
+    let response = api
+        .handle_app_request(crypto(CryptoRequest::Sign {
+            installed_app_id: "app".into(),
+            sign: Sign::new_raw(alice.clone(), data.clone()),
+        }))
+        .await;
+    let signature = match response {
+        AppResponse::Crypto(response) => match *response {
+            CryptoResponse::Signed(signature) => signature,
+            other => panic!("unexpected crypto response {:?}", other),
+        },
+        other => panic!("unexpected response {:?}", other),
+    };
+    assert!(alice.verify_signature_raw(&signature, &data).await.unwrap());
+
+    // - Any other agent is refused
+    let response = api
+        .handle_app_request(crypto(CryptoRequest::Sign {
+            installed_app_id: "app".into(),
+            sign: Sign::new_raw(fixt!(AgentPubKey), data.clone()),
+        }))
+        .await;
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::CryptoUnauthorized(_))
+    );
+
+    // - Encrypted data round trips through the keystore
+    //   with x25519 keys created for the agent of the app
+    let sender = conductor.keystore().create_x25519_keypair().await.unwrap();
+    let recipient = conductor.keystore().create_x25519_keypair().await.unwrap();
+    for key in [sender, recipient].iter() {
+        conductor
+            .inner_handle()
+            .add_x25519_key_agent(*key, alice.clone())
+            .await
+            .unwrap();
+    }
+    let message = XSalsa20Poly1305Data::from(b"hello".to_vec());
+    let response = api
+        .handle_app_request(crypto(CryptoRequest::Encrypt {
+            installed_app_id: "app".into(),
+            encrypt: X25519XSalsa20Poly1305Encrypt::new(sender, recipient, message.clone()),
+        }))
+        .await;
+    let encrypted_data = match response {
+        AppResponse::Crypto(response) => match *response {
+            CryptoResponse::Encrypted(encrypted_data) => encrypted_data,
+            other => panic!("unexpected crypto response {:?}", other),
+        },
+        other => panic!("unexpected response {:?}", other),
+    };
+    let response = api
+        .handle_app_request(crypto(CryptoRequest::Decrypt {
+            installed_app_id: "app".into(),
+            decrypt: X25519XSalsa20Poly1305Decrypt::new(
+                sender,
+                recipient,
+                encrypted_data.clone(),
+            ),
+        }))
+        .await;
+    let decrypted = CryptoResponse::Decrypted(Some(message.clone()));
+    assert_matches!(
+        response,
+        AppResponse::Crypto(response) if *response == decrypted
+    );
+
+    // - x25519 keys which weren't created for an agent of the app are refused
+    let unrecorded = conductor.keystore().create_x25519_keypair().await.unwrap();
+    let other_agents_key = conductor.keystore().create_x25519_keypair().await.unwrap();
+    conductor
+        .inner_handle()
+        .add_x25519_key_agent(other_agents_key, fixt!(AgentPubKey))
+        .await
+        .unwrap();
+    for key in [unrecorded, other_agents_key].iter() {
+        let response = api
+            .handle_app_request(crypto(CryptoRequest::Encrypt {
+                installed_app_id: "app".into(),
+                encrypt: X25519XSalsa20Poly1305Encrypt::new(*key, recipient, message.clone()),
+            }))
+            .await;
+        assert_matches!(
+            response,
+            AppResponse::Error(ExternalApiWireError::CryptoUnauthorized(_))
+        );
+        let response = api
+            .handle_app_request(crypto(CryptoRequest::Decrypt {
+                installed_app_id: "app".into(),
+                decrypt: X25519XSalsa20Poly1305Decrypt::new(
+                    sender,
+                    *key,
+                    encrypted_data.clone(),
+                ),
+            }))
+            .await;
+        assert_matches!(
+            response,
+            AppResponse::Error(ExternalApiWireError::CryptoUnauthorized(_))
+        );
+    }
+
+    // - Uninstalling the app forgets the x25519 keys of its agent,
+    //   but not those of other agents
+    let handle = conductor.inner_handle();
+    handle.uninstall_app(&"app".to_string()).await.unwrap();
+    for key in [sender, recipient].iter() {
+        assert_eq!(handle.get_x25519_key_agent(key).await.unwrap(), None);
+    }
+    assert!(handle
+        .get_x25519_key_agent(&other_agents_key)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_app_interface_authentication() {
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (saf, _) = mk_saf("saf", InlineZome::new_unique(Vec::new()))
+        .await
+        .unwrap();
+    let app = conductor.setup_app("app", &[saf.clone()]).await.unwrap();
+    let other_app = conductor.setup_app("other", &[saf]).await.unwrap();
+    let api = RealAppInterfaceApi::new(conductor.inner_handle(), Default::default());
+    let issue = |installed_app_id: &str, expiry_seconds| {
+        conductor.issue_app_authentication_token(IssueAppAuthenticationTokenPayload {
+            installed_app_id: installed_app_id.into(),
+            expiry_seconds,
+        })
+    };
+    let app_info = |installed_app_id: &str| AppRequest::AppInfo {
+        installed_app_id: installed_app_id.into(),
+    };
+
+    // - Tokens can't be issued for apps which aren't installed
+    assert_matches!(
+        issue("missing", 30).await,
+        Err(ConductorError::AppNotInstalled(_))
+    );
+
+    // - Nothing but authentication is allowed before authenticating
+    assert_matches!(
+        api.handle_app_request(app_info("app")).await,
+        AppResponse::Error(ExternalApiWireError::AuthenticationFailed(_))
+    );
+
+    // - Expired tokens are refused
+    let expired = issue("app", 0).await.unwrap().token;
+    assert_matches!(
+        api.handle_app_request(AppRequest::Authenticate { token: expired })
+            .await,
+        AppResponse::Error(ExternalApiWireError::AuthenticationFailed(_))
+    );
+
+    // - A token authenticates the connection as its app, only once
+    let token = issue("app", 30).await.unwrap().token;
+    assert_matches!(
+        api.handle_app_request(AppRequest::Authenticate {
+            token: token.clone()
+        })
+        .await,
+        AppResponse::Authenticated(id) if id == "app"
+    );
+    assert_matches!(
+        api.handle_app_request(AppRequest::Authenticate { token })
+            .await,
+        AppResponse::Error(ExternalApiWireError::AuthenticationFailed(_))
+    );
+    assert_matches!(
+        api.handle_app_request(app_info("app")).await,
+        AppResponse::AppInfo(Some(_))
+    );
+
+    // - The connection can't reach the cells or info of other apps
+    assert_matches!(
+        api.handle_app_request(app_info("other")).await,
+        AppResponse::Error(ExternalApiWireError::AppUnauthorized(_))
+    );
+    let zome_call = |cell: &SweetCell| {
+        AppRequest::ZomeCall(Box::new(ZomeCall {
+            cell_id: cell.cell_id().clone(),
+            zome_name: "zome1".into(),
+            fn_name: "any".into(),
+            payload: ExternIO::encode(()).unwrap(),
+            cap: None,
+            provenance: cell.agent_pubkey().clone(),
+        }))
+    };
+    assert_matches!(
+        api.handle_app_request(zome_call(&other_app.cells()[0]))
+            .await,
+        AppResponse::Error(ExternalApiWireError::AppUnauthorized(_))
+    );
+    let uninstalled = |installed_app_id: &str| {
+        Signal::from(SystemSignal::AppUninstalled {
+            installed_app_id: installed_app_id.into(),
+        })
+    };
+    assert!(!api.allows_signal(&uninstalled("other")).await);
+
+    // - The connection stops reaching the app's cells once it is uninstalled
+    conductor
+        .inner_handle()
+        .uninstall_app(&"app".to_string())
+        .await
+        .unwrap();
+    assert!(api.allows_signal(&uninstalled("app")).await);
+    assert_matches!(
+        api.handle_app_request(zome_call(&app.cells()[0])).await,
+        AppResponse::Error(ExternalApiWireError::AppUnauthorized(_))
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unix_socket_interfaces() {
+    use aingle_websocket::connect_unix;
+    use aingle_websocket::WebsocketConfig;
+    use std::os::unix::fs::PermissionsExt;
+
+    observability::test_run().ok();
+    let tmpdir = tempdir::TempDir::new("unix_socket_interfaces").unwrap();
+    let admin_path = tmpdir.path().join("admin.sock");
+    let app_path = tmpdir.path().join("app.sock");
+    let mut conductor = SweetConductor::from_config(ConductorConfig {
+        admin_interfaces: Some(vec![AdminInterfaceConfig {
+            driver: InterfaceDriver::UnixSocket {
+                path: admin_path.clone(),
+                mode: None,
+            },
+        }]),
+        ..standard_config()
+    })
+    .await;
+    let (saf, _) = mk_saf("saf", InlineZome::new_unique(Vec::new()))
+        .await
+        .unwrap();
+    conductor.setup_app("app", &[saf]).await.unwrap();
+    let mode =
+        |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    let config = Arc::new(WebsocketConfig::default());
+
+    // - The admin interface is only accessible to the conductor's user
+    assert_eq!(mode(&admin_path), 0o600);
+    let (mut admin_tx, _) = connect_unix(&admin_path, config.clone()).await.unwrap();
+
+    // - App interfaces can be attached on a socket too,
+    //   whose permissions can be chosen, and which is listed by its path
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::AttachAppUnixSocketInterface {
+            path: app_path.clone(),
+            mode: Some(0o660),
+        })
+        .await
+        .unwrap();
+    assert_matches!(
+        response,
+        AdminResponse::AppUnixSocketInterfaceAttached { path } if path == app_path
+    );
+    assert_eq!(mode(&app_path), 0o660);
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::ListAppInterfaces)
+        .await
+        .unwrap();
+    let app_driver = InterfaceDriver::UnixSocket {
+        path: app_path.clone(),
+        mode: Some(0o660),
+    };
+    assert_matches!(
+        response,
+        AdminResponse::AppInterfacesListed(drivers) if drivers == vec![app_driver]
+    );
+
+    // - Both interfaces are bound again, over their old sockets, after a restart
+    conductor.shutdown().await;
+    conductor.startup().await;
+    assert_eq!(mode(&app_path), 0o660);
+    let (mut admin_tx, _) = connect_unix(&admin_path, config.clone()).await.unwrap();
+
+    // - Connections to an app socket still authenticate with an issued token
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::IssueAppAuthenticationToken(
+            IssueAppAuthenticationTokenPayload::for_installed_app_id("app".into()),
+        ))
+        .await
+        .unwrap();
+    let token = match response {
+        AdminResponse::AppAuthenticationTokenIssued(issued) => issued.token,
+        other => panic!("Couldn't issue an app token: {:?}", other),
+    };
+    let (mut app_tx, _) = connect_unix(&app_path, config).await.unwrap();
+    let response: AppResponse = app_tx
+        .request(AppRequest::Authenticate { token })
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::Authenticated(id) if id == "app");
+    let response: AppResponse = app_tx
+        .request(AppRequest::AppInfo {
+            installed_app_id: "app".into(),
+        })
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::AppInfo(Some(_)));
+}
+
+/// Wait for the next system signal about the given cell
+async fn next_signal_about_cell(
+    signals: &mut (impl tokio_stream::Stream<Item = SystemSignal> + Unpin),
+    cell_id: &CellId,
+) -> SystemSignal {
+    use tokio_stream::StreamExt;
+    loop {
+        let signal = tokio::time::timeout(std::time::Duration::from_secs(10), signals.next())
+            .await
+            .expect("timed out waiting for a system signal")
+            .unwrap();
+        if signal.cell_id() == Some(cell_id) {
+            return signal;
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_system_signals() {
+    use tokio_stream::StreamExt;
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let mut signals = Box::pin(conductor.signals().filter_map(|signal| match signal {
+        Signal::System(signal) => Some(signal),
+        Signal::App(_, _) => None,
+    }));
+    let (saf, _) = mk_saf("saf", InlineZome::new_unique(Vec::new()))
+        .await
+        .unwrap();
+    let app = conductor.setup_app("app", &[saf]).await.unwrap();
+    let cell_id = app.cells()[0].cell_id().clone();
+
+    // - The cell is reported active and its genesis ops get validated
+    let (mut active, mut validated) = (false, false);
+    while !(active && validated) {
+        match next_signal_about_cell(&mut signals, &cell_id).await {
+            SystemSignal::CellStatusChanged { status, .. } => {
+                assert_eq!(status, CellStatus::Active);
+                active = true;
+            }
+            SystemSignal::OpsValidated { .. } => validated = true,
+            _ => (),
+        }
+    }
+
+    // - Deactivating the app reports the cell inactive
+    conductor
+        .deactivate_app("app".to_string(), DeactivationReason::Normal)
+        .await
+        .unwrap();
+    loop {
+        if let SystemSignal::CellStatusChanged { status, .. } =
+            next_signal_about_cell(&mut signals, &cell_id).await
+        {
+            assert_eq!(status, CellStatus::Inactive);
+            break;
+        }
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_signing_error_during_genesis() {
     observability::test_run().ok();
@@ -525,8 +1594,8 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
     let (cell1,) = app1.into_tuple();
     let (cell2,) = app2.into_tuple();
 
-    let app_port = conductor.inner_handle().add_app_interface(0).await.unwrap();
-    let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut app_client_1, _) = conductor.app_ws_client("app1").await;
+    let (mut app_client_2, _) = conductor.app_ws_client("app2").await;
     let (mut admin_client, _) = conductor.admin_ws_client().await;
 
     // Now use the bad keystore to cause a signing error on the next zome call
@@ -547,7 +1616,7 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
 
     // TODO: match the errors more tightly
     assert_matches!(response, AdminResponse::Error(_));
-    let response = make_signing_call(&mut app_client, &cell2).await;
+    let response = make_signing_call(&mut app_client_2, &cell2).await;
     dbg!(&response);
 
     assert_matches!(response, AppResponse::Error(_));
@@ -555,10 +1624,10 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
     // Go back to the good keystore, see if we can proceed
     todo!("switch keystore to always-correct mode");
 
-    let response = make_signing_call(&mut app_client, &cell2).await;
+    let response = make_signing_call(&mut app_client_2, &cell2).await;
     assert_matches!(response, AppResponse::ZomeCall(_));
 
-    let response = make_signing_call(&mut app_client, &cell1).await;
+    let response = make_signing_call(&mut app_client_1, &cell1).await;
     assert_matches!(response, AppResponse::ZomeCall(_));
 
     // conductor
@@ -0,0 +1,194 @@
+//! The key registry used to check whether an agent's key was valid
+//! at the time it signed something.
+//!
+//! A conductor configured with a [DpkiConfig] designates an installed app as
+//! its DPKI service, which is asked about keys through a [DpkiKeyRegistry].
+//! Any other registry can be passed to
+//! [ConductorBuilder::with_key_registry](super::ConductorBuilder::with_key_registry)
+//! instead. Without a registry every key is treated as valid.
+
+use super::handle::ConductorHandleT;
+use super::ConductorHandle;
+use aingle_conductor_api::config::DpkiConfig;
+use aingle_conductor_api::ZomeCall;
+use aingle_types::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Weak;
+use thiserror::Error;
+
+/// The zome in the DPKI app which answers [KeyRegistry] requests
+pub const DPKI_ZOME_NAME: &str = "dpki";
+
+/// The function in [DPKI_ZOME_NAME] which is called with an [AgentPubKey]
+/// and returns its [KeyState]
+pub const DPKI_KEY_STATE_FN: &str = "key_state";
+
+/// A shared reference to a [KeyRegistry]
+pub type KeyRegistryRef = Arc<dyn KeyRegistry>;
+
+/// The lifecycle state of a key as known to a [KeyRegistry]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum KeyState {
+    /// The key has never been revoked or rotated
+    Valid,
+    /// The key was revoked at this time
+    Revoked(Timestamp),
+    /// The key was replaced by a new key at this time
+    Rotated {
+        /// The key that replaced this one
+        new_key: AgentPubKey,
+        /// When the rotation happened
+        at: Timestamp,
+    },
+}
+
+impl KeyState {
+    /// The time after which signatures by this key are no longer valid, if any
+    pub fn invalidated_at(&self) -> Option<Timestamp> {
+        match self {
+            KeyState::Valid => None,
+            KeyState::Revoked(at) | KeyState::Rotated { at, .. } => Some(*at),
+        }
+    }
+
+    /// Was the key still valid at this time?
+    pub fn is_valid_at(&self, timestamp: Timestamp) -> bool {
+        self.invalidated_at().map_or(true, |at| timestamp < at)
+    }
+}
+
+#[derive(Error, Debug)]
+/// Errors returned by a [KeyRegistry]
+pub enum KeyRegistryError {
+    /// The registry couldn't answer the request
+    #[error("The key registry is unavailable: {0}")]
+    Unavailable(String),
+}
+
+/// Result type for [KeyRegistry] requests
+pub type KeyRegistryResult<T> = Result<T, KeyRegistryError>;
+
+/// A source of truth for which agent keys have been revoked or rotated
+#[async_trait::async_trait]
+pub trait KeyRegistry: Send + Sync {
+    /// Get the current state of this key
+    async fn key_state(&self, key: &AgentPubKey) -> KeyRegistryResult<KeyState>;
+
+    /// Should keys in ops validated by this cell be checked against the registry?
+    /// A registry backed by a cell can't check that cell's own ops
+    /// because answering would need the cell to validate again.
+    async fn checks_cell(&self, _cell_id: &CellId) -> KeyRegistryResult<bool> {
+        Ok(true)
+    }
+}
+
+/// A [KeyRegistry] held in memory, useful for tests
+#[derive(Default)]
+pub struct InMemoryKeyRegistry(parking_lot::RwLock<HashMap<AgentPubKey, KeyState>>);
+
+impl InMemoryKeyRegistry {
+    /// Create an empty registry where every key is valid
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Revoke this key from this time onwards
+    pub fn revoke(&self, key: AgentPubKey, at: Timestamp) {
+        self.0.write().insert(key, KeyState::Revoked(at));
+    }
+
+    /// Replace this key with a new key from this time onwards
+    pub fn rotate(&self, key: AgentPubKey, new_key: AgentPubKey, at: Timestamp) {
+        self.0
+            .write()
+            .insert(key, KeyState::Rotated { new_key, at });
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyRegistry for InMemoryKeyRegistry {
+    async fn key_state(&self, key: &AgentPubKey) -> KeyRegistryResult<KeyState> {
+        Ok(self.0.read().get(key).cloned().unwrap_or(KeyState::Valid))
+    }
+}
+
+/// A [KeyRegistry] backed by the DPKI app named in the conductor's [DpkiConfig].
+/// Each request calls [DPKI_KEY_STATE_FN] on the app's first cell.
+pub struct DpkiKeyRegistry {
+    installed_app_id: InstalledAppId,
+    conductor_handle: parking_lot::RwLock<Option<Weak<dyn ConductorHandleT>>>,
+}
+
+impl DpkiKeyRegistry {
+    /// Create a registry for the app named in this config.
+    /// It can't answer requests until it has a conductor handle.
+    pub fn new(config: &DpkiConfig) -> Self {
+        Self {
+            installed_app_id: config.instance_id.clone(),
+            conductor_handle: parking_lot::RwLock::new(None),
+        }
+    }
+
+    /// Point the registry at the conductor running the DPKI app.
+    /// Only a weak reference is kept because the conductor holds the registry.
+    pub fn set_conductor_handle(&self, conductor_handle: &ConductorHandle) {
+        *self.conductor_handle.write() = Some(Arc::downgrade(conductor_handle));
+    }
+
+    fn conductor_handle(&self) -> KeyRegistryResult<ConductorHandle> {
+        self.conductor_handle
+            .read()
+            .as_ref()
+            .and_then(Weak::upgrade)
+            .ok_or_else(|| KeyRegistryError::Unavailable("There is no conductor".to_string()))
+    }
+
+    async fn dpki_cell_id(&self, conductor_handle: &ConductorHandle) -> KeyRegistryResult<CellId> {
+        Ok(conductor_handle
+            .get_app_info(&self.installed_app_id)
+            .await
+            .map_err(unavailable)?
+            .and_then(|app| app.cell_data.into_iter().next())
+            .ok_or_else(|| {
+                KeyRegistryError::Unavailable(format!(
+                    "The DPKI app {} has no cells",
+                    self.installed_app_id
+                ))
+            })?
+            .into_id())
+    }
+}
+
+fn unavailable(e: impl std::fmt::Display) -> KeyRegistryError {
+    KeyRegistryError::Unavailable(e.to_string())
+}
+
+#[async_trait::async_trait]
+impl KeyRegistry for DpkiKeyRegistry {
+    async fn key_state(&self, key: &AgentPubKey) -> KeyRegistryResult<KeyState> {
+        let conductor_handle = self.conductor_handle()?;
+        let cell_id = self.dpki_cell_id(&conductor_handle).await?;
+        let response = conductor_handle
+            .call_zome(ZomeCall {
+                cell_id: cell_id.clone(),
+                zome_name: DPKI_ZOME_NAME.into(),
+                fn_name: DPKI_KEY_STATE_FN.into(),
+                payload: ExternIO::encode(key).map_err(unavailable)?,
+                cap: None,
+                provenance: cell_id.agent_pubkey().clone(),
+            })
+            .await
+            .map_err(unavailable)?
+            .map_err(unavailable)?;
+        match response {
+            ZomeCallResponse::Ok(output) => output.decode().map_err(unavailable),
+            response => Err(KeyRegistryError::Unavailable(format!("{:?}", response))),
+        }
+    }
+
+    async fn checks_cell(&self, cell_id: &CellId) -> KeyRegistryResult<bool> {
+        let conductor_handle = self.conductor_handle()?;
+        Ok(self.dpki_cell_id(&conductor_handle).await? != *cell_id)
+    }
+}
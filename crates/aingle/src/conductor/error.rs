@@ -33,6 +33,9 @@ pub enum ConductorError {
     #[error("Cell is not initialized.")]
     CellNotInitialized,
 
+    #[error("An agent can't migrate into an existing Cell. CellId: {0:?}")]
+    MigrationTargetExists(CellId),
+
     #[error("Cell was referenced, but is missing from the conductor. CellId: {0:?}")]
     CellMissing(CellId),
 
@@ -94,6 +97,18 @@ pub enum ConductorError {
     #[error("Tried to perform an operation on an app that was not active: {0}")]
     AppNotActive(InstalledAppId),
 
+    #[error("The SAF bundle is not signed by any trusted publisher")]
+    UntrustedSafPublisher,
+
+    #[error("{0} carries no publisher signature, so it is refused while only trusted publishers are accepted")]
+    UnsignedSourceRefused(String),
+
+    #[error(transparent)]
+    BundleSignatureError(#[from] BundleSignatureError),
+
+    #[error("App interface authentication failed: {0}")]
+    AppAuthenticationFailed(String),
+
     #[error(transparent)]
     AIngleP2pError(#[from] aingle_p2p::AIngleP2pError),
 
@@ -115,6 +130,12 @@ pub enum ConductorError {
     #[error(transparent)]
     StateMutationError(#[from] aingle_state::mutations::StateMutationError),
 
+    #[error(transparent)]
+    SourceChainError(#[from] aingle_state::source_chain::SourceChainError),
+
+    #[error(transparent)]
+    SourceChainArchiveError(#[from] SourceChainArchiveError),
+
     #[error(transparent)]
     RusqliteError(#[from] rusqlite::Error),
 }
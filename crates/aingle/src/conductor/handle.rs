@@ -40,28 +40,44 @@
 //! code which interacted with the Conductor would also have to be highly generic.
 
 use super::api::error::ConductorApiResult;
+use super::api::CellConductorApi;
 use super::api::ZomeCall;
+use super::conductor::export_source_chain;
+use super::conductor::running_cell_metrics;
+use super::conductor::StateGeneration;
 use super::config::AdminInterfaceConfig;
+use super::config::InterfaceDriver;
+use super::dpki::KeyRegistryRef;
+use super::error::ConductorError;
 use super::error::ConductorResult;
 use super::error::CreateAppError;
+use super::interface::error::InterfaceError;
 use super::interface::SignalBroadcaster;
 use super::manager::TaskManagerRunHandle;
 use super::p2p_agent_store::get_agent_info_signed;
 use super::p2p_agent_store::put_agent_info_signed;
 use super::p2p_agent_store::query_agent_info_signed;
+use super::state::AppInterfaceId;
 use super::Cell;
 use super::Conductor;
 use crate::conductor::p2p_metrics::put_metric_datum;
 use crate::conductor::p2p_metrics::query_metrics;
+use crate::core::workflow::genesis_checks;
 use crate::core::workflow::ZomeCallResult;
 use crate::core::{queue_consumer::InitialQueueTriggers, ribosome::real_ribosome::RealRibosome};
 use derive_more::From;
 use futures::future::FutureExt;
 use futures::StreamExt;
+use aingle_conductor_api::AdminSignal;
+use aingle_conductor_api::AppAuthenticationToken;
+use aingle_conductor_api::AppAuthenticationTokenIssued;
 use aingle_conductor_api::AppStatusFilter;
+use aingle_conductor_api::CellMetrics;
 use aingle_conductor_api::InstalledAppInfo;
+use aingle_conductor_api::IssueAppAuthenticationTokenPayload;
 use aingle_p2p::event::AIngleP2pEvent;
 use aingle_p2p::event::AIngleP2pEvent::*;
+use aingle_p2p::AgentPubKeyExt as _;
 use aingle_p2p::SafHashExt;
 use aingle_p2p::AIngleP2pCellT;
 use aingle_state::host_fn_workspace::HostFnWorkspace;
@@ -69,6 +85,7 @@ use aingle_types::prelude::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p::KitsuneSpace;
 use kitsune_p2p_types::config::JOIN_NETWORK_TIMEOUT;
+use std::path::PathBuf;
 use std::{collections::HashSet, sync::Arc};
 use tokio::sync::RwLock;
 use tracing::*;
@@ -110,8 +127,16 @@ pub trait ConductorHandleT: Send + Sync {
     /// Add an app interface
     async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16>;
 
-    /// List the app interfaces currently install.
-    async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>>;
+    /// Add an app interface on a Unix domain socket at the given path,
+    /// whose file has the permissions `mode`, if given
+    async fn add_app_unix_socket_interface(
+        self: Arc<Self>,
+        path: PathBuf,
+        mode: Option<u32>,
+    ) -> ConductorResult<()>;
+
+    /// List the drivers of the app interfaces currently installed.
+    async fn list_app_interfaces(&self) -> ConductorResult<Vec<InterfaceDriver>>;
 
     /// Install a [Saf] in this Conductor
     async fn register_saf(&self, saf: SafFile) -> ConductorResult<()>;
@@ -171,14 +196,51 @@ pub trait ConductorHandleT: Send + Sync {
     /// Request access to this conductor's networking handle
     fn aingle_p2p(&self) -> &aingle_p2p::AIngleP2pRef;
 
+    /// The longest a single call to the `sleep` host function may suspend a zome call for
+    fn max_sleep_per_call(&self) -> std::time::Duration;
+
+    /// How many times the conductor state has been written.
+    /// Anything read from the state, like the Cells of an App, may have
+    /// changed since this last changed.
+    fn state_generation(&self) -> u64;
+
+    /// Whether incoming ops from agents with a warrant against them are dropped
+    fn block_warranted_agents(&self) -> bool;
+
+    /// The registry used to check whether author keys were valid, if any
+    fn key_registry(&self) -> Option<KeyRegistryRef>;
+
+    /// Whether only bundles signed by a trusted publisher may be installed
+    fn requires_trusted_publisher(&self) -> bool;
+
+    /// Check that a SAF bundle is signed by a trusted publisher, if the
+    /// conductor requires one, and only let it resolve the resources its
+    /// signatures cover
+    async fn verify_saf_bundle(&self, bundle: SafBundle) -> ConductorResult<SafBundle>;
+
     /// Create a new Cell in an existing App based on an existing SAF
     async fn create_clone_cell(
         self: Arc<Self>,
         payload: CreateCloneCellPayload,
     ) -> ConductorResult<CellId>;
 
-    /// Destroy a cloned Cell
-    async fn destroy_clone_cell(self: Arc<Self>, cell_id: CellId) -> ConductorResult<()>;
+    /// Stop a cloned Cell and archive it, keeping its data
+    async fn archive_clone_cell(&self, payload: ArchiveCloneCellPayload) -> ConductorResult<()>;
+
+    /// Delete all archived clone Cells of a slot along with their data
+    async fn delete_archived_clone_cells(
+        &self,
+        payload: DeleteArchivedCloneCellsPayload,
+    ) -> ConductorResult<()>;
+
+    /// Migrate the agent of an app slot to a new SAF: close its chain in the
+    /// slot's provisioned cell, open it in a new cell with the app state
+    /// carried forward, and run the new cell in the slot instead.
+    /// Returns the new CellId.
+    async fn migrate_agent(
+        self: Arc<Self>,
+        payload: MigrateAgentPayload,
+    ) -> ConductorResult<CellId>;
 
     /// Install Cells into ConductorState based on installation info, and run
     /// genesis on all new source chains
@@ -194,7 +256,8 @@ pub trait ConductorHandleT: Send + Sync {
         payload: InstallAppBundlePayload,
     ) -> ConductorResult<InactiveApp>;
 
-    /// Uninstall an app from the state DB and remove all running Cells
+    /// Uninstall an app from the state DB, remove its running Cells and
+    /// delete the databases of any Cells and SAFs no other app uses
     async fn uninstall_app(&self, app: &InstalledAppId) -> ConductorResult<()>;
 
     /// Setup the cells from the database
@@ -232,16 +295,81 @@ pub trait ConductorHandleT: Send + Sync {
     /// Dump the cells state
     async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String>;
 
+    /// Collect structured metrics for a running cell,
+    /// or for every running cell if `cell_id` is `None`
+    async fn get_cell_metrics(
+        &self,
+        cell_id: Option<CellId>,
+    ) -> ConductorApiResult<Vec<CellMetrics>>;
+
+    /// Spawn a task sending [CellMetrics] snapshots of every running cell as
+    /// [AdminSignal]s on `signal_tx`, until aborted or nothing is listening
+    async fn subscribe_cell_metrics(
+        &self,
+        interval: std::time::Duration,
+        signal_tx: tokio::sync::broadcast::Sender<AdminSignal>,
+    ) -> ConductorResult<tokio::task::JoinHandle<()>>;
+
+    /// Export a running cell's source chain from `from_seq` onwards,
+    /// as an archive signed by the cell's agent
+    async fn export_source_chain(
+        &self,
+        cell_id: &CellId,
+        from_seq: u32,
+    ) -> ConductorResult<SourceChainArchive>;
+
+    /// Verify a source chain archive and write it to its cell's database as
+    /// authored ops. An archive of a whole chain must pass the SAF's genesis
+    /// checks again and can only restore into an empty cell, while a partial
+    /// one must extend the existing chain.
+    async fn import_source_chain(
+        self: Arc<Self>,
+        archive: SourceChainArchive,
+    ) -> ConductorResult<()>;
+
     /// Access the broadcast Sender which will send a Signal across every
     /// attached app interface
     async fn signal_broadcaster(&self) -> SignalBroadcaster;
 
+    /// Issue a short-lived, single use token which an app interface
+    /// connection can authenticate with to act for the given App
+    async fn issue_app_authentication_token(
+        &self,
+        payload: IssueAppAuthenticationTokenPayload,
+    ) -> ConductorResult<AppAuthenticationTokenIssued>;
+
+    /// Use up an issued token, returning the App it gives access to
+    async fn authenticate_app_token(
+        &self,
+        token: &AppAuthenticationToken,
+    ) -> ConductorResult<InstalledAppId>;
+
     /// Get info about an installed App, whether active or inactive
     async fn get_app_info(
         &self,
         installed_app_id: &InstalledAppId,
     ) -> ConductorResult<Option<InstalledAppInfo>>;
 
+    /// Get info about the active App which contains this Cell
+    async fn find_app_containing_cell(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<Option<InstalledAppInfo>>;
+
+    /// Record the agent an x25519 keypair in the keystore was created for
+    async fn add_x25519_key_agent(
+        &self,
+        key: X25519PubKey,
+        agent: AgentPubKey,
+    ) -> ConductorResult<()>;
+
+    /// Get the agent an x25519 keypair in the keystore was created for,
+    /// if it was recorded
+    async fn get_x25519_key_agent(
+        &self,
+        key: &X25519PubKey,
+    ) -> ConductorResult<Option<AgentPubKey>>;
+
     /// Add signed agent info to the conductor
     async fn add_agent_infos(&self, agent_infos: Vec<AgentInfoSigned>) -> ConductorApiResult<()>;
 
@@ -295,6 +423,12 @@ pub struct ConductorHandleImpl<DS: SafStore + 'static> {
     pub(crate) conductor: RwLock<Conductor<DS>>,
     pub(crate) keystore: KeystoreSender,
     pub(crate) aingle_p2p: aingle_p2p::AIngleP2pRef,
+    pub(crate) max_sleep_per_call: std::time::Duration,
+    pub(crate) state_generation: StateGeneration,
+    pub(crate) block_warranted_agents: bool,
+    pub(crate) trusted_publishers: Option<Vec<AgentPubKey>>,
+    pub(crate) bundle_resources: mr_bundle::RemoteConfig,
+    pub(crate) key_registry: Option<KeyRegistryRef>,
 }
 
 #[async_trait::async_trait]
@@ -330,11 +464,32 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
 
     async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16> {
         let mut lock = self.conductor.write().await;
-        lock.add_app_interface_via_handle(either::Left(port), self.clone())
-            .await
+        lock.add_app_interface_via_handle(
+            AppInterfaceId::new(port),
+            InterfaceDriver::Websocket { port },
+            self.clone(),
+        )
+        .await?
+        .port()
+        .ok_or_else(|| Box::new(InterfaceError::PortError).into())
     }
 
-    async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>> {
+    async fn add_app_unix_socket_interface(
+        self: Arc<Self>,
+        path: PathBuf,
+        mode: Option<u32>,
+    ) -> ConductorResult<()> {
+        let mut lock = self.conductor.write().await;
+        lock.add_app_interface_via_handle(
+            AppInterfaceId::unix_socket(&path),
+            InterfaceDriver::UnixSocket { path, mode },
+            self.clone(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn list_app_interfaces(&self) -> ConductorResult<Vec<InterfaceDriver>> {
         self.conductor.read().await.list_app_interfaces().await
     }
 
@@ -397,10 +552,31 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
                 ..
             } => {
                 // TODO: This read lock isn't needed to get the p2p_env.
-                let env = { self.conductor.read().await.p2p_env(space) };
-                let res = put_agent_info_signed(env, agent_info_signed)
-                    .await
-                    .map_err(aingle_p2p::AIngleP2pError::other);
+                let (env, mut signal_tx) = {
+                    let lock = self.conductor.read().await;
+                    (lock.p2p_env(space), lock.signal_broadcaster())
+                };
+                let agent = AgentPubKey::from_kitsune(&agent_info_signed.agent);
+                let res = put_agent_info_signed(env, agent_info_signed).await;
+                if let Ok(is_new) = &res {
+                    // The space stores a cell's own agent info whenever it
+                    // (re)joins, including on the retries after a failed join.
+                    if agent == *cell_id.agent_pubkey()
+                        && self.conductor.read().await.mark_cell_joined(cell_id)
+                    {
+                        signal_tx.send_system(SystemSignal::CellStatusChanged {
+                            cell_id: cell_id.clone(),
+                            status: CellStatus::Active,
+                        });
+                    }
+                    if *is_new {
+                        signal_tx.send_system(SystemSignal::PeerJoined {
+                            saf_hash: cell_id.saf_hash().clone(),
+                            agent,
+                        });
+                    }
+                }
+                let res = res.map(|_| ()).map_err(aingle_p2p::AIngleP2pError::other);
                 respond.respond(Ok(async move { res }.boxed().into()));
             }
             GetAgentInfoSigned {
@@ -507,6 +683,44 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         &self.aingle_p2p
     }
 
+    fn max_sleep_per_call(&self) -> std::time::Duration {
+        self.max_sleep_per_call
+    }
+
+    fn state_generation(&self) -> u64 {
+        self.state_generation.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    fn block_warranted_agents(&self) -> bool {
+        self.block_warranted_agents
+    }
+
+    fn key_registry(&self) -> Option<KeyRegistryRef> {
+        self.key_registry.clone()
+    }
+
+    fn requires_trusted_publisher(&self) -> bool {
+        self.trusted_publishers.is_some()
+    }
+
+    async fn verify_saf_bundle(&self, bundle: SafBundle) -> ConductorResult<SafBundle> {
+        let trusted_publishers = match &self.trusted_publishers {
+            Some(trusted_publishers) => trusted_publishers,
+            None => return Ok(bundle),
+        };
+        let bundle = bundle.into_inner();
+        let publishers = verified_publishers(&bundle).await?;
+        if !publishers.iter().any(|p| trusted_publishers.contains(p)) {
+            return Err(ConductorError::UntrustedSafPublisher);
+        }
+        // The signatures only vouch for bundled resources and the hashes
+        // of pinned ones, so nothing else may be resolved
+        Ok(bundle
+            .with_remote_config(self.bundle_resources.clone())
+            .with_content_addressed_only(true)
+            .into())
+    }
+
     async fn create_clone_cell(
         self: Arc<Self>,
         payload: CreateCloneCellPayload,
@@ -519,24 +733,126 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             slot_id,
             membrane_proof,
         } = payload;
-        {
-            let conductor = self.conductor.read().await;
-            let cell_id = CellId::new(saf_hash, agent_key);
-            let cells = vec![(cell_id.clone(), membrane_proof)];
-            conductor.genesis_cells(cells, self.clone()).await?;
+        let genesis_cell_id = CellId::new(saf_hash, agent_key);
+        let result = async {
+            {
+                let conductor = self.conductor.read().await;
+                let cells = vec![(genesis_cell_id.clone(), membrane_proof)];
+                conductor.genesis_cells(cells, self.clone()).await?;
+            }
+            {
+                let mut conductor = self.conductor.write().await;
+                let properties = properties.unwrap_or_else(|| ().into());
+                conductor
+                    .add_clone_cell_to_app(&installed_app_id, &slot_id, properties)
+                    .await
+            }
         }
-        {
+        .await;
+        if result.is_err() {
+            // Don't leave the clone's database behind when it wasn't added
+            if let Err(e) = self
+                .conductor
+                .read()
+                .await
+                .remove_cell_envs(vec![genesis_cell_id])
+                .await
+            {
+                error!(?e, "Failed to remove the database of a clone cell which wasn't created");
+            }
+        }
+        result
+    }
+
+    async fn archive_clone_cell(&self, payload: ArchiveCloneCellPayload) -> ConductorResult<()> {
+        let ArchiveCloneCellPayload {
+            installed_app_id,
+            slot_id,
+            cell_id,
+        } = payload;
+        self.conductor
+            .write()
+            .await
+            .archive_clone_cell_in_db(&installed_app_id, &slot_id, &cell_id)
+            .await?;
+        self.remove_cells(vec![cell_id]).await;
+        Ok(())
+    }
+
+    async fn delete_archived_clone_cells(
+        &self,
+        payload: DeleteArchivedCloneCellsPayload,
+    ) -> ConductorResult<()> {
+        let DeleteArchivedCloneCellsPayload {
+            installed_app_id,
+            slot_id,
+        } = payload;
+        let (cell_ids, mut saf_hashes) = {
             let mut conductor = self.conductor.write().await;
-            let properties = properties.unwrap_or_else(|| ().into());
-            let cell_id = conductor
-                .add_clone_cell_to_app(&installed_app_id, &slot_id, properties)
+            let cell_ids = conductor
+                .delete_archived_clone_cells_in_db(&installed_app_id, &slot_id)
                 .await?;
-            Ok(cell_id)
+            // Clones usually have a SAF of their own which nothing else uses
+            let safs_in_use = conductor.get_state().await?.all_safs();
+            let saf_hashes: HashSet<_> = cell_ids
+                .iter()
+                .map(|cell_id| cell_id.saf_hash().clone())
+                .filter(|saf_hash| !safs_in_use.contains(saf_hash))
+                .collect();
+            (cell_ids, saf_hashes)
+        };
+        // Clones which didn't stop when they were archived are stopped again
+        let stopped = self.remove_cells(cell_ids.clone()).await;
+        for cell_id in cell_ids.iter().filter(|cell_id| !stopped.contains(cell_id)) {
+            saf_hashes.remove(cell_id.saf_hash());
         }
+        let mut conductor = self.conductor.write().await;
+        conductor.remove_cell_envs(stopped).await?;
+        conductor
+            .remove_safs(saf_hashes.into_iter().collect())
+            .await
     }
 
-    async fn destroy_clone_cell(self: Arc<Self>, _cell_id: CellId) -> ConductorResult<()> {
-        todo!()
+    async fn migrate_agent(
+        self: Arc<Self>,
+        payload: MigrateAgentPayload,
+    ) -> ConductorResult<CellId> {
+        let MigrateAgentPayload {
+            installed_app_id,
+            slot_id,
+            saf_hash,
+            membrane_proof,
+        } = payload;
+        let cell_id = self
+            .conductor
+            .read()
+            .await
+            .migrate_agent_chain(
+                &installed_app_id,
+                &slot_id,
+                saf_hash,
+                membrane_proof,
+                self.clone(),
+            )
+            .await?;
+        let old_cell_id = self
+            .conductor
+            .write()
+            .await
+            .migrate_slot_in_db(&installed_app_id, &slot_id, cell_id.clone())
+            .await?;
+        self.remove_cells(vec![old_cell_id]).await;
+        // Start the new cell
+        let errors = self.clone().setup_cells().await?;
+        if let Some(error) = errors.into_iter().find(|error| match error {
+            CreateAppError::Failed {
+                installed_app_id: error_app_id,
+                ..
+            } => error_app_id == &installed_app_id,
+        }) {
+            return Err(error.into());
+        }
+        Ok(cell_id)
     }
 
     async fn install_app(
@@ -544,6 +860,13 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         installed_app_id: InstalledAppId,
         cell_data: Vec<(InstalledCell, Option<MembraneProof>)>,
     ) -> ConductorResult<()> {
+        // The SAFs are only named by their hashes, so there are no
+        // signatures to check
+        if self.trusted_publishers.is_some() {
+            return Err(ConductorError::UnsignedSourceRefused(
+                "An app installed from SAF hashes".to_string(),
+            ));
+        }
         self.conductor
             .read()
             .await
@@ -584,12 +907,27 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
 
         let bundle: AppBundle = {
             let original_bundle = source.resolve().await?;
+            // Check provenance before any modification, which would drop the signatures
+            if let Some(trusted_publishers) = &self.trusted_publishers {
+                let publishers = verified_publishers(&original_bundle)
+                    .await
+                    .map_err(AppBundleError::from)?;
+                if !publishers.iter().any(|p| trusted_publishers.contains(p)) {
+                    return Err(AppBundleError::UntrustedPublisher.into());
+                }
+            }
+            // The signatures only vouch for bundled resources and the hashes
+            // of pinned ones, so nothing else may be resolved from a signed app
+            let bundle = original_bundle
+                .into_inner()
+                .with_remote_config(self.bundle_resources.clone())
+                .with_content_addressed_only(self.trusted_publishers.is_some());
             if let Some(uid) = uid {
-                let mut manifest = original_bundle.manifest().to_owned();
+                let mut manifest = bundle.manifest().to_owned();
                 manifest.set_uid(uid);
-                AppBundle::from(original_bundle.into_inner().update_manifest(manifest)?)
+                AppBundle::from(bundle.update_manifest(manifest)?)
             } else {
-                original_bundle
+                AppBundle::from(bundle)
             }
         };
 
@@ -650,12 +988,14 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
     }
 
     async fn activate_app(&self, installed_app_id: InstalledAppId) -> ConductorResult<ActiveApp> {
-        let app = self
-            .conductor
-            .write()
-            .await
-            .activate_app_in_db(installed_app_id)
-            .await?;
+        let mut conductor = self.conductor.write().await;
+        let app = conductor.activate_app_in_db(installed_app_id).await?;
+        conductor
+            .signal_broadcaster()
+            .send_system(SystemSignal::AppStatusChanged {
+                installed_app_id: app.installed_app_id().clone(),
+                status: InstalledAppStatus::Active,
+            });
         Ok(app)
         // MD: Should we be doing `Conductor::add_cells()` here? (see below comment)
     }
@@ -665,23 +1005,80 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         installed_app_id: InstalledAppId,
         reason: DeactivationReason,
     ) -> ConductorResult<()> {
-        let mut conductor = self.conductor.write().await;
-        let cell_ids_to_remove = conductor
-            .deactivate_app_in_db(installed_app_id, reason)
+        let cell_ids_to_remove = self
+            .conductor
+            .write()
+            .await
+            .deactivate_app_in_db(installed_app_id.clone(), reason.clone())
             .await?;
         // MD: I'm not sure about this. We never add the cells back in after re-activating an app,
         //     so it seems either we shouldn't remove them here, or we should be sure to add them
         //     back in when re-activating.
-        conductor.remove_cells(cell_ids_to_remove).await;
+        self.remove_cells(cell_ids_to_remove).await;
+        self.conductor
+            .read()
+            .await
+            .signal_broadcaster()
+            .send_system(SystemSignal::AppStatusChanged {
+                installed_app_id,
+                status: InstalledAppStatus::Inactive { reason },
+            });
         Ok(())
     }
 
     async fn uninstall_app(&self, installed_app_id: &InstalledAppId) -> ConductorResult<()> {
+        // The app is removed from the state and its cells are taken out of
+        // the conductor under one lock, so the cells never run without an
+        // app and nothing can start using them in between.
+        let (cell_ids, cells, saf_hashes) = {
+            let mut conductor = self.conductor.write().await;
+            let (state, app) = conductor.remove_app_from_db(installed_app_id).await?;
+            let cells_in_use = state.all_cells();
+            let cell_ids: Vec<_> = app
+                .all_cells()
+                .chain(app.archived_cells())
+                .filter(|cell_id| !cells_in_use.contains(cell_id))
+                .cloned()
+                .collect();
+            let safs_in_use = state.all_safs();
+            let saf_hashes: HashSet<_> = app
+                .all_safs()
+                .into_iter()
+                .filter(|saf_hash| !safs_in_use.contains(saf_hash))
+                .collect();
+            let cells = conductor.take_cells(&cell_ids);
+            conductor
+                .signal_broadcaster()
+                .send_system(SystemSignal::AppUninstalled {
+                    installed_app_id: installed_app_id.clone(),
+                });
+            (cell_ids, cells, saf_hashes)
+        };
+        let stopped = self.stop_cells(cell_ids.clone(), cells).await;
+
+        // Another app may have been installed with some of these cells or
+        // SAFs while they were stopping, so they are checked against the
+        // state again under the lock that deletes them.
         let mut conductor = self.conductor.write().await;
-        if let Some(cell_ids_to_remove) = conductor.remove_app_from_db(installed_app_id).await? {
-            conductor.remove_cells(cell_ids_to_remove).await;
+        let state = conductor.get_state().await?;
+        let cells_in_use = state.all_cells();
+        let stopped: Vec<_> = stopped
+            .into_iter()
+            .filter(|cell_id| !cells_in_use.contains(cell_id))
+            .collect();
+        let safs_in_use = state.all_safs();
+        let mut saf_hashes: HashSet<_> = saf_hashes
+            .into_iter()
+            .filter(|saf_hash| !safs_in_use.contains(saf_hash))
+            .collect();
+        // Cells which didn't stop are kept along with their SAFs
+        for cell_id in cell_ids.iter().filter(|cell_id| !stopped.contains(cell_id)) {
+            saf_hashes.remove(cell_id.saf_hash());
         }
-        Ok(())
+        conductor.remove_cell_envs(stopped).await?;
+        conductor
+            .remove_safs(saf_hashes.into_iter().collect())
+            .await
     }
 
     async fn list_cell_ids(&self) -> ConductorResult<Vec<CellId>> {
@@ -714,10 +1111,113 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         self.conductor.read().await.dump_cell_state(cell_id).await
     }
 
+    async fn get_cell_metrics(
+        &self,
+        cell_id: Option<CellId>,
+    ) -> ConductorApiResult<Vec<CellMetrics>> {
+        self.conductor.read().await.get_cell_metrics(cell_id).await
+    }
+
+    async fn subscribe_cell_metrics(
+        &self,
+        interval: std::time::Duration,
+        signal_tx: tokio::sync::broadcast::Sender<AdminSignal>,
+    ) -> ConductorResult<tokio::task::JoinHandle<()>> {
+        self.check_running().await?;
+        // Only the cell databases are read on each tick,
+        // so the conductor lock is only taken here.
+        let cell_envs = self.conductor.read().await.cell_envs();
+        Ok(tokio::task::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match running_cell_metrics(&cell_envs).await {
+                    Ok(metrics) => {
+                        if signal_tx.send(AdminSignal::CellMetrics(metrics)).is_err() {
+                            // The connection has closed
+                            break;
+                        }
+                    }
+                    Err(e) => warn!(?e, "Failed to collect cell metrics"),
+                }
+            }
+        }))
+    }
+
+    async fn export_source_chain(
+        &self,
+        cell_id: &CellId,
+        from_seq: u32,
+    ) -> ConductorResult<SourceChainArchive> {
+        // Only the cell's database is read and the keystore is on the handle,
+        // so the conductor lock isn't held while the chain is exported.
+        let env = self
+            .conductor
+            .read()
+            .await
+            .cell_by_id(cell_id)?
+            .env()
+            .clone();
+        export_source_chain(env, &self.keystore, cell_id, from_seq).await
+    }
+
+    async fn import_source_chain(
+        self: Arc<Self>,
+        archive: SourceChainArchive,
+    ) -> ConductorResult<()> {
+        archive.verify().await?;
+        if archive.from_seq == 0 {
+            let saf_hash = archive.cell_id.saf_hash();
+            let saf_file = self
+                .get_saf(saf_hash)
+                .await
+                .ok_or_else(|| SafError::SafMissing(saf_hash.clone()))?;
+            let ribosome = self.get_ribosome(saf_hash).await?;
+            let api = CellConductorApi::new(self.clone(), archive.cell_id.clone());
+            genesis_checks(
+                &api,
+                ribosome,
+                saf_file.saf_def().clone(),
+                archive.cell_id.agent_pubkey(),
+                archive.membrane_proof(),
+            )
+            .await?;
+        }
+        self.conductor
+            .read()
+            .await
+            .import_source_chain(archive)
+            .await
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.conductor.read().await.signal_broadcaster()
     }
 
+    async fn issue_app_authentication_token(
+        &self,
+        payload: IssueAppAuthenticationTokenPayload,
+    ) -> ConductorResult<AppAuthenticationTokenIssued> {
+        let IssueAppAuthenticationTokenPayload {
+            installed_app_id,
+            expiry_seconds,
+        } = payload;
+        if self.get_app_info(&installed_app_id).await?.is_none() {
+            return Err(ConductorError::AppNotInstalled(installed_app_id));
+        }
+        self.conductor
+            .write()
+            .await
+            .issue_app_authentication_token(installed_app_id, expiry_seconds)
+    }
+
+    async fn authenticate_app_token(
+        &self,
+        token: &AppAuthenticationToken,
+    ) -> ConductorResult<InstalledAppId> {
+        self.conductor.write().await.authenticate_app_token(token)
+    }
+
     async fn get_app_info(
         &self,
         installed_app_id: &InstalledAppId,
@@ -731,6 +1231,42 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .get_app_info(installed_app_id))
     }
 
+    async fn find_app_containing_cell(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<Option<InstalledAppInfo>> {
+        Ok(self
+            .conductor
+            .read()
+            .await
+            .get_state()
+            .await?
+            .find_app_containing_cell(cell_id))
+    }
+
+    async fn add_x25519_key_agent(
+        &self,
+        key: X25519PubKey,
+        agent: AgentPubKey,
+    ) -> ConductorResult<()> {
+        self.conductor
+            .read()
+            .await
+            .add_x25519_key_agent_to_db(key, agent)
+            .await
+    }
+
+    async fn get_x25519_key_agent(
+        &self,
+        key: &X25519PubKey,
+    ) -> ConductorResult<Option<AgentPubKey>> {
+        self.conductor
+            .read()
+            .await
+            .get_x25519_key_agent_from_db(*key)
+            .await
+    }
+
     async fn add_agent_infos(&self, agent_infos: Vec<AgentInfoSigned>) -> ConductorApiResult<()> {
         self.conductor
             .read()
@@ -796,34 +1332,85 @@ impl<DS: SafStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
 }
 
 impl<DS: SafStore + 'static> ConductorHandleImpl<DS> {
+    /// Take cells out of the conductor and stop them.
+    /// The conductor lock isn't held while they stop because their queue
+    /// consumers may need it to finish the workflows they are running.
+    /// Cells which don't stop in time are kept by the conductor.
+    /// Returns the ids of the cells which are no longer running, which are
+    /// the only ones whose databases can be deleted.
+    async fn remove_cells(&self, cell_ids: Vec<CellId>) -> Vec<CellId> {
+        let cells = self.conductor.write().await.take_cells(&cell_ids);
+        self.stop_cells(cell_ids, cells).await
+    }
+
+    /// Stop cells which have already been taken out of the conductor.
+    /// Returns the ids of the cells which are no longer running.
+    async fn stop_cells(&self, cell_ids: Vec<CellId>, cells: Vec<Arc<Cell>>) -> Vec<CellId> {
+        let mut still_running = Vec::new();
+        for cell in cells {
+            if let Err(err) = cell.cleanup().await {
+                error!("Error cleaning up Cell: {:?}\nCellId: {}", err, cell.id());
+                still_running.push(cell);
+            }
+        }
+        let stopped = cell_ids
+            .into_iter()
+            .filter(|cell_id| !still_running.iter().any(|cell| cell.id() == cell_id))
+            .collect();
+        if !still_running.is_empty() {
+            self.conductor
+                .write()
+                .await
+                .put_stopping_cells(still_running);
+        }
+        stopped
+    }
+
     async fn cell_by_id(&self, cell_id: &CellId) -> ConductorApiResult<Arc<Cell>> {
         let lock = self.conductor.read().await;
         Ok(lock.cell_by_id(cell_id)?)
     }
 
     /// Add cells to the map then join the network then initialize workflows.
+    /// Each cell is reported active once it has joined the network.
     async fn initialize_cells(&self, cells: Vec<(Cell, InitialQueueTriggers)>) {
         let (cells, triggers): (Vec<_>, Vec<_>) = cells.into_iter().unzip();
         let networks: Vec<_> = cells
             .iter()
-            .map(|cell| cell.aingle_p2p_cell().clone())
+            .map(|cell| (cell.id().clone(), cell.aingle_p2p_cell().clone()))
             .collect();
         // Add the cells to the conductor map.
         // This write lock can't be held while join is awaited as join calls the conductor.
         // Cells need to be in the map before join is called so it can route the call.
-        self.conductor.write().await.add_cells(cells);
+        let signal_tx = {
+            let mut conductor = self.conductor.write().await;
+            conductor.add_cells(cells);
+            conductor.signal_broadcaster()
+        };
         // Join the network but ignore errors because the
         // space retries joining all cells every 5 minutes.
+        // A cell which joins on a retry is reported active when the space
+        // stores its agent info.
         futures::stream::iter(networks)
-            .for_each_concurrent(100, |mut network| async move {
-                match tokio::time::timeout(JOIN_NETWORK_TIMEOUT, network.join()).await {
-                    Ok(Err(e)) => {
-                        tracing::info!(failed_to_join_network = ?e);
-                    }
-                    Err(_) => {
-                        tracing::info!("Timed out trying to join the network");
+            .for_each_concurrent(100, |(cell_id, mut network)| {
+                let mut signal_tx = signal_tx.clone();
+                async move {
+                    match tokio::time::timeout(JOIN_NETWORK_TIMEOUT, network.join()).await {
+                        Ok(Err(e)) => {
+                            tracing::info!(failed_to_join_network = ?e);
+                        }
+                        Err(_) => {
+                            tracing::info!("Timed out trying to join the network");
+                        }
+                        Ok(Ok(_)) => {
+                            if self.conductor.read().await.mark_cell_joined(&cell_id) {
+                                signal_tx.send_system(SystemSignal::CellStatusChanged {
+                                    cell_id,
+                                    status: CellStatus::Active,
+                                });
+                            }
+                        }
                     }
-                    Ok(Ok(_)) => (),
                 }
             })
             .await;
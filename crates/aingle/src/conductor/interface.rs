@@ -6,10 +6,19 @@
 //! implementation can be found in the `websocket` module here.
 
 use crate::conductor::api::*;
+use aingle_conductor_api::signal_subscription::SignalFilterSet;
+use aingle_conductor_api::signal_subscription::SignalSubscription;
+use aingle_types::app::InstalledAppId;
+use aingle_types::signal::Signal;
+use aingle_types::signal::SystemSignal;
+use aingle_zome_types::cell::CellId;
 use error::InterfaceError;
 use error::InterfaceResult;
-use aingle_types::signal::Signal;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::TryInto;
+use std::sync::Arc;
 use tokio::sync::broadcast;
 
 #[allow(missing_docs)]
@@ -63,6 +72,18 @@ impl SignalBroadcaster {
         Ok(())
     }
 
+    /// send a system signal to every interface which has a connected client.
+    /// Interfaces with no clients are skipped rather than treated as errors,
+    /// since the system emits these whether anyone is listening or not.
+    pub fn send_system(&mut self, sig: SystemSignal) {
+        let sig = Signal::from(sig);
+        for tx in self.senders.iter_mut() {
+            if tx.receiver_count() > 0 {
+                let _ = tx.send(sig.clone());
+            }
+        }
+    }
+
     /// internal constructor
     pub fn new(senders: Vec<broadcast::Sender<Signal>>) -> Self {
         Self { senders }
@@ -98,4 +119,173 @@ impl SignalBroadcaster {
     }
 }
 
+/// The signal subscriptions of a single connection to an App interface,
+/// shared between the connection's request handler and its signal emitter.
+///
+/// A signal from a Cell is checked against the filters of the App the
+/// connection authenticated as, once [AppConnection::allows_signal] has found
+/// the Cell is part of that App. System signals which aren't about
+/// a single Cell are let through.
+#[derive(Clone, Debug, Default)]
+pub struct SignalFilters(Arc<RwLock<HashMap<InstalledAppId, SignalFilterSet>>>);
+
+impl SignalFilters {
+    /// Replace the subscription for an App
+    pub fn subscribe(&self, subscription: SignalSubscription) {
+        self.0.write().insert(
+            subscription.installed_app_id().clone(),
+            subscription.filters().clone(),
+        );
+    }
+
+    /// Should this signal about a Cell of the App be emitted to the connection?
+    pub fn allows(&self, installed_app_id: &InstalledAppId, signal: &Signal) -> bool {
+        let cell_id = match signal {
+            Signal::App(cell_id, _) => cell_id,
+            Signal::System(signal) => match signal.cell_id() {
+                Some(cell_id) => cell_id,
+                None => return true,
+            },
+        };
+        self.0
+            .read()
+            .get(installed_app_id)
+            .map_or(true, |filters| filters.allows(cell_id))
+    }
+}
+
+/// The state of a single connection to an App interface,
+/// shared between the connection's request handler and its signal emitter.
+///
+/// A connection starts out unauthenticated, and can neither make requests
+/// nor receive signals until it authenticates as an App.
+#[derive(Clone, Debug, Default)]
+pub struct AppConnection {
+    authenticated_app: Arc<RwLock<Option<AuthenticatedApp>>>,
+    signal_filters: SignalFilters,
+}
+
+/// The App a connection has authenticated as.
+///
+/// The Cells of the App are looked up when the connection authenticates,
+/// and again whenever the conductor state has been written since,
+/// rather than for each request and signal.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedApp {
+    /// The id of the App
+    pub installed_app_id: InstalledAppId,
+    /// The Cells of the App as of the last lookup,
+    /// none once the App is uninstalled
+    pub cells: Arc<HashSet<CellId>>,
+    /// The generation of the conductor state the Cells were looked up at
+    pub state_generation: u64,
+}
+
+impl AppConnection {
+    /// Mark the connection as acting for an App whose Cells were `cells`
+    /// at the `state_generation` of the conductor state
+    pub fn authenticate(
+        &self,
+        installed_app_id: InstalledAppId,
+        cells: HashSet<CellId>,
+        state_generation: u64,
+    ) {
+        *self.authenticated_app.write() = Some(AuthenticatedApp {
+            installed_app_id,
+            cells: Arc::new(cells),
+            state_generation,
+        });
+    }
+
+    /// The App the connection has authenticated as, if it has
+    pub fn authenticated_app(&self) -> Option<AuthenticatedApp> {
+        self.authenticated_app.read().clone()
+    }
+
+    /// Replace the Cells of the App the connection authenticated as,
+    /// unless it has authenticated as another App or newer Cells have been
+    /// set since they were looked up
+    pub fn set_app_cells(
+        &self,
+        installed_app_id: &InstalledAppId,
+        cells: HashSet<CellId>,
+        state_generation: u64,
+    ) {
+        if let Some(app) = self.authenticated_app.write().as_mut() {
+            if app.installed_app_id == *installed_app_id && app.state_generation <= state_generation
+            {
+                app.cells = Arc::new(cells);
+                app.state_generation = state_generation;
+            }
+        }
+    }
+
+    /// The signal subscriptions of the connection
+    pub fn signal_filters(&self) -> &SignalFilters {
+        &self.signal_filters
+    }
+
+    /// Should this signal be emitted to the connection,
+    /// given the App it authenticated as and its signal subscriptions?
+    pub fn allows_signal(&self, signal: &Signal) -> bool {
+        match self.authenticated_app() {
+            Some(app) => {
+                app_allows_signal(&app, signal)
+                    && self.signal_filters.allows(&app.installed_app_id, signal)
+            }
+            None => false,
+        }
+    }
+}
+
+/// Should this signal be emitted to a connection for an App,
+/// before its signal subscriptions are applied?
+fn app_allows_signal(app: &AuthenticatedApp, signal: &Signal) -> bool {
+    match signal {
+        Signal::App(cell_id, _) => app.cells.contains(cell_id),
+        Signal::System(SystemSignal::PeerJoined { saf_hash, .. }) => {
+            app.cells.iter().any(|cell| cell.saf_hash() == saf_hash)
+        }
+        Signal::System(SystemSignal::AppStatusChanged {
+            installed_app_id, ..
+        })
+        | Signal::System(SystemSignal::AppUninstalled { installed_app_id }) => {
+            *installed_app_id == app.installed_app_id
+        }
+        Signal::System(signal) => signal.cell_id().map_or(true, |c| app.cells.contains(c)),
+    }
+}
+
+/// The state of a single connection to an Admin interface,
+/// shared between the connection's request handler and its signal emitter.
+#[derive(Clone, Debug)]
+pub struct AdminConnection {
+    signal_tx: broadcast::Sender<AdminSignal>,
+    cell_metrics_task: Arc<parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>>,
+}
+
+impl Default for AdminConnection {
+    fn default() -> Self {
+        Self {
+            signal_tx: broadcast::channel(websocket::SIGNAL_BUFFER_SIZE).0,
+            cell_metrics_task: Default::default(),
+        }
+    }
+}
+
+impl AdminConnection {
+    /// The channel for sending signals to this connection only
+    pub fn signal_tx(&self) -> &broadcast::Sender<AdminSignal> {
+        &self.signal_tx
+    }
+
+    /// Replace the task sending [CellMetrics] snapshots to the connection,
+    /// aborting the previous one if there was one
+    pub fn set_cell_metrics_task(&self, task: Option<tokio::task::JoinHandle<()>>) {
+        if let Some(previous) = std::mem::replace(&mut *self.cell_metrics_task.lock(), task) {
+            previous.abort();
+        }
+    }
+}
+
 pub use aingle_conductor_api::config::InterfaceDriver;
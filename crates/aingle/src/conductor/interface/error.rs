@@ -27,6 +27,8 @@ pub enum InterfaceError {
     WebsocketError(#[from] aingle_websocket::WebsocketError),
     #[error("Failed to find free port")]
     PortError,
+    #[error("Unix domain socket interfaces are not supported on this platform")]
+    UnixSocketUnsupported,
 }
 
 impl From<String> for InterfaceError {
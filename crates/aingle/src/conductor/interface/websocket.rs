@@ -1,13 +1,19 @@
 //! Module for establishing Websocket-based Interfaces,
-//! i.e. those configured with `InterfaceDriver::Websocket`
+//! i.e. those configured with `InterfaceDriver::Websocket`,
+//! or with `InterfaceDriver::UnixSocket`, which speaks the same
+//! websocket protocol over a Unix domain socket.
 
-use super::error::InterfaceError;
 use super::error::InterfaceResult;
+use crate::conductor::api::AdminInterfaceApi;
+use crate::conductor::api::AppInterfaceApi;
 use crate::conductor::conductor::StopReceiver;
 use crate::conductor::interface::*;
 use crate::conductor::manager::ManagedTaskHandle;
 use crate::conductor::manager::ManagedTaskResult;
+use aingle_conductor_api::config::InterfaceDriver;
+use aingle_conductor_api::config::DEFAULT_UNIX_SOCKET_MODE;
 use aingle_middleware_bytes::SerializedBytes;
+use aingle_middleware_bytes::SerializedBytesError;
 use aingle_types::signal::Signal;
 use aingle_websocket::ListenerHandle;
 use aingle_websocket::ListenerItem;
@@ -16,7 +22,9 @@ use aingle_websocket::WebsocketListener;
 use aingle_websocket::WebsocketMessage;
 use aingle_websocket::WebsocketReceiver;
 use aingle_websocket::WebsocketSender;
+use futures::stream::BoxStream;
 use std::convert::TryFrom;
+use std::path::Path;
 
 use std::sync::atomic::AtomicIsize;
 use std::sync::atomic::Ordering;
@@ -49,9 +57,52 @@ pub async fn spawn_websocket_listener(
     Ok(listener)
 }
 
-/// Create an Admin Interface, which only receives AdminRequest messages
-/// from the external client
-pub fn spawn_admin_interface_task<A: InterfaceApi>(
+/// A listener bound for an interface, whichever its driver
+pub type InterfaceListener = (ListenerHandle, BoxStream<'static, ListenerItem>);
+
+/// Create a listener for an interface with the given driver
+pub async fn spawn_interface_listener(
+    driver: &InterfaceDriver,
+) -> InterfaceResult<InterfaceListener> {
+    match driver {
+        InterfaceDriver::Websocket { port } => {
+            let (handle, listener) = spawn_websocket_listener(*port).await?;
+            Ok((handle, Box::pin(listener)))
+        }
+        InterfaceDriver::UnixSocket { path, mode } => {
+            spawn_unix_socket_listener(path, mode.unwrap_or(DEFAULT_UNIX_SOCKET_MODE)).await
+        }
+    }
+}
+
+/// Create a WebsocketListener on a Unix domain socket to be used in interfaces.
+/// The socket file gets the permissions `mode`.
+#[cfg(unix)]
+pub async fn spawn_unix_socket_listener(
+    path: &Path,
+    mode: u32,
+) -> InterfaceResult<InterfaceListener> {
+    trace!("Initializing interface at {}", path.display());
+    let (handle, listener) =
+        WebsocketListener::bind_unix_with_handle(path, mode, Arc::new(WebsocketConfig::default()))
+            .await?;
+    trace!("LISTENING AT: {}", handle.local_addr());
+    Ok((handle, Box::pin(listener)))
+}
+
+/// Unix domain sockets are not available on this platform
+#[cfg(not(unix))]
+pub async fn spawn_unix_socket_listener(
+    _path: &Path,
+    _mode: u32,
+) -> InterfaceResult<InterfaceListener> {
+    Err(super::error::InterfaceError::UnixSocketUnsupported)
+}
+
+/// Create an Admin Interface, which receives AdminRequest messages
+/// from the external client and pushes AdminSignals back to it.
+/// Each connection only receives the signals it subscribed to.
+pub fn spawn_admin_interface_task<A: InterfaceApi + AdminInterfaceApi>(
     handle: ListenerHandle,
     listener: impl futures::stream::Stream<Item = ListenerItem> + Send + 'static,
     api: A,
@@ -68,16 +119,21 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
         // establish a new connection to a client
         while let Some(connection) = listener.next().await {
             match connection {
-                Ok((_, rx_from_iface)) => {
+                Ok((tx_to_iface, rx_from_iface)) => {
                     if num_connections.fetch_add(1, Ordering::Relaxed) > MAX_CONNECTIONS {
                         // Max connections so drop this connection
                         // which will close it.
                         continue;
                     };
+                    let connection = AdminConnection::default();
+                    spawn_outgoing_signals(connection.signal_tx().subscribe(), tx_to_iface, |_| {
+                        futures::future::ready(true)
+                    });
                     tokio::task::spawn(recv_incoming_admin_msgs(
-                        api.clone(),
+                        api.for_connection(connection.clone()),
                         rx_from_iface,
                         num_connections.clone(),
+                        connection,
                     ));
                 }
                 Err(err) => {
@@ -90,24 +146,16 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 }
 
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
-    port: u16,
+/// from Cells via a broadcast channel.
+/// Each connection must authenticate as an App, and then only receives
+/// the signals of that App which it is subscribed to.
+pub fn spawn_app_interface_task<A: InterfaceApi + AppInterfaceApi>(
+    handle: ListenerHandle,
+    mut listener: impl futures::stream::Stream<Item = ListenerItem> + Send + Unpin + 'static,
     api: A,
     signal_broadcaster: broadcast::Sender<Signal>,
     mut stop_rx: StopReceiver,
-) -> InterfaceResult<(u16, ManagedTaskHandle)> {
-    trace!("Initializing App interface");
-    let (handle, mut listener) = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
-    trace!("LISTENING AT: {}", handle.local_addr());
-    let port = handle
-        .local_addr()
-        .port()
-        .ok_or(InterfaceError::PortError)?;
+) -> InterfaceResult<ManagedTaskHandle> {
     // Task that will kill the listener and all child connections.
     tokio::task::spawn(
         handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
@@ -119,7 +167,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
                 Ok((tx_to_iface, rx_from_iface)) => {
                     let rx_from_cell = signal_broadcaster.subscribe();
                     spawn_recv_incoming_msgs_and_outgoing_signals(
-                        api.clone(),
+                        api.for_connection(AppConnection::default()),
                         rx_from_iface,
                         rx_from_cell,
                         tx_to_iface,
@@ -133,7 +181,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
 
         ManagedTaskResult::Ok(())
     });
-    Ok((port, task))
+    Ok(task)
 }
 
 /// Polls for messages coming in from the external client.
@@ -142,6 +190,7 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(
     api: A,
     rx_from_iface: WebsocketReceiver,
     num_connections: Arc<AtomicIsize>,
+    connection: AdminConnection,
 ) {
     use futures::stream::StreamExt;
 
@@ -156,13 +205,15 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(
             }
         })
         .await;
+    // Stop pushing metrics to the closed connection
+    connection.set_cell_metrics_task(None);
     num_connections.fetch_sub(1, Ordering::SeqCst);
 }
 
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
 /// App interface.
-fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
+fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi + AppInterfaceApi>(
     api: A,
     rx_from_iface: WebsocketReceiver,
     rx_from_cell: broadcast::Receiver<Signal>,
@@ -172,15 +223,41 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
 
     trace!("CONNECTION: {}", rx_from_iface.remote_addr());
 
-    let rx_from_cell = futures::stream::unfold(rx_from_cell, |mut rx_from_cell| async move {
-        if let Ok(item) = rx_from_cell.recv().await {
-            Some((item, rx_from_cell))
-        } else {
-            None
-        }
+    let signal_api = api.clone();
+    spawn_outgoing_signals(rx_from_cell, tx_to_iface, move |signal| {
+        let api = signal_api.clone();
+        let signal = signal.clone();
+        async move { api.allows_signal(&signal).await }
     });
 
-    tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
+    tokio::task::spawn(rx_from_iface.for_each_concurrent(4096, move |msg| {
+        let api = api.clone();
+        async move {
+            if let Err(err) = handle_incoming_message(msg, api).await {
+                error!(?err, "error handling websocket message");
+            }
+        }
+    }));
+}
+
+/// Forwards every signal sent on the broadcast channel which passes
+/// the filter to the external client until the channel closes.
+/// If the client falls too far behind, the signals it missed are skipped.
+fn spawn_outgoing_signals<S, F, Fut>(
+    rx_from_conductor: broadcast::Receiver<S>,
+    tx_to_iface: WebsocketSender,
+    filter: F,
+) where
+    S: std::fmt::Debug + Clone + Send + 'static,
+    SerializedBytes: TryFrom<S, Error = SerializedBytesError>,
+    F: Fn(&S) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = bool> + Send + 'static,
+{
+    use futures::stream::StreamExt;
+
+    let rx_from_conductor = recv_signals(rx_from_conductor).filter(filter);
+
+    tokio::task::spawn(rx_from_conductor.for_each_concurrent(4096, move |signal| {
         let mut tx_to_iface = tx_to_iface.clone();
         async move {
             trace!(msg = "Sending signal!", ?signal);
@@ -195,15 +272,25 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
             }
         }
     }));
+}
 
-    tokio::task::spawn(rx_from_iface.for_each_concurrent(4096, move |msg| {
-        let api = api.clone();
-        async move {
-            if let Err(err) = handle_incoming_message(msg, api).await {
-                error!(?err, "error handling websocket message");
+/// The signals sent on a broadcast channel until it closes.
+/// If the receiver falls too far behind, the signals it missed are skipped
+/// and it carries on from the oldest signal still in the channel.
+fn recv_signals<S: Clone>(
+    rx_from_conductor: broadcast::Receiver<S>,
+) -> impl futures::stream::Stream<Item = S> {
+    futures::stream::unfold(rx_from_conductor, |mut rx_from_conductor| async move {
+        loop {
+            match rx_from_conductor.recv().await {
+                Ok(item) => return Some((item, rx_from_conductor)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(%skipped, "skipping signals the connection fell behind on");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
             }
         }
-    }));
+    })
 }
 
 /// Handles messages on all interfaces
@@ -220,6 +307,10 @@ where
 /// Test items needed by other crates
 #[cfg(any(test, feature = "test_utils"))]
 pub mod test_utils {
+    use crate::conductor::api::AppInterfaceApi;
+    use crate::conductor::api::AppRequest;
+    use crate::conductor::api::AppResponse;
+    use crate::conductor::api::IssueAppAuthenticationTokenPayload;
     use crate::conductor::api::RealAppInterfaceApi;
     use crate::conductor::conductor::ConductorBuilder;
     use crate::conductor::ConductorHandle;
@@ -230,6 +321,7 @@ pub mod test_utils {
     use tempdir::TempDir;
 
     /// One of various ways to setup an app, used somewhere...
+    /// The returned api is already authenticated as the installed app.
     pub async fn setup_app(
         cell_data: Vec<(InstalledCell, Option<SerializedBytes>)>,
         saf_store: MockSafStore,
@@ -256,13 +348,21 @@ pub mod test_utils {
 
         assert!(errors.is_empty());
 
-        let handle = conductor_handle.clone();
-
-        (
-            envs.tempdir(),
-            RealAppInterfaceApi::new(conductor_handle, Default::default()),
-            handle,
-        )
+        let issued = conductor_handle
+            .issue_app_authentication_token(
+                IssueAppAuthenticationTokenPayload::for_installed_app_id("test app".to_string()),
+            )
+            .await
+            .unwrap();
+        let app_api = RealAppInterfaceApi::new(conductor_handle.clone(), Default::default());
+        let response = app_api
+            .handle_app_request(AppRequest::Authenticate {
+                token: issued.token,
+            })
+            .await;
+        assert!(matches!(response, AppResponse::Authenticated(_)));
+
+        (envs.tempdir(), app_api, conductor_handle)
     }
 }
 
@@ -357,6 +457,29 @@ pub mod test {
         conductor_handle
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn lagging_signals_are_skipped() {
+        use futures::stream::StreamExt;
+
+        let (tx, rx) = broadcast::channel(2);
+        let signals = recv_signals(rx);
+        futures::pin_mut!(signals);
+
+        for i in 0..5 {
+            tx.send(i).unwrap();
+        }
+        // The receiver lagged, so only the newest signals are left
+        assert_eq!(signals.next().await, Some(3));
+        assert_eq!(signals.next().await, Some(4));
+
+        // and it keeps receiving afterwards
+        tx.send(5).unwrap();
+        assert_eq!(signals.next().await, Some(5));
+
+        drop(tx);
+        assert_eq!(signals.next().await, None);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn serialization_failure() {
         let (_tmpdir, conductor_handle) = setup_admin().await;
@@ -685,6 +808,7 @@ pub mod test {
                 uid: uid.to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: zomes.clone().into_iter().map(Into::into).collect(),
+                rate_limit: None,
             },
             zomes.into_iter().map(Into::into),
         )
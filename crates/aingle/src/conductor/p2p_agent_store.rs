@@ -107,12 +107,13 @@ pub fn query_peer_density(
     Ok(bucket.density())
 }
 
-/// Put single agent info into store
+/// Put single agent info into store.
+/// Returns true if the agent wasn't in the store before.
 pub async fn put_agent_info_signed(
     environ: EnvWrite,
     agent_info_signed: kitsune_p2p::agent_store::AgentInfoSigned,
-) -> ConductorResult<()> {
-    Ok(p2p_put(&environ, &agent_info_signed).await?)
+) -> ConductorResult<bool> {
+    Ok(p2p_put_is_new(&environ, &agent_info_signed).await?)
 }
 
 fn now() -> u64 {
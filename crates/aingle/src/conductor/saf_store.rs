@@ -26,6 +26,10 @@ impl SafStore for RealSafStore {
     fn get(&self, hash: &SafHash) -> Option<SafFile> {
         self.safs.get(hash).cloned()
     }
+    #[instrument]
+    fn remove_saf(&mut self, hash: &SafHash) -> Option<SafFile> {
+        self.safs.remove(hash)
+    }
     fn add_entry_def(&mut self, k: EntryDefBufferKey, entry_def: EntryDef) {
         self.entry_defs.insert(k, entry_def);
     }
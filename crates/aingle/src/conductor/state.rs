@@ -7,6 +7,9 @@ use aingle_types::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
 
 /// Mutable conductor state, stored in a DB and writeable only via Admin interface.
 ///
@@ -53,6 +56,13 @@ impl AppInterfaceId {
         };
         Self { port, id }
     }
+    /// Create an id for an interface on the Unix domain socket at `path`
+    pub fn unix_socket(path: &Path) -> Self {
+        Self {
+            port: 0,
+            id: Some(path.display().to_string()),
+        }
+    }
     /// Get the port intended for this interface
     pub fn port(&self) -> u16 {
         self.port
@@ -73,6 +83,37 @@ impl ConductorState {
             })
     }
 
+    /// Retrieve info about the active App which contains this Cell, if any
+    pub fn find_app_containing_cell(&self, cell_id: &CellId) -> Option<InstalledAppInfo> {
+        self.active_apps
+            .values()
+            .find(|app| app.all_cells().any(|c| c == cell_id))
+            .map(|app| InstalledAppInfo::from_installed_app(&app.clone().into()))
+    }
+
+    /// The Cells of every installed App, active or not, including archived clones
+    pub fn all_cells(&self) -> HashSet<CellId> {
+        self.active_apps
+            .values()
+            .flat_map(|app| app.all_cells().chain(app.archived_cells()))
+            .chain(
+                self.inactive_apps
+                    .values()
+                    .flat_map(|app| app.all_cells().chain(app.archived_cells())),
+            )
+            .cloned()
+            .collect()
+    }
+
+    /// The SAFs used by every installed App, active or not
+    pub fn all_safs(&self) -> HashSet<SafHash> {
+        self.active_apps
+            .values()
+            .flat_map(|app| app.all_safs())
+            .chain(self.inactive_apps.values().flat_map(|app| app.all_safs()))
+            .collect()
+    }
+
     /// Returns the interface configuration with the given ID if present
     pub fn interface_by_id(&self, id: &AppInterfaceId) -> Option<AppInterfaceConfig> {
         self.app_interfaces.get(id).cloned()
@@ -83,8 +124,6 @@ impl ConductorState {
 /// GUIs, browser based web UIs, local native UIs, other local applications and scripts.
 /// We currently have:
 /// * websockets
-///
-/// We will also soon develop
 /// * Unix domain sockets
 ///
 /// The cells (referenced by ID) that are to be made available via that interface should be listed.
@@ -94,7 +133,7 @@ pub struct AppInterfaceConfig {
     /// The signal subscription settings for each App
     pub signal_subscriptions: HashMap<InstalledAppId, SignalSubscription>,
 
-    /// The driver for the interface, e.g. Websocket
+    /// The driver for the interface, e.g. Websocket or UnixSocket
     pub driver: InterfaceDriver,
 }
 
@@ -106,6 +145,14 @@ impl AppInterfaceConfig {
             driver: InterfaceDriver::Websocket { port },
         }
     }
+
+    /// Create config for an interface on a Unix domain socket
+    pub fn unix_socket(path: PathBuf, mode: Option<u32>) -> Self {
+        Self {
+            signal_subscriptions: HashMap::new(),
+            driver: InterfaceDriver::UnixSocket { path, mode },
+        }
+    }
 }
 
 // TODO: Tons of consistency check tests were ripped out in the great legacy code cleanup
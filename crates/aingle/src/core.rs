@@ -6,6 +6,7 @@ pub mod queue_consumer;
 #[allow(missing_docs)]
 pub mod ribosome;
 mod validation;
+mod warrant;
 #[allow(missing_docs)]
 pub mod workflow;
 
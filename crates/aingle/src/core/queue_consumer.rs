@@ -20,6 +20,8 @@
 //! | SgdOpIntegr.   | IntegrationLimbo | IntegratedSgdOps | SysVal + VR    |
 //! | ValReceipt.    | IntegratedSgdOps | IntegratedSgdOps | *n/a           |
 //! | Publish        | AuthoredSgdOps   | *n/a*            | *n/a*          |
+//! |                          **scheduling**                               |
+//! | Schedule       | ScheduledFns     | ScheduledFns     | *n/a*          |
 //!
 //! († Auth'd + IntQ is short for: AuthoredSgdOps + IntegrationLimbo)
 //!
@@ -43,6 +45,8 @@ use app_validation_consumer::*;
 mod publish_sgd_ops_consumer;
 use validation_receipt_consumer::*;
 mod validation_receipt_consumer;
+mod schedule_consumer;
+use schedule_consumer::*;
 use crate::conductor::{api::CellConductorApiT, error::ConductorError, manager::ManagedTaskResult};
 use crate::conductor::{manager::ManagedTaskAdd, ConductorHandle};
 use aingle_p2p::AIngleP2pCell;
@@ -64,8 +68,9 @@ pub async fn spawn_queue_consumer_tasks(
     conductor_api: impl CellConductorApiT + 'static,
     task_sender: sync::mpsc::Sender<ManagedTaskAdd>,
     stop: sync::broadcast::Sender<()>,
-) -> (QueueTriggers, InitialQueueTriggers) {
+) -> (QueueTriggers, InitialQueueTriggers, QueueConsumersJoined) {
     let cell_id = cell_network.cell_id();
+    let (joined_tx, joined) = QueueConsumersJoined::new();
     // Publish
     let (tx_publish, handle) = spawn_publish_sgd_ops_consumer(
         env.clone(),
@@ -75,7 +80,7 @@ pub async fn spawn_queue_consumer_tasks(
     );
     task_sender
         .send(ManagedTaskAdd::cell_critical(
-            handle,
+            watch_consumer(handle, joined_tx.clone()),
             cell_id.clone(),
             "publish_sgd_ops_consumer",
         ))
@@ -91,7 +96,7 @@ pub async fn spawn_queue_consumer_tasks(
     );
     task_sender
         .send(ManagedTaskAdd::cell_critical(
-            handle,
+            watch_consumer(handle, joined_tx.clone()),
             cell_id.clone(),
             "validation_receipt_consumer",
         ))
@@ -111,13 +116,29 @@ pub async fn spawn_queue_consumer_tasks(
     );
     task_sender
         .send(ManagedTaskAdd::cell_critical(
-            handle,
+            watch_consumer(handle, joined_tx.clone()),
             cell_id.clone(),
             "integrate_sgd_ops_consumer",
         ))
         .await
         .expect("Failed to manage workflow handle");
 
+    // Scheduled functions
+    let (tx_schedule, handle) = spawn_schedule_consumer(
+        env.clone(),
+        conductor_handle.clone(),
+        stop.subscribe(),
+        conductor_api.clone(),
+    );
+    task_sender
+        .send(ManagedTaskAdd::cell_critical(
+            watch_consumer(handle, joined_tx.clone()),
+            cell_id.clone(),
+            "schedule_consumer",
+        ))
+        .await
+        .expect("Failed to manage workflow handle");
+
     // App validation
     let (tx_app, handle) = spawn_app_validation_consumer(
         env.clone(),
@@ -130,7 +151,7 @@ pub async fn spawn_queue_consumer_tasks(
     );
     task_sender
         .send(ManagedTaskAdd::cell_critical(
-            handle,
+            watch_consumer(handle, joined_tx.clone()),
             cell_id.clone(),
             "app_validation_consumer",
         ))
@@ -149,7 +170,7 @@ pub async fn spawn_queue_consumer_tasks(
     );
     task_sender
         .send(ManagedTaskAdd::cell_critical(
-            handle,
+            watch_consumer(handle, joined_tx.clone()),
             cell_id.clone(),
             "sys_validation_consumer",
         ))
@@ -160,11 +181,49 @@ pub async fn spawn_queue_consumer_tasks(
     }
 
     (
-        QueueTriggers::new(tx_sys.clone(), tx_publish.clone()),
-        InitialQueueTriggers::new(tx_sys, tx_publish, tx_app, tx_integration, tx_receipt),
+        QueueTriggers::new(tx_sys.clone(), tx_publish.clone(), tx_schedule.clone()),
+        InitialQueueTriggers::new(
+            tx_sys,
+            tx_publish,
+            tx_app,
+            tx_integration,
+            tx_receipt,
+            tx_schedule,
+        ),
+        joined,
     )
 }
 
+/// Lets a Cell wait for its queue consumers to finish after asking them to stop.
+pub struct QueueConsumersJoined(sync::Mutex<mpsc::Receiver<()>>);
+
+impl QueueConsumersJoined {
+    fn new() -> (mpsc::Sender<()>, Self) {
+        let (tx, rx) = mpsc::channel(1);
+        (tx, Self(sync::Mutex::new(rx)))
+    }
+
+    /// Wait until the join handles of all the consumers have resolved.
+    pub async fn wait(&self) {
+        // Nothing is ever sent, so this only returns once every
+        // consumer's watcher has dropped its sender.
+        while self.0.lock().await.recv().await.is_some() {}
+    }
+}
+
+/// Join a consumer's handle for the task manager, holding a
+/// [`QueueConsumersJoined`] sender until the consumer has finished.
+fn watch_consumer(
+    handle: tokio::task::JoinHandle<ManagedTaskResult>,
+    joined: mpsc::Sender<()>,
+) -> tokio::task::JoinHandle<ManagedTaskResult> {
+    tokio::spawn(async move {
+        let result = handle.await;
+        drop(joined);
+        result?
+    })
+}
+
 #[derive(Clone)]
 /// The entry points for kicking off a chain reaction of queue activity
 pub struct QueueTriggers {
@@ -172,6 +231,8 @@ pub struct QueueTriggers {
     pub sys_validation: TriggerSender,
     /// Notify the ProduceSgdOps workflow to run, i.e. after InvokeCallZome
     pub publish_sgd_ops: TriggerSender,
+    /// Notify the Schedule workflow to run, i.e. after InvokeCallZome
+    pub schedule: TriggerSender,
 }
 
 /// The triggers to run once at the start of a cell
@@ -183,14 +244,20 @@ pub struct InitialQueueTriggers {
     app_validation: TriggerSender,
     integrate_sgd_ops: TriggerSender,
     validation_receipt: TriggerSender,
+    schedule: TriggerSender,
 }
 
 impl QueueTriggers {
     /// Create a new queue trigger
-    pub fn new(sys_validation: TriggerSender, publish_sgd_ops: TriggerSender) -> Self {
+    pub fn new(
+        sys_validation: TriggerSender,
+        publish_sgd_ops: TriggerSender,
+        schedule: TriggerSender,
+    ) -> Self {
         Self {
             sys_validation,
             publish_sgd_ops,
+            schedule,
         }
     }
 }
@@ -202,6 +269,7 @@ impl InitialQueueTriggers {
         app_validation: TriggerSender,
         integrate_sgd_ops: TriggerSender,
         validation_receipt: TriggerSender,
+        schedule: TriggerSender,
     ) -> Self {
         Self {
             sys_validation,
@@ -209,6 +277,7 @@ impl InitialQueueTriggers {
             app_validation,
             integrate_sgd_ops,
             validation_receipt,
+            schedule,
         }
     }
 
@@ -219,6 +288,7 @@ impl InitialQueueTriggers {
         self.integrate_sgd_ops.trigger();
         self.publish_sgd_ops.trigger();
         self.validation_receipt.trigger();
+        self.schedule.trigger();
     }
 }
 /// The means of nudging a queue consumer to tell it to look for more work
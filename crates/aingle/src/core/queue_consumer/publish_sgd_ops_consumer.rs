@@ -4,6 +4,7 @@ use super::*;
 
 use crate::conductor::manager::ManagedTaskResult;
 use crate::core::workflow::publish_sgd_ops_workflow::publish_sgd_ops_workflow;
+use crate::core::workflow::publish_sgd_ops_workflow::MIN_PUBLISH_INTERVAL;
 use tokio::task::JoinHandle;
 use tracing::*;
 
@@ -28,8 +29,16 @@ pub fn spawn_publish_sgd_ops_consumer(
             }
 
             // Run the workflow
-            match publish_sgd_ops_workflow(env.clone(), cell_network.clone()).await {
-                Ok(WorkComplete::Incomplete) => trigger_self.trigger(),
+            let signal_tx = conductor_handle.signal_broadcaster().await;
+            match publish_sgd_ops_workflow(env.clone(), cell_network.clone(), signal_tx).await {
+                // Some ops failed to send, so try again once the network has had a chance to recover
+                Ok(WorkComplete::Incomplete) => {
+                    let mut trigger_self = trigger_self.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(MIN_PUBLISH_INTERVAL).await;
+                        trigger_self.trigger();
+                    });
+                }
                 Err(err) => {
                     handle_workflow_error(
                         conductor_handle.clone(),
@@ -0,0 +1,72 @@
+//! The workflow and queue consumer for scheduled zome functions
+
+use super::*;
+use crate::conductor::manager::ManagedTaskResult;
+use crate::core::workflow::schedule_workflow::schedule_workflow;
+use aingle_state::schedule::next_scheduled_run_ms;
+use aingle_state::schedule::now_ms;
+use std::convert::TryFrom;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::*;
+
+/// The longest the scheduler will sleep without checking the database.
+/// Schedules are normally picked up by a trigger after the zome call that
+/// made them, so this is only a backstop.
+pub const MAX_SCHEDULER_SLEEP: Duration = Duration::from_secs(60);
+
+/// Spawn the QueueConsumer for the schedule workflow
+#[instrument(skip(env, conductor_handle, stop, conductor_api))]
+pub fn spawn_schedule_consumer<Api: CellConductorApiT + 'static>(
+    env: EnvWrite,
+    conductor_handle: ConductorHandle,
+    mut stop: sync::broadcast::Receiver<()>,
+    conductor_api: Api,
+) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
+    let (tx, mut rx) = TriggerSender::new();
+    let handle = tokio::spawn(async move {
+        let cell_id = conductor_api.cell_id().clone();
+        loop {
+            // Wait for the next job or until the next function is due
+            let sleep_for = match env.async_reader(|txn| next_scheduled_run_ms(&txn)).await {
+                Ok(Some(next_run_ms)) => {
+                    // A function that is already due converts to no wait at all
+                    let wait_ms = u64::try_from(next_run_ms.saturating_sub(now_ms())).unwrap_or(0);
+                    Duration::from_millis(wait_ms).min(MAX_SCHEDULER_SLEEP)
+                }
+                Ok(None) => MAX_SCHEDULER_SLEEP,
+                Err(err) => {
+                    handle_workflow_error(
+                        conductor_handle.clone(),
+                        cell_id.clone(),
+                        err.into(),
+                        "schedule_workflow failure",
+                    )
+                    .await?;
+                    MAX_SCHEDULER_SLEEP
+                }
+            };
+            let next_job = next_job_or_exit(&mut rx, &mut stop);
+            tokio::pin!(next_job);
+            if let Either::Left((Job::Shutdown, _)) =
+                futures::future::select(next_job, Box::pin(tokio::time::sleep(sleep_for))).await
+            {
+                tracing::warn!("Cell is shutting down: stopping schedule_workflow queue consumer.");
+                break;
+            }
+
+            // Run the workflow
+            if let Err(err) = schedule_workflow(env.clone(), conductor_api.clone()).await {
+                handle_workflow_error(
+                    conductor_handle.clone(),
+                    cell_id.clone(),
+                    err,
+                    "schedule_workflow failure",
+                )
+                .await?
+            }
+        }
+        Ok(())
+    });
+    (tx, handle)
+}
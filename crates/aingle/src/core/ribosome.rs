@@ -59,18 +59,38 @@ use self::{
 #[derive(Clone)]
 pub struct CallContext {
     pub(crate) zome: Zome,
+    pub(crate) function_name: FunctionName,
+    pub(crate) auth: InvocationAuth,
     pub(crate) host_access: HostAccess,
 }
 
 impl CallContext {
-    pub fn new(zome: Zome, host_access: HostAccess) -> Self {
-        Self { zome, host_access }
+    pub fn new(
+        zome: Zome,
+        function_name: FunctionName,
+        auth: InvocationAuth,
+        host_access: HostAccess,
+    ) -> Self {
+        Self {
+            zome,
+            function_name,
+            auth,
+            host_access,
+        }
     }
 
     pub fn zome(&self) -> Zome {
         self.zome.clone()
     }
 
+    pub fn function_name(&self) -> &FunctionName {
+        &self.function_name
+    }
+
+    pub fn auth(&self) -> InvocationAuth {
+        self.auth.clone()
+    }
+
     pub fn host_access(&self) -> HostAccess {
         self.host_access.clone()
     }
@@ -250,6 +270,18 @@ pub trait Invocation: Clone {
     /// this is intentionally NOT a reference to self because ExternIO may be huge we want to be
     /// careful about cloning invocations
     fn host_input(self) -> Result<ExternIO, SerializedBytesError>;
+    /// Who is making the invocation and with what capability.
+    /// Callbacks triggered by the host itself are always local.
+    fn auth(&self) -> InvocationAuth;
+}
+
+/// The authorization an invocation is made with
+#[derive(Clone, Debug, PartialEq)]
+pub enum InvocationAuth {
+    /// The host is invoking a callback on behalf of the current agent
+    LocalCallback,
+    /// A zome call made by this provenance with this optional secret
+    Cap(AgentPubKey, Option<CapSecret>),
 }
 
 impl ZomeCallInvocation {
@@ -279,6 +311,7 @@ mockall::mock! {
         fn zomes(&self) -> ZomesToInvoke;
         fn fn_components(&self) -> FnComponents;
         fn host_input(self) -> Result<ExternIO, SerializedBytesError>;
+        fn auth(&self) -> InvocationAuth;
     }
     trait Clone {
         fn clone(&self) -> Self;
@@ -317,6 +350,9 @@ impl Invocation for ZomeCallInvocation {
     fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
         Ok(self.payload)
     }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::Cap(self.provenance.clone(), self.cap)
+    }
 }
 
 impl ZomeCallInvocation {
@@ -437,6 +473,9 @@ pub trait RibosomeT: Sized + std::fmt::Debug {
         to_call: &FunctionName,
     ) -> Result<Option<ExternIO>, RibosomeError>;
 
+    /// Whether a zome exports a function or callback with this name
+    fn zome_fn_exists(&self, zome: &Zome, fn_name: &FunctionName) -> RibosomeResult<bool>;
+
     /// @todo list out all the available callbacks and maybe cache them somewhere
     fn list_callbacks(&self) {
         unimplemented!()
@@ -1,6 +1,7 @@
 use crate::core::ribosome::FnComponents;
 use crate::core::ribosome::HostAccess;
 use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
 use crate::core::ribosome::ZomesToInvoke;
 use derive_more::Constructor;
 use ai_hash::EntryHash;
@@ -26,6 +27,8 @@ pub struct InitHostAccess {
     pub workspace: HostFnWorkspace,
     pub keystore: KeystoreSender,
     pub network: AIngleP2pCell,
+    /// The conductor's limit on a single call to the `sleep` host function
+    pub max_sleep_per_call: std::time::Duration,
 }
 
 impl From<InitHostAccess> for HostAccess {
@@ -50,6 +53,9 @@ impl Invocation for InitInvocation {
     fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
         ExternIO::encode(())
     }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
 }
 
 impl TryFrom<InitInvocation> for ExternIO {
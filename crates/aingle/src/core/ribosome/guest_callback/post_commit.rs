@@ -1,6 +1,7 @@
 use crate::core::ribosome::FnComponents;
 use crate::core::ribosome::HostAccess;
 use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
 use crate::core::ribosome::ZomesToInvoke;
 use derive_more::Constructor;
 use aingle_keystore::KeystoreSender;
@@ -26,6 +27,8 @@ pub struct PostCommitHostAccess {
     pub workspace: HostFnWorkspace,
     pub keystore: KeystoreSender,
     pub network: AIngleP2pCell,
+    /// The conductor's limit on a single call to the `sleep` host function
+    pub max_sleep_per_call: std::time::Duration,
 }
 
 impl From<PostCommitHostAccess> for HostAccess {
@@ -50,6 +53,9 @@ impl Invocation for PostCommitInvocation {
     fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
         ExternIO::encode(self.headers)
     }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
 }
 
 impl TryFrom<PostCommitInvocation> for ExternIO {
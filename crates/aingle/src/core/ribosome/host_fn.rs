@@ -50,13 +50,13 @@ host_fn_api_impls! {
     // Info about the calling agent.
     fn agent_info (()) -> zt::info::AgentInfo;
 
-    // @todo
+    // Info about the installed app the cell belongs to.
     fn app_info (()) -> zt::info::AppInfo;
 
-    // @todo
+    // Info about the current saf.
     fn saf_info (()) -> zt::info::SafInfo;
 
-    // @todo
+    // Info about the current call.
     fn call_info (()) -> zt::info::CallInfo;
 
     fn call (zt::call::Call) -> zt::ZomeCallResponse;
@@ -64,14 +64,14 @@ host_fn_api_impls! {
     // Header hash of the DeleteLink element.
     fn call_remote (zt::call_remote::CallRemote) -> zt::ZomeCallResponse;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List all the local capability claims.
+    fn capability_claims (()) -> Vec<zt::capability::CapClaim>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List all the live local capability grants.
+    fn capability_grants (()) -> Vec<zt::capability::ZomeCallCapGrant>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the capability grant for the current zome call.
+    fn capability_info (()) -> zt::capability::CapGrant;
 
     // The EntryDefId determines how a create is handled on the host side.
     // CapGrant and CapClaim are handled natively.
@@ -124,6 +124,9 @@ host_fn_api_impls! {
 
     fn get_link_details (zt::link::GetLinksInput) -> zt::link::LinkDetails;
 
+    // Count the live links by entry hash from the cascade.
+    fn count_links (zt::link::GetLinksInput) -> usize;
+
     // Hash an entry on the host.
     fn hash_entry (zt::entry::Entry) -> ai_hash::EntryHash;
 
@@ -139,10 +142,10 @@ host_fn_api_impls! {
     // // @todo
     // fn send (()) -> ();
 
-    // @todo
-    fn schedule (core::time::Duration) -> ();
+    // Schedule a zome function in the current zome to be called in the future.
+    fn schedule (zt::schedule::Schedule) -> ();
 
-    // @todo
+    // Suspend the zome call for a duration.
     fn sleep (core::time::Duration) -> ();
 
     // @todo
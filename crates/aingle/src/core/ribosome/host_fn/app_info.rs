@@ -1,14 +1,70 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostAccess;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use aingle_types::prelude::*;
+use aingle_util::tokio_helper;
 use aingle_wasmer_host::prelude::WasmError;
-use aingle_zome_types::info::AppInfo;
+use std::sync::Arc;
 
+/// The installed app that the current cell belongs to.
+///
+/// Only zome calls have access to the conductor, so this errors for callbacks.
 pub fn app_info(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: (),
 ) -> Result<AppInfo, WasmError> {
-    unimplemented!()
+    let call_zome_handle = match &call_context.host_access {
+        HostAccess::ZomeCall(access) => access.call_zome_handle.clone(),
+        _ => {
+            return Err(WasmError::Host(
+                "app_info is only available to zome calls".to_string(),
+            ))
+        }
+    };
+    let cell_id = call_zome_handle.cell_id().clone();
+    let app_info =
+        tokio_helper::block_forever_on(async move { call_zome_handle.installed_app_info().await })
+            .map_err(|conductor_api_error| WasmError::Host(conductor_api_error.to_string()))?
+            .ok_or_else(|| {
+                WasmError::Host(format!("No installed app contains the cell {}", cell_id))
+            })?;
+    Ok(AppInfo {
+        installed_app_id: app_info.installed_app_id,
+        cells: app_info
+            .cell_data
+            .into_iter()
+            .map(|cell| {
+                let (cell_id, slot_id) = cell.into_inner();
+                (slot_id, cell_id)
+            })
+            .collect(),
+    })
 }
 
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod test {
+    use crate::sweettest::SweetConductor;
+    use crate::sweettest::SweetSafFile;
+    use aingle_types::prelude::*;
+    use aingle_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn invoke_import_app_info_test() {
+        observability::test_run().ok();
+        let (saf_file, _) = SweetSafFile::unique_from_test_wasms(vec![TestWasm::ZomeInfo])
+            .await
+            .unwrap();
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let app = conductor.setup_app("app", &[saf_file]).await.unwrap();
+        let cell_id = app.cells()[0].cell_id().clone();
+
+        let app_info: AppInfo = conductor
+            .call(&app.cells()[0].zome(TestWasm::ZomeInfo), "app_info", ())
+            .await;
+        assert_eq!(app_info.installed_app_id, "app");
+        assert_eq!(app_info.cells.len(), 1);
+        assert_eq!(app_info.cells.values().next(), Some(&cell_id));
+    }
+}
@@ -1,14 +1,78 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::InvocationAuth;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use aingle_types::prelude::*;
 use aingle_wasmer_host::prelude::WasmError;
-use aingle_zome_types::info::CallInfo;
+use std::sync::Arc;
 
 pub fn call_info(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: (),
 ) -> Result<CallInfo, WasmError> {
-    unimplemented!()
+    let source_chain = call_context.host_access.workspace().source_chain();
+    let (provenance, cap_grant) = match call_context.auth() {
+        InvocationAuth::Cap(provenance, cap_secret) => {
+            let check_function = (
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+            );
+            // The call was already authorized so there must be a grant
+            let cap_grant = source_chain
+                .valid_cap_grant(&check_function, &provenance, cap_secret.as_ref())
+                .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))?
+                .ok_or_else(|| {
+                    WasmError::Host(format!(
+                        "No valid cap grant for the current call to {:?}",
+                        check_function
+                    ))
+                })?;
+            (provenance, cap_grant)
+        }
+        InvocationAuth::LocalCallback => {
+            let author = source_chain.agent_pubkey().clone();
+            (author.clone(), CapGrant::ChainAuthor(author))
+        }
+    };
+    let as_at = source_chain
+        .chain_head()
+        .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))?;
+    Ok(CallInfo {
+        provenance,
+        function_name: call_context.function_name().clone(),
+        as_at,
+        cap_grant,
+    })
 }
 
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod test {
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use aingle_state::host_fn_workspace::HostFnWorkspace;
+    use aingle_wasm_test_utils::TestWasm;
+    use aingle_zome_types::prelude::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn invoke_import_call_info_test() {
+        let test_env = aingle_state::test_utils::test_cell_env();
+        let test_cache = aingle_state::test_utils::test_cache_env();
+        let env = test_env.env();
+        let author = fake_agent_pubkey_1();
+        crate::test_utils::fake_genesis(env.clone()).await.unwrap();
+        let workspace = HostFnWorkspace::new(env.clone(), test_cache.env(), author.clone())
+            .await
+            .unwrap();
+        let chain_head = workspace.source_chain().chain_head().unwrap();
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace;
+        let call_info: CallInfo =
+            crate::call_test_ribosome!(host_access, TestWasm::ZomeInfo, "call_info", ());
+        assert_eq!(call_info.function_name, "call_info".into());
+        assert_eq!(call_info.provenance, author);
+        assert_eq!(call_info.cap_grant, CapGrant::ChainAuthor(author));
+        assert_eq!(call_info.as_at, chain_head);
+    }
+}
@@ -58,6 +58,7 @@ pub mod wasm_test {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::WhoAmI.into()].into(),
+            rate_limit: None,
         };
         let saf_file = SafFile::new(saf_def, vec![TestWasm::WhoAmI.into()])
             .await
@@ -1,13 +1,65 @@
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use aingle_types::prelude::*;
 use aingle_wasmer_host::prelude::WasmError;
+use std::sync::Arc;
 
-/// lists all the local claims filtered by tag
+/// lists all the local claims
 pub fn capability_claims(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: (),
-) -> Result<(), WasmError> {
-    unimplemented!();
+) -> Result<Vec<CapClaimEntry>, WasmError> {
+    call_context
+        .host_access
+        .workspace()
+        .source_chain()
+        .cap_claims()
+        .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use adk::prelude::*;
+    use aingle_state::host_fn_workspace::HostFnWorkspace;
+    use aingle_types::fixt::CapSecretFixturator;
+    use aingle_types::test_utils::fake_agent_pubkey_1;
+    use aingle_types::test_utils::fake_agent_pubkey_2;
+    use aingle_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_capability_claims_test<'a>() {
+        observability::test_run().ok();
+        // test workspace boilerplate
+        let test_env = aingle_state::test_utils::test_cell_env();
+        let test_cache = aingle_state::test_utils::test_cache_env();
+        let env = test_env.env();
+        let author = fake_agent_pubkey_1();
+        crate::test_utils::fake_genesis(env.clone())
+            .await
+            .unwrap();
+        let workspace = HostFnWorkspace::new(env.clone(), test_cache.env(), author).await.unwrap();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace.clone();
+
+        let claims: Vec<CapClaimEntry> =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "capability_claims", ());
+        assert!(claims.is_empty());
+
+        let secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
+        let claim = CapClaim::new("claim".into(), fake_agent_pubkey_2(), secret);
+        let _header: HeaderHash = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Capability,
+            "accept_cap_claim",
+            claim.clone()
+        );
+
+        let claims: Vec<CapClaimEntry> =
+            crate::call_test_ribosome!(host_access, TestWasm::Capability, "capability_claims", ());
+        assert_eq!(claims, vec![claim]);
+    }
 }
@@ -1,16 +1,22 @@
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use aingle_types::prelude::*;
 use aingle_wasmer_host::prelude::WasmError;
 use std::sync::Arc;
 
-/// list all the grants stored locally in the chain filtered by tag
+/// list all the grants stored locally in the chain
 /// this is only the current grants as per local CRUD
 pub fn capability_grants(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: (),
-) -> Result<(), WasmError> {
-    unimplemented!();
+) -> Result<Vec<CapGrantEntry>, WasmError> {
+    call_context
+        .host_access
+        .workspace()
+        .source_chain()
+        .live_cap_grants()
+        .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))
 }
 
 #[cfg(test)]
@@ -147,6 +153,16 @@ pub mod wasm_test {
             .call(&bobbo, "transferable_cap_grant", original_secret)
             .await;
 
+        // BOB CAN LIST THE GRANT AND SEES HIS OWN AUTHORSHIP FOR HIS OWN CALLS
+
+        let grants: Vec<CapGrantEntry> = conductor.call(&bobbo, "capability_grants", ()).await;
+        assert!(grants
+            .iter()
+            .any(|grant| grant.access == CapAccess::from(original_secret)));
+
+        let cap_info: CapGrant = conductor.call(&bobbo, "capability_info", ()).await;
+        assert_eq!(cap_info, CapGrant::ChainAuthor(bob_agent_id.clone()));
+
         // ALICE CAN NOW CALL THE AUTHED REMOTE FN
 
         let response: ZomeCallResponse = conductor
@@ -229,6 +245,12 @@ pub mod wasm_test {
         // the inner response should be unauthorized
         assert_matches!(output, ZomeCallResponse::Unauthorized(_, _, _, _));
 
+        // only the unrestricted grant from init is still live
+        let grants: Vec<CapGrantEntry> = conductor.call(&bobbo, "capability_grants", ()).await;
+        assert!(grants
+            .iter()
+            .all(|grant| grant.access == CapAccess::Unrestricted));
+
         let mut conductor = conductor;
         conductor.shutdown().await;
 
@@ -1,14 +1,16 @@
+use super::call_info::call_info;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use aingle_types::prelude::*;
 use aingle_wasmer_host::prelude::WasmError;
+use std::sync::Arc;
 
 /// return the access info used for this call
-/// also return who is originated the call (pubkey)
+/// who originated the call is available from `call_info`
 pub fn capability_info(
-    _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
     _input: (),
-) -> Result<(), WasmError> {
-    unimplemented!();
+) -> Result<CapGrant, WasmError> {
+    Ok(call_info(ribosome, call_context, ())?.cap_grant)
 }
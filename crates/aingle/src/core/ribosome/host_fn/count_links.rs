@@ -0,0 +1,96 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::RibosomeT;
+use aingle_cascade::Cascade;
+use aingle_p2p::actor::GetLinksOptions;
+use aingle_types::prelude::*;
+use aingle_wasmer_host::prelude::WasmError;
+use std::sync::Arc;
+
+pub fn count_links(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetLinksInput,
+) -> Result<usize, WasmError> {
+    let GetLinksInput {
+        base_address,
+        tag_prefix,
+        filter,
+    } = input;
+
+    // Get zome id
+    let zome_id = ribosome
+        .zome_to_id(&call_context.zome)
+        .expect("Failed to get ID for current zome.");
+
+    // Get the network from the context
+    let network = call_context.host_access.network().clone();
+
+    tokio_helper::block_forever_on(async move {
+        // Create the key
+        let key = WireLinkKey {
+            base: base_address,
+            zome_id,
+            tag: tag_prefix,
+        };
+        let workspace = call_context.host_access.workspace();
+        let mut cascade = Cascade::from_workspace_network(workspace, network);
+
+        // Only the live links are sent back by the authorities
+        cascade
+            .count_links(
+                key,
+                GetLinksOptions {
+                    filter,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|cascade_error| WasmError::Host(cascade_error.to_string()))
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use ai_hash::HeaderHash;
+    use aingle_state::host_fn_workspace::HostFnWorkspace;
+    use aingle_wasm_test_utils::TestWasm;
+    use aingle_zome_types::fake_agent_pubkey_1;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_count_links() {
+        let test_env = aingle_state::test_utils::test_cell_env();
+        let test_cache = aingle_state::test_utils::test_cache_env();
+        let env = test_env.env();
+        let author = fake_agent_pubkey_1();
+        crate::test_utils::fake_genesis(env.clone()).await.unwrap();
+        let workspace = HostFnWorkspace::new(env.clone(), test_cache.env(), author)
+            .await
+            .unwrap();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace;
+
+        let count: usize =
+            crate::call_test_ribosome!(host_access, TestWasm::Link, "count_links", ());
+        assert_eq!(count, 0);
+
+        let link_one: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Link, "create_link", ());
+        let _: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Link, "create_link", ());
+
+        let count: usize =
+            crate::call_test_ribosome!(host_access, TestWasm::Link, "count_links", ());
+        assert_eq!(count, 2);
+
+        // Deleted links are not counted
+        let _: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Link, "delete_link", link_one);
+
+        let count: usize =
+            crate::call_test_ribosome!(host_access, TestWasm::Link, "count_links", ());
+        assert_eq!(count, 1);
+    }
+}
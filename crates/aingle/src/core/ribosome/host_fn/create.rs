@@ -209,6 +209,7 @@ pub mod wasm_test {
                 uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::MultipleCalls.into()].into(),
+                rate_limit: None,
             },
             vec![TestWasm::MultipleCalls.into()],
         )
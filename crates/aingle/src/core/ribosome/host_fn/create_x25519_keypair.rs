@@ -1,4 +1,5 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostAccess;
 use crate::core::ribosome::RibosomeT;
 use aingle_keystore::keystore_actor::KeystoreSenderExt;
 use aingle_util::tokio_helper;
@@ -6,19 +7,35 @@ use aingle_wasmer_host::prelude::WasmError;
 use aingle_zome_types::X25519PubKey;
 use std::sync::Arc;
 
+/// Create an x25519 keypair in the keystore.
+///
+/// Keypairs created by zome calls are recorded as belonging to the cell's
+/// agent, so app clients of the agent's app can use them too.
+/// Keypairs created by callbacks aren't recorded.
 pub fn create_x25519_keypair(
     _ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     _input: (),
 ) -> Result<X25519PubKey, WasmError> {
-    tokio_helper::block_forever_on(async move {
+    let call_zome_handle = match &call_context.host_access {
+        HostAccess::ZomeCall(access) => Some(access.call_zome_handle.clone()),
+        _ => None,
+    };
+    let key = tokio_helper::block_forever_on(async move {
         call_context
             .host_access
             .keystore()
             .create_x25519_keypair()
             .await
     })
-    .map_err(|keystore_error| WasmError::Host(keystore_error.to_string()))
+    .map_err(|keystore_error| WasmError::Host(keystore_error.to_string()))?;
+    if let Some(call_zome_handle) = call_zome_handle {
+        tokio_helper::block_forever_on(async move {
+            call_zome_handle.add_x25519_keypair(key).await
+        })
+        .map_err(|conductor_api_error| WasmError::Host(conductor_api_error.to_string()))?;
+    }
+    Ok(key)
 }
 
 // See x_25519_x_salsa20_poly1305_encrypt for testing encryption using created keypairs.
@@ -15,6 +15,7 @@ pub fn get_link_details<'a>(
     let GetLinksInput {
         base_address,
         tag_prefix,
+        ..
     } = input;
 
     // Get zome id
@@ -15,6 +15,7 @@ pub fn get_links<'a>(
     let GetLinksInput {
         base_address,
         tag_prefix,
+        filter,
     } = input;
 
     // Get zome id
@@ -37,7 +38,13 @@ pub fn get_links<'a>(
 
         // Get the links from the sgd
         let links = cascade
-            .sgd_get_links(key, GetLinksOptions::default())
+            .sgd_get_links(
+                key,
+                GetLinksOptions {
+                    filter,
+                    ..Default::default()
+                },
+            )
             .await
             .map_err(|cascade_error| WasmError::Host(cascade_error.to_string()))?;
 
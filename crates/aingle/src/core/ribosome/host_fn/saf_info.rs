@@ -1,14 +1,48 @@
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use ai_hash::HasHash;
+use aingle_types::prelude::*;
 use aingle_wasmer_host::prelude::WasmError;
-use aingle_zome_types::info::SafInfo;
+use std::sync::Arc;
 
 pub fn saf_info(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     _call_context: Arc<CallContext>,
     _input: (),
 ) -> Result<SafInfo, WasmError> {
-    unimplemented!()
+    let saf_def = ribosome.saf_def();
+    Ok(SafInfo {
+        name: saf_def.name.clone(),
+        hash: saf_def.as_hash().clone(),
+        properties: saf_def.properties.clone(),
+        uid: saf_def.uid.clone(),
+    })
 }
 
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod test {
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use aingle_state::host_fn_workspace::HostFnWorkspace;
+    use aingle_wasm_test_utils::TestWasm;
+    use aingle_zome_types::prelude::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn invoke_import_saf_info_test() {
+        let test_env = aingle_state::test_utils::test_cell_env();
+        let test_cache = aingle_state::test_utils::test_cache_env();
+        let env = test_env.env();
+        let author = fake_agent_pubkey_1();
+        crate::test_utils::fake_genesis(env.clone()).await.unwrap();
+        let workspace = HostFnWorkspace::new(env.clone(), test_cache.env(), author)
+            .await
+            .unwrap();
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace;
+        let saf_info: SafInfo =
+            crate::call_test_ribosome!(host_access, TestWasm::ZomeInfo, "saf_info", ());
+        assert_eq!(saf_info.name, "test",);
+    }
+}
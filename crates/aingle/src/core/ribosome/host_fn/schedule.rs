@@ -1,12 +1,151 @@
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use aingle_state::schedule::now_ms;
+use aingle_state::schedule::ScheduledFn;
 use aingle_wasmer_host::prelude::WasmError;
+use aingle_zome_types::schedule::Schedule;
+use aingle_zome_types::zome::ZomeName;
+use std::sync::Arc;
 
+/// Stage a function of the calling zome to be run in the future.
+/// The schedule is written along with the rest of the zome call so it is
+/// dropped if the zome call fails.
+/// The scheduler queue consumer picks it up once the zome call is done.
+/// Functions which the zome doesn't export are rejected here rather than
+/// failing every time the schedule comes due.
 pub fn schedule(
-    _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: core::time::Duration,
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: Schedule,
 ) -> Result<(), WasmError> {
-    unimplemented!()
+    let zome = call_context.zome();
+    let fn_exists = ribosome
+        .zome_fn_exists(&zome, &input.fn_name)
+        .map_err(|e| WasmError::Host(e.to_string()))?;
+    let zome_name: ZomeName = zome.into();
+    if !fn_exists {
+        return Err(WasmError::Host(
+            RibosomeError::ZomeFnNotExists(zome_name, input.fn_name).to_string(),
+        ));
+    }
+    let scheduled_fn = ScheduledFn::from_schedule(zome_name, input, now_ms());
+    call_context
+        .host_access
+        .workspace()
+        .source_chain()
+        .scratch()
+        .apply(|scratch| scratch.add_scheduled_fn(scheduled_fn))
+        .map_err(|e| WasmError::Host(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::conductor::api::error::ConductorApiResult;
+    use crate::sweettest::SweetConductor;
+    use crate::sweettest::SweetSafFile;
+    use adk::prelude::*;
+
+    fn zome(num_ticks: Arc<AtomicUsize>) -> InlineZome {
+        InlineZome::new_unique(vec![])
+            .callback("schedule_once", |api, ()| {
+                api.schedule(Schedule::once("tick", Duration::from_millis(10)))?;
+                Ok(())
+            })
+            .callback("schedule_recurring", |api, ()| {
+                api.schedule(Schedule::recurring(
+                    "tick",
+                    Duration::from_millis(10),
+                    Duration::from_millis(10),
+                ))?;
+                Ok(())
+            })
+            .callback("schedule_missing", |api, ()| {
+                api.schedule(Schedule::once("tock", Duration::from_millis(10)))?;
+                Ok(())
+            })
+            .callback("tick", move |_api, ()| {
+                num_ticks.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg(feature = "test_utils")]
+    async fn schedule_once_test() -> anyhow::Result<()> {
+        observability::test_run().ok();
+        let num_ticks = Arc::new(AtomicUsize::new(0));
+
+        let (saf_file, _) =
+            SweetSafFile::unique_from_inline_zome("zome1", zome(num_ticks.clone())).await?;
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let app = conductor.setup_app("app", &[saf_file]).await?;
+        let zome = app.cells()[0].zome("zome1");
+
+        let _: () = conductor.call(&zome, "schedule_once", ()).await;
+
+        crate::assert_eq_retry_10s!(num_ticks.load(Ordering::SeqCst), 1);
+
+        // A one-off schedule is removed once it has run.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(num_ticks.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg(feature = "test_utils")]
+    async fn schedule_recurring_test() -> anyhow::Result<()> {
+        observability::test_run().ok();
+        let num_ticks = Arc::new(AtomicUsize::new(0));
+
+        let (saf_file, _) =
+            SweetSafFile::unique_from_inline_zome("zome1", zome(num_ticks.clone())).await?;
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let app = conductor.setup_app("app", &[saf_file]).await?;
+        let zome = app.cells()[0].zome("zome1");
+
+        let _: () = conductor.call(&zome, "schedule_recurring", ()).await;
+
+        crate::wait_for_any_10s!(
+            num_ticks.load(Ordering::SeqCst),
+            |n: &usize| *n >= 3,
+            |n: usize| assert!(n >= 3)
+        );
+
+        // Rescheduling as a one-off stops the recurrence.
+        let _: () = conductor.call(&zome, "schedule_once", ()).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let n = num_ticks.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(num_ticks.load(Ordering::SeqCst), n);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg(feature = "test_utils")]
+    async fn schedule_missing_fn_test() -> anyhow::Result<()> {
+        observability::test_run().ok();
+        let num_ticks = Arc::new(AtomicUsize::new(0));
+
+        let (saf_file, _) =
+            SweetSafFile::unique_from_inline_zome("zome1", zome(num_ticks.clone())).await?;
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let app = conductor.setup_app("app", &[saf_file]).await?;
+        let zome = app.cells()[0].zome("zome1");
+
+        // The zome has no "tock" function, so nothing is scheduled.
+        let result: ConductorApiResult<()> =
+            conductor.call_fallible(&zome, "schedule_missing", ()).await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
 }
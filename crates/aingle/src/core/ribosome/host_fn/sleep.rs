@@ -1,12 +1,36 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostAccess;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
 use aingle_wasmer_host::prelude::WasmError;
+use std::sync::Arc;
+use std::time::Duration;
 
+/// Suspend the zome call for the requested duration.
+///
+/// Host functions are synchronous, so the calling wasm instance can't give up
+/// its thread while it waits. Zome calls run on tokio's blocking pool rather
+/// than on the runtime's worker threads, so a sleeping call holds one blocking
+/// pool thread for the duration of the sleep and leaves the rest of the
+/// conductor running. The conductor's `max_sleep_per_call_ms` keeps that time
+/// short, and longer sleeps are refused.
 pub fn sleep(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: core::time::Duration,
+    call_context: Arc<CallContext>,
+    input: Duration,
 ) -> Result<(), WasmError> {
-    unimplemented!()
+    let max_sleep = match &call_context.host_access {
+        HostAccess::ZomeCall(access) => access.call_zome_handle.max_sleep_per_call(),
+        HostAccess::Init(access) => access.max_sleep_per_call,
+        HostAccess::PostCommit(access) => access.max_sleep_per_call,
+        // Only callbacks that are allowed non-determinism can sleep
+        _ => Duration::from_millis(0),
+    };
+    if input > max_sleep {
+        return Err(WasmError::Host(format!(
+            "Requested sleep of {:?} is longer than the maximum of {:?}",
+            input, max_sleep
+        )));
+    }
+    aingle_util::tokio_helper::block_forever_on(tokio::time::sleep(input));
+    Ok(())
 }
@@ -24,11 +24,14 @@ pub fn x_25519_x_salsa20_poly1305_encrypt(
 #[cfg(feature = "slow_tests")]
 pub mod wasm_test {
 
+    use crate::conductor::api::CellConductorApi;
+    use crate::conductor::handle::MockConductorHandleT;
     use crate::fixt::ZomeCallHostAccessFixturator;
     use ::fixt::prelude::*;
     use adk::prelude::*;
     use aingle_state::host_fn_workspace::HostFnWorkspace;
     use aingle_wasm_test_utils::TestWasm;
+    use std::sync::Arc;
 
     #[tokio::test(flavor = "multi_thread")]
     async fn invoke_import_x_25519_x_salsa20_poly1305_encrypt_test() {
@@ -43,6 +46,16 @@ pub mod wasm_test {
 
         let mut host_access = fixt!(ZomeCallHostAccess);
         host_access.workspace = workspace;
+        // every created keypair is recorded as belonging to the cell's agent
+        let mut conductor_handle = MockConductorHandleT::new();
+        conductor_handle
+            .expect_add_x25519_key_agent()
+            .times(3)
+            .returning(|_, _| Ok(()));
+        host_access.call_zome_handle = Arc::new(CellConductorApi::new(
+            Arc::new(conductor_handle),
+            host_access.cell_id.clone(),
+        ));
         let alice: X25519PubKey = crate::call_test_ribosome!(
             host_access,
             TestWasm::XSalsa20Poly1305,
@@ -37,6 +37,7 @@ use crate::core::ribosome::host_fn::call_remote::call_remote;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
 use crate::core::ribosome::host_fn::capability_grants::capability_grants;
 use crate::core::ribosome::host_fn::capability_info::capability_info;
+use crate::core::ribosome::host_fn::count_links::count_links;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
 use crate::core::ribosome::host_fn::create_x25519_keypair::create_x25519_keypair;
@@ -282,14 +283,25 @@ impl RealRibosome {
             host_fn_builder
                 .with_host_function(&mut ns, "__zome_info", zome_info)
                 .with_host_function(&mut ns, "__app_info", app_info)
-                .with_host_function(&mut ns, "__saf_info", saf_info)
-                .with_host_function(&mut ns, "__call_info", call_info);
+                .with_host_function(&mut ns, "__saf_info", saf_info);
         } else {
             host_fn_builder
                 .with_host_function(&mut ns, "__zome_info", unreachable)
                 .with_host_function(&mut ns, "__app_info", unreachable)
-                .with_host_function(&mut ns, "__saf_info", unreachable)
-                .with_host_function(&mut ns, "__call_info", unreachable);
+                .with_host_function(&mut ns, "__saf_info", unreachable);
+        }
+
+        // The call info includes who is calling so, like agent info,
+        // it isn't available to validation.
+        if let HostFnAccess {
+            saf_bindings: Permission::Allow,
+            agent_info: Permission::Allow,
+            ..
+        } = host_fn_access
+        {
+            host_fn_builder.with_host_function(&mut ns, "__call_info", call_info);
+        } else {
+            host_fn_builder.with_host_function(&mut ns, "__call_info", unreachable);
         }
 
         if let HostFnAccess {
@@ -336,6 +348,7 @@ impl RealRibosome {
                 .with_host_function(&mut ns, "__get_details", get_details)
                 .with_host_function(&mut ns, "__get_links", get_links)
                 .with_host_function(&mut ns, "__get_link_details", get_link_details)
+                .with_host_function(&mut ns, "__count_links", count_links)
                 .with_host_function(&mut ns, "__get_agent_activity", get_agent_activity)
                 .with_host_function(&mut ns, "__query", query);
         } else {
@@ -344,6 +357,7 @@ impl RealRibosome {
                 .with_host_function(&mut ns, "__get_details", unreachable)
                 .with_host_function(&mut ns, "__get_links", unreachable)
                 .with_host_function(&mut ns, "__get_link_details", unreachable)
+                .with_host_function(&mut ns, "__count_links", unreachable)
                 .with_host_function(&mut ns, "__get_agent_activity", unreachable)
                 .with_host_function(&mut ns, "__query", unreachable);
         }
@@ -433,6 +447,8 @@ impl RibosomeT for RealRibosome {
     ) -> Result<Option<ExternIO>, RibosomeError> {
         let call_context = CallContext {
             zome: zome.clone(),
+            function_name: to_call.clone(),
+            auth: invocation.auth(),
             host_access,
         };
 
@@ -471,6 +487,17 @@ impl RibosomeT for RealRibosome {
         }
     }
 
+    fn zome_fn_exists(&self, zome: &Zome, fn_name: &FunctionName) -> RibosomeResult<bool> {
+        Ok(match zome.zome_def() {
+            ZomeDef::Wasm(_) => self
+                .module(zome.zome_name())?
+                .info()
+                .exports
+                .contains_key(fn_name.as_ref()),
+            ZomeDef::Inline(zome) => zome.has_callback(fn_name),
+        })
+    }
+
     fn call_iterator<I: crate::core::ribosome::Invocation>(
         &self,
         access: HostAccess,
@@ -9,6 +9,7 @@ use crate::conductor::entry_def_store::get_entry_def;
 use aingle_keystore::AgentPubKeyExt;
 use aingle_p2p::AIngleP2pCell;
 use aingle_types::prelude::*;
+use std::convert::TryFrom;
 use std::convert::TryInto;
 
 pub(super) use error::*;
@@ -44,11 +45,78 @@ pub async fn verify_header_signature(
     }
 }
 
+/// Verify the warrantor's signature for this warrant
+pub async fn verify_warrant_signature(warrant: &SignedWarrant) -> SysValidationResult<bool> {
+    Ok(warrant
+        .warrantor()
+        .verify_signature(warrant.signature(), warrant.warrant().clone())
+        .await?)
+}
+
 /// Verify the author key was valid at the time
-/// of signing with dpki
-/// TODO: This is just a stub until we have dpki.
-pub async fn author_key_is_valid(_author: &AgentPubKey) -> SysValidationResult<bool> {
-    Ok(true)
+/// of signing with dpki.
+/// Keys are always valid if the conductor has no key registry
+/// or the registry doesn't check this cell.
+/// If the registry can't answer the op waits until it can.
+pub async fn author_key_is_valid(
+    author: &AgentPubKey,
+    timestamp: Timestamp,
+    conductor_api: &impl CellConductorApiT,
+) -> SysValidationResult<bool> {
+    let key_registry = match conductor_api.key_registry() {
+        Some(key_registry) => key_registry,
+        None => return Ok(true),
+    };
+    let key_state = match key_registry.checks_cell(conductor_api.cell_id()).await {
+        Ok(true) => key_registry.key_state(author).await,
+        Ok(false) => return Ok(true),
+        Err(e) => Err(e),
+    };
+    match key_state {
+        Ok(key_state) => Ok(key_state.is_valid_at(timestamp)),
+        Err(e) => Err(ValidationOutcome::KeyRegistryUnavailable(e.to_string()).into()),
+    }
+}
+
+/// Check that the header wasn't authored after
+/// its author's key was revoked or rotated
+pub async fn check_author_key(
+    header: &Header,
+    conductor_api: &impl CellConductorApiT,
+) -> SysValidationResult<()> {
+    if author_key_is_valid(header.author(), header.timestamp(), conductor_api).await? {
+        Ok(())
+    } else {
+        Err(ValidationOutcome::InvalidAuthorKey(header.author().clone(), header.timestamp()).into())
+    }
+}
+
+/// Check that the warrant wasn't issued after
+/// its warrantor's key was revoked or rotated
+pub async fn check_warrantor_key(
+    warrant: &SignedWarrant,
+    conductor_api: &impl CellConductorApiT,
+) -> SysValidationResult<()> {
+    let warrant = warrant.warrant();
+    if author_key_is_valid(&warrant.warrantor, warrant.timestamp, conductor_api).await? {
+        Ok(())
+    } else {
+        Err(
+            ValidationOutcome::InvalidAuthorKey(warrant.warrantor.clone(), warrant.timestamp)
+                .into(),
+        )
+    }
+}
+
+/// Check that a warrant was issued for this header
+/// and names its author as the offender.
+pub fn check_warrant(header: &Header, warrant: &SignedWarrant) -> SysValidationResult<()> {
+    let warrant = warrant.warrant();
+    if warrant.author == *header.author() && warrant.header == HeaderHash::with_data_sync(header) {
+        Ok(())
+    } else {
+        Err(ValidationOutcome::WarrantMismatch(warrant.clone()).into())
+    }
 }
 
 /// Check that previous header makes sense
@@ -89,33 +157,52 @@ pub async fn check_valid_if_saf(
     }
 }
 
-/// Check if there are other headers at this
-/// sequence number
-pub async fn check_chain_rollback(
+/// Check the author isn't publishing headers faster than
+/// the SAF's [`RateLimit`] allows.
+/// Header timestamps always increase along a chain so if the header
+/// `max_headers` positions back is inside the window then this header
+/// is one too many.
+/// SAFs without a rate limit, or with a `max_headers` of 0, always pass.
+/// If any header between the two isn't held yet the op waits for it.
+pub async fn check_spam(
     header: &Header,
     workspace: &SysValidationWorkspace,
+    conductor_api: &impl CellConductorApiT,
 ) -> SysValidationResult<()> {
-    let empty = workspace.header_seq_is_empty(header)?;
-
-    // Ok or log warning
-    if empty {
-        Ok(())
-    } else {
-        // TODO: implement real rollback detection once we know what that looks like
-        tracing::error!(
-            "Chain rollback detected at position {} for agent {:?} from header {:?}",
-            header.header_seq(),
-            header.author(),
-            header,
-        );
-        Ok(())
+    let saf_file = conductor_api.get_this_saf().await.map_err(Box::new)?;
+    let rate_limit = match saf_file.saf_def().rate_limit {
+        Some(rate_limit) if rate_limit.max_headers > 0 => rate_limit,
+        _ => return Ok(()),
+    };
+    let earlier_seq = match header.header_seq().checked_sub(rate_limit.max_headers) {
+        Some(earlier_seq) => earlier_seq,
+        // Not enough headers to go over the limit.
+        None => return Ok(()),
+    };
+    // Follow this header's own chain back so a fork can't hide the
+    // earlier header. Ops can arrive in any order, so wait for any
+    // header that isn't held yet.
+    let mut earlier = header.clone();
+    while earlier.header_seq() > earlier_seq {
+        let prev_header_hash = match earlier.prev_header() {
+            Some(prev_header_hash) => prev_header_hash.clone(),
+            None => return Ok(()),
+        };
+        earlier = workspace
+            .header(&prev_header_hash)?
+            .ok_or_else(|| ValidationOutcome::not_holding(&prev_header_hash))?;
+    }
+    let window =
+        chrono::Duration::milliseconds(i64::try_from(rate_limit.window_ms).unwrap_or(i64::MAX));
+    match header
+        .timestamp()
+        .checked_difference_signed(&earlier.timestamp())
+    {
+        Some(elapsed) if elapsed < window => {
+            Err(ValidationOutcome::RateLimitExceeded(rate_limit, header.header_seq()).into())
+        }
+        _ => Ok(()),
     }
-}
-
-/// Placeholder for future spam check.
-/// Check header timestamps don't exceed MAX_PUBLISH_FREQUENCY
-pub async fn check_spam(_header: &Header) -> SysValidationResult<()> {
-    Ok(())
 }
 
 /// Check previous header timestamp is before this header
@@ -8,6 +8,7 @@ use crate::core::workflow::error::WorkflowError;
 use crate::from_sub_error;
 use ai_hash::AnySgdHash;
 use ai_hash::HeaderHash;
+use ai_hash::SgdOpHash;
 use aingle_keystore::KeystoreError;
 use aingle_sqlite::error::DatabaseError;
 use aingle_state::workspace::WorkspaceError;
@@ -101,6 +102,10 @@ pub enum ValidationOutcome {
     EntryVisibility(AppEntryType),
     #[error("The link tag size {0} was bigger then the MAX_TAG_SIZE {1}")]
     TagTooLarge(usize, usize),
+    #[error("The author key {0} was revoked or rotated before the header was authored at {1}")]
+    InvalidAuthorKey(AgentPubKey, Timestamp),
+    #[error("The key registry couldn't say whether the author key was valid: {0}")]
+    KeyRegistryUnavailable(String),
     #[error("The header {0:?} was expected to be a link add header")]
     NotCreateLink(HeaderHash),
     #[error("The header was expected to be a new entry header but was a {0:?}")]
@@ -111,10 +116,16 @@ pub enum ValidationOutcome {
     PrevHeaderError(#[from] PrevHeaderError),
     #[error("StoreEntry should not be gossiped for private entries")]
     PrivateEntry,
+    #[error("Header {1} was published faster than the SAF's rate limit {0:?} allows")]
+    RateLimitExceeded(RateLimit, u32),
     #[error("Update original EntryType: {0:?} doesn't match new EntryType {1:?}")]
     UpdateTypeMismatch(EntryType, EntryType),
     #[error("Signature {0:?} failed to verify for Header {1:?}")]
     VerifySignature(Signature, Header),
+    #[error("The warrant {0:?} doesn't match the header it was issued for")]
+    WarrantMismatch(Warrant),
+    #[error("The op {0:?} that the warrant was issued for is valid")]
+    WarrantedOpValid(SgdOpHash),
     #[error("The app entry type {0:?} zome id was out of range")]
     ZomeId(AppEntryType),
 }
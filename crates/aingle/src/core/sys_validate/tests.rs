@@ -1,12 +1,15 @@
 use super::*;
 use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::MockCellConductorApi;
+use crate::conductor::dpki::*;
 use crate::test_utils::fake_genesis;
 use ::fixt::prelude::*;
 use error::SysValidationError;
 
 use aingle_keystore::AgentPubKeyExt;
 use aingle_middleware_bytes::SerializedBytes;
+use aingle_sqlite::db::WriteManager;
+use aingle_state::prelude::insert_header;
 use aingle_state::prelude::test_cache_env;
 use aingle_state::prelude::test_cell_env;
 use aingle_wasm_test_utils::TestWasm;
@@ -14,6 +17,7 @@ use aingle_zome_types::Header;
 use matches::assert_matches;
 use observability;
 use std::convert::TryFrom;
+use std::sync::Arc;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn verify_header_signature_test() {
@@ -152,6 +156,214 @@ async fn check_previous_seq() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn check_author_key_test() {
+    let key_registry = Arc::new(InMemoryKeyRegistry::new());
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api.expect_cell_id().return_const(fixt!(CellId));
+    conductor_api.expect_key_registry().returning({
+        let key_registry = key_registry.clone();
+        move || Some(key_registry.clone())
+    });
+
+    let mut header = fixt!(CreateLink);
+    header.timestamp = Timestamp(1_000, 0);
+    let header: Header = header.into();
+
+    // Every key is valid until the registry says otherwise.
+    assert_matches!(check_author_key(&header, &conductor_api).await, Ok(()));
+
+    // Rotating the key afterwards doesn't invalidate earlier headers.
+    key_registry.rotate(
+        header.author().clone(),
+        fixt!(AgentPubKey),
+        Timestamp(1_001, 0),
+    );
+    assert_matches!(check_author_key(&header, &conductor_api).await, Ok(()));
+
+    // A header authored after the key was revoked is invalid.
+    key_registry.revoke(header.author().clone(), Timestamp(999, 0));
+    assert_matches!(
+        check_author_key(&header, &conductor_api).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::InvalidAuthorKey(a, t)
+        )) if a == *header.author() && t == header.timestamp()
+    );
+
+    // Without a registry every key is valid.
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api.expect_key_registry().returning(|| None);
+    assert_matches!(check_author_key(&header, &conductor_api).await, Ok(()));
+
+    // The op waits if the registry can't answer.
+    struct UnavailableKeyRegistry;
+    #[async_trait::async_trait]
+    impl KeyRegistry for UnavailableKeyRegistry {
+        async fn key_state(&self, _key: &AgentPubKey) -> KeyRegistryResult<KeyState> {
+            Err(KeyRegistryError::Unavailable("Down".into()))
+        }
+    }
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api.expect_cell_id().return_const(fixt!(CellId));
+    conductor_api
+        .expect_key_registry()
+        .returning(|| Some(Arc::new(UnavailableKeyRegistry)));
+    assert_matches!(
+        check_author_key(&header, &conductor_api).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::KeyRegistryUnavailable(_)
+        ))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn check_warrantor_key_test() {
+    let key_registry = Arc::new(InMemoryKeyRegistry::new());
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api.expect_cell_id().return_const(fixt!(CellId));
+    conductor_api.expect_key_registry().returning({
+        let key_registry = key_registry.clone();
+        move || Some(key_registry.clone())
+    });
+
+    let header: Header = fixt!(CreateLink).into();
+    let warrantor = fixt!(AgentPubKey);
+    let warrant = SignedWarrant::new(
+        Warrant {
+            author: header.author().clone(),
+            header: HeaderHash::with_data_sync(&header),
+            op: fixt!(SgdOpHash),
+            reason: WarrantReason::SysValidation("Bad".into()),
+            warrantor: warrantor.clone(),
+            timestamp: Timestamp(1_000, 0),
+        },
+        fixt!(Signature),
+    );
+
+    // The offender's key being revoked doesn't matter.
+    key_registry.revoke(header.author().clone(), Timestamp(0, 0));
+    assert_matches!(check_warrantor_key(&warrant, &conductor_api).await, Ok(()));
+
+    // A warrant issued after the warrantor's key was revoked is invalid.
+    key_registry.revoke(warrantor.clone(), Timestamp(999, 0));
+    assert_matches!(
+        check_warrantor_key(&warrant, &conductor_api).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::InvalidAuthorKey(a, t)
+        )) if a == warrantor && t == Timestamp(1_000, 0)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn check_spam_test() {
+    let tmp = test_cell_env();
+    let tmp_cache = test_cache_env();
+    let env = tmp.env();
+    let workspace = SysValidationWorkspace::new(env.clone(), tmp_cache.env());
+
+    let rate_limit = RateLimit {
+        max_headers: 2,
+        window_ms: 1000,
+    };
+    let saf_with_limit = |rate_limit| async move {
+        SafFile::new(
+            SafDef {
+                name: "check_spam_test".to_string(),
+                uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
+                properties: SerializedBytes::try_from(()).unwrap(),
+                zomes: vec![TestWasm::Create.into()].into(),
+                rate_limit: Some(rate_limit),
+            },
+            vec![TestWasm::Create.into()],
+        )
+        .await
+        .unwrap()
+    };
+    let saf_file = saf_with_limit(rate_limit).await;
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api
+        .expect_sync_get_this_saf()
+        .returning(move || Ok(saf_file.clone()));
+
+    let mut earlier = fixt!(CreateLink);
+    let author = earlier.author.clone();
+    earlier.header_seq = 1;
+    earlier.timestamp = Timestamp(1_000, 0);
+    let earlier = HeaderHashed::from_content_sync(earlier.into());
+    let mut middle = fixt!(CreateLink);
+    middle.author = author.clone();
+    middle.header_seq = 2;
+    middle.prev_header = earlier.as_hash().clone();
+    middle.timestamp = Timestamp(1_000, 250_000_000);
+    let middle = HeaderHashed::from_content_sync(middle.into());
+    let mut header = fixt!(CreateLink);
+    header.author = author;
+
+    // Too early in the chain to go over the limit.
+    header.header_seq = 1;
+    header.timestamp = Timestamp(1_000, 0);
+    assert_matches!(
+        check_spam(&header.clone().into(), &workspace, &conductor_api).await,
+        Ok(())
+    );
+
+    // A third header within a second of the first.
+    header.header_seq = 3;
+    header.prev_header = middle.as_hash().clone();
+    header.timestamp = Timestamp(1_000, 500_000_000);
+
+    // The headers before it haven't arrived yet.
+    let insert = |header: HeaderHashed| {
+        let shh = SignedHeaderHashed::with_presigned(header, fixt!(Signature));
+        env.conn()
+            .unwrap()
+            .with_commit_test(|txn| insert_header(txn, shh).unwrap())
+            .unwrap();
+    };
+    assert_matches!(
+        check_spam(&header.clone().into(), &workspace, &conductor_api).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotHoldingDep(h)
+        )) if h == AnySgdHash::from(middle.as_hash().clone())
+    );
+    insert(middle);
+    assert_matches!(
+        check_spam(&header.clone().into(), &workspace, &conductor_api).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotHoldingDep(h)
+        )) if h == AnySgdHash::from(earlier.as_hash().clone())
+    );
+    insert(earlier);
+    assert_matches!(
+        check_spam(&header.clone().into(), &workspace, &conductor_api).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::RateLimitExceeded(r, 3)
+        )) if r == rate_limit
+    );
+
+    // A limit of no headers is no limit.
+    let saf_file = saf_with_limit(RateLimit {
+        max_headers: 0,
+        window_ms: 1000,
+    })
+    .await;
+    let mut unlimited_api = MockCellConductorApi::new();
+    unlimited_api
+        .expect_sync_get_this_saf()
+        .returning(move || Ok(saf_file.clone()));
+    assert_matches!(
+        check_spam(&header.clone().into(), &workspace, &unlimited_api).await,
+        Ok(())
+    );
+
+    // The window has passed.
+    header.timestamp = Timestamp(1_001, 0);
+    assert_matches!(
+        check_spam(&header.clone().into(), &workspace, &conductor_api).await,
+        Ok(())
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn check_entry_type_test() {
     let entry_fixt = EntryFixturator::new(Predictable);
@@ -287,6 +499,7 @@ async fn check_app_entry_type_test() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::EntryDefs.into()].into(),
+            rate_limit: None,
         },
         vec![TestWasm::EntryDefs.into()],
     )
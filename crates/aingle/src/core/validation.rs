@@ -44,6 +44,7 @@ pub enum SgdOpOrder {
     RegisterDeletedEntryHeader(aingle_zome_types::timestamp::Timestamp),
     RegisterAddLink(aingle_zome_types::timestamp::Timestamp),
     RegisterRemoveLink(aingle_zome_types::timestamp::Timestamp),
+    RegisterWarrant(aingle_zome_types::timestamp::Timestamp),
 }
 
 /// Op data that will be ordered by [SgdOpOrder]
@@ -88,6 +89,7 @@ impl From<&SgdOp> for SgdOpOrder {
             SgdOp::RegisterDeletedEntryHeader(_, h) => RegisterDeletedEntryHeader(h.timestamp),
             SgdOp::RegisterAddLink(_, h) => RegisterAddLink(h.timestamp),
             SgdOp::RegisterRemoveLink(_, h) => RegisterRemoveLink(h.timestamp),
+            SgdOp::RegisterWarrant(_, _, w) => RegisterWarrant(w.warrant.timestamp),
         }
     }
 }
@@ -0,0 +1,71 @@
+//! Issuing warrants for ops that failed validation
+
+use super::workflow::error::WorkflowResult;
+use ai_hash::SgdOpHash;
+use aingle_keystore::AgentPubKeyExt;
+use aingle_p2p::AIngleP2pCell;
+use aingle_p2p::AIngleP2pCellT;
+use aingle_state::prelude::*;
+use aingle_types::prelude::*;
+use tracing::*;
+
+/// Sign a warrant against the author of this invalid op,
+/// store it and publish it to the author's agent activity authorities.
+///
+/// The warrant is stored like any other incoming op,
+/// so it is validated and integrated by this node as well.
+/// If it can't be published now, the publish workflow sends it later.
+pub(crate) async fn issue_warrant(
+    env: &EnvWrite,
+    network: &AIngleP2pCell,
+    op: &SgdOp,
+    op_hash: SgdOpHash,
+    reason: WarrantReason,
+) -> WorkflowResult<()> {
+    let warrantor = network.from_agent();
+    let (signature, header, _) = op.clone().into_inner();
+    let warrant = Warrant {
+        author: header.author().clone(),
+        header: HeaderHash::with_data_sync(&header),
+        op: op_hash,
+        reason,
+        warrantor: warrantor.clone(),
+        timestamp: aingle_types::timestamp::now(),
+    };
+    let warrant_signature = warrantor.sign(&env.keystore(), warrant.clone()).await?;
+    let warrant = SignedWarrant::new(warrant, warrant_signature);
+    let warrant_op = SgdOpHashed::from_content_sync(SgdOp::RegisterWarrant(
+        signature,
+        header,
+        Box::new(warrant),
+    ));
+    let basis = warrant_op.as_content().sgd_basis();
+    let warrant_op_hash = warrant_op.as_hash().clone();
+    let to_publish = vec![(warrant_op_hash.clone(), warrant_op.as_content().clone())];
+
+    env.async_commit(move |txn| insert_op(txn, warrant_op, false))
+        .await?;
+
+    match network
+        .clone()
+        .publish(false, basis, to_publish, None)
+        .await
+    {
+        Ok(()) => {
+            let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?;
+            env.async_commit(move |txn| set_last_publish_time(txn, warrant_op_hash, now))
+                .await?;
+        }
+        Err(e) => info!(failed_to_send_warrant = ?e),
+    }
+    Ok(())
+}
+
+/// Find the op a warrant was issued for among the ops of its element,
+/// so that the op can be validated again before the warrant is accepted.
+pub(crate) fn warranted_op(element: &Element, warrant: &Warrant) -> Option<SgdOp> {
+    produce_ops_from_element(element)
+        .ok()?
+        .into_iter()
+        .find(|op| SgdOpHash::with_data_sync(op) == warrant.op)
+}
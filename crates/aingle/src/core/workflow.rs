@@ -29,7 +29,9 @@ pub mod genesis_workflow;
 pub mod incoming_sgd_ops_workflow;
 pub mod initialize_zomes_workflow;
 pub mod integrate_sgd_ops_workflow;
+pub mod migrate_agent_workflow;
 pub mod publish_sgd_ops_workflow;
+pub mod schedule_workflow;
 pub mod sys_validation_workflow;
 pub mod validation_receipt_workflow;
 
@@ -28,6 +28,8 @@ use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::Invocation;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomesToInvoke;
+use crate::core::warrant::issue_warrant;
+use crate::core::warrant::warranted_op;
 use error::AppValidationResult;
 pub use error::*;
 use ai_hash::SgdOpHash;
@@ -38,6 +40,7 @@ use aingle_p2p::AIngleP2pCellT;
 use aingle_state::host_fn_workspace::HostFnWorkspace;
 use aingle_state::prelude::*;
 use aingle_types::prelude::*;
+use futures::future::FutureExt;
 use tracing::*;
 pub use types::Outcome;
 
@@ -73,6 +76,8 @@ async fn app_validation_workflow_inner(
 ) -> WorkflowResult<WorkComplete> {
     let env = workspace.vault.clone().into();
     let sorted_ops = validation_query::get_ops_to_app_validate(&env).await?;
+    let mut signal_tx = conductor_api.signal_broadcaster().await;
+    let (mut validated, mut rejected) = (Vec::new(), Vec::new());
 
     // Validate all the ops
     for so in sorted_ops {
@@ -95,25 +100,55 @@ async fn app_validation_workflow_inner(
         match outcome {
             Outcome::Accepted => {
                 workspace
-                    .put_integration_limbo(op_hash, ValidationStatus::Valid)
+                    .put_integration_limbo(op_hash.clone(), ValidationStatus::Valid)
                     .await?;
+                validated.push(op_hash);
             }
             Outcome::AwaitingDeps(deps) => {
                 let status = ValidationLimboStatus::AwaitingAppDeps(deps);
                 workspace.put_validation_limbo(op_hash, status).await?;
             }
-            Outcome::Rejected(_) => {
+            Outcome::Rejected(reason) => {
                 if *op.header().author() == network.from_agent() {
                     tracing::warn!("Authored invalid op! If you didn't hack your node, this is a bug in AIngle.\nOp: {:?}", op.to_light());
                 } else {
-                    tracing::warn!("Received invalid op! Warrants aren't implemented yet, so we can't do anything about this right now, but be warned that somebody on the network has maliciously hacked their node.\nOp: {:?}", op.to_light());
+                    tracing::warn!(
+                        "Received invalid op! Issuing a warrant against its author.\nOp: {:?}",
+                        op.to_light()
+                    );
+                    // Failing to issue a warrant doesn't change this op's outcome.
+                    if let Err(e) = issue_warrant(
+                        &workspace.vault,
+                        network,
+                        &op,
+                        op_hash.clone(),
+                        WarrantReason::AppValidation(reason),
+                    )
+                    .await
+                    {
+                        error!(?e, ?op_hash, "Failed to issue a warrant");
+                    }
                 }
                 workspace
-                    .put_integration_limbo(op_hash, ValidationStatus::Rejected)
+                    .put_integration_limbo(op_hash.clone(), ValidationStatus::Rejected)
                     .await?;
+                rejected.push(op_hash);
             }
         }
     }
+
+    if !validated.is_empty() {
+        signal_tx.send_system(SystemSignal::OpsValidated {
+            cell_id: conductor_api.cell_id().clone(),
+            op_hashes: validated,
+        });
+    }
+    if !rejected.is_empty() {
+        signal_tx.send_system(SystemSignal::OpsRejected {
+            cell_id: conductor_api.cell_id().clone(),
+            op_hashes: rejected,
+        });
+    }
     Ok(WorkComplete::Complete)
 }
 
@@ -131,6 +166,10 @@ async fn validate_op(
     workspace: &mut AppValidationWorkspace,
     network: &AIngleP2pCell,
 ) -> AppValidationOutcome<Outcome> {
+    if let SgdOp::RegisterWarrant(_, _, warrant) = &op {
+        return validate_warranted_op(warrant.warrant(), conductor_api, workspace, network).await;
+    }
+
     // Get the workspace for the validation calls
     let workspace_lock = workspace.validation_workspace(network.from_agent()).await?;
 
@@ -236,6 +275,44 @@ async fn validate_op(
     Ok(outcome)
 }
 
+/// Warrants only reach app validation when the op they were issued for
+/// passed sys validation, so they are accepted only if app validation
+/// rejects that op.
+async fn validate_warranted_op(
+    warrant: &Warrant,
+    conductor_api: &impl CellConductorApiT,
+    workspace: &mut AppValidationWorkspace,
+    network: &AIngleP2pCell,
+) -> AppValidationOutcome<Outcome> {
+    let element = workspace
+        .full_cascade(network.clone())
+        .retrieve(warrant.header.clone().into(), Default::default())
+        .await?
+        .ok_or_else(|| Outcome::awaiting(&warrant.header))?;
+    let op = match warranted_op(&element, warrant) {
+        Some(op) => op,
+        None => {
+            return Outcome::exit_with_rejected(format!(
+                "The warrant {:?} doesn't match the header it was issued for",
+                warrant
+            ))
+        }
+    };
+    // Boxed because validating a warrant validates another op
+    let outcome = validate_op(op, conductor_api, workspace, network)
+        .boxed()
+        .await
+        .or_else(|outcome_or_err| outcome_or_err.try_into())?;
+    match outcome {
+        Outcome::Rejected(_) => Ok(Outcome::Accepted),
+        Outcome::Accepted => Outcome::exit_with_rejected(format!(
+            "The op {:?} that the warrant was issued for is valid",
+            warrant.op
+        )),
+        awaiting @ Outcome::AwaitingDeps(_) => Ok(awaiting),
+    }
+}
+
 /// Get the [EntryDef] associated with this
 /// element if there is one.
 ///
@@ -266,7 +343,7 @@ async fn get_associated_entry_def(
 /// validate this op.
 fn get_element(op: SgdOp) -> AppValidationOutcome<Element> {
     match op {
-        SgdOp::RegisterAgentActivity(_, _) => Outcome::accepted(),
+        SgdOp::RegisterAgentActivity(_, _) | SgdOp::RegisterWarrant(_, _, _) => Outcome::accepted(),
         SgdOp::StoreElement(s, h, e) => match h {
             Header::Delete(_) | Header::CreateLink(_) | Header::DeleteLink(_) => Ok(Element::new(
                 SignedHeaderHashed::with_presigned(HeaderHashed::from_content_sync(h), s),
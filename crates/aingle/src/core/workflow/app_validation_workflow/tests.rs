@@ -1,5 +1,6 @@
 use crate::conductor::ConductorHandle;
 use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::workflow::incoming_sgd_ops_workflow::incoming_sgd_ops_workflow;
 use crate::test_utils::host_fn_caller::*;
 use crate::test_utils::new_invocation;
 use crate::test_utils::new_zome_call;
@@ -8,6 +9,8 @@ use crate::test_utils::wait_for_integration;
 use ai_hash::AnySgdHash;
 use ai_hash::EntryHash;
 use ai_hash::HeaderHash;
+use ai_hash::SgdOpHash;
+use aingle_keystore::AgentPubKeyExt;
 use aingle_middleware_bytes::SerializedBytes;
 use aingle_state::prelude::fresh_reader_test;
 use aingle_state::prelude::from_blob;
@@ -18,6 +21,7 @@ use aingle_wasm_test_utils::TestWasm;
 use aingle_zome_types::Entry;
 use aingle_zome_types::ValidationStatus;
 use rusqlite::named_params;
+use rusqlite::OptionalExtension;
 use rusqlite::Transaction;
 use std::convert::TryFrom;
 use std::convert::TryInto;
@@ -38,6 +42,7 @@ async fn app_validation_workflow_test() {
                 TestWasm::Create.into(),
             ]
             .into(),
+            rate_limit: None,
         },
         vec![
             TestWasm::Validate.into(),
@@ -73,13 +78,14 @@ async fn app_validation_workflow_test() {
     )
     .await;
     run_test_entry_def_id(
-        alice_cell_id,
-        bob_cell_id,
+        alice_cell_id.clone(),
+        bob_cell_id.clone(),
         handle.clone(),
         &saf_file,
         expected_count,
     )
     .await;
+    run_test_forged_warrant(alice_cell_id, bob_cell_id, handle.clone()).await;
 
     let shutdown = handle.take_shutdown_handle().await.unwrap();
     handle.shutdown().await;
@@ -208,9 +214,22 @@ fn show_limbo(txn: &Transaction) -> Vec<SgdOpLight> {
 
 fn num_valid(txn: &Transaction) -> usize {
     txn
-    .query_row("SELECT COUNT(hash) FROM SgdOP WHERE when_integrated IS NOT NULL AND validation_status = :status", 
+    .query_row("SELECT COUNT(hash) FROM SgdOP WHERE when_integrated IS NOT NULL AND validation_status = :status AND type != :warrant", 
+            named_params!{
+                ":status": ValidationStatus::Valid,
+                ":warrant": SgdOpType::RegisterWarrant,
+            },
+            |row| row.get(0))
+            .unwrap()
+}
+
+// Alice issues a warrant for every one of bob's ops that she rejects.
+fn num_warrants(txn: &Transaction) -> usize {
+    txn
+    .query_row("SELECT COUNT(hash) FROM SgdOP WHERE when_integrated IS NOT NULL AND validation_status = :status AND type = :warrant", 
             named_params!{
                 ":status": ValidationStatus::Valid,
+                ":warrant": SgdOpType::RegisterWarrant,
             },
             |row| row.get(0))
             .unwrap()
@@ -260,7 +279,13 @@ async fn run_test(
     // So they will be valid.
     let expected_count = 3 + expected_count;
     let alice_env = handle.get_cell_env(&alice_cell_id).await.unwrap();
-    wait_for_integration(&alice_env, expected_count, num_attempts, delay_per_attempt).await;
+    wait_for_integration(
+        &alice_env,
+        expected_count + 2,
+        num_attempts,
+        delay_per_attempt,
+    )
+    .await;
 
     fresh_reader_test(alice_env, |txn| {
         // Validation should be empty
@@ -273,6 +298,7 @@ async fn run_test(
             &invalid_entry_hash
         ));
         assert_eq!(num_valid(&txn), expected_count - 2);
+        assert_eq!(num_warrants(&txn), 2);
     });
 
     let invocation =
@@ -282,7 +308,13 @@ async fn run_test(
     // Integration should have 6 ops in it
     let expected_count = 6 + expected_count;
     let alice_env = handle.get_cell_env(&alice_cell_id).await.unwrap();
-    wait_for_integration(&alice_env, expected_count, num_attempts, delay_per_attempt).await;
+    wait_for_integration(
+        &alice_env,
+        expected_count + 2,
+        num_attempts,
+        delay_per_attempt,
+    )
+    .await;
 
     fresh_reader_test(alice_env, |txn| {
         // Validation should be empty
@@ -295,6 +327,7 @@ async fn run_test(
             &invalid_entry_hash
         ));
         assert_eq!(num_valid(&txn), expected_count - 2);
+        assert_eq!(num_warrants(&txn), 2);
     });
 
     let invocation =
@@ -308,7 +341,13 @@ async fn run_test(
     // Integration should have 9 ops in it
     let expected_count = 9 + expected_count;
     let alice_env = handle.get_cell_env(&alice_cell_id).await.unwrap();
-    wait_for_integration(&alice_env, expected_count, num_attempts, delay_per_attempt).await;
+    wait_for_integration(
+        &alice_env,
+        expected_count + 4,
+        num_attempts,
+        delay_per_attempt,
+    )
+    .await;
 
     fresh_reader_test(alice_env, |txn| {
         // Validation should be empty
@@ -322,6 +361,7 @@ async fn run_test(
         ));
         assert!(expected_invalid_link(&txn, &invalid_link_hash));
         assert_eq!(num_valid(&txn), expected_count - 4);
+        assert_eq!(num_warrants(&txn), 4);
     });
 
     let invocation = new_invocation(
@@ -336,7 +376,13 @@ async fn run_test(
     // Integration should have 9 ops in it
     let expected_count = 9 + expected_count;
     let alice_env = handle.get_cell_env(&alice_cell_id).await.unwrap();
-    wait_for_integration(&alice_env, expected_count, num_attempts, delay_per_attempt).await;
+    wait_for_integration(
+        &alice_env,
+        expected_count + 4,
+        num_attempts,
+        delay_per_attempt,
+    )
+    .await;
 
     fresh_reader_test(alice_env, |txn| {
         // Validation should be empty
@@ -350,6 +396,7 @@ async fn run_test(
         ));
         assert!(expected_invalid_link(&txn, &invalid_link_hash));
         assert_eq!(num_valid(&txn), expected_count - 4);
+        assert_eq!(num_warrants(&txn), 4);
     });
 
     let invocation = new_invocation(
@@ -368,7 +415,13 @@ async fn run_test(
     // Integration should have 12 ops in it
     let expected_count = 12 + expected_count;
     let alice_env = handle.get_cell_env(&alice_cell_id).await.unwrap();
-    wait_for_integration(&alice_env, expected_count, num_attempts, delay_per_attempt).await;
+    wait_for_integration(
+        &alice_env,
+        expected_count + 8,
+        num_attempts,
+        delay_per_attempt,
+    )
+    .await;
 
     fresh_reader_test(alice_env, |txn| {
         // Validation should be empty
@@ -384,6 +437,7 @@ async fn run_test(
         assert!(expected_invalid_remove_link(&txn, &invalid_remove_hash));
         // 6 invalid ops above plus 2 extra invalid ops that `remove_invalid_link` commits.
         assert_eq!(num_valid(&txn), expected_count - (6 + 2));
+        assert_eq!(num_warrants(&txn), 8);
     });
     expected_count
 }
@@ -413,7 +467,13 @@ async fn run_test_entry_def_id(
     // StoreEntry and StoreElement should be invalid.
     let expected_count = 3 + expected_count;
     let alice_env = handle.get_cell_env(&alice_cell_id).await.unwrap();
-    wait_for_integration(&alice_env, expected_count, num_attempts, delay_per_attempt).await;
+    wait_for_integration(
+        &alice_env,
+        expected_count + 10,
+        num_attempts,
+        delay_per_attempt,
+    )
+    .await;
 
     fresh_reader_test(alice_env, |txn| {
         // Validation should be empty
@@ -426,9 +486,104 @@ async fn run_test_entry_def_id(
             &invalid_entry_hash
         ));
         assert_eq!(num_valid(&txn), expected_count - 10);
+        assert_eq!(num_warrants(&txn), 10);
     });
 }
 
+/// Alice forges a warrant against one of bob's valid ops.
+/// The warranted op is validated again, so the warrant is rejected.
+async fn run_test_forged_warrant(
+    alice_cell_id: CellId,
+    bob_cell_id: CellId,
+    handle: ConductorHandle,
+) {
+    let num_attempts = 100;
+    let delay_per_attempt = Duration::from_millis(100);
+
+    let invocation =
+        new_zome_call(&bob_cell_id, "always_validates", (), TestWasm::Validate).unwrap();
+    let header_hash: HeaderHash = match handle.call_zome(invocation).await.unwrap().unwrap() {
+        ZomeCallResponse::Ok(output) => output.decode().unwrap(),
+        other => panic!("Unexpected zome call response {:?}", other),
+    };
+
+    // Wait for alice to hold bob's header
+    let alice_env = handle.get_cell_env(&alice_cell_id).await.unwrap();
+    let mut signed_header = None;
+    for _ in 0..num_attempts {
+        signed_header = fresh_reader_test(alice_env.clone(), |txn| {
+            txn.query_row(
+                "SELECT blob FROM Header WHERE hash = :hash",
+                named_params! { ":hash": header_hash },
+                |row| row.get("blob"),
+            )
+            .optional()
+            .unwrap()
+            .map(|blob| from_blob::<SignedHeader>(blob).unwrap())
+        });
+        if signed_header.is_some() {
+            break;
+        }
+        tokio::time::sleep(delay_per_attempt).await;
+    }
+    let (header, signature): (Header, Signature) = signed_header
+        .expect("Alice never received bob's header")
+        .into();
+
+    let warrant = Warrant {
+        author: bob_cell_id.agent_pubkey().clone(),
+        header: header_hash,
+        op: SgdOpHash::with_data_sync(&SgdOp::StoreElement(
+            signature.clone(),
+            header.clone(),
+            None,
+        )),
+        reason: WarrantReason::AppValidation("Forged".to_string()),
+        warrantor: alice_cell_id.agent_pubkey().clone(),
+        timestamp: aingle_types::timestamp::now(),
+    };
+    let warrant_signature = alice_cell_id
+        .agent_pubkey()
+        .sign(&alice_env.keystore(), warrant.clone())
+        .await
+        .unwrap();
+    let warrant_op = SgdOpHashed::from_content_sync(SgdOp::RegisterWarrant(
+        signature,
+        header,
+        Box::new(SignedWarrant::new(warrant, warrant_signature)),
+    ));
+    let (warrant_op, warrant_op_hash) = warrant_op.into_inner();
+    let triggers = handle.get_cell_triggers(&alice_cell_id).await.unwrap();
+    incoming_sgd_ops_workflow(
+        &alice_env,
+        triggers.sys_validation,
+        vec![(warrant_op_hash.clone(), warrant_op)],
+        None,
+        false,
+    )
+    .await
+    .unwrap();
+
+    let mut status = None;
+    for _ in 0..num_attempts {
+        status = fresh_reader_test(alice_env.clone(), |txn| {
+            txn.query_row(
+                "SELECT validation_status FROM SgdOp WHERE hash = :hash AND when_integrated IS NOT NULL",
+                named_params! { ":hash": warrant_op_hash },
+                |row| row.get::<_, Option<ValidationStatus>>(0),
+            )
+            .optional()
+            .unwrap()
+            .flatten()
+        });
+        if status.is_some() {
+            break;
+        }
+        tokio::time::sleep(delay_per_attempt).await;
+    }
+    assert_eq!(status, Some(ValidationStatus::Rejected));
+}
+
 // Need to "hack aingle" because otherwise the invalid
 // commit is caught by the call zome workflow
 async fn commit_invalid(
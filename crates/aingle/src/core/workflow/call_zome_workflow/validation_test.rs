@@ -21,6 +21,7 @@ async fn direct_validation_test() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Update.into()].into(),
+            rate_limit: None,
         },
         vec![TestWasm::Update.into()],
     )
@@ -64,3 +65,59 @@ async fn run_test(alice_cell_id: CellId, handle: ConductorHandle) {
     }
 }
 // ,
+
+#[tokio::test(flavor = "multi_thread")]
+async fn inline_rate_limit_test() {
+    observability::test_run().ok();
+
+    let max_headers = 10;
+    let saf_file = SafFile::new(
+        SafDef {
+            name: "inline_rate_limit_test".to_string(),
+            uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
+            properties: SerializedBytes::try_from(()).unwrap(),
+            zomes: vec![TestWasm::Create.into()].into(),
+            rate_limit: Some(RateLimit {
+                max_headers,
+                window_ms: 60 * 60 * 1000,
+            }),
+        },
+        vec![TestWasm::Create.into()],
+    )
+    .await
+    .unwrap();
+
+    let alice_agent_id = fake_agent_pubkey_1();
+    let alice_cell_id = CellId::new(saf_file.saf_hash().to_owned(), alice_agent_id.clone());
+    let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+
+    let (_tmpdir, _app_api, handle) = setup_app(
+        vec![("test_app", vec![(alice_installed_cell, None)])],
+        vec![saf_file.clone()],
+    )
+    .await;
+
+    // Genesis and init use up some of the limit so the
+    // commit is rejected before `max_headers` calls.
+    let mut committed = 0;
+    let result = loop {
+        let invocation =
+            new_zome_call(&alice_cell_id, "create_entry", (), TestWasm::Create).unwrap();
+        match handle.call_zome(invocation).await {
+            Ok(Ok(_)) if committed < max_headers => committed += 1,
+            result => break result,
+        }
+    };
+    assert!(committed > 0);
+    match &result {
+        Err(ConductorApiError::CellError(CellError::WorkflowError(wfe))) => match **wfe {
+            WorkflowError::SourceChainError(SourceChainError::InvalidCommit(_)) => {}
+            _ => panic!("Expected InvalidCommit got {:?}", result),
+        },
+        _ => panic!("Expected InvalidCommit got {:?}", result),
+    }
+
+    let shutdown = handle.take_shutdown_handle().await.unwrap();
+    handle.shutdown().await;
+    shutdown.await.unwrap().unwrap();
+}
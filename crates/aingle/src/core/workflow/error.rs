@@ -21,6 +21,9 @@ pub enum WorkflowError {
     #[error("The genesis self-check failed. App cannot be installed. Reason: {0}")]
     GenesisFailure(String),
 
+    #[error("Zome {0} refused to migrate the agent. Reason: {1}")]
+    MigrateAgentFailure(ZomeName, String),
+
     #[error(transparent)]
     AppValidationError(#[from] AppValidationError),
 
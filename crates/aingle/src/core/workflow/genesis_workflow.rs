@@ -65,12 +65,42 @@ where
         return Ok(());
     }
 
+    genesis_checks(
+        &api,
+        ribosome,
+        saf_file.saf_def().clone(),
+        &agent_pubkey,
+        membrane_proof.clone(),
+    )
+    .await?;
+
+    source_chain::genesis(
+        workspace.vault.clone(),
+        saf_file.saf_hash().clone(),
+        agent_pubkey,
+        membrane_proof,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Check that an agent may join the SAF, without creating its source chain.
+/// This runs the SAF's genesis self-check on the membrane proof and the
+/// placeholder DPKI check on the agent key.
+pub async fn genesis_checks<Api: CellConductorApiT, Ribosome: RibosomeT + Send>(
+    api: &Api,
+    ribosome: Ribosome,
+    saf_def: SafDef,
+    agent_pubkey: &AgentPubKey,
+    membrane_proof: Option<SerializedBytes>,
+) -> WorkflowResult<()> {
     let result = ribosome.run_genesis_self_check(
         GenesisSelfCheckHostAccess,
         GenesisSelfCheckInvocation {
             payload: GenesisSelfCheckData {
-                saf_def: saf_file.saf_def().clone(),
-                membrane_proof: membrane_proof.clone(),
+                saf_def,
+                membrane_proof,
                 agent_key: agent_pubkey.clone(),
             },
         },
@@ -90,15 +120,6 @@ where
     {
         return Err(WorkflowError::AgentInvalid(agent_pubkey.clone()));
     }
-
-    source_chain::genesis(
-        workspace.vault.clone(),
-        saf_file.saf_hash().clone(),
-        agent_pubkey,
-        membrane_proof,
-    )
-    .await?;
-
     Ok(())
 }
 
@@ -3,6 +3,7 @@
 use super::error::WorkflowResult;
 use super::sys_validation_workflow::counterfeit_check;
 use crate::core::queue_consumer::TriggerSender;
+use crate::core::verify_warrant_signature;
 use ai_hash::AgentPubKey;
 use ai_hash::SgdOpHash;
 use aingle_sqlite::error::DatabaseResult;
@@ -196,6 +197,42 @@ pub async fn incoming_sgd_ops_workflow(
     rcv.await.expect("sender dropped")
 }
 
+/// Drop ops authored by agents that have a valid warrant against them.
+/// Warrants are always kept so they keep spreading.
+pub async fn drop_ops_from_warranted_authors(
+    vault: &EnvWrite,
+    ops: Vec<(SgdOpHash, SgdOp)>,
+) -> WorkflowResult<Vec<(SgdOpHash, SgdOp)>> {
+    vault
+        .async_reader(move |txn| {
+            let mut warranted = HashMap::new();
+            let mut keep = Vec::with_capacity(ops.len());
+            for (hash, op) in ops {
+                if op.warrant().is_none() {
+                    let author = op.header().author().clone();
+                    let blocked = match warranted.get(&author) {
+                        Some(blocked) => *blocked,
+                        None => {
+                            let blocked = is_warranted(&txn, &author)?;
+                            warranted.insert(author, blocked);
+                            blocked
+                        }
+                    };
+                    if blocked {
+                        tracing::info!(
+                            msg = "Dropping op because its author has been warranted",
+                            ?op
+                        );
+                        continue;
+                    }
+                }
+                keep.push((hash, op));
+            }
+            WorkflowResult::Ok(keep)
+        })
+        .await
+}
+
 fn needs_receipt(op: &SgdOp, from_agent: &Option<AgentPubKey>) -> bool {
     from_agent
         .as_ref()
@@ -204,10 +241,16 @@ fn needs_receipt(op: &SgdOp, from_agent: &Option<AgentPubKey>) -> bool {
 }
 
 #[instrument(skip(op))]
-/// If this op fails the counterfeit check it should be dropped
+/// If this op fails the counterfeit check it should be dropped.
+/// Warrants must also be signed by their warrantor.
 async fn should_keep(op: &SgdOp) -> WorkflowResult<bool> {
     let header = op.header();
     let signature = op.signature();
+    if let Some(warrant) = op.warrant() {
+        if !verify_warrant_signature(warrant).await? {
+            return Ok(false);
+        }
+    }
     Ok(counterfeit_check(signature, &header).await?)
 }
 
@@ -63,7 +63,12 @@ where
     } = args;
     // Call the init callback
     let result = {
-        let host_access = InitHostAccess::new(workspace.clone(), keystore, network.clone());
+        let host_access = InitHostAccess::new(
+            workspace.clone(),
+            keystore,
+            network.clone(),
+            conductor_api.max_sleep_per_call(),
+        );
         let invocation = InitInvocation { saf_def };
         ribosome.run_init(host_access, invocation)?
     };
@@ -136,8 +141,11 @@ pub mod tests {
         ribosome.expect_saf_def().return_const(saf_def_hashed);
 
         let cell_id = CellId::new(saf_hash, fixt!(AgentPubKey));
-        let conductor_api = Arc::new(MockConductorHandleT::new());
-        let conductor_api = CellConductorApi::new(conductor_api, cell_id);
+        let mut conductor_api = MockConductorHandleT::new();
+        conductor_api
+            .expect_max_sleep_per_call()
+            .return_const(std::time::Duration::from_millis(100));
+        let conductor_api = CellConductorApi::new(Arc::new(conductor_api), cell_id);
         let args = InitializeZomesWorkflowArgs {
             ribosome,
             saf_def,
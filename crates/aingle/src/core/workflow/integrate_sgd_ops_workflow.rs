@@ -37,8 +37,10 @@ pub async fn integrate_sgd_ops_workflow(
                     ":deleted_entry_header": SgdOpType::RegisterDeletedEntryHeader,
                     ":create_link": SgdOpType::RegisterAddLink,
                     ":delete_link": SgdOpType::RegisterRemoveLink,
+                    ":register_warrant": SgdOpType::RegisterWarrant,
 
                 })?;
+            record_chain_forks(txn, time)?;
             WorkflowResult::Ok(changed)
         })
         .await?;
@@ -51,3 +53,20 @@ pub async fn integrate_sgd_ops_workflow(
         Ok(WorkComplete::Complete)
     }
 }
+
+/// Record a fork against the author of any agent activity integrated at
+/// `when_integrated` which shares its position with another held header.
+/// The header itself is still valid, it's the chain that is forked.
+fn record_chain_forks(
+    txn: &mut rusqlite::Transaction<'_>,
+    when_integrated: Timestamp,
+) -> WorkflowResult<()> {
+    for (author, fork) in get_chain_forks_integrated_at(txn, when_integrated)? {
+        warn!(
+            "Chain fork detected at position {} for agent {:?} between {:?} and {:?}",
+            fork.fork_seq, author, fork.first_header, fork.second_header,
+        );
+        insert_chain_fork(txn, author, fork)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! Migrate Agent Workflow: Move an agent's source chain from one SAF to another
+//! - Run the `migrate_agent` close callbacks of the old SAF
+//! - Open the new chain, which has already been through genesis, with an
+//!   `OpenChain` header pointing back at the old SAF
+//! - Carry the live app state of the old chain forward onto the new chain
+//! - Run the `migrate_agent` open callbacks of the new SAF
+//! - Close the old chain with a `CloseChain` header pointing at the new SAF
+//!
+//! Neither chain is written to unless the callbacks on both sides pass, and
+//! the new chain is written before the old one is closed, so a failure in
+//! between never leaves the agent without an open chain.
+
+use super::error::WorkflowError;
+use super::error::WorkflowResult;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::ribosome::RibosomeT;
+use aingle_state::host_fn_workspace::HostFnWorkspace;
+use aingle_state::source_chain::SourceChain;
+use aingle_types::prelude::*;
+use derive_more::Constructor;
+use std::collections::HashSet;
+use tracing::*;
+
+/// The struct which implements the migrate agent Workflow
+#[derive(Constructor)]
+pub struct MigrateAgentWorkflowArgs<Ribosome>
+where
+    Ribosome: RibosomeT + Send + 'static,
+{
+    old_ribosome: Ribosome,
+    new_ribosome: Ribosome,
+}
+
+#[instrument(skip(old_workspace, new_workspace, args))]
+pub async fn migrate_agent_workflow<Ribosome>(
+    old_workspace: HostFnWorkspace,
+    new_workspace: HostFnWorkspace,
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<()>
+where
+    Ribosome: RibosomeT + Send + 'static,
+{
+    let MigrateAgentWorkflowArgs {
+        old_ribosome,
+        new_ribosome,
+    } = args;
+    let old_saf = old_ribosome.saf_def();
+    let new_saf = new_ribosome.saf_def();
+
+    run_migrate_agent(&old_ribosome, old_workspace.clone(), MigrateAgent::Close)?;
+
+    let new_chain = new_workspace.source_chain();
+    new_chain
+        .put(
+            builder::OpenChain {
+                prev_saf_hash: old_saf.as_hash().clone(),
+            },
+            None,
+        )
+        .await?;
+    carry_forward(
+        old_workspace.source_chain(),
+        new_chain,
+        old_saf.as_content(),
+        new_saf.as_content(),
+    )
+    .await?;
+
+    // The open callbacks can read the carried state from the scratch.
+    run_migrate_agent(&new_ribosome, new_workspace.clone(), MigrateAgent::Open)?;
+    new_workspace.flush().await?;
+
+    old_workspace
+        .source_chain()
+        .put(
+            builder::CloseChain {
+                new_saf_hash: new_saf.as_hash().clone(),
+            },
+            None,
+        )
+        .await?;
+    old_workspace.flush().await?;
+    Ok(())
+}
+
+fn run_migrate_agent<Ribosome: RibosomeT>(
+    ribosome: &Ribosome,
+    workspace: HostFnWorkspace,
+    migrate_agent: MigrateAgent,
+) -> WorkflowResult<()> {
+    let invocation =
+        MigrateAgentInvocation::new(ribosome.saf_def().as_content().clone(), migrate_agent);
+    match ribosome.run_migrate_agent(MigrateAgentHostAccess::new(workspace), invocation)? {
+        MigrateAgentResult::Pass => Ok(()),
+        MigrateAgentResult::Fail(zome_name, reason) => {
+            Err(WorkflowError::MigrateAgentFailure(zome_name, reason))
+        }
+    }
+}
+
+/// Copy the live state of the old chain onto the new one: entries which
+/// haven't been updated or deleted, links which haven't been deleted, and
+/// capability grants and claims.
+/// App entries and links move to the zome with the same name in the new SAF,
+/// keeping their entry def index, and are dropped if there is no such zome.
+async fn carry_forward(
+    old_chain: &SourceChain,
+    new_chain: &SourceChain,
+    old_saf: &SafDef,
+    new_saf: &SafDef,
+) -> WorkflowResult<()> {
+    let elements = old_chain
+        .query(ChainQueryFilter::new().include_entries(true))
+        .await?;
+
+    let dead_headers: HashSet<_> = elements
+        .iter()
+        .filter_map(|element| match element.header() {
+            Header::Update(update) => Some(update.original_header_address.clone()),
+            Header::Delete(delete) => Some(delete.deletes_address.clone()),
+            Header::DeleteLink(delete_link) => Some(delete_link.link_add_address.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let new_zome_id = |zome_id: ZomeId| {
+        let (zome_name, _) = old_saf.zomes.get(zome_id.index())?;
+        new_saf
+            .zomes
+            .iter()
+            .position(|(name, _)| name == zome_name)
+            .map(|index| ZomeId::from(index as u8))
+    };
+
+    for element in elements {
+        if dead_headers.contains(element.header_address()) {
+            continue;
+        }
+        match element.header() {
+            Header::Create(Create {
+                entry_type,
+                entry_hash,
+                ..
+            })
+            | Header::Update(Update {
+                entry_type,
+                entry_hash,
+                ..
+            }) => {
+                let entry_type = match entry_type {
+                    EntryType::App(app_entry_type) => match new_zome_id(app_entry_type.zome_id()) {
+                        Some(zome_id) => EntryType::App(AppEntryType::new(
+                            app_entry_type.id(),
+                            zome_id,
+                            *app_entry_type.visibility(),
+                        )),
+                        None => {
+                            debug!(?entry_hash, "Dropping entry of a removed zome");
+                            continue;
+                        }
+                    },
+                    EntryType::CapGrant | EntryType::CapClaim => entry_type.clone(),
+                    // The new chain has its own agent key from genesis.
+                    EntryType::AgentPubKey => continue,
+                };
+                let entry = match element.entry().as_option() {
+                    Some(entry) => entry.clone(),
+                    None => continue,
+                };
+                new_chain
+                    .put(
+                        builder::Create {
+                            entry_type,
+                            entry_hash: entry_hash.clone(),
+                        },
+                        Some(entry),
+                    )
+                    .await?;
+            }
+            Header::CreateLink(create_link) => match new_zome_id(create_link.zome_id) {
+                Some(zome_id) => {
+                    new_chain
+                        .put(
+                            builder::CreateLink {
+                                base_address: create_link.base_address.clone(),
+                                target_address: create_link.target_address.clone(),
+                                zome_id,
+                                tag: create_link.tag.clone(),
+                            },
+                            None,
+                        )
+                        .await?;
+                }
+                None => debug!(?create_link, "Dropping link of a removed zome"),
+            },
+            _ => (),
+        }
+    }
+    Ok(())
+}
@@ -11,6 +11,7 @@
 //!
 
 use super::error::WorkflowResult;
+use crate::conductor::interface::SignalBroadcaster;
 use crate::core::queue_consumer::WorkComplete;
 use ai_hash::*;
 use aingle_p2p::AIngleP2pCell;
@@ -18,6 +19,7 @@ use aingle_p2p::AIngleP2pCellT;
 use aingle_state::prelude::*;
 use aingle_types::prelude::*;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::time;
 use tracing::*;
 
@@ -34,22 +36,42 @@ pub const DEFAULT_RECEIPT_BUNDLE_SIZE: u32 = 5;
 /// flooding the network with spurious publishes.
 pub const MIN_PUBLISH_INTERVAL: time::Duration = time::Duration::from_secs(5);
 
-#[instrument(skip(env, network))]
+#[instrument(skip(env, network, signal_tx))]
 pub async fn publish_sgd_ops_workflow(
     env: EnvWrite,
     mut network: AIngleP2pCell,
+    mut signal_tx: SignalBroadcaster,
 ) -> WorkflowResult<WorkComplete> {
     let (to_publish, hashes) =
         publish_sgd_ops_workflow_inner(env.clone().into(), network.from_agent()).await?;
 
     // Commit to the network
     tracing::info!("sending {} ops", to_publish.len());
+    let mut unsent_warrants = HashSet::new();
     for (basis, ops) in to_publish {
+        // Our own ops are gossiped as well, but nothing else will send
+        // a warrant, so one that fails to publish is tried again later.
+        let warrants: Vec<_> = ops
+            .iter()
+            .filter(|(_, op)| op.warrant().is_some())
+            .map(|(hash, _)| hash.clone())
+            .collect();
         if let Err(e) = network.publish(true, basis, ops, None).await {
             tracing::info!(failed_to_send_publish = ?e);
+            unsent_warrants.extend(warrants);
         }
     }
+    let hashes: Vec<_> = hashes
+        .into_iter()
+        .filter(|hash| !unsent_warrants.contains(hash))
+        .collect();
     tracing::info!("sent {} ops", hashes.len());
+    if !hashes.is_empty() {
+        signal_tx.send_system(SystemSignal::OpsPublished {
+            cell_id: network.cell_id(),
+            op_hashes: hashes.clone(),
+        });
+    }
     let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?;
     env.async_commit(move |writer| {
         for hash in hashes {
@@ -61,7 +83,11 @@ pub async fn publish_sgd_ops_workflow(
     tracing::info!("commited sent ops");
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
-    Ok(WorkComplete::Complete)
+    if unsent_warrants.is_empty() {
+        Ok(WorkComplete::Complete)
+    } else {
+        Ok(WorkComplete::Incomplete)
+    }
 }
 
 /// Read the authored for ops with receipt count < R
@@ -93,6 +119,7 @@ pub async fn publish_sgd_ops_workflow_inner(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixt::CreateFixturator;
     use crate::fixt::CreateLinkFixturator;
     use crate::fixt::EntryFixturator;
     use crate::test_utils::fake_genesis;
@@ -100,6 +127,7 @@ mod tests {
     use crate::test_utils::TestNetwork;
     use ::fixt::prelude::*;
     use futures::future::FutureExt;
+    use aingle_p2p::actor::AIngleP2pRefToCell;
     use aingle_p2p::actor::AIngleP2pSender;
     use aingle_p2p::AIngleP2pRef;
     use observability;
@@ -211,7 +239,7 @@ mod tests {
 
     /// Call the workflow
     async fn call_workflow(env: EnvWrite, cell_network: AIngleP2pCell) {
-        publish_sgd_ops_workflow(env.clone().into(), cell_network)
+        publish_sgd_ops_workflow(env.clone().into(), cell_network, SignalBroadcaster::noop())
             .await
             .unwrap();
     }
@@ -320,6 +348,83 @@ mod tests {
         });
     }
 
+    /// A warrant this agent issued which fails to publish is sent again
+    /// the next time the workflow runs.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failed_warrant_publish_is_retried() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+
+        // No authored ops, so the warrant is the only op to publish
+        let (test_network, cell_network, _recv_task, _) = setup(env.clone(), 1, 0, false).await;
+        let warrantor = cell_network.from_agent();
+
+        let header = Header::Create(fixt!(Create));
+        let invalid_op = SgdOpHashed::from_content_sync(SgdOp::RegisterAgentActivity(
+            fixt!(Signature),
+            header.clone(),
+        ));
+        let warrant = SignedWarrant::new(
+            Warrant {
+                author: header.author().clone(),
+                header: HeaderHash::with_data_sync(&header),
+                op: invalid_op.as_hash().clone(),
+                reason: WarrantReason::SysValidation("Bad".into()),
+                warrantor: warrantor.clone(),
+                timestamp: aingle_types::timestamp::now(),
+            },
+            fixt!(Signature),
+        );
+        let warrant_op = SgdOpHashed::from_content_sync(SgdOp::RegisterWarrant(
+            fixt!(Signature),
+            header,
+            Box::new(warrant),
+        ));
+        let warrant_op_hash = warrant_op.as_hash().clone();
+        env.conn()
+            .unwrap()
+            .with_commit_test(|txn| mutations::insert_op(txn, warrant_op, false).unwrap())
+            .unwrap();
+
+        let is_published = |env: &EnvWrite| {
+            let hash = warrant_op_hash.clone();
+            fresh_reader_test!(env, |txn: Transaction| {
+                txn.query_row(
+                    "SELECT last_publish_time IS NOT NULL FROM SgdOp WHERE hash = ?",
+                    [hash],
+                    |row| row.get::<_, bool>(0),
+                )
+                .unwrap()
+            })
+        };
+
+        // The network has no space for this SAF, so the first publish fails
+        let unjoined_network = test_network
+            .network()
+            .to_cell(fixt!(SafHash), warrantor.clone());
+        let work = publish_sgd_ops_workflow(
+            env.clone().into(),
+            unjoined_network,
+            SignalBroadcaster::noop(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(work, WorkComplete::Incomplete);
+        assert!(!is_published(&env));
+
+        // The next run sends it
+        let work = publish_sgd_ops_workflow(
+            env.clone().into(),
+            cell_network,
+            SignalBroadcaster::noop(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(work, WorkComplete::Complete);
+        assert!(is_published(&env));
+    }
+
     /// There is a test to shows that SGDOps that were produced on private entries are not published.
     /// Some do get published
     /// Current private constraints:
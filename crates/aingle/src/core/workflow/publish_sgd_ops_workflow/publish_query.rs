@@ -11,6 +11,7 @@ use aingle_types::env::EnvRead;
 use aingle_zome_types::Entry;
 use aingle_zome_types::EntryVisibility;
 use aingle_zome_types::SignedHeader;
+use aingle_zome_types::SignedWarrant;
 use rusqlite::named_params;
 
 use crate::core::workflow::error::WorkflowResult;
@@ -18,6 +19,7 @@ use crate::core::workflow::error::WorkflowResult;
 // use super::MIN_PUBLISH_INTERVAL;
 
 /// Get all sgd ops on an agents chain that need to be published.
+/// - Also get warrants this agent issued which haven't been published yet.
 /// - Don't publish private entries.
 /// - Only get ops that haven't been published within the minimum publish interval
 /// - Only get ops that have less then the RECEIPT_BUNDLE_SIZE
@@ -40,6 +42,7 @@ pub async fn get_ops_to_publish(
             SELECT 
             Header.blob as header_blob,
             Entry.blob as entry_blob,
+            Warrant.blob as warrant_blob,
             SgdOp.type as sgd_type,
             SgdOp.hash as sgd_hash
             FROM Header
@@ -47,12 +50,20 @@ pub async fn get_ops_to_publish(
             SgdOp ON SgdOp.header_hash = Header.hash
             LEFT JOIN
             Entry ON Header.entry_hash = Entry.hash
+            LEFT JOIN
+            Warrant ON Warrant.hash = SgdOp.hash
             WHERE
-            SgdOp.is_authored = 1
-            AND
-            Header.author = :author
-            AND
-            (SgdOp.type != :store_entry OR Header.private_entry = 0)
+            (
+                (
+                    SgdOp.is_authored = 1
+                    AND
+                    Header.author = :author
+                    AND
+                    (SgdOp.type != :store_entry OR Header.private_entry = 0)
+                )
+                OR
+                Warrant.warrantor = :author
+            )
             AND
             SgdOp.last_publish_time IS NULL
             ",
@@ -81,8 +92,13 @@ pub async fn get_ops_to_publish(
                         }
                         _ => None,
                     };
+                    let warrant: Option<Vec<u8>> = row.get("warrant_blob")?;
+                    let warrant = match warrant {
+                        Some(warrant) => Some(from_blob::<SignedWarrant>(warrant)?),
+                        None => None,
+                    };
                     WorkflowResult::Ok(SgdOpHashed::with_pre_hashed(
-                        SgdOp::from_type(op_type, header, entry)?,
+                        SgdOp::from_type(op_type, header, entry, warrant)?,
                         hash,
                     ))
                 },
@@ -0,0 +1,55 @@
+//! # Schedule Workflow
+//!
+//! Calls the zome functions which were scheduled with the `schedule` host
+//! function and are now due, then moves recurring functions on to their next
+//! run and removes the rest.
+
+use super::error::WorkflowResult;
+use crate::conductor::api::CellConductorApiT;
+use crate::conductor::api::ZomeCall;
+use crate::core::queue_consumer::WorkComplete;
+use aingle_state::prelude::*;
+use aingle_types::prelude::*;
+use tracing::*;
+
+#[instrument(skip(env, conductor_api))]
+/// Run every scheduled function that is due.
+pub async fn schedule_workflow<Api: CellConductorApiT>(
+    env: EnvWrite,
+    conductor_api: Api,
+) -> WorkflowResult<WorkComplete> {
+    let now = now_ms();
+    let due = env
+        .async_reader(move |txn| due_scheduled_fns(&txn, now))
+        .await?;
+
+    let cell_id = conductor_api.cell_id().clone();
+    for scheduled_fn in due {
+        let call = ZomeCall {
+            cell_id: cell_id.clone(),
+            zome_name: scheduled_fn.zome_name.clone(),
+            fn_name: scheduled_fn.fn_name.clone(),
+            // Calls as the author are always authorized.
+            cap: None,
+            payload: ExternIO::encode(())?,
+            provenance: cell_id.agent_pubkey().clone(),
+        };
+        // A failing scheduled function must not stop the others from running
+        // or leave a one-off function to be retried forever.
+        match conductor_api.call_zome(&cell_id, call).await {
+            Ok(Ok(ZomeCallResponse::Ok(_))) => (),
+            r => warn!(
+                zome_name = %scheduled_fn.zome_name,
+                fn_name = %scheduled_fn.fn_name,
+                failed_scheduled_fn = ?r
+            ),
+        }
+        // Reschedule from after the call so a slow function
+        // can't keep the scheduler permanently busy.
+        let now = now_ms();
+        env.async_commit(move |txn| reschedule_or_delete(txn, scheduled_fn, now))
+            .await?;
+    }
+
+    Ok(WorkComplete::Complete)
+}
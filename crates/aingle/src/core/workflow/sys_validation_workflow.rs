@@ -7,6 +7,8 @@ use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
 use crate::core::sys_validate::*;
 use crate::core::validation::*;
+use crate::core::warrant::issue_warrant;
+use crate::core::warrant::warranted_op;
 use error::WorkflowResult;
 use ai_hash::SgdOpHash;
 use aingle_cascade::Cascade;
@@ -15,6 +17,7 @@ use aingle_p2p::AIngleP2pCellT;
 use aingle_sqlite::prelude::*;
 
 use aingle_sqlite::db::ReadManager;
+use aingle_sqlite::rusqlite::OptionalExtension;
 use aingle_state::host_fn_workspace::HostFnStores;
 use aingle_state::host_fn_workspace::HostFnWorkspace;
 use aingle_state::prelude::*;
@@ -22,6 +25,7 @@ use aingle_state::scratch::SyncScratch;
 use aingle_types::prelude::*;
 use aingle_zome_types::Entry;
 use aingle_zome_types::ValidationStatus;
+use futures::future::FutureExt;
 use std::convert::TryInto;
 use tracing::*;
 
@@ -77,6 +81,8 @@ async fn sys_validation_workflow_inner(
 ) -> WorkflowResult<WorkComplete> {
     let env = workspace.vault.clone().into();
     let sorted_ops = validation_query::get_ops_to_sys_validate(&env).await?;
+    let mut signal_tx = conductor_api.signal_broadcaster().await;
+    let (mut validated, mut rejected) = (Vec::new(), Vec::new());
 
     // Process each op
     for so in sorted_ops {
@@ -105,8 +111,9 @@ async fn sys_validation_workflow_inner(
             }
             Outcome::SkipAppValidation => {
                 workspace
-                    .put_integration_limbo(op_hash, ValidationStatus::Valid)
+                    .put_integration_limbo(op_hash.clone(), ValidationStatus::Valid)
                     .await?;
+                validated.push(op_hash);
             }
             Outcome::AwaitingOpDep(missing_dep) => {
                 // TODO: Try and get this dependency to add to limbo
@@ -127,13 +134,51 @@ async fn sys_validation_workflow_inner(
                     .put_validation_limbo(op_hash, ValidationLimboStatus::Pending)
                     .await?;
             }
-            Outcome::Rejected => {
+            // Pending ops are picked up again on the next run.
+            Outcome::AwaitingKeyRegistry => {
                 workspace
-                    .put_integration_limbo(op_hash, ValidationStatus::Rejected)
+                    .put_validation_limbo(op_hash, ValidationLimboStatus::Pending)
                     .await?;
             }
+            Outcome::Rejected(reason) => {
+                // Warrants are issued against other agents' ops,
+                // never against our own or against other warrants.
+                // Failing to issue a warrant doesn't change this op's outcome.
+                if op.warrant().is_none() && *op.header().author() != network.from_agent() {
+                    if let Err(e) = issue_warrant(
+                        &workspace.vault,
+                        &network,
+                        &op,
+                        op_hash.clone(),
+                        WarrantReason::SysValidation(reason),
+                    )
+                    .await
+                    {
+                        error!(?e, ?op_hash, "Failed to issue a warrant");
+                    }
+                }
+                workspace
+                    .put_integration_limbo(op_hash.clone(), ValidationStatus::Rejected)
+                    .await?;
+                rejected.push(op_hash);
+            }
         }
     }
+
+    // One signal per outcome for the whole run, so a sync doesn't flood
+    // app interfaces with a signal per op.
+    if !validated.is_empty() {
+        signal_tx.send_system(SystemSignal::OpsValidated {
+            cell_id: conductor_api.cell_id().clone(),
+            op_hashes: validated,
+        });
+    }
+    if !rejected.is_empty() {
+        signal_tx.send_system(SystemSignal::OpsRejected {
+            cell_id: conductor_api.cell_id().clone(),
+            op_hashes: rejected,
+        });
+    }
     Ok(WorkComplete::Complete)
 }
 
@@ -157,6 +202,9 @@ async fn validate_op(
             // TODO: Check strict mode where store element
             // is also run through app validation
             SgdOp::RegisterAgentActivity(_, _) => Ok(Outcome::SkipAppValidation),
+            SgdOp::RegisterWarrant(_, _, warrant) => {
+                validate_warranted_op(warrant.warrant(), workspace, network, conductor_api).await
+            }
             _ => Ok(Outcome::Accepted),
         },
         // Handle the errors that result in pending or awaiting deps
@@ -169,7 +217,7 @@ async fn validate_op(
                 error_msg = %e
             );
             let outcome = handle_failed(e);
-            if let Outcome::Rejected = outcome {
+            if let Outcome::Rejected(_) = outcome {
                 warn!(
                     agent = %which_agent(conductor_api.cell_id().agent_pubkey()),
                     msg = "SgdOp was rejected during system validation.",
@@ -182,34 +230,78 @@ async fn validate_op(
     }
 }
 
-/// For now errors result in an outcome but in the future
-/// we might find it useful to include the reason something
-/// was rejected etc.
-/// This is why the errors contain data but is currently unread.
+/// A warrant only proves invalid authoring if the op it was issued for
+/// really is invalid, so that op is fetched and validated again.
+///
+/// The warrant is accepted if the op fails sys validation. If the op passes
+/// sys validation the warrant goes on to app validation, which checks the
+/// op again.
+async fn validate_warranted_op(
+    warrant: &Warrant,
+    workspace: &mut SysValidationWorkspace,
+    network: AIngleP2pCell,
+    conductor_api: &impl CellConductorApiT,
+) -> WorkflowResult<Outcome> {
+    let element = workspace
+        .full_cascade(network.clone())
+        .retrieve(warrant.header.clone().into(), Default::default())
+        .await?;
+    let element = match element {
+        Some(element) => element,
+        None => return Ok(Outcome::AwaitingOpDep(warrant.header.clone().into())),
+    };
+    let op = match warranted_op(&element, warrant) {
+        Some(op) => op,
+        None => return Ok(handle_failed(ValidationOutcome::WarrantMismatch(warrant.clone()))),
+    };
+    // Boxed because validating a warrant validates another op
+    let outcome = validate_op(&op, workspace, network, conductor_api, None)
+        .boxed()
+        .await?;
+    Ok(match outcome {
+        Outcome::Rejected(_) => Outcome::SkipAppValidation,
+        Outcome::Accepted => Outcome::Accepted,
+        Outcome::SkipAppValidation => {
+            handle_failed(ValidationOutcome::WarrantedOpValid(warrant.op.clone()))
+        }
+        awaiting @ Outcome::AwaitingOpDep(_)
+        | awaiting @ Outcome::MissingSgdDep
+        | awaiting @ Outcome::AwaitingKeyRegistry => awaiting,
+    })
+}
+
+/// Map a failed validation to an outcome.
+/// Rejections keep the reason so it can be put in a warrant.
 fn handle_failed(error: ValidationOutcome) -> Outcome {
     use Outcome::*;
+    let rejected = Rejected(error.to_string());
     match error {
         ValidationOutcome::Counterfeit(_, _) => {
             unreachable!("Counterfeit ops are dropped before sys validation")
         }
         ValidationOutcome::DepMissingFromSgd(_) => MissingSgdDep,
-        ValidationOutcome::EntryDefId(_) => Rejected,
-        ValidationOutcome::EntryHash => Rejected,
-        ValidationOutcome::EntryTooLarge(_, _) => Rejected,
-        ValidationOutcome::EntryType => Rejected,
-        ValidationOutcome::EntryVisibility(_) => Rejected,
-        ValidationOutcome::TagTooLarge(_, _) => Rejected,
-        ValidationOutcome::NotCreateLink(_) => Rejected,
-        ValidationOutcome::NotNewEntry(_) => Rejected,
+        ValidationOutcome::EntryDefId(_) => rejected,
+        ValidationOutcome::EntryHash => rejected,
+        ValidationOutcome::EntryTooLarge(_, _) => rejected,
+        ValidationOutcome::EntryType => rejected,
+        ValidationOutcome::EntryVisibility(_) => rejected,
+        ValidationOutcome::TagTooLarge(_, _) => rejected,
+        ValidationOutcome::InvalidAuthorKey(_, _) => rejected,
+        ValidationOutcome::KeyRegistryUnavailable(_) => AwaitingKeyRegistry,
+        ValidationOutcome::NotCreateLink(_) => rejected,
+        ValidationOutcome::NotNewEntry(_) => rejected,
         ValidationOutcome::NotHoldingDep(dep) => AwaitingOpDep(dep),
         ValidationOutcome::PrevHeaderError(PrevHeaderError::MissingMeta(dep)) => {
             AwaitingOpDep(dep.into())
         }
-        ValidationOutcome::PrevHeaderError(_) => Rejected,
-        ValidationOutcome::PrivateEntry => Rejected,
-        ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
-        ValidationOutcome::VerifySignature(_, _) => Rejected,
-        ValidationOutcome::ZomeId(_) => Rejected,
+        ValidationOutcome::PrevHeaderError(_) => rejected,
+        ValidationOutcome::PrivateEntry => rejected,
+        ValidationOutcome::RateLimitExceeded(_, _) => rejected,
+        ValidationOutcome::UpdateTypeMismatch(_, _) => rejected,
+        ValidationOutcome::VerifySignature(_, _) => rejected,
+        ValidationOutcome::WarrantMismatch(_) => rejected,
+        ValidationOutcome::WarrantedOpValid(_) => rejected,
+        ValidationOutcome::ZomeId(_) => rejected,
     }
 }
 
@@ -220,6 +312,14 @@ async fn validate_op_inner(
     conductor_api: &impl CellConductorApiT,
     incoming_sgd_ops_sender: Option<IncomingSgdOpSender>,
 ) -> SysValidationResult<()> {
+    match op {
+        // A warrant carries the offender's header, whose key may well be
+        // revoked by now, so it's the warrantor's key that's checked.
+        SgdOp::RegisterWarrant(_, _, warrant) => {
+            check_warrantor_key(warrant, conductor_api).await?
+        }
+        _ => check_author_key(&op.header(), conductor_api).await?,
+    }
     match op {
         SgdOp::StoreElement(_, header, entry) => {
             store_element(header, workspace, network.clone()).await?;
@@ -252,8 +352,14 @@ async fn validate_op_inner(
             Ok(())
         }
         SgdOp::RegisterAgentActivity(_, header) => {
-            register_agent_activity(header, workspace, network.clone(), incoming_sgd_ops_sender)
-                .await?;
+            register_agent_activity(
+                header,
+                workspace,
+                network.clone(),
+                conductor_api,
+                incoming_sgd_ops_sender,
+            )
+            .await?;
             store_element(header, workspace, network).await?;
             Ok(())
         }
@@ -306,6 +412,10 @@ async fn validate_op_inner(
             register_delete_link(header, workspace, network, incoming_sgd_ops_sender).await?;
             Ok(())
         }
+        SgdOp::RegisterWarrant(_, header, warrant) => {
+            check_warrant(header, warrant)?;
+            Ok(())
+        }
     }
 }
 
@@ -360,6 +470,10 @@ async fn sys_validate_element_inner(
     if !counterfeit_check(signature, header).await? {
         return Err(ValidationOutcome::Counterfeit(signature.clone(), header.clone()).into());
     }
+    check_author_key(header, conductor_api).await?;
+    // Authors are held to the SAF's rate limit before
+    // anything is published rather than being warranted later.
+    check_spam(header, workspace, conductor_api).await?;
     store_element(header, workspace, network.clone()).await?;
     if let Some((entry, EntryVisibility::Public)) =
         &entry.and_then(|e| header.entry_type().map(|et| (e, et.visibility())))
@@ -394,20 +508,22 @@ async fn sys_validate_element_inner(
     Ok(())
 }
 
-/// Check if the op has valid signature and author.
+/// Check if the op has a valid signature.
 /// Ops that fail this check should be dropped.
+/// Whether the author's key was still valid is checked
+/// during validation so the author can be warranted.
 pub async fn counterfeit_check(
     signature: &Signature,
     header: &Header,
 ) -> SysValidationResult<bool> {
-    Ok(verify_header_signature(&signature, &header).await?
-        && author_key_is_valid(header.author()).await?)
+    verify_header_signature(&signature, &header).await
 }
 
 async fn register_agent_activity(
     header: &Header,
     workspace: &mut SysValidationWorkspace,
     network: AIngleP2pCell,
+    conductor_api: &impl CellConductorApiT,
     incoming_sgd_ops_sender: Option<IncomingSgdOpSender>,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
@@ -426,7 +542,7 @@ async fn register_agent_activity(
         )
         .await?;
     }
-    check_chain_rollback(&header, &workspace).await?;
+    check_spam(&header, &workspace, conductor_api).await?;
     Ok(())
 }
 
@@ -712,44 +828,40 @@ impl SysValidationWorkspace {
         };
         Ok(!chain_not_empty)
     }
-    pub fn header_seq_is_empty(&self, header: &Header) -> SourceChainResult<bool> {
-        let author = header.author();
-        let seq = header.header_seq();
-        let hash = HeaderHash::with_data_sync(header);
-        let header_seq_is_not_empty = self.vault.conn()?.with_reader(|txn| {
-            DatabaseResult::Ok(txn.query_row(
-                "
-                SELECT EXISTS(
+    /// Get a header by its hash if it is held.
+    /// This looks at the vault and then the scratch, if there is one.
+    pub fn header(&self, hash: &HeaderHash) -> SourceChainResult<Option<Header>> {
+        let header = self.vault.conn()?.with_reader(|txn| {
+            let header = txn
+                .query_row(
+                    "
                     SELECT
-                    1
+                    Header.blob
                     FROM Header
                     WHERE
-                    Header.author = :author
-                    AND
-                    Header.seq = :seq
-                    AND
-                    Header.hash != :hash
+                    Header.hash = :hash
+                    ",
+                    named_params! {
+                        ":hash": hash,
+                    },
+                    |row| row.get("blob"),
                 )
-                ",
-                named_params! {
-                    ":author": author,
-                    ":seq": seq,
-                    ":hash": hash,
-                },
-                |row| row.get(0),
-            )?)
-        })?;
-        let header_seq_is_not_empty = match &self.scratch {
-            Some(scratch) => {
-                scratch.apply(|scratch| {
-                    scratch.headers().any(|shh| {
-                        shh.header().header_seq() == seq && *shh.header_address() != hash
-                    })
-                })? || header_seq_is_not_empty
+                .optional()?;
+            match header {
+                Some(blob) => SourceChainResult::Ok(Some(from_blob::<SignedHeader>(blob)?.0)),
+                None => Ok(None),
             }
-            None => header_seq_is_not_empty,
+        })?;
+        let header = match (header, &self.scratch) {
+            (None, Some(scratch)) => scratch.apply(|scratch| {
+                scratch
+                    .headers()
+                    .find(|shh| shh.header_address() == hash)
+                    .map(|shh| shh.header().clone())
+            })?,
+            (header, _) => header,
         };
-        Ok(!header_seq_is_not_empty)
+        Ok(header)
     }
     /// Create a cascade with local data only
     pub fn local_cascade(&mut self) -> Cascade {
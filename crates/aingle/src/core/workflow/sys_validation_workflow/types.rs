@@ -14,6 +14,10 @@ pub(super) enum Outcome {
     /// be found currently on the SGD.
     /// Note this is not proof it doesn't exist.
     MissingSgdDep,
+    /// Stays in limbo because the key registry couldn't
+    /// say whether the author's key was valid.
+    /// The op is tried again on the next run.
+    AwaitingKeyRegistry,
     /// Moves to integration with status rejected
-    Rejected,
+    Rejected(String),
 }
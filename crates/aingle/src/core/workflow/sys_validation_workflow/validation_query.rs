@@ -6,6 +6,7 @@ use aingle_types::sgd_op::SgdOpType;
 use aingle_types::env::EnvRead;
 use aingle_zome_types::Entry;
 use aingle_zome_types::SignedHeader;
+use aingle_zome_types::SignedWarrant;
 
 use crate::core::workflow::error::WorkflowResult;
 
@@ -28,6 +29,7 @@ async fn get_ops_to_validate(env: &EnvRead, system: bool) -> WorkflowResult<Vec<
         SELECT 
         Header.blob as header_blob,
         Entry.blob as entry_blob,
+        Warrant.blob as warrant_blob,
         SgdOp.type as sgd_type,
         SgdOp.hash as sgd_hash
         FROM Header
@@ -35,6 +37,8 @@ async fn get_ops_to_validate(env: &EnvRead, system: bool) -> WorkflowResult<Vec<
         SgdOp ON SgdOp.header_hash = Header.hash
         LEFT JOIN
         Entry ON Header.entry_hash = Entry.hash
+        LEFT JOIN
+        Warrant ON Warrant.hash = SgdOp.hash
         "
     .to_string();
     if system {
@@ -69,8 +73,13 @@ async fn get_ops_to_validate(env: &EnvRead, system: bool) -> WorkflowResult<Vec<
                 Some(entry) => Some(from_blob::<Entry>(entry)?),
                 None => None,
             };
+            let warrant: Option<Vec<u8>> = row.get("warrant_blob")?;
+            let warrant = match warrant {
+                Some(warrant) => Some(from_blob::<SignedWarrant>(warrant)?),
+                None => None,
+            };
             WorkflowResult::Ok(SgdOpHashed::with_pre_hashed(
-                SgdOp::from_type(op_type, header, entry)?,
+                SgdOp::from_type(op_type, header, entry, warrant)?,
                 hash,
             ))
         })?;
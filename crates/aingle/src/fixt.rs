@@ -24,6 +24,7 @@ use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::FnComponents;
 use crate::core::ribosome::HostAccess;
+use crate::core::ribosome::InvocationAuth;
 use crate::core::ribosome::ZomeCallHostAccess;
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::ribosome::ZomesToInvoke;
@@ -43,6 +44,7 @@ use rand::thread_rng;
 use rand::Rng;
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Duration;
 use strum::IntoEnumIterator;
 
 pub use aingle_types::fixt::*;
@@ -292,6 +294,11 @@ fn make_call_zome_handle(cell_id: CellId) -> CellConductorReadHandle {
     Arc::new(cell_conductor_api)
 }
 
+fixturator!(
+    Duration;
+    constructor fn from_millis(U64);
+);
+
 fixturator!(
     CellConductorReadHandle;
     vanilla fn make_call_zome_handle(CellId);
@@ -319,7 +326,7 @@ fixturator!(
 
 fixturator!(
     InitHostAccess;
-    constructor fn new(HostFnWorkspace, KeystoreSender, AIngleP2pCell);
+    constructor fn new(HostFnWorkspace, KeystoreSender, AIngleP2pCell, Duration);
 );
 
 fixturator!(
@@ -339,7 +346,7 @@ fixturator!(
 
 fixturator!(
     PostCommitHostAccess;
-    constructor fn new(HostFnWorkspace, KeystoreSender, AIngleP2pCell);
+    constructor fn new(HostFnWorkspace, KeystoreSender, AIngleP2pCell, Duration);
 );
 
 fixturator!(
@@ -430,9 +437,28 @@ fixturator!(
     ];
 );
 
+fixturator!(
+    InvocationAuth;
+    curve Empty InvocationAuth::LocalCallback;
+    curve Unpredictable InvocationAuth::Cap(
+        AgentPubKeyFixturator::new(Unpredictable).next().unwrap(),
+        Some(CapSecretFixturator::new(Unpredictable).next().unwrap()),
+    );
+    curve Predictable InvocationAuth::Cap(
+        AgentPubKeyFixturator::new_indexed(Predictable, get_fixt_index!())
+            .next()
+            .unwrap(),
+        Some(
+            CapSecretFixturator::new_indexed(Predictable, get_fixt_index!())
+                .next()
+                .unwrap(),
+        ),
+    );
+);
+
 fixturator!(
     CallContext;
-    constructor fn new(Zome, HostAccess);
+    constructor fn new(Zome, FunctionName, InvocationAuth, HostAccess);
 );
 
 fixturator!(
@@ -520,6 +520,7 @@ async fn setup(
             uid,
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: zomes.clone().into_iter().map(Into::into).collect(),
+            rate_limit: None,
         },
         zomes.into_iter().map(Into::into),
     )
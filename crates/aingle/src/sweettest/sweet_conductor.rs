@@ -9,6 +9,7 @@ use crate::conductor::{
 use adk::prelude::*;
 use ai_hash::SafHash;
 use aingle_conductor_api::{AdminInterfaceConfig, InterfaceDriver};
+use aingle_conductor_api::{AppRequest, AppResponse, IssueAppAuthenticationTokenPayload};
 use aingle_keystore::KeystoreSender;
 use aingle_state::test_utils::{test_environments, TestEnvs};
 use aingle_types::prelude::*;
@@ -280,6 +281,39 @@ impl SweetConductor {
         websocket_client_by_port(port).await.unwrap()
     }
 
+    /// Attach a new app interface and get a websocket client connected to it,
+    /// which has already authenticated as the given App.
+    pub async fn app_ws_client(
+        &self,
+        installed_app_id: &str,
+    ) -> (WebsocketSender, WebsocketReceiver) {
+        let port = self
+            .inner_handle()
+            .add_app_interface(0)
+            .await
+            .expect("Couldn't attach an app interface");
+        let issued = self
+            .issue_app_authentication_token(
+                IssueAppAuthenticationTokenPayload::for_installed_app_id(
+                    installed_app_id.to_string(),
+                ),
+            )
+            .await
+            .expect("Couldn't issue an app authentication token");
+        let (mut tx, rx) = websocket_client_by_port(port).await.unwrap();
+        let response: AppResponse = tx
+            .request(AppRequest::Authenticate {
+                token: issued.token,
+            })
+            .await
+            .unwrap();
+        match response {
+            AppResponse::Authenticated(id) if id == installed_app_id => (),
+            _ => panic!("Couldn't authenticate app interface: {:?}", response),
+        }
+        (tx, rx)
+    }
+
     /// Shutdown this conductor.
     /// This will wait for the conductor to shutdown but
     /// keep the inner state to restart it.
@@ -170,6 +170,7 @@ impl ConductorTestData {
                 uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: zomes.clone().into_iter().map(Into::into).collect(),
+                rate_limit: None,
             },
             zomes.into_iter().map(Into::into),
         )
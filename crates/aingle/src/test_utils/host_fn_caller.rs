@@ -9,6 +9,7 @@ use crate::core::ribosome::host_fn;
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostAccess;
+use crate::core::ribosome::InvocationAuth;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallHostAccess;
 use crate::core::ribosome::ZomeCallInvocation;
@@ -164,6 +165,8 @@ impl HostFnCaller {
 
         let (cell_id, zome_name) = zome_path.into();
 
+        // The host fn caller acts as the agent calling its own zome.
+        let auth = InvocationAuth::Cap(cell_id.agent_pubkey().clone(), None);
         let workspace_lock =
             HostFnWorkspace::new(env.clone(), cache, cell_id.agent_pubkey().clone())
                 .await
@@ -178,7 +181,12 @@ impl HostFnCaller {
         );
         let ribosome = Arc::new(ribosome);
         let zome = ribosome.saf_def().get_zome(&zome_name).unwrap();
-        let call_context = Arc::new(CallContext::new(zome, host_access.into()));
+        let call_context = Arc::new(CallContext::new(
+            zome,
+            "host_fn_caller".into(),
+            auth,
+            host_access.into(),
+        ));
         (env, ribosome, call_context, workspace_lock)
     }
 }
@@ -282,10 +290,10 @@ impl HostFnCaller {
         &self,
         base: EntryHash,
         link_tag: Option<LinkTag>,
-        _options: GetLinksOptions,
+        options: GetLinksOptions,
     ) -> Vec<Link> {
         let (_, ribosome, call_context, workspace_lock) = self.unpack().await;
-        let input = GetLinksInput::new(base, link_tag);
+        let input = GetLinksInput::new(base, link_tag).with_filter(options.filter);
         let output = { host_fn::get_links::get_links(ribosome, call_context, input).unwrap() };
 
         // Write
@@ -7,6 +7,7 @@ use aingle::{
     conductor::api::error::ConductorApiResult,
     sweettest::{SweetAgents, SweetConductor, SweetSafFile},
 };
+use aingle::{conductor::config::ConductorConfig, sweettest::standard_config};
 use aingle::{
     conductor::{api::error::ConductorApiError, CellError},
     core::workflow::error::WorkflowError,
@@ -300,7 +301,10 @@ async fn signal_subscription() {
     let app = conductor.setup_app("app", &[saf_file]).await.unwrap();
     let zome = &app.cells()[0].zome("zome1");
 
-    let signals = conductor.signals().take(N);
+    let signals = conductor
+        .signals()
+        .filter(|signal| matches!(signal, Signal::App(..)))
+        .take(N);
 
     // Emit N signals
     for _ in 0..N {
@@ -418,3 +422,78 @@ async fn can_call_real_zomes_too() {
         .await;
     assert_eq!(el.unwrap().header_address(), &hash)
 }
+
+/// An InlineZome which sleeps for as long as it's asked to
+fn sleep_zome() -> InlineZome {
+    InlineZome::new_unique(vec![])
+        .callback("sleep", |api, millis: u64| {
+            api.sleep(std::time::Duration::from_millis(millis))?;
+            Ok(())
+        })
+        .callback("ping", |_api, ()| Ok(()))
+}
+
+/// Many zome calls sleeping at once must not stop other zome calls from running
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_sleeps_do_not_starve_other_calls() -> anyhow::Result<()> {
+    use std::time::Duration;
+    use std::time::Instant;
+
+    // More sleeping calls than there are tokio worker threads
+    const NUM_SLEEPERS: usize = 32;
+    const SLEEP_MS: u64 = 3000;
+
+    observability::test_run().ok();
+    let (saf_file, _) = SweetSafFile::unique_from_inline_zome("zome1", sleep_zome()).await?;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &[saf_file]).await?;
+    let zome = app.cells()[0].zome("zome1");
+
+    let start = Instant::now();
+    let sleeping = futures::future::join_all(
+        (0..NUM_SLEEPERS).map(|_| conductor.call::<_, (), _>(&zome, "sleep", SLEEP_MS)),
+    );
+    let pinging = async {
+        // Give the sleeping calls a chance to start
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let ping_start = Instant::now();
+        let _: () = conductor.call(&zome, "ping", ()).await;
+        ping_start.elapsed()
+    };
+    let (_, ping_time) = futures::future::join(sleeping, pinging).await;
+
+    // The ping didn't have to wait for any of the sleeps to finish
+    assert!(
+        ping_time < Duration::from_millis(SLEEP_MS / 2),
+        "ping took {:?}",
+        ping_time
+    );
+    // and the sleeps ran concurrently
+    assert!(
+        start.elapsed() < Duration::from_millis(SLEEP_MS * 2),
+        "sleeping took {:?}",
+        start.elapsed()
+    );
+
+    Ok(())
+}
+
+/// Sleeps longer than the configured maximum are refused
+#[tokio::test(flavor = "multi_thread")]
+async fn sleep_longer_than_max_fails() -> anyhow::Result<()> {
+    observability::test_run().ok();
+    let (saf_file, _) = SweetSafFile::unique_from_inline_zome("zome1", sleep_zome()).await?;
+    let mut conductor = SweetConductor::from_config(ConductorConfig {
+        max_sleep_per_call_ms: Some(100),
+        ..standard_config()
+    })
+    .await;
+    let app = conductor.setup_app("app", &[saf_file]).await?;
+    let zome = app.cells()[0].zome("zome1");
+
+    let _: () = conductor.call(&zome, "sleep", 10u64).await;
+    let result: ConductorApiResult<()> = conductor.call_fallible(&zome, "sleep", 1000u64).await;
+    assert!(result.is_err());
+
+    Ok(())
+}
@@ -171,6 +171,7 @@ async fn get_from_another_agent() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            rate_limit: None,
         },
         vec![TestWasm::Create.into()],
     )
@@ -315,6 +316,7 @@ async fn get_links_from_another_agent() {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            rate_limit: None,
         },
         vec![TestWasm::Create.into()],
     )
@@ -348,7 +350,10 @@ async fn get_links_from_another_agent() {
     )
     .await;
 
-    let link_options = GetLinksOptions { timeout_ms: None };
+    let link_options = GetLinksOptions {
+        timeout_ms: None,
+        ..Default::default()
+    };
 
     // Bob store links
     let base = Post("Bananas are good for you".into());
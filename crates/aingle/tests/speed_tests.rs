@@ -23,6 +23,7 @@ use aingle::conductor::api::AdminRequest;
 use aingle::conductor::api::AdminResponse;
 use aingle::conductor::api::AppRequest;
 use aingle::conductor::api::AppResponse;
+use aingle::conductor::api::IssueAppAuthenticationTokenPayload;
 use aingle::conductor::api::RealAppInterfaceApi;
 use aingle::conductor::api::ZomeCall;
 use aingle::conductor::config::AdminInterfaceConfig;
@@ -134,6 +135,7 @@ async fn speed_test(n: Option<usize>) -> TestEnvs {
             uid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Anchor.into()].into(),
+            rate_limit: None,
         },
         vec![TestWasm::Anchor.into()],
     )
@@ -201,6 +203,18 @@ async fn speed_test(n: Option<usize>) -> TestEnvs {
         _ => panic!("Attach app interface failed: {:?}", response),
     };
     let (mut app_interface, _) = websocket_client_by_port(app_port).await.unwrap();
+    let request = AdminRequest::IssueAppAuthenticationToken(
+        IssueAppAuthenticationTokenPayload::for_installed_app_id("test app".to_string()),
+    );
+    let token = match client.request(request).await.unwrap() {
+        AdminResponse::AppAuthenticationTokenIssued(issued) => issued.token,
+        response => panic!("Issue app authentication token failed: {:?}", response),
+    };
+    let response = app_interface
+        .request(AppRequest::Authenticate { token })
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::Authenticated(_));
 
     // /////////////
     // END CONDUCTOR
@@ -10,6 +10,7 @@ use aingle::sweettest::SweetSafFile;
 use aingle::{
     conductor::api::ZomeCall,
     conductor::{
+        api::IssueAppAuthenticationTokenPayload,
         api::{AdminRequest, AdminResponse, AppRequest, AppResponse},
         config::*,
         error::ConductorError,
@@ -83,6 +84,10 @@ fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
         }),
         keystore_path: None,
         use_dangerous_test_keystore: true,
+        max_sleep_per_call_ms: None,
+        block_warranted_agents: false,
+        trusted_publishers: None,
+        bundle_resources: Default::default(),
     }
 }
 
@@ -210,9 +215,14 @@ pub async fn start_aingle(config_path: PathBuf) -> Child {
     child
 }
 
-pub async fn call_foo_fn(app_port: u16, original_saf_hash: SafHash, aingle: &mut Child) {
+pub async fn call_foo_fn(
+    app_port: u16,
+    admin_tx: &mut WebsocketSender,
+    original_saf_hash: SafHash,
+    aingle: &mut Child,
+) {
     // Connect to App Interface
-    let (mut app_tx, _) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut app_tx, _) = connect_app_interface(aingle, admin_tx, app_port, "test").await;
     let cell_id = CellId::from((original_saf_hash, fake_agent_pubkey_1()));
     call_zome_fn(
         aingle,
@@ -251,6 +261,20 @@ pub async fn call_zome_fn<S>(
     assert_matches!(call_response, AppResponse::ZomeCallInvocation(_));
 }
 
+/// Wait up to a second for the next App signal on an app interface connection,
+/// skipping over any system signals
+pub async fn next_app_signal(app_rx: WebsocketReceiver) -> Option<Signal> {
+    let mut app_rx = Box::pin(app_rx.timeout(Duration::from_secs(1)));
+    loop {
+        let (bytes, msg) = app_rx.next().await?.ok()?;
+        assert!(!msg.is_request());
+        let signal = Signal::try_from(bytes).unwrap();
+        if let Signal::App(..) = signal {
+            return Some(signal);
+        }
+    }
+}
+
 pub async fn attach_app_interface(
     client: &mut WebsocketSender,
     aingle: &mut Child,
@@ -265,6 +289,29 @@ pub async fn attach_app_interface(
     }
 }
 
+/// Connect to an app interface and authenticate as the App
+/// with a token issued over the admin interface
+pub async fn connect_app_interface(
+    aingle: &mut Child,
+    admin_tx: &mut WebsocketSender,
+    app_port: u16,
+    installed_app_id: &str,
+) -> (WebsocketSender, WebsocketReceiver) {
+    let request = AdminRequest::IssueAppAuthenticationToken(
+        IssueAppAuthenticationTokenPayload::for_installed_app_id(installed_app_id.to_string()),
+    );
+    let response = admin_tx.request(request);
+    let token = match check_timeout(aingle, response, 3000).await {
+        AdminResponse::AppAuthenticationTokenIssued(issued) => issued.token,
+        response => panic!("Issue app authentication token failed: {:?}", response),
+    };
+    let (mut app_tx, app_rx) = websocket_client_by_port(app_port).await.unwrap();
+    let response = app_tx.request(AppRequest::Authenticate { token });
+    let response = check_timeout(aingle, response, 3000).await;
+    assert_matches!(response, AppResponse::Authenticated(_));
+    (app_tx, app_rx)
+}
+
 pub async fn retry_admin_interface(
     port: u16,
     mut attempts: usize,
@@ -392,7 +439,13 @@ async fn call_zome() {
     assert_eq!(app_port, app_port_rcvd);
 
     // Call Zome
-    call_foo_fn(app_port, original_saf_hash.clone(), &mut aingle).await;
+    call_foo_fn(
+        app_port,
+        &mut client,
+        original_saf_hash.clone(),
+        &mut aingle,
+    )
+    .await;
 
     // Ensure that the other client does not receive any messages, i.e. that
     // responses are not broadcast to all connected clients, only the one
@@ -414,7 +467,8 @@ async fn call_zome() {
     tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
 
     // Call Zome again on the existing app interface port
-    call_foo_fn(app_port, original_saf_hash, &mut aingle).await;
+    let (mut client, _) = websocket_client_by_port(admin_port).await.unwrap();
+    call_foo_fn(app_port, &mut client, original_saf_hash, &mut aingle).await;
 
     // Shutdown aingle
     aingle.kill().await.expect("Failed to kill aingle");
@@ -469,9 +523,10 @@ async fn remote_signals() -> anyhow::Result<()> {
 
     let signal = AppSignal::new(signal);
     for mut rx in rxs {
-        let r = rx.try_recv();
+        // Skip any system signals emitted in the meantime
+        let r = std::iter::from_fn(|| rx.try_recv().ok()).find(|s| matches!(s, Signal::App(..)));
         // Each handle should recv a signal
-        assert_matches!(r, Ok(Signal::App(_, a)) if a == signal);
+        assert_matches!(r, Some(Signal::App(_, a)) if a == signal);
     }
 
     Ok(())
@@ -533,8 +588,9 @@ async fn emit_signals() {
     ///////////////////////////////////////////////////////
     // Emit signals (the real test!)
 
-    let (mut app_tx_1, app_rx_1) = websocket_client_by_port(app_port).await.unwrap();
-    let (_, app_rx_2) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut app_tx_1, app_rx_1) =
+        connect_app_interface(&mut aingle, &mut admin_tx, app_port, "test").await;
+    let (_, app_rx_2) = connect_app_interface(&mut aingle, &mut admin_tx, app_port, "test").await;
 
     call_zome_fn(
         &mut aingle,
@@ -546,23 +602,12 @@ async fn emit_signals() {
     )
     .await;
 
-    let (sig1, msg1) = Box::pin(app_rx_1.timeout(Duration::from_secs(1)))
-        .next()
-        .await
-        .unwrap()
-        .unwrap();
-    assert!(!msg1.is_request());
-
-    let (sig2, msg2) = Box::pin(app_rx_2.timeout(Duration::from_secs(1)))
-        .next()
-        .await
-        .unwrap()
-        .unwrap();
-    assert!(!msg2.is_request());
+    let sig1 = next_app_signal(app_rx_1).await.unwrap();
+    let sig2 = next_app_signal(app_rx_2).await.unwrap();
 
     assert_eq!(
         Signal::App(cell_id, AppSignal::new(ExternIO::encode(()).unwrap())),
-        Signal::try_from(sig1.clone()).unwrap(),
+        sig1,
     );
     assert_eq!(sig1, sig2);
 
@@ -572,6 +617,91 @@ async fn emit_signals() {
     aingle.kill().await.expect("Failed to kill aingle");
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "slow_tests")]
+async fn signal_subscription_filters() {
+    use aingle_conductor_api::signal_subscription::{SignalFilterSet, SignalSubscription};
+    observability::test_run().ok();
+    // NOTE: This is a full integration test that
+    // actually runs the aingle binary
+
+    let port = 9912;
+
+    let tmp_dir = TempDir::new("conductor_cfg_signal_subscription").unwrap();
+    let path = tmp_dir.path().to_path_buf();
+    let environment_path = path.clone();
+    let config = create_config(port, environment_path);
+    let config_path = write_config(path, &config);
+
+    let mut aingle = start_aingle(config_path.clone()).await;
+
+    let (mut admin_tx, _) = websocket_client_by_port(port).await.unwrap();
+
+    let uuid = uuid::Uuid::new_v4();
+    let saf = fake_saf_zomes(
+        &uuid.to_string(),
+        vec![(TestWasm::EmitSignal.into(), TestWasm::EmitSignal.into())],
+    );
+    let orig_saf_hash = saf.saf_hash().clone();
+    let (fake_saf_path, _tmpdir) = write_fake_saf_file(saf).await.unwrap();
+    let agent_key = fake_agent_pubkey_1();
+
+    let saf_hash = register_and_install_saf(
+        &mut aingle,
+        &mut admin_tx,
+        orig_saf_hash,
+        fake_agent_pubkey_1(),
+        fake_saf_path,
+        None,
+        "".into(),
+    )
+    .await;
+    let cell_id = CellId::new(saf_hash.clone(), agent_key.clone());
+
+    let request = AdminRequest::ActivateApp {
+        installed_app_id: "test".to_string(),
+    };
+    let response = admin_tx.request(request);
+    let response = check_timeout(&mut aingle, response, 3000).await;
+    assert_matches!(response, AdminResponse::AppActivated(_));
+
+    let app_port = attach_app_interface(&mut admin_tx, &mut aingle, None).await;
+
+    let (mut app_tx_1, app_rx_1) =
+        connect_app_interface(&mut aingle, &mut admin_tx, app_port, "test").await;
+    let (_, app_rx_2) = connect_app_interface(&mut aingle, &mut admin_tx, app_port, "test").await;
+
+    // The first connection opts out of all signals from the app
+    let request = AppRequest::SignalSubscription(SignalSubscription::new(
+        "test".to_string(),
+        SignalFilterSet::block_all(),
+    ));
+    let response = app_tx_1.request(request);
+    let response = check_timeout(&mut aingle, response, 3000).await;
+    assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+
+    call_zome_fn(
+        &mut aingle,
+        &mut app_tx_1,
+        cell_id.clone(),
+        TestWasm::EmitSignal,
+        "emit".into(),
+        (),
+    )
+    .await;
+
+    // The second connection never subscribed so still gets every signal
+    assert_eq!(
+        Signal::App(cell_id, AppSignal::new(ExternIO::encode(()).unwrap())),
+        next_app_signal(app_rx_2).await.unwrap(),
+    );
+
+    assert!(next_app_signal(app_rx_1).await.is_none());
+
+    // Shutdown aingle
+    aingle.kill().await.expect("Failed to kill aingle");
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn conductor_admin_interface_runs_from_config() -> Result<()> {
     observability::test_run().ok();
@@ -21,6 +21,8 @@ fn merge_hashes(
 ) -> AgentActivityResponse<HeaderHash> {
     let mut valid = HashSet::new();
     let mut rejected = HashSet::new();
+    let mut merged_warrants = HashSet::new();
+    let mut merged_fork = None;
     let mut merged_highest_observed = None;
     for result in results {
         let AgentActivityResponse {
@@ -28,11 +30,19 @@ fn merge_hashes(
             highest_observed,
             valid_activity,
             rejected_activity,
-            ..
+            warrants,
+            status,
         } = result;
         if the_agent != agent {
             continue;
         }
+        merged_warrants.extend(warrants);
+
+        // Authorities can know about forks that aren't
+        // visible from the activity they return.
+        if let ChainStatus::Forked(fork) = status {
+            merged_fork = Some(earliest_fork(merged_fork, fork));
+        }
 
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
@@ -68,6 +78,10 @@ fn merge_hashes(
     }
 
     let (status, valid, rejected) = compute_chain_status(valid, rejected);
+    let status = match merged_fork {
+        Some(fork) => add_fork(status, fork),
+        None => status,
+    };
     let valid_activity = if options.include_valid_activity {
         ChainItems::Hashes(valid)
     } else {
@@ -84,6 +98,7 @@ fn merge_hashes(
         valid_activity,
         rejected_activity,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants.into_iter().collect(),
     }
 }
 
@@ -96,9 +111,10 @@ fn compute_chain_status(
 ) -> (ChainStatus, ValidHashes, RejectedHashes) {
     let mut valid: Vec<_> = valid.into_iter().collect();
     let mut rejected: Vec<_> = rejected.into_iter().collect();
-    // Sort ascending.
-    valid.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-    rejected.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    // Sort ascending by sequence then hash so forks are found
+    // in the same order by everyone.
+    valid.sort_unstable();
+    rejected.sort_unstable();
     let mut valid_out = Vec::with_capacity(valid.len());
     let mut status = None;
     for (seq, hash) in valid {
@@ -107,11 +123,11 @@ fn compute_chain_status(
                 .last()
                 .and_then(|v: &(u32, HeaderHash)| if seq == v.0 { Some(v) } else { None });
             if let Some(fork) = fork {
-                status = Some(ChainStatus::Forked(ChainFork {
-                    fork_seq: seq,
-                    first_header: hash.clone(),
-                    second_header: fork.1.clone(),
-                }));
+                status = Some(ChainStatus::Forked(ChainFork::new(
+                    seq,
+                    hash.clone(),
+                    fork.1.clone(),
+                )));
             }
         }
 
@@ -141,22 +157,57 @@ fn compute_chain_status(
     (status, valid_out, rejected)
 }
 
+/// Combine a chain status with a fork that is known about
+/// but might not be reflected in the status.
+/// The earliest problem in the chain wins.
+pub(crate) fn add_fork(status: ChainStatus, fork: ChainFork) -> ChainStatus {
+    match status {
+        ChainStatus::Empty | ChainStatus::Valid(_) => ChainStatus::Forked(fork),
+        ChainStatus::Forked(other) => ChainStatus::Forked(earliest_fork(Some(other), fork)),
+        ChainStatus::Invalid(head) => {
+            if fork.fork_seq < head.header_seq {
+                ChainStatus::Forked(fork)
+            } else {
+                ChainStatus::Invalid(head)
+            }
+        }
+    }
+}
+
+/// Choose the same fork regardless of the order they were found in.
+fn earliest_fork(a: Option<ChainFork>, b: ChainFork) -> ChainFork {
+    match a {
+        Some(a) => {
+            let key = |f: &ChainFork| (f.fork_seq, f.first_header.clone(), f.second_header.clone());
+            if key(&a) <= key(&b) {
+                a
+            } else {
+                b
+            }
+        }
+        None => b,
+    }
+}
+
 fn merge_status_only(
     agent: AgentPubKey,
     results: Vec<AgentActivityResponse<HeaderHash>>,
 ) -> AgentActivityResponse<HeaderHash> {
     let mut merged_status = None;
+    let mut merged_warrants = HashSet::new();
     let mut merged_highest_observed = None;
     for result in results {
         let AgentActivityResponse {
             status,
             agent: the_agent,
             highest_observed,
+            warrants,
             ..
         } = result;
         if the_agent != agent {
             continue;
         }
+        merged_warrants.extend(warrants);
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
             (Some(h), None) | (None, Some(h)) => {
@@ -199,8 +250,7 @@ fn merge_status_only(
                     merged_status = Some(ChainStatus::Invalid(c));
                 }
                 (ChainStatus::Forked(a), ChainStatus::Forked(b)) => {
-                    let c = if a.fork_seq < b.fork_seq { a } else { b };
-                    merged_status = Some(ChainStatus::Forked(c));
+                    merged_status = Some(ChainStatus::Forked(earliest_fork(Some(a), b)));
                 }
                 (ChainStatus::Invalid(a), ChainStatus::Invalid(b)) => {
                     let c = if a.header_seq < b.header_seq { a } else { b };
@@ -232,5 +282,6 @@ fn merge_status_only(
         valid_activity: ChainItems::NotRequested,
         rejected_activity: ChainItems::NotRequested,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants.into_iter().collect(),
     }
 }
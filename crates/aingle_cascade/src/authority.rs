@@ -1,11 +1,13 @@
 use self::get_agent_activity_query::hashes::GetAgentActivityQuery;
 use self::get_entry_ops_query::GetEntryOpsQuery;
 use self::get_links_ops_query::GetLinksOpsQuery;
+use self::get_meta_query::GetMetaQuery;
 use self::{
     get_agent_activity_query::deterministic::DeterministicGetAgentActivityQuery,
     get_element_query::GetElementOpsQuery,
 };
 
+use super::agent_activity::add_fork;
 use super::error::CascadeResult;
 use ai_hash::AgentPubKey;
 use ai_hash::HeaderHash;
@@ -24,6 +26,7 @@ mod get_agent_activity_query;
 mod get_element_query;
 mod get_entry_ops_query;
 mod get_links_ops_query;
+mod get_meta_query;
 
 #[instrument(skip(state_env))]
 pub async fn handle_get_entry(
@@ -51,6 +54,19 @@ pub async fn handle_get_element(
     Ok(results)
 }
 
+#[instrument(skip(env))]
+pub async fn handle_get_meta(
+    env: EnvRead,
+    hash: AnySgdHash,
+    options: aingle_p2p::event::GetMetaOptions,
+) -> CascadeResult<MetadataSet> {
+    let query = GetMetaQuery::new(hash, options.metadata_request);
+    let results = env
+        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .await?;
+    Ok(results)
+}
+
 #[instrument(skip(env))]
 pub async fn handle_get_agent_activity(
     env: EnvRead,
@@ -58,9 +74,18 @@ pub async fn handle_get_agent_activity(
     query: ChainQueryFilter,
     options: aingle_p2p::event::GetActivityOptions,
 ) -> CascadeResult<AgentActivityResponse<HeaderHash>> {
-    let query = GetAgentActivityQuery::new(agent, query, options);
+    let query = GetAgentActivityQuery::new(agent.clone(), query, options);
     let results = env
-        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .async_reader(move |txn| {
+            let mut response = query.run(Txn::from(&txn))?;
+            response.warrants = aingle_state::warrant::get_warrants_for_agent(&txn, &agent)?;
+            // A fork found during sys validation might involve a header that
+            // isn't part of the activity returned above.
+            if let Some(fork) = aingle_state::chain_fork::get_chain_fork(&txn, &agent)? {
+                response.status = add_fork(response.status, fork);
+            }
+            CascadeResult::Ok(response)
+        })
         .await?;
     Ok(results)
 }
@@ -79,13 +104,13 @@ pub async fn handle_get_agent_activity_deterministic(
     Ok(results)
 }
 
-#[instrument(skip(env, _options))]
+#[instrument(skip(env, options))]
 pub async fn handle_get_links(
     env: EnvRead,
     link_key: WireLinkKey,
-    _options: aingle_p2p::event::GetLinksOptions,
+    options: aingle_p2p::event::GetLinksOptions,
 ) -> CascadeResult<WireLinkOps> {
-    let query = GetLinksOpsQuery::new(link_key);
+    let query = GetLinksOpsQuery::new(link_key, options.filter, options.response_type);
     let results = env
         .async_reader(move |txn| query.run(Txn::from(&txn)))
         .await?;
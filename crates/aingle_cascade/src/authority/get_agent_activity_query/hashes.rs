@@ -52,7 +52,7 @@ impl Query for GetAgentActivityQuery {
             JOIN SgdOp ON SgdOp.header_hash = Header.hash
             WHERE Header.author = :author
             AND SgdOp.type = :op_type
-            ORDER BY Header.seq ASC
+            ORDER BY Header.seq ASC, Header.hash ASC
         "
         .to_string()
     }
@@ -104,11 +104,11 @@ impl Query for GetAgentActivityQuery {
                         }
                     });
                     if let Some(fork) = fork {
-                        state.status = Some(ChainStatus::Forked(ChainFork {
-                            fork_seq: seq,
-                            first_header: header.as_hash().clone(),
-                            second_header: fork.as_hash().clone(),
-                        }));
+                        state.status = Some(ChainStatus::Forked(ChainFork::new(
+                            seq,
+                            header.as_hash().clone(),
+                            fork.as_hash().clone(),
+                        )));
                     }
                 }
 
@@ -165,6 +165,8 @@ impl Query for GetAgentActivityQuery {
             rejected_activity,
             status,
             highest_observed,
+            // Warrants are added by the authority.
+            warrants: Vec::with_capacity(0),
         })
     }
 }
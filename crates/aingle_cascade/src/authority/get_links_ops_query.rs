@@ -1,18 +1,24 @@
 use ai_hash::EntryHash;
+use ai_hash::HeaderHash;
 use aingle_sqlite::rusqlite::named_params;
 use aingle_sqlite::rusqlite::Row;
 use aingle_state::query::prelude::*;
 use aingle_state::query::StateQueryError;
 use aingle_types::sgd_op::SgdOpType;
+use aingle_types::link::GetLinksResponseType;
 use aingle_types::link::WireCreateLink;
 use aingle_types::link::WireDeleteLink;
 use aingle_types::link::WireLinkOps;
+use aingle_zome_types::GetLinksFilter;
 use aingle_zome_types::HasValidationStatus;
 use aingle_zome_types::Header;
 use aingle_zome_types::Judged;
+use aingle_zome_types::LinkOrder;
 use aingle_zome_types::LinkTag;
 use aingle_zome_types::SignedHeader;
+use aingle_zome_types::ValidationStatus;
 use aingle_zome_types::ZomeId;
+use std::collections::HashSet;
 
 use super::WireLinkKey;
 
@@ -21,38 +27,50 @@ pub struct GetLinksOpsQuery {
     base: Arc<EntryHash>,
     zome_id: ZomeId,
     tag: Option<Arc<LinkTag>>,
+    filter: Arc<GetLinksFilter>,
+    response_type: GetLinksResponseType,
 }
 
 impl GetLinksOpsQuery {
-    pub fn new(key: WireLinkKey) -> Self {
+    pub fn new(
+        key: WireLinkKey,
+        filter: GetLinksFilter,
+        response_type: GetLinksResponseType,
+    ) -> Self {
         Self {
             base: Arc::new(key.base),
             zome_id: key.zome_id,
             tag: key.tag.map(Arc::new),
+            filter: Arc::new(filter),
+            response_type,
         }
     }
 }
 
 pub struct Item {
     header: SignedHeader,
+    header_hash: HeaderHash,
     op_type: SgdOpType,
 }
 
+#[derive(Default)]
+pub struct State {
+    /// Creates on the requested page, with their header hashes.
+    creates: Vec<(HeaderHash, WireCreateLink)>,
+    deletes: Vec<WireDeleteLink>,
+}
+
 impl Query for GetLinksOpsQuery {
     type Item = Judged<Item>;
-    type State = WireLinkOps;
-    type Output = Self::State;
+    type State = State;
+    type Output = WireLinkOps;
 
     fn query(&self) -> String {
-        let create = "
-            SELECT Header.blob AS header_blob, SgdOp.type AS sgd_type,
-            SgdOp.validation_status AS status
+        let creates = "
+            SELECT Header.blob AS header_blob, Header.hash AS header_hash,
+            SgdOp.type AS sgd_type, SgdOp.validation_status AS status,
+            Header.timestamp_s AS timestamp_s, Header.timestamp_ns AS timestamp_ns
             FROM SgdOp
-        ";
-        let sub_create = "
-            SELECT Header.hash FROM SgdOp
-        ";
-        let common = "
             JOIN Header On SgdOp.header_hash = Header.hash
             WHERE SgdOp.type = :create
             AND
@@ -67,28 +85,110 @@ impl Query for GetLinksOpsQuery {
             AND
             Header.tag = :tag
         ";
-        let common_query = if self.tag.is_some() {
-            format!("{}{}", common, tag)
+        let author = "
+            AND
+            Header.author = :author
+        ";
+        let after = "
+            AND
+            (Header.timestamp_s, Header.timestamp_ns) >= (:after_s, :after_ns)
+        ";
+        let before = "
+            AND
+            (Header.timestamp_s, Header.timestamp_ns) < (:before_s, :before_ns)
+        ";
+        let (order, past_cursor, within_page) = match self.filter.order {
+            LinkOrder::Ascending => ("ASC", ">", "<="),
+            LinkOrder::Descending => ("DESC", "<", ">="),
+        };
+        let mut creates_query = creates.to_string();
+        if self.tag.is_some() {
+            creates_query.push_str(tag);
+        }
+        if self.filter.author.is_some() {
+            creates_query.push_str(author);
+        }
+        if self.filter.after.is_some() {
+            creates_query.push_str(after);
+        }
+        if self.filter.before.is_some() {
+            creates_query.push_str(before);
+        }
+        if self.filter.cursor.is_some() {
+            creates_query.push_str(&format!(
+                "
+                AND
+                (Header.timestamp_s, Header.timestamp_ns, Header.hash)
+                {} (:cursor_s, :cursor_ns, :cursor_hash)
+                ",
+                past_cursor
+            ));
+        }
+        // Only live links count towards the limit, so the page ends at the
+        // `limit`th valid create which has no valid delete. The deleted and
+        // invalid links before the end of the page are still sent so the
+        // requester can cache them.
+        let page_query = if self.filter.limit.is_some() {
+            format!(
+                "
+                PageEnd AS (
+                    SELECT timestamp_s, timestamp_ns, header_hash FROM Creates
+                    WHERE status = :valid
+                    AND NOT EXISTS (
+                        SELECT 1 FROM SgdOp
+                        JOIN Header On SgdOp.header_hash = Header.hash
+                        WHERE SgdOp.type = :delete
+                        AND
+                        SgdOp.when_integrated IS NOT NULL
+                        AND
+                        SgdOp.validation_status = :valid
+                        AND
+                        Header.create_link_hash = Creates.header_hash
+                    )
+                    ORDER BY timestamp_s {order}, timestamp_ns {order}, header_hash {order}
+                    LIMIT 1 OFFSET :limit - 1
+                ),
+                Page AS (
+                    SELECT * FROM Creates
+                    WHERE :limit > 0
+                    AND (
+                        NOT EXISTS (SELECT 1 FROM PageEnd)
+                        OR
+                        (timestamp_s, timestamp_ns, header_hash) {within_page} (
+                            SELECT timestamp_s, timestamp_ns, header_hash FROM PageEnd
+                        )
+                    )
+                )
+                ",
+                order = order,
+                within_page = within_page,
+            )
         } else {
-            common.into()
+            "
+            Page AS (
+                SELECT * FROM Creates
+            )
+            "
+            .to_string()
         };
-        let create_query = format!("{}{}", create, common_query);
-        let sub_create_query = format!("{}{}", sub_create, common_query);
-        let delete_query = format!(
+        format!(
             "
-            SELECT Header.blob AS header_blob, SgdOp.type AS sgd_type,
-            SgdOp.validation_status AS status
+            WITH Creates AS ({}),
+            {}
+            SELECT header_blob, header_hash, sgd_type, status FROM Page
+            UNION ALL
+            SELECT Header.blob AS header_blob, Header.hash AS header_hash,
+            SgdOp.type AS sgd_type, SgdOp.validation_status AS status
             FROM SgdOp
             JOIN Header On SgdOp.header_hash = Header.hash
             WHERE SgdOp.type = :delete
             AND
             SgdOp.when_integrated IS NOT NULL
             AND
-            Header.create_link_hash IN ({})
+            Header.create_link_hash IN (SELECT header_hash FROM Page)
             ",
-            sub_create_query
-        );
-        format!("{} UNION ALL {}", create_query, delete_query)
+            creates_query, page_query
+        )
     }
 
     fn params(&self) -> Vec<Params> {
@@ -104,21 +204,59 @@ impl Query for GetLinksOpsQuery {
                 ":tag": self.tag,
             });
         }
+        if self.filter.author.is_some() {
+            params.extend(named_params! {
+                ":author": self.filter.author,
+            });
+        }
+        if let Some(after) = &self.filter.after {
+            params.extend(named_params! {
+                ":after_s": after.0,
+                ":after_ns": after.1,
+            });
+        }
+        if let Some(before) = &self.filter.before {
+            params.extend(named_params! {
+                ":before_s": before.0,
+                ":before_ns": before.1,
+            });
+        }
+        if let Some(cursor) = &self.filter.cursor {
+            params.extend(named_params! {
+                ":cursor_s": cursor.timestamp.0,
+                ":cursor_ns": cursor.timestamp.1,
+                ":cursor_hash": cursor.create_link_hash,
+            });
+        }
+        if let Some(limit) = &self.filter.limit {
+            params.extend(named_params! {
+                ":limit": *limit,
+                ":valid": ValidationStatus::Valid,
+            });
+        }
         params
     }
 
     fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
         let f = |row: &Row| {
             let header = from_blob::<SignedHeader>(row.get(row.column_index("header_blob")?)?)?;
+            let header_hash = row.get(row.column_index("header_hash")?)?;
             let op_type = row.get(row.column_index("sgd_type")?)?;
             let validation_status = row.get(row.column_index("status")?)?;
-            Ok(Judged::raw(Item { header, op_type }, validation_status))
+            Ok(Judged::raw(
+                Item {
+                    header,
+                    header_hash,
+                    op_type,
+                },
+                validation_status,
+            ))
         };
         Arc::new(f)
     }
 
     fn init_fold(&self) -> StateQueryResult<Self::State> {
-        Ok(WireLinkOps::new())
+        Ok(State::default())
     }
 
     fn fold(&self, mut state: Self::State, sgd_op: Self::Item) -> StateQueryResult<Self::State> {
@@ -126,24 +264,18 @@ impl Query for GetLinksOpsQuery {
             SgdOpType::RegisterAddLink => {
                 let validation_status = sgd_op.validation_status();
                 let item = sgd_op.data.header;
+                let header_hash = sgd_op.data.header_hash;
                 if let (
                     SignedHeader(Header::CreateLink(header), signature),
                     Some(validation_status),
                 ) = (item, validation_status)
                 {
-                    if self.tag.is_some() {
-                        state.creates.push(WireCreateLink::condense_base_only(
-                            header,
-                            signature,
-                            validation_status,
-                        ));
+                    let create = if self.tag.is_some() {
+                        WireCreateLink::condense_base_only(header, signature, validation_status)
                     } else {
-                        state.creates.push(WireCreateLink::condense(
-                            header,
-                            signature,
-                            validation_status,
-                        ));
-                    }
+                        WireCreateLink::condense(header, signature, validation_status)
+                    };
+                    state.creates.push((header_hash, create));
                 }
             }
             SgdOpType::RegisterRemoveLink => {
@@ -170,6 +302,45 @@ impl Query for GetLinksOpsQuery {
     where
         S: Store,
     {
-        Ok(state)
+        let State {
+            mut creates,
+            mut deletes,
+        } = state;
+        creates.sort_by(|(a_hash, a), (b_hash, b)| {
+            self.filter
+                .compare((a.timestamp, a_hash), (b.timestamp, b_hash))
+        });
+
+        let deleted: HashSet<_> = deletes
+            .iter()
+            .filter(|d| d.validation_status == ValidationStatus::Valid)
+            .map(|d| d.link_add_address.clone())
+            .collect();
+        let is_live = |(hash, create): &(HeaderHash, WireCreateLink)| {
+            create.validation_status == ValidationStatus::Valid && !deleted.contains(hash)
+        };
+
+        match self.response_type {
+            GetLinksResponseType::Ops => (),
+            // Only the live links are sent when the requester just wants
+            // to list them, so there are no deletes to send either.
+            GetLinksResponseType::Live => {
+                creates.retain(|create| is_live(create));
+                deletes.clear();
+            }
+            // A requester counting links gets no ops at all.
+            GetLinksResponseType::Count => {
+                return Ok(WireLinkOps {
+                    count: Some(creates.iter().filter(|create| is_live(create)).count()),
+                    ..Default::default()
+                });
+            }
+        }
+
+        Ok(WireLinkOps {
+            creates: creates.into_iter().map(|(_, create)| create).collect(),
+            deletes,
+            count: None,
+        })
     }
 }
@@ -0,0 +1,195 @@
+use ai_hash::hash_type::AnySgd;
+use ai_hash::AnySgdHash;
+use ai_hash::HeaderHash;
+use aingle_sqlite::rusqlite::named_params;
+use aingle_sqlite::rusqlite::Row;
+use aingle_state::query::prelude::*;
+use aingle_state::query::StateQueryError;
+use aingle_types::metadata::EntrySgdStatus;
+use aingle_types::metadata::MetadataSet;
+use aingle_types::metadata::TimedHeaderHash;
+use aingle_types::sgd_op::SgdOpType;
+use aingle_zome_types::request::MetadataRequest;
+use aingle_zome_types::HasValidationStatus;
+use aingle_zome_types::Header;
+use aingle_zome_types::Judged;
+use aingle_zome_types::SignedHeader;
+use aingle_zome_types::ValidationStatus;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone)]
+pub struct GetMetaQuery(AnySgdHash, MetadataRequest);
+
+impl GetMetaQuery {
+    pub fn new(hash: AnySgdHash, request: MetadataRequest) -> Self {
+        Self(hash, request)
+    }
+}
+
+pub struct Item {
+    op_type: SgdOpType,
+    header: SignedHeader,
+    header_hash: HeaderHash,
+}
+
+#[derive(Default)]
+pub struct State {
+    meta: MetadataSet,
+    /// Headers that have a valid delete.
+    deleted: HashSet<HeaderHash>,
+    link_adds: HashSet<HeaderHash>,
+    link_removes: HashSet<HeaderHash>,
+}
+
+impl Query for GetMetaQuery {
+    type Item = Judged<Item>;
+    type State = State;
+    type Output = MetadataSet;
+
+    fn query(&self) -> String {
+        "
+        SELECT Header.blob AS header_blob, Header.hash AS header_hash,
+        SgdOp.type AS sgd_type, SgdOp.validation_status AS status
+        FROM SgdOp
+        JOIN Header On SgdOp.header_hash = Header.hash
+        WHERE SgdOp.type IN (
+            :store_element, :store_entry, :update_content, :update_element,
+            :deleted_by, :deleted_entry_header, :add_link, :remove_link
+        )
+        AND
+        SgdOp.basis_hash = :basis_hash
+        AND
+        SgdOp.when_integrated IS NOT NULL
+        "
+        .into()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        let params = named_params! {
+            ":store_element": SgdOpType::StoreElement,
+            ":store_entry": SgdOpType::StoreEntry,
+            ":update_content": SgdOpType::RegisterUpdatedContent,
+            ":update_element": SgdOpType::RegisterUpdatedElement,
+            ":deleted_by": SgdOpType::RegisterDeletedBy,
+            ":deleted_entry_header": SgdOpType::RegisterDeletedEntryHeader,
+            ":add_link": SgdOpType::RegisterAddLink,
+            ":remove_link": SgdOpType::RegisterRemoveLink,
+            ":basis_hash": self.0,
+        };
+        params.to_vec()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = |row: &Row| {
+            let header = from_blob::<SignedHeader>(row.get(row.column_index("header_blob")?)?)?;
+            let header_hash = row.get(row.column_index("header_hash")?)?;
+            let op_type = row.get(row.column_index("sgd_type")?)?;
+            let validation_status = row.get(row.column_index("status")?)?;
+            Ok(Judged::raw(
+                Item {
+                    op_type,
+                    header,
+                    header_hash,
+                },
+                validation_status,
+            ))
+        };
+        Arc::new(f)
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(State::default())
+    }
+
+    fn fold(&self, mut state: Self::State, sgd_op: Self::Item) -> StateQueryResult<Self::State> {
+        let status = sgd_op.validation_status();
+        let is_valid = status == Some(ValidationStatus::Valid);
+        let Item {
+            op_type,
+            header: SignedHeader(header, _),
+            header_hash,
+        } = sgd_op.data;
+        let timed = TimedHeaderHash {
+            timestamp: header.timestamp(),
+            header_hash,
+        };
+        match op_type {
+            SgdOpType::StoreElement | SgdOpType::StoreEntry => {
+                // Any valid op is enough to consider the basis valid.
+                if state.meta.validation_status != Some(ValidationStatus::Valid) {
+                    state.meta.validation_status = status;
+                }
+                if is_valid {
+                    state.meta.headers.insert(timed);
+                } else {
+                    state.meta.invalid_headers.insert(timed);
+                }
+            }
+            SgdOpType::RegisterDeletedBy | SgdOpType::RegisterDeletedEntryHeader => {
+                if is_valid {
+                    if let Header::Delete(delete) = header {
+                        state.deleted.insert(delete.deletes_address);
+                    }
+                    state.meta.deletes.insert(timed);
+                }
+            }
+            SgdOpType::RegisterUpdatedContent | SgdOpType::RegisterUpdatedElement => {
+                if is_valid {
+                    state.meta.updates.insert(timed);
+                }
+            }
+            SgdOpType::RegisterAddLink => {
+                if is_valid {
+                    state.link_adds.insert(timed.header_hash);
+                }
+            }
+            SgdOpType::RegisterRemoveLink => {
+                if let (true, Header::DeleteLink(delete_link)) = (is_valid, header) {
+                    state.link_removes.insert(delete_link.link_add_address);
+                }
+            }
+            op_type => return Err(StateQueryError::UnexpectedOp(op_type)),
+        }
+        Ok(state)
+    }
+
+    fn render<S>(&self, state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        let State {
+            mut meta,
+            deleted,
+            link_adds,
+            link_removes,
+        } = state;
+        let request = &self.1;
+        meta.link_count = link_adds.difference(&link_removes).count();
+        let is_entry = *self.0.hash_type() == AnySgd::Entry;
+        if request.entry_sgd_status && is_entry && meta.validation_status.is_some() {
+            let is_live = meta
+                .headers
+                .iter()
+                .any(|h| !deleted.contains(&h.header_hash));
+            meta.entry_sgd_status = Some(match meta.validation_status {
+                _ if is_live => EntrySgdStatus::Live,
+                Some(ValidationStatus::Valid) => EntrySgdStatus::Dead,
+                Some(ValidationStatus::Rejected) => EntrySgdStatus::Rejected,
+                _ => EntrySgdStatus::Abandoned,
+            });
+        }
+        if !request.all_valid_headers {
+            meta.headers.clear();
+        }
+        if !request.all_invalid_headers {
+            meta.invalid_headers.clear();
+        }
+        if !request.all_deletes {
+            meta.deletes.clear();
+        }
+        if !request.all_updates {
+            meta.updates.clear();
+        }
+        Ok(meta)
+    }
+}
@@ -1,11 +1,16 @@
 use super::*;
 use crate::authority::handle_get_agent_activity;
 use crate::test_utils::*;
+use ::fixt::prelude::*;
 use ghost_actor::dependencies::observability;
 use aingle_p2p::actor;
 use aingle_p2p::event::GetRequest;
+use aingle_sqlite::db::WriteManager;
+use aingle_sqlite::prelude::DatabaseResult;
+use aingle_state::mutations::insert_chain_fork;
 use aingle_state::prelude::test_cell_env;
 use aingle_types::activity::ChainItems;
+use std::collections::BTreeSet;
 
 fn options() -> aingle_p2p::event::GetOptions {
     aingle_p2p::event::GetOptions {
@@ -171,6 +176,7 @@ async fn get_links() {
     let expected = WireLinkOps {
         creates: vec![td.wire_create_link.clone()],
         deletes: vec![],
+        count: None,
     };
     assert_eq!(result, expected);
 
@@ -182,10 +188,236 @@ async fn get_links() {
     let expected = WireLinkOps {
         creates: vec![td.wire_create_link_base.clone()],
         deletes: vec![td.wire_delete_link.clone()],
+        count: None,
     };
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_links_with_filter() {
+    observability::test_run().ok();
+    let env = test_cell_env();
+
+    let base = fixt!(EntryHash);
+    let zome_id = fixt!(ZomeId);
+    let alice = fixt!(AgentPubKey);
+    let bob = fixt!(AgentPubKey);
+    // Four links, one a second, the first by bob and the rest by alice.
+    let creates: Vec<_> = (0..4)
+        .map(|i| {
+            let mut create_link = fixt!(CreateLink);
+            create_link.base_address = base.clone();
+            create_link.zome_id = zome_id;
+            create_link.timestamp = Timestamp(i, 0);
+            create_link.author = if i == 0 { bob.clone() } else { alice.clone() };
+            let hash = HeaderHash::with_data_sync(&Header::CreateLink(create_link.clone()));
+            fill_db(
+                &env.env(),
+                SgdOpHashed::from_content_sync(SgdOp::RegisterAddLink(
+                    fixt!(Signature),
+                    create_link.clone(),
+                )),
+            );
+            (hash, create_link)
+        })
+        .collect();
+    let cursor = |i: usize| LinkCursor {
+        timestamp: creates[i].1.timestamp,
+        create_link_hash: creates[i].0.clone(),
+    };
+
+    // The second link is deleted.
+    let mut delete_link = fixt!(DeleteLink);
+    delete_link.base_address = base.clone();
+    delete_link.link_add_address = creates[1].0.clone();
+    fill_db(
+        &env.env(),
+        SgdOpHashed::from_content_sync(SgdOp::RegisterRemoveLink(fixt!(Signature), delete_link)),
+    );
+
+    let link_key = WireLinkKey {
+        base,
+        zome_id,
+        tag: None,
+    };
+    let get = |filter: GetLinksFilter| {
+        let env = env.env();
+        let link_key = link_key.clone();
+        async move {
+            let options = actor::GetLinksOptions {
+                filter,
+                ..Default::default()
+            };
+            let result = handle_get_links(env.into(), link_key, (&options).into())
+                .await
+                .unwrap();
+            let creates: Vec<_> = result.creates.iter().map(|c| c.timestamp.0).collect();
+            (creates, result.deletes.len())
+        }
+    };
+
+    // No filter returns everything, oldest first.
+    assert_eq!(get(GetLinksFilter::new()).await, (vec![0, 1, 2, 3], 1));
+
+    // The deleted link doesn't count towards the limit, but is still
+    // returned with its delete.
+    let filter = GetLinksFilter::new().limit(2);
+    assert_eq!(get(filter.clone()).await, (vec![0, 1, 2], 1));
+    assert_eq!(get(filter.cursor(cursor(2))).await, (vec![3], 0));
+
+    let filter = GetLinksFilter::new().descending().limit(2);
+    assert_eq!(get(filter.clone()).await, (vec![3, 2], 0));
+    assert_eq!(get(filter.cursor(cursor(2))).await, (vec![1, 0], 1));
+
+    assert_eq!(get(GetLinksFilter::new().author(bob)).await, (vec![0], 0));
+    assert_eq!(
+        get(GetLinksFilter::new()
+            .after(Timestamp(1, 0))
+            .before(Timestamp(3, 0)))
+        .await,
+        (vec![1, 2], 1)
+    );
+
+    // Only the live links are sent, without any deletes.
+    let live = |filter: GetLinksFilter| {
+        let env = env.env();
+        let link_key = link_key.clone();
+        async move {
+            let options = actor::GetLinksOptions {
+                filter,
+                response_type: GetLinksResponseType::Live,
+                ..Default::default()
+            };
+            let result = handle_get_links(env.into(), link_key, (&options).into())
+                .await
+                .unwrap();
+            assert!(result.deletes.is_empty());
+            result.creates
+        }
+    };
+    let timestamps = |creates: Vec<WireCreateLink>| -> Vec<_> {
+        creates.iter().map(|c| c.timestamp.0).collect()
+    };
+    assert_eq!(timestamps(live(GetLinksFilter::new()).await), vec![0, 2, 3]);
+    assert_eq!(
+        timestamps(live(GetLinksFilter::new().limit(2)).await),
+        vec![0, 2]
+    );
+
+    // Only the number of live links is sent, without any ops.
+    let count = |filter: GetLinksFilter| {
+        let env = env.env();
+        let link_key = link_key.clone();
+        async move {
+            let options = actor::GetLinksOptions {
+                filter,
+                response_type: GetLinksResponseType::Count,
+                ..Default::default()
+            };
+            let result = handle_get_links(env.into(), link_key, (&options).into())
+                .await
+                .unwrap();
+            assert!(result.creates.is_empty());
+            assert!(result.deletes.is_empty());
+            result.count
+        }
+    };
+    assert_eq!(count(GetLinksFilter::new()).await, Some(3));
+    assert_eq!(count(GetLinksFilter::new().author(alice)).await, Some(2));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta_on_entry() {
+    observability::test_run().ok();
+    let env = test_cell_env();
+
+    let td = EntryTestData::create();
+    let hashes = |set: &BTreeSet<TimedHeaderHash>| -> Vec<HeaderHash> {
+        set.iter().map(|t| t.header_hash.clone()).collect()
+    };
+    let mut options = actor::GetMetaOptions::default();
+    options.metadata_request.entry_sgd_status = true;
+
+    fill_db(&env.env(), td.store_entry_op.clone());
+    fill_db(&env.env(), td.create_link_op.clone());
+
+    let result = handle_get_meta(env.env().into(), td.hash.clone().into(), (&options).into())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&result.headers), vec![td.create_hash.clone()]);
+    assert!(result.deletes.is_empty());
+    assert!(result.updates.is_empty());
+    assert_eq!(result.link_count, 1);
+    assert_eq!(result.validation_status, Some(ValidationStatus::Valid));
+    assert_eq!(result.entry_sgd_status, Some(EntrySgdStatus::Live));
+
+    fill_db(&env.env(), td.delete_entry_header_op.clone());
+    fill_db(&env.env(), td.update_content_op.clone());
+    fill_db(&env.env(), td.delete_link_op.clone());
+
+    let result = handle_get_meta(env.env().into(), td.hash.clone().into(), (&options).into())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&result.headers), vec![td.create_hash.clone()]);
+    assert_eq!(hashes(&result.deletes), vec![td.delete_hash.clone()]);
+    assert_eq!(hashes(&result.updates), vec![td.update_hash.clone()]);
+    assert_eq!(result.link_count, 0);
+    assert_eq!(result.entry_sgd_status, Some(EntrySgdStatus::Dead));
+
+    // - Only the requested metadata is returned.
+    options.metadata_request = MetadataRequest {
+        all_valid_headers: false,
+        all_deletes: false,
+        entry_sgd_status: false,
+        ..Default::default()
+    };
+    let result = handle_get_meta(env.env().into(), td.hash.clone().into(), (&options).into())
+        .await
+        .unwrap();
+    assert!(result.headers.is_empty());
+    assert!(result.deletes.is_empty());
+    assert_eq!(hashes(&result.updates), vec![td.update_hash.clone()]);
+    assert_eq!(result.entry_sgd_status, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta_on_header() {
+    observability::test_run().ok();
+    let env = test_cell_env();
+
+    let td = ElementTestData::create();
+    let options = actor::GetMetaOptions::default();
+
+    let result = handle_get_meta(
+        env.env().into(),
+        td.create_hash.clone().into(),
+        (&options).into(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result, MetadataSet::default());
+
+    fill_db(&env.env(), td.store_element_op.clone());
+    fill_db(&env.env(), td.deleted_by_op.clone());
+    fill_db(&env.env(), td.update_element_op.clone());
+
+    let result = handle_get_meta(
+        env.env().into(),
+        td.create_hash.clone().into(),
+        (&options).into(),
+    )
+    .await
+    .unwrap();
+    let header_hashes = |set: &BTreeSet<TimedHeaderHash>| -> Vec<HeaderHash> {
+        set.iter().map(|t| t.header_hash.clone()).collect()
+    };
+    assert_eq!(header_hashes(&result.headers), vec![td.create_hash.clone()]);
+    assert_eq!(header_hashes(&result.deletes), vec![td.delete_hash.clone()]);
+    assert_eq!(header_hashes(&result.updates), vec![td.update_hash.clone()]);
+    assert_eq!(result.validation_status, Some(ValidationStatus::Valid));
+    assert_eq!(result.entry_sgd_status, None);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_agent_activity() {
     observability::test_run().ok();
@@ -221,6 +453,7 @@ async fn get_agent_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: Vec::new(),
     };
     assert_eq!(result, expected);
 
@@ -241,3 +474,52 @@ async fn get_agent_activity() {
 
     assert_eq!(result, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_agent_activity_with_recorded_fork() {
+    observability::test_run().ok();
+    let env = test_cell_env();
+
+    let td = ActivityTestData::valid_chain_scenario();
+
+    for hash_op in td.hash_ops.iter().cloned() {
+        fill_db(&env.env(), hash_op);
+    }
+
+    // The fork was found during sys validation but one of the
+    // forking headers isn't part of the valid activity.
+    let fork = ChainFork::new(5, fixt!(HeaderHash), fixt!(HeaderHash));
+    env.env()
+        .conn()
+        .unwrap()
+        .with_commit_sync(|txn| {
+            insert_chain_fork(txn, td.agent.clone(), fork.clone()).unwrap();
+            DatabaseResult::Ok(())
+        })
+        .unwrap();
+
+    let options = actor::GetActivityOptions {
+        include_valid_activity: true,
+        include_rejected_activity: false,
+        include_full_headers: false,
+        ..Default::default()
+    };
+
+    let result = handle_get_agent_activity(
+        env.env().into(),
+        td.agent.clone(),
+        td.query_filter.clone(),
+        (&options).into(),
+    )
+    .await
+    .unwrap();
+    let expected = AgentActivityResponse {
+        agent: td.agent.clone(),
+        valid_activity: td.valid_hashes.clone(),
+        rejected_activity: ChainItems::NotRequested,
+        status: ChainStatus::Forked(fork),
+        highest_observed: Some(td.highest_observed.clone()),
+        warrants: Vec::new(),
+    };
+    assert_eq!(result, expected);
+}
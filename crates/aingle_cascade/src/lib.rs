@@ -12,6 +12,7 @@ use ai_hash::EntryHash;
 use ai_hash::HeaderHash;
 use aingle_p2p::actor::GetActivityOptions;
 use aingle_p2p::actor::GetLinksOptions;
+use aingle_p2p::actor::GetMetaOptions;
 use aingle_p2p::actor::GetOptions as NetworkGetOptions;
 use aingle_p2p::AIngleP2pCell;
 use aingle_p2p::AIngleP2pCellT;
@@ -606,21 +607,61 @@ where
 
     #[instrument(skip(self, options))]
     /// Gets an links from the cas or cache depending on it's metadata
+    /// The options' filter is applied both by the authorities we fetch from
+    /// and to the merged results, so the page returned is consistent.
     // The default behavior is to skip deleted or replaced entries.
     pub async fn sgd_get_links(
         &mut self,
         key: WireLinkKey,
         options: GetLinksOptions,
     ) -> CascadeResult<Vec<Link>> {
+        let filter = options.filter.clone();
         let authority = self.am_i_an_authority(key.base.clone().into()).await?;
         if !authority {
             self.fetch_links(key.clone(), options).await?;
         }
-        let query = GetLinksQuery::new(key.base, key.zome_id, key.tag);
+        let query = GetLinksQuery::new(key.base, key.zome_id, key.tag).with_filter(filter);
         let results = self.cascading(query)?;
         Ok(results)
     }
 
+    #[instrument(skip(self, key, options))]
+    /// Count the live links on a base.
+    /// If we are not an authority for the base then the authorities are
+    /// asked for only their count of live links, and the highest count
+    /// is added to the links in the scratch, which they can't have seen yet.
+    pub async fn count_links(
+        &mut self,
+        key: WireLinkKey,
+        mut options: GetLinksOptions,
+    ) -> CascadeResult<usize> {
+        let query = GetLinksQuery::new(key.base.clone(), key.zome_id, key.tag.clone())
+            .with_filter(options.filter.clone());
+        let authority = self.am_i_an_authority(key.base.clone().into()).await?;
+        if authority {
+            return Ok(self.cascading(query)?.len());
+        }
+        let network = match self.network.as_mut() {
+            Some(network) => network,
+            None => return Ok(self.cascading(query)?.len()),
+        };
+        options.response_type = GetLinksResponseType::Count;
+        let count = network
+            .get_links(key, options)
+            .await?
+            .into_iter()
+            .filter_map(|ops| ops.count)
+            .max()
+            .unwrap_or(0);
+        let scratch_count = match &self.scratch {
+            Some(scratch) => scratch
+                .apply_and_then(|scratch| query.run(DbScratch::new(&[], scratch)))?
+                .len(),
+            None => 0,
+        };
+        Ok(count + scratch_count)
+    }
+
     #[instrument(skip(self, key, options))]
     /// Return all CreateLink headers
     /// and DeleteLink headers ordered by time.
@@ -638,6 +679,31 @@ where
         Ok(results)
     }
 
+    #[instrument(skip(self, options))]
+    /// Get the metadata for any hash.
+    /// If we are not an authority for the hash then the metadata
+    /// held by the authorities is fetched and merged with our own.
+    pub async fn get_meta(
+        &mut self,
+        hash: AnySgdHash,
+        options: GetMetaOptions,
+    ) -> CascadeResult<MetadataSet> {
+        let mut meta = match self.vault.clone() {
+            Some(vault) => {
+                authority::handle_get_meta(vault, hash.clone(), (&options).into()).await?
+            }
+            None => MetadataSet::default(),
+        };
+        let authority = self.am_i_an_authority(hash.clone()).await?;
+        if !authority {
+            let network = ok_or_return!(self.network.as_mut(), meta);
+            for result in network.get_meta(hash, options).await? {
+                meta.merge(result);
+            }
+        }
+        Ok(meta)
+    }
+
     #[instrument(skip(self, agent, query, options))]
     /// Get agent activity from agent activity authorities.
     /// Hashes are requested from the authority and cache for valid chains.
@@ -706,6 +772,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         } = merged_response;
         let valid_activity = match valid_activity {
             ChainItems::Hashes(hashes) => {
@@ -754,6 +821,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         };
         Ok(r)
     }
@@ -114,10 +114,17 @@ impl AIngleP2pCellT for PassThroughNetwork {
     }
     async fn get_meta(
         &mut self,
-        _sgd_hash: ai_hash::AnySgdHash,
-        _options: actor::GetMetaOptions,
+        sgd_hash: ai_hash::AnySgdHash,
+        options: actor::GetMetaOptions,
     ) -> actor::AIngleP2pResult<Vec<MetadataSet>> {
-        todo!()
+        let mut out = Vec::new();
+        for env in &self.envs {
+            let r = authority::handle_get_meta(env.clone(), sgd_hash.clone(), (&options).into())
+                .await
+                .map_err(|e| AIngleP2pError::Other(e.into()))?;
+            out.push(r);
+        }
+        Ok(out)
     }
     async fn get_links(
         &mut self,
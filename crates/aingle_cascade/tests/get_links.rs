@@ -125,6 +125,7 @@ async fn links_authoring() {
         Ok(vec![WireLinkOps {
             creates: vec![],
             deletes: vec![],
+            count: None,
         }])
     });
     let mock = MockNetwork::new(mock);
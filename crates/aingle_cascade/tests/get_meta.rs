@@ -0,0 +1,111 @@
+use ghost_actor::dependencies::observability;
+use ai_hash::HeaderHash;
+use aingle_cascade::test_utils::*;
+use aingle_cascade::Cascade;
+use aingle_p2p::actor::GetMetaOptions;
+use aingle_p2p::MockAIngleP2pCellT;
+use aingle_state::prelude::test_cell_env;
+use aingle_types::prelude::*;
+use std::collections::BTreeSet;
+
+fn hashes(set: &BTreeSet<TimedHeaderHash>) -> Vec<HeaderHash> {
+    set.iter().map(|t| t.header_hash.clone()).collect()
+}
+
+fn options() -> GetMetaOptions {
+    let mut options = GetMetaOptions::default();
+    options.metadata_request.entry_sgd_status = true;
+    options
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn meta_authority() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cell_env();
+    let vault = test_cell_env();
+
+    // Data
+    let td = EntryTestData::create();
+    fill_db(&vault.env(), td.store_entry_op.clone());
+    fill_db(&vault.env(), td.create_link_op.clone());
+
+    // Network
+    // - Not expecting any calls to the network.
+    let mut mock = MockAIngleP2pCellT::new();
+    mock.expect_authority_for_hash().returning(|_| Ok(true));
+    let mock = MockNetwork::new(mock);
+
+    // Cascade
+    let mut cascade = Cascade::empty()
+        .with_network(mock, cache.env())
+        .with_vault(vault.env().into());
+
+    let r = cascade
+        .get_meta(td.hash.clone().into(), options())
+        .await
+        .unwrap();
+
+    assert_eq!(hashes(&r.headers), vec![td.create_hash.clone()]);
+    assert!(r.deletes.is_empty());
+    assert_eq!(r.link_count, 1);
+    assert_eq!(r.entry_sgd_status, Some(EntrySgdStatus::Live));
+
+    fill_db(&vault.env(), td.delete_entry_header_op.clone());
+
+    let r = cascade
+        .get_meta(td.hash.clone().into(), options())
+        .await
+        .unwrap();
+
+    assert_eq!(hashes(&r.deletes), vec![td.delete_hash.clone()]);
+    assert_eq!(r.entry_sgd_status, Some(EntrySgdStatus::Dead));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn meta_not_authority() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cell_env();
+    let vault = test_cell_env();
+    let authority = test_cell_env();
+
+    // Data
+    // - The local vault only knows about the update,
+    // the authority has the rest.
+    let td = EntryTestData::create();
+    fill_db(&vault.env(), td.update_content_op.clone());
+    fill_db(&authority.env(), td.store_entry_op.clone());
+    fill_db(&authority.env(), td.create_link_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.env().clone().into()]);
+
+    // Cascade
+    let mut cascade = Cascade::empty()
+        .with_network(network, cache.env())
+        .with_vault(vault.env().into());
+
+    let r = cascade
+        .get_meta(td.hash.clone().into(), options())
+        .await
+        .unwrap();
+
+    assert_eq!(hashes(&r.headers), vec![td.create_hash.clone()]);
+    assert_eq!(hashes(&r.updates), vec![td.update_hash.clone()]);
+    assert!(r.deletes.is_empty());
+    assert_eq!(r.link_count, 1);
+
+    fill_db(&authority.env(), td.delete_entry_header_op.clone());
+    fill_db(&authority.env(), td.delete_link_op.clone());
+
+    let r = cascade
+        .get_meta(td.hash.clone().into(), options())
+        .await
+        .unwrap();
+
+    assert_eq!(hashes(&r.deletes), vec![td.delete_hash.clone()]);
+    assert_eq!(r.link_count, 0);
+}
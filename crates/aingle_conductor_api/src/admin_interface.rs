@@ -2,8 +2,12 @@ use ai_hash::*;
 use aingle_types::prelude::*;
 use aingle_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use std::path::PathBuf;
 
+use crate::AppAuthenticationTokenIssued;
+use crate::CellMetrics;
 use crate::InstalledAppInfo;
+use crate::IssueAppAuthenticationTokenPayload;
 
 /// Represents the available conductor functions to call over an Admin interface
 /// and will result in a corresponding [`AdminResponse`] message being sent back over the
@@ -53,6 +57,45 @@ pub enum AdminRequest {
     /// [`AdminResponse::SafCloned`]: enum.AdminResponse.html#variant.SafCloned
     CreateCloneCell(Box<CreateCloneCellPayload>),
 
+    /// Archive a clone cell.
+    ///
+    /// The cell is stopped and moved out of its slot's clones, so it no longer
+    /// counts towards the slot's clone limit. Its data is kept until
+    /// [`AdminRequest::DeleteArchivedCloneCells`] is called for the slot.
+    ///
+    /// Will be responded to with an [`AdminResponse::CloneCellArchived`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`ArchiveCloneCellPayload`]: ../../../aingle_types/app/struct.ArchiveCloneCellPayload.html
+    /// [`AdminRequest::DeleteArchivedCloneCells`]: enum.AdminRequest.html#variant.DeleteArchivedCloneCells
+    /// [`AdminResponse::CloneCellArchived`]: enum.AdminResponse.html#variant.CloneCellArchived
+    ArchiveCloneCell(Box<ArchiveCloneCellPayload>),
+
+    /// Delete all archived clone cells of a slot, including their databases.
+    ///
+    /// Will be responded to with an [`AdminResponse::ArchivedCloneCellsDeleted`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`DeleteArchivedCloneCellsPayload`]: ../../../aingle_types/app/struct.DeleteArchivedCloneCellsPayload.html
+    /// [`AdminResponse::ArchivedCloneCellsDeleted`]: enum.AdminResponse.html#variant.ArchivedCloneCellsDeleted
+    DeleteArchivedCloneCells(Box<DeleteArchivedCloneCellsPayload>),
+
+    /// Migrate the agent of an app slot to a new, already-registered SAF.
+    ///
+    /// The `migrate_agent` callbacks of both SAFs must pass. The agent's chain
+    /// in the slot's provisioned cell is then closed with a `CloseChain` header,
+    /// and a new cell is created whose chain starts with an `OpenChain` header
+    /// followed by the live entries and links of the old chain. The new cell
+    /// replaces the old one in the slot, and the old cell's data is kept until
+    /// the app is uninstalled.
+    ///
+    /// Will be responded to with an [`AdminResponse::AgentMigrated`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`MigrateAgentPayload`]: ../../../aingle_types/app/struct.MigrateAgentPayload.html
+    /// [`AdminResponse::AgentMigrated`]: enum.AdminResponse.html#variant.AgentMigrated
+    MigrateAgent(Box<MigrateAgentPayload>),
+
     /// Install an app from a list of `Saf` paths.
     /// Triggers genesis to be run on all `Cell`s and to be stored.
     /// An `App` is intended for use by
@@ -169,16 +212,32 @@ pub enum AdminRequest {
         /// The InstalledAppId to deactivate
         installed_app_id: InstalledAppId,
     },
+    /// Uninstall the `App` specified by argument `installed_app_id` from the conductor,
+    /// whether it is active or not.
+    /// Its `Cell`s leave the network, and the databases of any `Cell`s and `Saf`s which
+    /// no other `App` uses are deleted. This cannot be undone.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppUninstalled`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AppUninstalled`]: enum.AdminResponse.html#variant.AppUninstalled
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    UninstallApp {
+        /// The InstalledAppId to uninstall
+        installed_app_id: InstalledAppId,
+    },
     /// Open up a new websocket interface at the networking port
     /// (optionally) specified by argument `port` (or using any free port if argument `port` is `None`)
     /// over which you can then use the [`AppRequest`] API.
-    /// Any active `App` will be callable via this interface.
+    /// Any active `App` will be callable via this interface, by connections
+    /// authenticated with a token from [`IssueAppAuthenticationToken`].
     /// The successful [`AdminResponse::AppInterfaceAttached`] message will contain
     /// the port chosen by the conductor if `None` was passed.
     ///
     /// Will be responded to with an [`AdminResponse::AppInterfaceAttached`]
     /// or an [`AdminResponse::Error`]
     ///
+    /// [`IssueAppAuthenticationToken`]: enum.AdminRequest.html#variant.IssueAppAuthenticationToken
     /// [`AdminResponse::AppInterfaceAttached`]: enum.AdminResponse.html#variant.AppInterfaceAttached
     /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
     AttachAppInterface {
@@ -186,7 +245,48 @@ pub enum AdminRequest {
         /// OS choose a free port
         port: Option<u16>,
     },
-    /// List all the app interfaces currently attached with [`AttachAppInterface`].
+    /// Open up a new app interface on a Unix domain socket at `path`,
+    /// over which you can then use the [`AppRequest`] API just as with
+    /// [`AttachAppInterface`].
+    /// The socket file is only accessible to the conductor's user unless
+    /// `mode` says otherwise, and replaces any socket already at `path`.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppUnixSocketInterfaceAttached`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AttachAppInterface`]: enum.AdminRequest.html#variant.AttachAppInterface
+    /// [`AdminResponse::AppUnixSocketInterfaceAttached`]: enum.AdminResponse.html#variant.AppUnixSocketInterfaceAttached
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    AttachAppUnixSocketInterface {
+        /// Where to create the socket file
+        path: PathBuf,
+        /// The permissions of the socket file, see [`InterfaceDriver::UnixSocket`]
+        ///
+        /// [`InterfaceDriver::UnixSocket`]: ../config/enum.InterfaceDriver.html#variant.UnixSocket
+        #[serde(default)]
+        mode: Option<u32>,
+    },
+    /// Issue a short-lived token which a client can use once to authenticate
+    /// a connection to an app interface, see [`AppRequest::Authenticate`].
+    /// The connection can then only act for the given `App`.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppAuthenticationTokenIssued`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AppRequest::Authenticate`]: enum.AppRequest.html#variant.Authenticate
+    /// [`AdminResponse::AppAuthenticationTokenIssued`]: enum.AdminResponse.html#variant.AppAuthenticationTokenIssued
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    IssueAppAuthenticationToken(IssueAppAuthenticationTokenPayload),
+    /// List the drivers of all the app interfaces currently attached with
+    /// [`AttachAppInterface`] or [`AttachAppUnixSocketInterface`].
+    ///
+    /// Will be responded to with an [`AdminResponse::AppInterfacesListed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AttachAppInterface`]: enum.AdminRequest.html#variant.AttachAppInterface
+    /// [`AttachAppUnixSocketInterface`]: enum.AdminRequest.html#variant.AttachAppUnixSocketInterface
+    /// [`AdminResponse::AppInterfacesListed`]: enum.AdminResponse.html#variant.AppInterfacesListed
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
     ListAppInterfaces,
     /// Dump the full state of the `Cell` specified by argument `cell_id`,
     /// including its chain, as a string containing JSON.
@@ -200,6 +300,78 @@ pub enum AdminRequest {
         /// The `CellId` for which to dump state
         cell_id: Box<CellId>,
     },
+    /// Collect structured metrics about the ops held by a running `Cell`.
+    /// If `cell_id` is `None` metrics for every running cell are returned.
+    ///
+    /// Will be responded to with an [`AdminResponse::CellMetricsReported`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::CellMetricsReported`]: enum.AdminResponse.html#variant.CellMetricsReported
+    GetCellMetrics {
+        /// Optionally choose a specific cell
+        cell_id: Option<CellId>,
+    },
+    /// Start pushing [`AdminSignal::CellMetrics`] snapshots for every running
+    /// `Cell` to this admin interface connection, once every `interval_ms`,
+    /// until it unsubscribes or closes.
+    /// Subscribing again replaces the previous interval.
+    ///
+    /// Will be responded to with an [`AdminResponse::CellMetricsSubscribed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminSignal::CellMetrics`]: enum.AdminSignal.html#variant.CellMetrics
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::CellMetricsSubscribed`]: enum.AdminResponse.html#variant.CellMetricsSubscribed
+    SubscribeCellMetrics {
+        /// Milliseconds between snapshots
+        interval_ms: u64,
+    },
+    /// Stop pushing the snapshots started by [`SubscribeCellMetrics`].
+    ///
+    /// Will be responded to with an [`AdminResponse::CellMetricsUnsubscribed`]
+    ///
+    /// [`SubscribeCellMetrics`]: enum.AdminRequest.html#variant.SubscribeCellMetrics
+    /// [`AdminResponse::CellMetricsUnsubscribed`]: enum.AdminResponse.html#variant.CellMetricsUnsubscribed
+    UnsubscribeCellMetrics,
+    /// Export the authored elements of a running `Cell`'s source chain,
+    /// including private entries, as a `SourceChainArchive` signed by the
+    /// cell's agent. Set `from_seq` to only export the elements from that
+    /// sequence number onwards, e.g. the ones added since the last backup.
+    ///
+    /// Will be responded to with an [`AdminResponse::SourceChainExported`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::SourceChainExported`]: enum.AdminResponse.html#variant.SourceChainExported
+    ExportSourceChain {
+        /// The `CellId` whose chain to export
+        cell_id: Box<CellId>,
+        /// The sequence number of the first element to export.
+        /// If omitted, the whole chain is exported.
+        from_seq: Option<u32>,
+    },
+    /// Restore a source chain from an archive made by [`ExportSourceChain`].
+    ///
+    /// An archive of a whole chain can only be imported into a cell with no
+    /// chain yet, and must pass the SAF's genesis checks again. Installing an
+    /// app for the archive's agent afterwards will then use the restored chain
+    /// instead of running genesis. An archive starting later must extend the
+    /// cell's existing chain. Either way the imported ops are republished.
+    ///
+    /// The SAF must be registered, and the agent's key must be in this
+    /// conductor's keystore for the restored cell to author anything new.
+    ///
+    /// Will be responded to with an [`AdminResponse::SourceChainImported`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`ExportSourceChain`]: enum.AdminRequest.html#variant.ExportSourceChain
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::SourceChainImported`]: enum.AdminResponse.html#variant.SourceChainImported
+    ImportSourceChain {
+        /// The archive to restore
+        archive: Box<SourceChainArchive>,
+    },
     /// Add a list [AgentInfoSigned] to this conductor's peer store.
     /// This is another way of finding peers on a sgd.
     ///
@@ -286,6 +458,28 @@ pub enum AdminResponse {
     /// [`CellId`]: ../../../aingle_types/cell/struct.CellId.html
     CloneCellCreated(CellId),
 
+    /// The successful response to an [`AdminRequest::ArchiveCloneCell`].
+    ///
+    /// It means the clone cell was stopped and archived
+    ///
+    /// [`AdminRequest::ArchiveCloneCell`]: enum.AdminRequest.html#variant.ArchiveCloneCell
+    CloneCellArchived,
+
+    /// The successful response to an [`AdminRequest::DeleteArchivedCloneCells`].
+    ///
+    /// It means the archived clone cells and their data were deleted
+    ///
+    /// [`AdminRequest::DeleteArchivedCloneCells`]: enum.AdminRequest.html#variant.DeleteArchivedCloneCells
+    ArchivedCloneCellsDeleted,
+
+    /// The successful response to an [`AdminRequest::MigrateAgent`].
+    ///
+    /// The response contains the [`CellId`] of the cell the agent migrated to.
+    ///
+    /// [`AdminRequest::MigrateAgent`]: enum.AdminRequest.html#variant.MigrateAgent
+    /// [`CellId`]: ../../../aingle_types/cell/struct.CellId.html
+    AgentMigrated(CellId),
+
     /// The succesful response to an [`AdminRequest::AddAdminInterfaces`].
     ///
     /// It means the `AdminInterface`s have successfully been added
@@ -340,8 +534,26 @@ pub enum AdminResponse {
         port: u16,
     },
 
-    /// The list of attached app interfaces.
-    AppInterfacesListed(Vec<u16>),
+    /// The succesful response to an [`AdminRequest::AttachAppUnixSocketInterface`].
+    ///
+    /// Contains the path of the socket file for the new App interface
+    ///
+    /// [`AdminRequest::AttachAppUnixSocketInterface`]: enum.AdminRequest.html#variant.AttachAppUnixSocketInterface
+    AppUnixSocketInterfaceAttached {
+        /// Path of the socket file of the new `AppInterfaceApi`
+        path: PathBuf,
+    },
+
+    /// The list of attached app interfaces,
+    /// with the ports that websocket interfaces were bound to.
+    AppInterfacesListed(Vec<crate::config::InterfaceDriver>),
+
+    /// The succesful response to an [`AdminRequest::IssueAppAuthenticationToken`].
+    ///
+    /// Contains the token and when it expires.
+    ///
+    /// [`AdminRequest::IssueAppAuthenticationToken`]: enum.AdminRequest.html#variant.IssueAppAuthenticationToken
+    AppAuthenticationTokenIssued(AppAuthenticationTokenIssued),
 
     /// The succesful response to an [`AdminRequest::ActivateApp`].
     ///
@@ -357,6 +569,13 @@ pub enum AdminResponse {
     /// [`AdminRequest::DeactivateApp`]: enum.AdminRequest.html#variant.DeactivateApp
     AppDeactivated,
 
+    /// The succesful response to an [`AdminRequest::UninstallApp`].
+    ///
+    /// It means the `App` was uninstalled and its unused data deleted.
+    ///
+    /// [`AdminRequest::UninstallApp`]: enum.AdminRequest.html#variant.UninstallApp
+    AppUninstalled,
+
     /// The succesful response to an [`AdminRequest::DumpState`].
     ///
     /// The result contains a string of serialized JSON data which can be deserialized to access the
@@ -365,6 +584,40 @@ pub enum AdminResponse {
     /// [`AdminRequest::DumpState`]: enum.AdminRequest.html#variant.DumpState
     StateDumped(String),
 
+    /// The succesful response to an [`AdminRequest::GetCellMetrics`].
+    ///
+    /// Contains the metrics of each requested cell.
+    ///
+    /// [`AdminRequest::GetCellMetrics`]: enum.AdminRequest.html#variant.GetCellMetrics
+    CellMetricsReported(Vec<CellMetrics>),
+
+    /// The succesful response to an [`AdminRequest::SubscribeCellMetrics`].
+    ///
+    /// Snapshots will now arrive as [`AdminSignal::CellMetrics`].
+    ///
+    /// [`AdminRequest::SubscribeCellMetrics`]: enum.AdminRequest.html#variant.SubscribeCellMetrics
+    /// [`AdminSignal::CellMetrics`]: enum.AdminSignal.html#variant.CellMetrics
+    CellMetricsSubscribed,
+
+    /// The succesful response to an [`AdminRequest::UnsubscribeCellMetrics`].
+    ///
+    /// [`AdminRequest::UnsubscribeCellMetrics`]: enum.AdminRequest.html#variant.UnsubscribeCellMetrics
+    CellMetricsUnsubscribed,
+
+    /// The succesful response to an [`AdminRequest::ExportSourceChain`].
+    ///
+    /// Contains the signed archive of the exported elements.
+    ///
+    /// [`AdminRequest::ExportSourceChain`]: enum.AdminRequest.html#variant.ExportSourceChain
+    SourceChainExported(Box<SourceChainArchive>),
+
+    /// The succesful response to an [`AdminRequest::ImportSourceChain`].
+    ///
+    /// It means the archive was verified and written to the cell's database.
+    ///
+    /// [`AdminRequest::ImportSourceChain`]: enum.AdminRequest.html#variant.ImportSourceChain
+    SourceChainImported,
+
     /// The succesful response to an [`AdminRequest::AddAgentInfo`].
     ///
     /// This means the agent info was successfully added to the peer store.
@@ -380,6 +633,21 @@ pub enum AdminResponse {
     AgentInfoRequested(Vec<AgentInfoSigned>),
 }
 
+/// Signals pushed by the conductor to every connection on an Admin interface.
+///
+/// Serializes the same way as [`AdminResponse`], e.g.
+/// `{ type: 'cell_metrics', data: [...] }`
+///
+/// [`AdminResponse`]: enum.AdminResponse.html
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, SerializedBytes)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum AdminSignal {
+    /// A periodic snapshot requested with [`AdminRequest::SubscribeCellMetrics`].
+    ///
+    /// [`AdminRequest::SubscribeCellMetrics`]: enum.AdminRequest.html#variant.SubscribeCellMetrics
+    CellMetrics(Vec<CellMetrics>),
+}
+
 /// Error type that goes over the websocket wire.
 /// This intends to be application developer facing
 /// so it should be readable and relevant
@@ -401,6 +669,13 @@ pub enum ExternalApiWireError {
     ActivateApp(String),
     /// The zome call is unauthorized
     ZomeCallUnauthorized(String),
+    /// The crypto request is for an agent outside of the App
+    CryptoUnauthorized(String),
+    /// The app interface connection could not be authenticated,
+    /// or made a request before authenticating
+    AuthenticationFailed(String),
+    /// The request is outside of the App the connection authenticated for
+    AppUnauthorized(String),
 }
 
 impl ExternalApiWireError {
@@ -0,0 +1,56 @@
+//! Types for authenticating connections to an App interface.
+//!
+//! An admin client issues a token for an App with
+//! [`AdminRequest::IssueAppAuthenticationToken`], and hands it to the
+//! client of the App, which sends it in an [`AppRequest::Authenticate`]
+//! as the first request on its App interface connection.
+//!
+//! [`AdminRequest::IssueAppAuthenticationToken`]: ../enum.AdminRequest.html#variant.IssueAppAuthenticationToken
+//! [`AppRequest::Authenticate`]: ../enum.AppRequest.html#variant.Authenticate
+
+use aingle_types::prelude::*;
+
+/// The number of seconds an issued token is valid for, if not specified
+pub const DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS: u64 = 30;
+
+/// An opaque token which lets one App interface connection act for one App.
+/// Each token can only be used once.
+pub type AppAuthenticationToken = Vec<u8>;
+
+/// The arguments to [`AdminRequest::IssueAppAuthenticationToken`]
+///
+/// [`AdminRequest::IssueAppAuthenticationToken`]: ../enum.AdminRequest.html#variant.IssueAppAuthenticationToken
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IssueAppAuthenticationTokenPayload {
+    /// The App the token will give access to
+    pub installed_app_id: InstalledAppId,
+    /// How many seconds the token can be used for before it expires
+    #[serde(default = "default_expiry_seconds")]
+    pub expiry_seconds: u64,
+}
+
+impl IssueAppAuthenticationTokenPayload {
+    /// A payload for the App which uses the default expiry
+    pub fn for_installed_app_id(installed_app_id: InstalledAppId) -> Self {
+        Self {
+            installed_app_id,
+            expiry_seconds: DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS,
+        }
+    }
+}
+
+fn default_expiry_seconds() -> u64 {
+    DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS
+}
+
+/// A newly issued token, returned as part of
+/// [`AdminResponse::AppAuthenticationTokenIssued`]
+///
+/// [`AdminResponse::AppAuthenticationTokenIssued`]: ../enum.AdminResponse.html#variant.AppAuthenticationTokenIssued
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AppAuthenticationTokenIssued {
+    /// The token to authenticate with
+    pub token: AppAuthenticationToken,
+    /// When the token stops being accepted
+    pub expires_at: Timestamp,
+}
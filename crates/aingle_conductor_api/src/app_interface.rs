@@ -1,4 +1,6 @@
-use crate::{signal_subscription::SignalSubscription, ExternalApiWireError};
+use crate::{
+    signal_subscription::SignalSubscription, AppAuthenticationToken, ExternalApiWireError,
+};
 use ai_hash::AgentPubKey;
 use aingle_types::prelude::*;
 
@@ -6,6 +8,22 @@ use aingle_types::prelude::*;
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum AppRequest {
+    /// Authenticate this connection with a token issued by
+    /// [`AdminRequest::IssueAppAuthenticationToken`].
+    /// This must be the first request on every connection,
+    /// all other requests are refused until it has succeeded.
+    /// Afterwards the connection can only act for the App the token was issued for.
+    ///
+    /// Will be responded to with an [`AppResponse::Authenticated`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`AdminRequest::IssueAppAuthenticationToken`]: enum.AdminRequest.html#variant.IssueAppAuthenticationToken
+    /// [`AppResponse::Authenticated`]: enum.AppResponse.html#variant.Authenticated
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
+    Authenticate {
+        /// The token issued for the App
+        token: AppAuthenticationToken,
+    },
     /// Get info about the App identified by the given `installed_app_id` argument,
     /// including info about each Cell installed by this App.
     /// Requires `installed_app_id` because an App interface can be the interface to multiple
@@ -20,10 +38,16 @@ pub enum AppRequest {
         /// The InstalledAppId for which to get information
         installed_app_id: InstalledAppId,
     },
-    /// Asks the conductor to do some crypto.
+    /// Asks the conductor to do some crypto with keys held in its keystore
+    /// on behalf of an agent of an installed App.
+    /// See [`CryptoRequest`] for the available operations.
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// Will be responded to with an [`AppResponse::Crypto`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`CryptoRequest`]: enum.CryptoRequest.html
+    /// [`AppResponse::Crypto`]: enum.AppResponse.html#variant.Crypto
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     Crypto(Box<CryptoRequest>),
     /// Call a zome function. See the inner [`ZomeCall`]
     /// struct to understand the data that must be provided.
@@ -39,10 +63,15 @@ pub enum AppRequest {
     /// DEPRECATED. Use `ZomeCall`.
     ZomeCallInvocation(Box<ZomeCall>),
 
-    /// Update signal subscriptions.
+    /// Update the signal subscription of this connection for an App.
+    /// Replaces any previous subscription for the same App,
+    /// other connections to the interface are unaffected.
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// Will be responded to with an [`AppResponse::SignalSubscriptionUpdated`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`AppResponse::SignalSubscriptionUpdated`]: enum.AppResponse.html#variant.SignalSubscriptionUpdated
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     SignalSubscription(SignalSubscription),
 }
 
@@ -62,6 +91,13 @@ pub enum AppResponse {
     /// [`ExternalApiWireError`]: error/enum.ExternalApiWireError.html
     Error(ExternalApiWireError),
 
+    /// The successful response to an [`AppRequest::Authenticate`].
+    ///
+    /// Contains the App the connection can now act for.
+    ///
+    /// [`AppRequest::Authenticate`]: enum.AppRequest.html#variant.Authenticate
+    Authenticated(InstalledAppId),
+
     /// The succesful response to an [`AppRequest::AppInfo`].
     ///
     /// Option will be `None` if there is no installed app with the given `installed_app_id` value from the request.
@@ -83,6 +119,16 @@ pub enum AppResponse {
 
     /// DEPRECATED. See `ZomeCall`.
     ZomeCallInvocation(Box<ExternIO>),
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    ///
+    /// [`AppRequest::SignalSubscription`]: enum.AppRequest.html#variant.SignalSubscription
+    SignalSubscriptionUpdated,
+
+    /// The successful response to an [`AppRequest::Crypto`].
+    ///
+    /// [`AppRequest::Crypto`]: enum.AppRequest.html#variant.Crypto
+    Crypto(Box<CryptoResponse>),
 }
 
 /// The data provided across an App interface in order to make a zome call
@@ -109,13 +155,77 @@ pub struct ZomeCall {
     pub provenance: AgentPubKey,
 }
 
-#[allow(missing_docs)]
+/// A crypto operation for the conductor to perform on behalf of an agent.
+///
+/// Every request names the App it is made for, and the conductor refuses
+/// to use any key which doesn't belong to the agent of one of that App's Cells.
+/// An x25519 key belongs to the agent whose zome call created it.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum CryptoRequest {
-    Sign(String),
-    Decrypt(String),
-    Encrypt(String),
+    /// Sign the data with the private key of `sign.key`,
+    /// which must be an agent of the App.
+    Sign {
+        /// The App the agent belongs to
+        installed_app_id: InstalledAppId,
+        /// The key to sign with and the data to sign
+        sign: Sign,
+    },
+    /// Encrypt data from `sender` to `recipient` with x25519/xsalsa20-poly1305.
+    /// The sender must be an x25519 key of an agent of the App.
+    Encrypt {
+        /// The App the agent belongs to
+        installed_app_id: InstalledAppId,
+        /// The keys to encrypt with and the data to encrypt
+        encrypt: X25519XSalsa20Poly1305Encrypt,
+    },
+    /// Decrypt data from `sender` to `recipient` with x25519/xsalsa20-poly1305.
+    /// The recipient must be an x25519 key of an agent of the App.
+    Decrypt {
+        /// The App the agent belongs to
+        installed_app_id: InstalledAppId,
+        /// The keys to decrypt with and the data to decrypt
+        decrypt: X25519XSalsa20Poly1305Decrypt,
+    },
+}
+
+impl CryptoRequest {
+    /// The App this request is made for
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        match self {
+            CryptoRequest::Sign {
+                installed_app_id, ..
+            }
+            | CryptoRequest::Encrypt {
+                installed_app_id, ..
+            }
+            | CryptoRequest::Decrypt {
+                installed_app_id, ..
+            } => installed_app_id,
+        }
+    }
+}
+
+/// The result of a [`CryptoRequest`], returned as part of [`AppResponse::Crypto`]
+///
+/// [`CryptoRequest`]: enum.CryptoRequest.html
+/// [`AppResponse::Crypto`]: enum.AppResponse.html#variant.Crypto
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum CryptoResponse {
+    /// The signature from a [`CryptoRequest::Sign`]
+    ///
+    /// [`CryptoRequest::Sign`]: enum.CryptoRequest.html#variant.Sign
+    Signed(Signature),
+    /// The encrypted data from a [`CryptoRequest::Encrypt`]
+    ///
+    /// [`CryptoRequest::Encrypt`]: enum.CryptoRequest.html#variant.Encrypt
+    Encrypted(XSalsa20Poly1305EncryptedData),
+    /// The data from a [`CryptoRequest::Decrypt`],
+    /// or `None` if it could not be decrypted with the given keys
+    ///
+    /// [`CryptoRequest::Decrypt`]: enum.CryptoRequest.html#variant.Decrypt
+    Decrypted(Option<XSalsa20Poly1305Data>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
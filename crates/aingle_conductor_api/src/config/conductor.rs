@@ -1,6 +1,7 @@
 #![deny(missing_docs)]
 //! This module is used to configure the conductor
 
+use ai_hash::AgentPubKeyB64;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde::Serialize;
@@ -19,10 +20,16 @@ pub use super::*;
 pub use dpki_config::DpkiConfig;
 //pub use logger_config::LoggerConfig;
 pub use error::*;
+pub use mr_bundle::RemoteConfig;
 pub use passphrase_service_config::PassphraseServiceConfig;
 //pub use signal_config::SignalConfig;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// The longest a single call to the `sleep` host function may suspend a
+/// zome call for if [`ConductorConfig::max_sleep_per_call_ms`] is not set.
+pub const DEFAULT_MAX_SLEEP_PER_CALL_MS: u64 = 5_000;
 
 // TODO change types from "stringly typed" to Url2
 /// All the config information for the conductor
@@ -57,6 +64,36 @@ pub struct ConductorConfig {
 
     /// Config options for the network module. Optional.
     pub network: Option<aingle_p2p::kitsune_p2p::KitsuneP2pConfig>,
+
+    /// The longest a single call to the `sleep` host function may suspend
+    /// a zome call for, in milliseconds. Longer sleeps are refused.
+    /// A sleeping call holds a thread from the blocking pool the whole time,
+    /// so this should be kept small.
+    /// If omitted, defaults to [`DEFAULT_MAX_SLEEP_PER_CALL_MS`].
+    #[serde(default)]
+    pub max_sleep_per_call_ms: Option<u64>,
+
+    /// Drop incoming ops authored by agents that have a valid warrant
+    /// against them, i.e. agents that were caught publishing invalid ops.
+    /// Warrants themselves are always accepted. Defaults to false.
+    #[serde(default)]
+    pub block_warranted_agents: bool,
+
+    /// If set, `InstallAppBundle` and `RegisterSaf` only accept bundles carrying
+    /// a valid signature from at least one of these publisher keys, and only
+    /// resolve their bundled resources and `url` resources pinned to a hash.
+    /// `InstallApp` and `RegisterSaf` from a SAF hash are refused, as they
+    /// have no signature to check.
+    /// If omitted, unsigned bundles are accepted.
+    #[serde(default)]
+    pub trusted_publishers: Option<Vec<AgentPubKeyB64>>,
+
+    /// How bundle resources at `url` locations are fetched: the directory
+    /// they are cached in by the hash they are pinned to, and whether to
+    /// only resolve them from that cache.
+    /// If omitted, they are always fetched and never cached.
+    #[serde(default)]
+    pub bundle_resources: RemoteConfig,
     //
     //
     // /// Which signals to emit
@@ -83,6 +120,14 @@ impl ConductorConfig {
         })?;
         config_from_yaml(&config_yaml)
     }
+
+    /// The longest a single call to the `sleep` host function may suspend a zome call for.
+    pub fn max_sleep_per_call(&self) -> Duration {
+        Duration::from_millis(
+            self.max_sleep_per_call_ms
+                .unwrap_or(DEFAULT_MAX_SLEEP_PER_CALL_MS),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -129,6 +174,10 @@ pub mod tests {
                 keystore_path: None,
                 admin_interfaces: None,
                 use_dangerous_test_keystore: false,
+                max_sleep_per_call_ms: None,
+                block_warranted_agents: false,
+                trusted_publishers: None,
+                bundle_resources: RemoteConfig::default(),
             }
         );
     }
@@ -178,6 +227,9 @@ pub mod tests {
         proxy_keepalive_ms: 42
         proxy_to_expire_ms: 42
       network_type: quic_bootstrap
+
+    max_sleep_per_call_ms: 42
+    block_warranted_agents: true
     "#;
         let result: ConductorConfigResult<ConductorConfig> = config_from_yaml(yaml);
         use aingle_p2p::kitsune_p2p::*;
@@ -221,6 +273,10 @@ pub mod tests {
                     driver: InterfaceDriver::Websocket { port: 1234 }
                 }]),
                 network: Some(network_config),
+                max_sleep_per_call_ms: Some(42),
+                block_warranted_agents: true,
+                trusted_publishers: None,
+                bundle_resources: RemoteConfig::default(),
             }
         );
     }
@@ -249,7 +305,83 @@ pub mod tests {
                 keystore_path: Some(PathBuf::from("/path/to/keystore").into()),
                 admin_interfaces: None,
                 use_dangerous_test_keystore: true,
+                max_sleep_per_call_ms: None,
+                block_warranted_agents: false,
+                trusted_publishers: None,
+                bundle_resources: RemoteConfig::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_config_trusted_publishers() {
+        let publisher: AgentPubKeyB64 = ai_hash::AgentPubKey::from_raw_36(vec![0xdb; 36]).into();
+        let yaml = format!(
+            r#"---
+    environment_path: /path/to/env
+    trusted_publishers:
+      - {}
+    "#,
+            publisher
+        );
+        let result: ConductorConfig = config_from_yaml(&yaml).unwrap();
+        assert_eq!(result.trusted_publishers, Some(vec![publisher]));
+    }
+
+    #[test]
+    fn test_config_bundle_resources() {
+        let yaml = r#"---
+    environment_path: /path/to/env
+    bundle_resources:
+      cache_dir: /path/to/cache
+      offline: true
+    "#;
+        let result: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            result.bundle_resources,
+            RemoteConfig {
+                cache_dir: Some(PathBuf::from("/path/to/cache")),
+                offline: true,
             }
         );
     }
+
+    #[test]
+    fn test_config_unix_socket_admin_interface() {
+        let yaml = r#"---
+    environment_path: /path/to/env
+    admin_interfaces:
+      - driver:
+          type: unix_socket
+          path: /path/to/admin.sock
+    "#;
+        let result: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            result.admin_interfaces,
+            Some(vec![AdminInterfaceConfig {
+                driver: InterfaceDriver::UnixSocket {
+                    path: PathBuf::from("/path/to/admin.sock"),
+                    mode: None,
+                }
+            }])
+        );
+        let yaml = r#"---
+    environment_path: /path/to/env
+    admin_interfaces:
+      - driver:
+          type: unix_socket
+          path: /path/to/admin.sock
+          mode: 0o660
+    "#;
+        let result: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            result.admin_interfaces,
+            Some(vec![AdminInterfaceConfig {
+                driver: InterfaceDriver::UnixSocket {
+                    path: PathBuf::from("/path/to/admin.sock"),
+                    mode: Some(0o660),
+                }
+            }])
+        );
+    }
 }
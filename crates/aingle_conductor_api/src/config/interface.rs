@@ -1,11 +1,13 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::PathBuf;
 
 /// Information neeeded to spawn an Admin interface
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct AdminInterfaceConfig {
     /// By what means will the interface be exposed?
-    /// Current only option is a local websocket running on a configurable port.
+    /// Either a local websocket running on a configurable port,
+    /// or a Unix domain socket at a configurable path.
     pub driver: InterfaceDriver,
     // /// How long will this interface be accessible between authentications?
     // /// TODO: implement once we have authentication
@@ -25,13 +27,34 @@ pub enum InterfaceDriver {
         /// The port on which to establish the WebsocketListener
         port: u16,
     },
+    /// An interface implemented via Websockets over a Unix domain socket.
+    ///
+    /// Access to the interface is controlled by the permissions of the socket
+    /// file and of the directory it is in, rather than by exposing a TCP port.
+    UnixSocket {
+        /// The path at which to create the socket file
+        path: PathBuf,
+        /// The permissions of the socket file, e.g. `0o660` to let the
+        /// conductor's group connect as well.
+        /// Defaults to [`DEFAULT_UNIX_SOCKET_MODE`].
+        ///
+        /// The file has these permissions before anyone can connect to it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+    },
 }
 
+/// The permissions of a Unix domain socket interface's file when
+/// its driver doesn't set any: readable and writable by the conductor's
+/// user only.
+pub const DEFAULT_UNIX_SOCKET_MODE: u32 = 0o600;
+
 impl InterfaceDriver {
-    /// Get the port for this driver.
-    pub fn port(&self) -> u16 {
+    /// Get the port for this driver, if it listens on one.
+    pub fn port(&self) -> Option<u16> {
         match self {
-            InterfaceDriver::Websocket { port } => *port,
+            InterfaceDriver::Websocket { port } => Some(*port),
+            InterfaceDriver::UnixSocket { .. } => None,
         }
     }
 }
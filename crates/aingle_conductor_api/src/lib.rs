@@ -1,12 +1,14 @@
 #![allow(deprecated)]
 
 mod admin_interface;
+pub mod app_authentication;
 mod app_interface;
 pub mod config;
 pub mod signal_subscription;
 pub mod state_dump;
 
 pub use admin_interface::*;
+pub use app_authentication::*;
 pub use app_interface::*;
 pub use config::*;
 pub use state_dump::*;
@@ -13,6 +13,26 @@ pub struct SignalSubscription {
     filters: SignalFilterSet,
 }
 
+impl SignalSubscription {
+    /// Constructor
+    pub fn new(installed_app_id: InstalledAppId, filters: SignalFilterSet) -> Self {
+        Self {
+            installed_app_id,
+            filters,
+        }
+    }
+
+    /// Accessor
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        &self.installed_app_id
+    }
+
+    /// Accessor
+    pub fn filters(&self) -> &SignalFilterSet {
+        &self.filters
+    }
+}
+
 /// Associate a SignalFilter with each Cell in an App.
 /// The filtering can be interpreted as inclusive or exclusive,
 /// depending on the use case.
@@ -46,6 +66,14 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// Should signals from this Cell be let through?
+    pub fn allows(&self, cell_id: &CellId) -> bool {
+        match self {
+            SignalFilterSet::Include(filters) => filters.contains_key(cell_id),
+            SignalFilterSet::Exclude(filters) => !filters.contains_key(cell_id),
+        }
+    }
 }
 
 /// Specifies fine-grained filter controls for the signals
@@ -65,3 +93,34 @@ impl SignalFilter {
         SignalFilter
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aingle_types::prelude::*;
+
+    fn cell_id(byte: u8) -> CellId {
+        CellId::new(
+            SafHash::from_raw_36(vec![byte; 36]),
+            AgentPubKey::from_raw_36(vec![byte; 36]),
+        )
+    }
+
+    #[test]
+    fn filter_set_allows() {
+        let cell_a = cell_id(1);
+        let cell_b = cell_id(2);
+        let only_a = || std::iter::once((cell_a.clone(), SignalFilter::empty())).collect();
+
+        assert!(SignalFilterSet::allow_all().allows(&cell_a));
+        assert!(!SignalFilterSet::block_all().allows(&cell_a));
+
+        let include = SignalFilterSet::Include(only_a());
+        assert!(include.allows(&cell_a));
+        assert!(!include.allows(&cell_b));
+
+        let exclude = SignalFilterSet::Exclude(only_a());
+        assert!(!exclude.allows(&cell_a));
+        assert!(exclude.allows(&cell_b));
+    }
+}
@@ -1,8 +1,12 @@
 use ai_hash::AgentPubKey;
 use ai_hash::SafHash;
 use aingle_state::source_chain::SourceChainJsonDump;
+use aingle_types::sgd_op::SgdOpType;
+use aingle_zome_types::cell::CellId;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Serialize, Deserialize)]
@@ -17,7 +21,7 @@ pub struct JsonDump {
 /// Use display to see a nice printout.
 pub struct IntegrationStateDumps(pub Vec<IntegrationStateDump>);
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 /// A high level view of the incoming ops and where
 /// they are currently.
 /// Ops start in the validation limbo then proceed
@@ -33,6 +37,41 @@ pub struct IntegrationStateDump {
     pub integrated: usize,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// Structured metrics for a single cell's sgd database.
+/// Unlike [`JsonDump`] this is meant to be consumed by
+/// tooling rather than read by a person.
+pub struct CellMetrics {
+    /// The cell these metrics were collected from.
+    pub cell_id: CellId,
+    /// Where the ops held by this cell currently are.
+    pub integration: IntegrationStateDump,
+    /// The number of ops held by this cell for each op type.
+    pub op_counts: HashMap<SgdOpType, usize>,
+    /// Histogram of the ops still in validation limbo
+    /// keyed by the number of validation attempts made on them.
+    pub validation_attempts: BTreeMap<u32, usize>,
+    /// Publish and validation receipt stats.
+    pub publish: PublishMetrics,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+/// Publish and validation receipt stats.
+pub struct PublishMetrics {
+    /// Total ops authored by this cell.
+    pub authored_ops: usize,
+    /// Authored ops that have never been published.
+    pub awaiting_publish: usize,
+    /// Total validation receipts received for authored ops.
+    pub receipts_received: usize,
+    /// Ops received from other agents that this cell still
+    /// needs to send a validation receipt for.
+    pub receipts_to_send: usize,
+    /// The most recent time an op was published
+    /// as a unix timestamp in seconds.
+    pub last_publish_time: Option<i64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 /// State dump of all the peer info
 pub struct P2pAgentsDump {
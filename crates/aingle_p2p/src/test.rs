@@ -446,6 +446,7 @@ mod tests {
                 fixt!(Signature),
                 ValidationStatus::Valid,
             )],
+            count: None,
         };
 
         let test_1_clone = test_1.clone();
@@ -142,11 +142,25 @@ pub struct GetLinksOptions {
     /// Note - if all requests time-out you will receive an empty result,
     /// not a timeout error.
     pub timeout_ms: Option<u64>,
+
+    /// [Remote]
+    /// Author, time range and pagination filters applied by the
+    /// authorities so only the matching links are sent back.
+    pub filter: GetLinksFilter,
+
+    /// [Remote]
+    /// Whether the authorities send back all the link ops, or only
+    /// the create link ops of the live links.
+    pub response_type: GetLinksResponseType,
 }
 
 impl Default for GetLinksOptions {
     fn default() -> Self {
-        Self { timeout_ms: None }
+        Self {
+            timeout_ms: None,
+            filter: GetLinksFilter::default(),
+            response_type: GetLinksResponseType::default(),
+        }
     }
 }
 
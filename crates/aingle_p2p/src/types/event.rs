@@ -54,22 +54,37 @@ impl Default for GetRequest {
 }
 
 /// GetMeta options help control how the get is processed at various levels.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetMetaOptions {}
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct GetMetaOptions {
+    /// Tells the remote-end which metadata to return
+    pub metadata_request: MetadataRequest,
+}
 
 impl From<&actor::GetMetaOptions> for GetMetaOptions {
-    fn from(_a: &actor::GetMetaOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetMetaOptions) -> Self {
+        Self {
+            metadata_request: a.metadata_request.clone(),
+        }
     }
 }
 
 /// GetLinks options help control how the get is processed at various levels.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetLinksOptions {}
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct GetLinksOptions {
+    /// Tells the remote-end which links to return
+    #[serde(default)]
+    pub filter: GetLinksFilter,
+    /// Tells the remote-end what to send back for those links
+    #[serde(default)]
+    pub response_type: GetLinksResponseType,
+}
 
 impl From<&actor::GetLinksOptions> for GetLinksOptions {
-    fn from(_a: &actor::GetLinksOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetLinksOptions) -> Self {
+        Self {
+            filter: a.filter.clone(),
+            response_type: a.response_type,
+        }
     }
 }
 
@@ -150,11 +150,18 @@ impl DbWrite {
         Self::new(tmpdir.path(), kind)
     }
 
-    /// Remove the db and directory
-    #[deprecated = "is this used?"]
+    /// Remove the db's files, along with its write-ahead log.
+    /// Other databases of the same kind share the parent directory, so it
+    /// is left in place.
     pub async fn remove(self) -> DatabaseResult<()> {
-        if let Some(parent) = self.0.path.parent() {
-            std::fs::remove_dir_all(parent)?;
+        DATABASE_HANDLES.remove(&self.0.path);
+        for suffix in &["", "-wal", "-shm"] {
+            let mut path = self.0.path.clone().into_os_string();
+            path.push(suffix);
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => (),
+            }
         }
         Ok(())
     }
@@ -382,3 +389,32 @@ where
         tokio::time::sleep(RETRY_INTERVAL).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_hash::AgentPubKey;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn remove_only_deletes_its_own_files() {
+        let tmpdir = tempdir::TempDir::new("aingle_sqlite_remove").unwrap();
+        let cell_kind = |n: u8| {
+            DbKind::Cell(CellId::new(
+                SafHash::from_raw_36(vec![n; 36]),
+                AgentPubKey::from_raw_36(vec![n; 36]),
+            ))
+        };
+        let removed = DbWrite::open(tmpdir.path(), cell_kind(0)).unwrap();
+        let kept = DbWrite::open(tmpdir.path(), cell_kind(1)).unwrap();
+        let (removed_path, kept_path) = (removed.path().clone(), kept.path().clone());
+        assert!(removed_path.exists() && kept_path.exists());
+
+        removed.remove().await.unwrap();
+        assert!(!removed_path.exists());
+        assert!(kept_path.exists());
+
+        // Opening it again creates a fresh database
+        let reopened = DbWrite::open(tmpdir.path(), cell_kind(0)).unwrap();
+        assert!(reopened.path().exists());
+    }
+}
@@ -69,6 +69,19 @@ pub async fn p2p_put(db: &DbWrite, signed: &AgentInfoSigned) -> DatabaseResult<(
     db.async_commit(move |txn| tx_p2p_put(txn, record)).await
 }
 
+/// Put an AgentInfoSigned record into the p2p_store,
+/// returning whether the agent wasn't in the store before.
+/// The check and the put happen in the same transaction.
+pub async fn p2p_put_is_new(db: &DbWrite, signed: &AgentInfoSigned) -> DatabaseResult<bool> {
+    let record = P2pRecord::from_signed(signed)?;
+    db.async_commit(move |txn| {
+        let is_new = txn.p2p_get(&record.agent)?.is_none();
+        tx_p2p_put(txn, record)?;
+        Ok(is_new)
+    })
+    .await
+}
+
 /// Put an iterator of AgentInfoSigned records into the p2p_store
 pub async fn p2p_put_all(
     db: &DbWrite,
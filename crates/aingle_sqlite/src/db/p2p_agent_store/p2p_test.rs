@@ -32,7 +32,7 @@ fn rand_signed_at_ms() -> u64 {
     now - rng.gen_range(1000, 2000)
 }
 
-async fn rand_insert(db: &DbWrite, space: &Arc<KitsuneSpace>, agent: &Arc<KitsuneAgent>) {
+async fn rand_signed(space: &Arc<KitsuneSpace>, agent: &Arc<KitsuneAgent>) -> AgentInfoSigned {
     let mut rng = rand::thread_rng();
 
     let signed_at_ms = rand_signed_at_ms();
@@ -44,7 +44,7 @@ async fn rand_insert(db: &DbWrite, space: &Arc<KitsuneSpace>, agent: &Arc<Kitsun
         _ => rng.gen_range(0, u32::MAX / 2),
     };
 
-    let signed = AgentInfoSigned::sign(
+    AgentInfoSigned::sign(
         space.clone(),
         agent.clone(),
         half_len,
@@ -54,9 +54,29 @@ async fn rand_insert(db: &DbWrite, space: &Arc<KitsuneSpace>, agent: &Arc<Kitsun
         |_| async { Ok(Arc::new(KitsuneSignature(vec![0; 64]))) },
     )
     .await
-    .unwrap();
+    .unwrap()
+}
+
+async fn rand_insert(db: &DbWrite, space: &Arc<KitsuneSpace>, agent: &Arc<KitsuneAgent>) {
+    p2p_put(db, &rand_signed(space, agent).await).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_p2p_put_is_new() {
+    let tmp_dir = tempdir::TempDir::new("p2p_put_is_new").unwrap();
+
+    let space = rand_space();
+
+    let db = DbWrite::test(&tmp_dir, DbKind::P2pAgentStore(space.clone())).unwrap();
+
+    let agent = rand_agent();
+    let first = rand_signed(&space, &agent).await;
+    let second = rand_signed(&space, &agent).await;
+    let other = rand_signed(&space, &rand_agent()).await;
 
-    p2p_put(db, &signed).await.unwrap();
+    assert!(p2p_put_is_new(&db, &first).await.unwrap());
+    assert!(!p2p_put_is_new(&db, &second).await.unwrap());
+    assert!(p2p_put_is_new(&db, &other).await.unwrap());
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -41,6 +41,11 @@ pub enum DatabaseError {
     #[error(transparent)]
     DbConnectionPoolError(#[from] r2d2::Error),
 
+    #[error(
+        "Database {0} is at schema version {1}, but this version of AIngle only supports up to version {2}"
+    )]
+    SchemaVersionTooNew(String, u16, u16),
+
     #[error("Empty keys cannot be used with SQLite")]
     EmptyKey,
 
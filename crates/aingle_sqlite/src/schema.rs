@@ -1,107 +1,117 @@
+//! Versioned schemas for each kind of database.
+//!
+//! Each [Schema] is an ordered list of [Migration]s. The number of migrations
+//! which have been applied to a database is tracked in its `user_version`
+//! pragma, so opening a database only runs the migrations it hasn't seen yet.
+//!
+//! Migrations must never be edited or reordered once released: to change a
+//! schema, append a new migration to the end of the list.
+
+use aingle_zome_types::SignedHeader;
 use once_cell::sync::Lazy;
 use rusqlite::Connection;
+use rusqlite::Transaction;
 
 use crate::db::DbKind;
+use crate::error::DatabaseError;
+use crate::error::DatabaseResult;
 use crate::sql::*;
 
 pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_cell::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+    Schema::new(vec![
+        Migration::initial(sql_cell::SCHEMA),
+        Migration::forward(sql_cell::LINK_TIMESTAMPS).then(backfill_link_timestamps),
+    ])
 });
 
 pub static SCHEMA_CONDUCTOR: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_conductor::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+    Schema::new(vec![
+        Migration::initial(sql_conductor::SCHEMA),
+        Migration::forward(sql_conductor::X25519_KEY_AGENT),
+    ])
 });
 
-pub static SCHEMA_WASM: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_wasm::SCHEMA);
+pub static SCHEMA_WASM: Lazy<Schema> =
+    Lazy::new(|| Schema::new(vec![Migration::initial(sql_wasm::SCHEMA)]));
 
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
-});
+pub static SCHEMA_P2P_STATE: Lazy<Schema> =
+    Lazy::new(|| Schema::new(vec![Migration::initial(sql_p2p_agent_store::SCHEMA)]));
 
-pub static SCHEMA_P2P_STATE: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_p2p_agent_store::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
-});
-
-pub static SCHEMA_P2P_METRICS: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_p2p_metrics::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
-});
+pub static SCHEMA_P2P_METRICS: Lazy<Schema> =
+    Lazy::new(|| Schema::new(vec![Migration::initial(sql_p2p_metrics::SCHEMA)]));
 
 pub struct Schema {
-    current_index: usize,
     migrations: Vec<Migration>,
 }
 
 impl Schema {
-    /// Determine if any database migrations need to run, and run them if so.
-    /// The decision is based on the difference between this Schema's
-    /// current_index and the user_version pragma value in the database itself.
-    /// NB: The current_index is 0-based, and the user_version is 1-based.
+    /// Create a schema from its migrations, in the order they must be applied.
+    pub fn new(migrations: Vec<Migration>) -> Self {
+        assert!(
+            !migrations.is_empty(),
+            "a schema needs at least its initial migration"
+        );
+        Self { migrations }
+    }
+
+    /// The `user_version` of a database which has had every migration applied.
+    pub fn current_version(&self) -> u16 {
+        self.migrations.len() as u16
+    }
+
+    /// Run any migrations which haven't yet been applied to this database.
+    ///
+    /// The database's `user_version` pragma holds the number of migrations
+    /// already applied. Databases created before versioning was introduced
+    /// have a `user_version` of 0, so the initial migration is re-run over
+    /// them, which is harmless because it only creates missing tables.
+    ///
+    /// Databases written by a newer version of AIngle are refused rather
+    /// than opened with a schema we don't understand.
     pub fn initialize(
         &self,
         conn: &mut Connection,
         db_kind: Option<&DbKind>,
-    ) -> rusqlite::Result<()> {
+    ) -> DatabaseResult<()> {
         let user_version: u16 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
         let db_kind = db_kind
             .map(ToString::to_string)
             .unwrap_or_else(|| "<no name>".to_string());
+        let current_version = self.current_version();
 
-        if user_version == 0 {
-            // database just needs to be created / initialized
-            self.migrations[self.current_index].initialize(conn)?;
-            tracing::info!("database initialized: {}", db_kind);
-            return Ok(());
-        } else {
-            let current_index = user_version as usize - 1;
-            match current_index.cmp(&self.current_index) {
-                std::cmp::Ordering::Less => {
-                    // run forward migrations
-                    for v in current_index..self.current_index + 1 {
-                        self.migrations[v].run(conn)?;
-                    }
-                    // set the DB user_version so that next time we don't run
-                    // the same migration
-                    let new_user_version = (self.current_index + 1) as u16;
-                    conn.pragma_update(None, "user_version", &new_user_version)?;
+        match user_version.cmp(&current_version) {
+            std::cmp::Ordering::Less => {
+                for (index, migration) in self
+                    .migrations
+                    .iter()
+                    .enumerate()
+                    .skip(user_version as usize)
+                {
+                    migration.run(conn, (index + 1) as u16)?;
+                }
+                if user_version == 0 {
+                    tracing::info!("database initialized: {}", db_kind);
+                } else {
                     tracing::info!(
                         "database forward migrated: {} from {} to {}",
                         db_kind,
-                        current_index,
-                        self.current_index
-                    );
-                }
-                std::cmp::Ordering::Equal => {
-                    tracing::debug!(
-                        "database needed no migration or initialization, good to go: {}",
-                        db_kind
+                        user_version,
+                        current_version
                     );
                 }
-                std::cmp::Ordering::Greater => {
-                    unimplemented!("backward migrations unimplemented");
-                }
+            }
+            std::cmp::Ordering::Equal => {
+                tracing::debug!(
+                    "database needed no migration or initialization, good to go: {}",
+                    db_kind
+                );
+            }
+            std::cmp::Ordering::Greater => {
+                return Err(DatabaseError::SchemaVersionTooNew(
+                    db_kind,
+                    user_version,
+                    current_version,
+                ));
             }
         }
 
@@ -110,28 +120,355 @@ impl Schema {
 }
 
 pub struct Migration {
-    schema: Sql,
-    _forward: Sql,
-    _backward: Option<Sql>,
+    forward: Sql,
+    backfill: Option<Backfill>,
 }
 
 impl Migration {
+    /// The first migration of a schema, which creates its tables.
+    /// This must only use `IF NOT EXISTS` statements, because it is re-run
+    /// over databases created before schemas were versioned.
     pub fn initial(schema: &str) -> Self {
+        Self::forward(schema)
+    }
+
+    /// A migration which moves a database forward from the previous version.
+    pub fn forward(sql: &str) -> Self {
         Self {
-            schema: schema.into(),
-            _forward: "".into(),
-            _backward: None,
+            forward: sql.into(),
+            backfill: None,
         }
     }
 
-    pub fn initialize(&self, conn: &mut Connection) -> rusqlite::Result<()> {
-        conn.execute_batch(&self.schema)?;
-        Ok(())
+    /// Fill in data which can't be derived in SQL, e.g. from blobs,
+    /// after this migration's SQL has run.
+    pub fn then(mut self, backfill: Backfill) -> Self {
+        self.backfill = Some(backfill);
+        self
     }
 
-    pub fn run(&self, _conn: &mut Connection) -> rusqlite::Result<()> {
-        unimplemented!("actual migrations not yet implemented")
+    /// Apply this migration and bump the `user_version` to this migration's
+    /// version in one transaction, so a failed migration leaves the database
+    /// as it was.
+    fn run(&self, conn: &mut Connection, version: u16) -> rusqlite::Result<()> {
+        let txn = conn.transaction()?;
+        txn.execute_batch(&self.forward)?;
+        if let Some(backfill) = self.backfill {
+            backfill(&txn)?;
+        }
+        txn.pragma_update(None, "user_version", &version)?;
+        txn.commit()
     }
 }
 
 type Sql = String;
+
+/// Rust run by a [Migration] in the same transaction as its SQL.
+pub type Backfill = fn(&Transaction) -> rusqlite::Result<()>;
+
+/// Copy the timestamp of each existing CreateLink header out of its blob.
+/// Only CreateLink headers have a base.
+fn backfill_link_timestamps(txn: &Transaction) -> rusqlite::Result<()> {
+    let headers = {
+        let mut stmt = txn.prepare(
+            "
+            SELECT hash, blob FROM Header
+            WHERE base_hash IS NOT NULL AND timestamp_s IS NULL
+            ",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    for (hash, blob) in headers {
+        let SignedHeader(header, _) = aingle_middleware_bytes::decode(&blob).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Blob, Box::new(e))
+        })?;
+        let timestamp = header.timestamp();
+        txn.execute(
+            "UPDATE Header SET timestamp_s = ?, timestamp_ns = ? WHERE hash = ?",
+            rusqlite::params![timestamp.0, timestamp.1, hash],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The cell schema as it was before schemas were versioned.
+    /// Other kinds of database still start from the schema they had then.
+    const UNVERSIONED_CELL_SCHEMA: &str =
+        include_str!("../fixtures/cell_schema_unversioned.sql");
+
+    fn user_version(conn: &Connection) -> u16 {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    fn table_exists(conn: &Connection, table: &str) -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+            [table],
+            |row| row.get::<_, u32>(0),
+        )
+        .unwrap()
+            > 0
+    }
+
+    /// The names of every table and its columns, in order
+    fn tables(conn: &Connection) -> Vec<(String, Vec<String>)> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")
+            .unwrap();
+        let names: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        names
+            .into_iter()
+            .map(|name| {
+                let mut stmt = conn
+                    .prepare(&format!("SELECT name FROM pragma_table_info('{}')", name))
+                    .unwrap();
+                let columns = stmt
+                    .query_map([], |row| row.get(0))
+                    .unwrap()
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                (name, columns)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn new_databases_are_at_the_current_version() {
+        for (schema, table) in [
+            (&*SCHEMA_CELL, "SgdOp"),
+            (&*SCHEMA_CONDUCTOR, "ConductorState"),
+            (&*SCHEMA_CONDUCTOR, "X25519KeyAgent"),
+            (&*SCHEMA_WASM, "Wasm"),
+            (&*SCHEMA_P2P_STATE, "p2p_agent_store"),
+            (&*SCHEMA_P2P_METRICS, "p2p_metrics"),
+        ]
+        .iter()
+        {
+            let mut conn = Connection::open_in_memory().unwrap();
+            schema.initialize(&mut conn, None).unwrap();
+            assert_eq!(user_version(&conn), schema.current_version());
+            assert!(table_exists(&conn, table));
+
+            // Opening again is a no-op
+            schema.initialize(&mut conn, None).unwrap();
+            assert_eq!(user_version(&conn), schema.current_version());
+        }
+    }
+
+    #[test]
+    fn unversioned_databases_are_upgraded_in_place() {
+        // A database created before schemas were versioned has the tables
+        // of that time but a `user_version` of 0.
+        for (schema, sql) in [
+            (&*SCHEMA_CELL, UNVERSIONED_CELL_SCHEMA),
+            (&*SCHEMA_CONDUCTOR, sql_conductor::SCHEMA),
+            (&*SCHEMA_WASM, sql_wasm::SCHEMA),
+            (&*SCHEMA_P2P_STATE, sql_p2p_agent_store::SCHEMA),
+            (&*SCHEMA_P2P_METRICS, sql_p2p_metrics::SCHEMA),
+        ]
+        .iter()
+        {
+            let mut conn = Connection::open_in_memory().unwrap();
+            conn.execute_batch(sql).unwrap();
+            assert_eq!(user_version(&conn), 0);
+            schema.initialize(&mut conn, None).unwrap();
+            assert_eq!(user_version(&conn), schema.current_version());
+
+            // It ends up with the same tables and columns as a new database
+            let mut new_conn = Connection::open_in_memory().unwrap();
+            schema.initialize(&mut new_conn, None).unwrap();
+            assert_eq!(tables(&conn), tables(&new_conn));
+        }
+
+        // Existing data survives the upgrade
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(sql_conductor::SCHEMA).unwrap();
+        conn.execute(
+            "INSERT INTO ConductorState (id, blob) VALUES (1, x'0102')",
+            [],
+        )
+        .unwrap();
+        SCHEMA_CONDUCTOR.initialize(&mut conn, None).unwrap();
+        let blob: Vec<u8> = conn
+            .query_row("SELECT blob FROM ConductorState WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(blob, vec![1, 2]);
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(UNVERSIONED_CELL_SCHEMA).unwrap();
+        conn.execute(
+            "
+            INSERT INTO Header (hash, type, seq, author, blob)
+            VALUES (x'01', 'Create', 3, x'02', x'03')
+            ",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "
+            INSERT INTO SgdOp (
+                hash, type, basis_hash, header_hash, is_authored, require_receipt,
+                storage_center_loc, authored_timestamp_ms, op_order, blob
+            )
+            VALUES (x'04', 'StoreElement', x'05', x'01', 1, 0, 6, 7, 'order', x'08')
+            ",
+            [],
+        )
+        .unwrap();
+        SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+        let header: (u32, Vec<u8>) = conn
+            .query_row("SELECT seq, author FROM Header WHERE hash = x'01'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(header, (3, vec![2]));
+        let op: (Vec<u8>, u32, Option<u32>) = conn
+            .query_row(
+                "
+                SELECT header_hash, storage_center_loc, validation_status
+                FROM SgdOp WHERE hash = x'04'
+                ",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(op, (vec![1], 6, None));
+        // The tables added since can be used
+        for table in ["Warrant", "ChainFork", "ScheduledFunctions"].iter() {
+            assert!(table_exists(&conn, table));
+        }
+        conn.execute(
+            "
+            INSERT INTO ChainFork (author, fork_seq, first_header, second_header)
+            VALUES (x'02', 3, x'01', x'09')
+            ",
+            [],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn existing_links_get_their_timestamps() {
+        use ai_hash::AgentPubKey;
+        use ai_hash::EntryHash;
+        use ai_hash::HeaderHash;
+        use aingle_zome_types::prelude::*;
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        Schema::new(vec![Migration::initial(sql_cell::SCHEMA)])
+            .initialize(&mut conn, None)
+            .unwrap();
+        let timestamp = Timestamp(1_600_000_000, 42);
+        let header = Header::CreateLink(CreateLink {
+            author: AgentPubKey::from_raw_36(vec![1; 36]),
+            timestamp,
+            header_seq: 4,
+            prev_header: HeaderHash::from_raw_36(vec![2; 36]),
+            base_address: EntryHash::from_raw_36(vec![3; 36]),
+            target_address: EntryHash::from_raw_36(vec![4; 36]),
+            zome_id: 0u8.into(),
+            tag: LinkTag::new(vec![]),
+        });
+        let blob =
+            aingle_middleware_bytes::encode(&SignedHeader(header, Signature([0; 64]))).unwrap();
+        conn.execute(
+            "
+            INSERT INTO Header (hash, type, seq, author, base_hash, zome_id, blob)
+            VALUES (x'01', 'CreateLink', 4, x'02', x'03', 0, ?)
+            ",
+            [blob],
+        )
+        .unwrap();
+
+        SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+        let backfilled: (i64, u32) = conn
+            .query_row(
+                "SELECT timestamp_s, timestamp_ns FROM Header WHERE hash = x'01'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(backfilled, (timestamp.0, timestamp.1));
+    }
+
+    #[test]
+    fn only_unapplied_migrations_run() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        Schema::new(vec![Migration::initial(sql_conductor::SCHEMA)])
+            .initialize(&mut conn, None)
+            .unwrap();
+        conn.execute(
+            "INSERT INTO ConductorState (id, blob) VALUES (1, x'01')",
+            [],
+        )
+        .unwrap();
+
+        // A later version adds a column to an existing table.
+        // Re-running the first migration would fail, because the column
+        // would already exist.
+        let schema = Schema::new(vec![
+            Migration::initial(sql_conductor::SCHEMA),
+            Migration::forward("ALTER TABLE ConductorState ADD COLUMN extra INTEGER DEFAULT 7;"),
+            Migration::forward("CREATE TABLE Other (id INTEGER PRIMARY KEY);"),
+        ]);
+        schema.initialize(&mut conn, None).unwrap();
+        assert_eq!(user_version(&conn), 3);
+        assert!(table_exists(&conn, "Other"));
+        let extra: u32 = conn
+            .query_row("SELECT extra FROM ConductorState WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(extra, 7);
+
+        schema.initialize(&mut conn, None).unwrap();
+        assert_eq!(user_version(&conn), 3);
+    }
+
+    #[test]
+    fn failed_migrations_are_rolled_back() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let schema = Schema::new(vec![
+            Migration::initial(sql_conductor::SCHEMA),
+            Migration::forward("CREATE TABLE Other (id INTEGER); NOT VALID SQL;"),
+        ]);
+        assert!(matches!(
+            schema.initialize(&mut conn, None),
+            Err(DatabaseError::SqliteError(_))
+        ));
+        assert_eq!(user_version(&conn), 1);
+        assert!(table_exists(&conn, "ConductorState"));
+        assert!(!table_exists(&conn, "Other"));
+    }
+
+    #[test]
+    fn newer_databases_are_refused() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        SCHEMA_WASM.initialize(&mut conn, None).unwrap();
+        let newer = SCHEMA_WASM.current_version() + 1;
+        conn.pragma_update(None, "user_version", &newer).unwrap();
+
+        assert!(matches!(
+            SCHEMA_WASM.initialize(&mut conn, Some(&DbKind::Wasm)),
+            Err(DatabaseError::SchemaVersionTooNew(kind, found, supported))
+            if kind == DbKind::Wasm.to_string()
+                && found == newer
+                && supported == SCHEMA_WASM.current_version()
+        ));
+        assert_eq!(user_version(&conn), newer);
+    }
+}
@@ -1,5 +1,6 @@
 pub mod sql_cell {
     pub(crate) const SCHEMA: &str = include_str!("sql/cell/schema.sql");
+    pub(crate) const LINK_TIMESTAMPS: &str = include_str!("sql/cell/link_timestamps.sql");
     pub const UPDATE_INTEGRATE_OPS: &str = include_str!("sql/cell/update_integrate_ops.sql");
     pub const FETCH_OP_HASHES_FULL: &str = include_str!("sql/cell/fetch_op_hashes_full.sql");
     pub const FETCH_OP_HASHES_SINGLE: &str = include_str!("sql/cell/fetch_op_hashes_single.sql");
@@ -8,6 +9,7 @@ pub mod sql_cell {
 
 pub(crate) mod sql_conductor {
     pub(crate) const SCHEMA: &str = include_str!("sql/conductor/schema.sql");
+    pub(crate) const X25519_KEY_AGENT: &str = include_str!("sql/conductor/x25519_key_agent.sql");
 }
 
 pub(crate) mod sql_wasm {
@@ -0,0 +1,9 @@
+-- The timestamp of each CreateLink header, so link authorities can
+-- filter, order and page through the links on a base in SQL.
+ALTER TABLE Header ADD COLUMN timestamp_s INTEGER NULL;
+ALTER TABLE Header ADD COLUMN timestamp_ns INTEGER NULL;
+
+CREATE INDEX IF NOT EXISTS Header_link_idx ON Header (
+    base_hash, zome_id, timestamp_s, timestamp_ns, hash
+);
+CREATE INDEX IF NOT EXISTS Header_create_link_hash_idx ON Header ( create_link_hash );
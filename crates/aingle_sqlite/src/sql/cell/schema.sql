@@ -143,3 +143,41 @@ CREATE TABLE IF NOT EXISTS ValidationReceipt (
     blob            BLOB           NOT NULL,
     FOREIGN KEY(op_hash) REFERENCES SgdOp(hash)
 );
+
+-- Warrants carried by RegisterWarrant ops.
+-- The hash is the hash of the RegisterWarrant op.
+CREATE TABLE IF NOT EXISTS Warrant (
+    hash            BLOB           PRIMARY KEY ON CONFLICT IGNORE,
+    -- The agent that authored the invalid op.
+    author          BLOB           NOT NULL,
+    -- The authority that issued the warrant.
+    warrantor       BLOB           NOT NULL,
+    blob            BLOB           NOT NULL,
+    FOREIGN KEY(hash) REFERENCES SgdOp(hash)
+);
+CREATE INDEX IF NOT EXISTS Warrant_author_idx ON Warrant ( author );
+
+-- Forks detected in an agent's source chain by its agent activity authorities.
+-- The two headers are ordered by hash.
+CREATE TABLE IF NOT EXISTS ChainFork (
+    author          BLOB           NOT NULL,
+    fork_seq        INTEGER        NOT NULL,
+    first_header    BLOB           NOT NULL,
+    second_header   BLOB           NOT NULL,
+
+    PRIMARY KEY (author, fork_seq, first_header, second_header) ON CONFLICT IGNORE
+);
+
+-- Zome functions scheduled by the `schedule` host function.
+-- A zome can only have one schedule per function.
+CREATE TABLE IF NOT EXISTS ScheduledFunctions (
+    zome_name        TEXT           NOT NULL,
+    fn_name          TEXT           NOT NULL,
+    -- When the function should next be run.
+    next_run_ms      INTEGER        NOT NULL,   -- UNIX TIMESTAMP MILLISECONDS
+    -- If this is null the function is removed after it has run once.
+    repeat_every_ms  INTEGER        NULL,
+
+    PRIMARY KEY (zome_name, fn_name) ON CONFLICT REPLACE
+);
+CREATE INDEX IF NOT EXISTS ScheduledFunctions_next_run_ms_idx ON ScheduledFunctions ( next_run_ms );
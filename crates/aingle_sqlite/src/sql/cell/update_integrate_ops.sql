@@ -126,4 +126,5 @@ WHERE
                         AND OP_DEP.type = :create_link
                 )
         )
+        WHEN :register_warrant THEN 1
     END 
\ No newline at end of file
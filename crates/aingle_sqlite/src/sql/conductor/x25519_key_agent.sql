@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS X25519KeyAgent (
+    key             BLOB           PRIMARY KEY ON CONFLICT REPLACE,
+    agent           BLOB           NOT NULL
+);
+CREATE INDEX IF NOT EXISTS X25519KeyAgent_agent_idx ON X25519KeyAgent ( agent );
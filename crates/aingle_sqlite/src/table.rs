@@ -3,9 +3,10 @@
 use rusqlite::Connection;
 
 use crate::db::DbKind;
+use crate::error::DatabaseResult;
 
 /// Enumeration of all databases needed by AIngle
-pub(crate) fn initialize_database(conn: &mut Connection, db_kind: &DbKind) -> rusqlite::Result<()> {
+pub(crate) fn initialize_database(conn: &mut Connection, db_kind: &DbKind) -> DatabaseResult<()> {
     match db_kind {
         DbKind::Cell(_) => {
             crate::schema::SCHEMA_CELL.initialize(conn, Some(db_kind))?;
@@ -0,0 +1,175 @@
+//! Queries for source chain forks detected by agent activity authorities.
+
+use ai_hash::AgentPubKey;
+use ai_hash::HeaderHash;
+use aingle_sqlite::rusqlite::named_params;
+use aingle_sqlite::rusqlite::OptionalExtension;
+use aingle_sqlite::rusqlite::Transaction;
+use aingle_types::sgd_op::SgdOpType;
+use aingle_types::timestamp::Timestamp;
+use aingle_zome_types::ChainFork;
+
+use crate::prelude::StateQueryResult;
+
+/// Get the earliest fork recorded in this agent's source chain.
+pub fn get_chain_fork(
+    txn: &Transaction,
+    author: &AgentPubKey,
+) -> StateQueryResult<Option<ChainFork>> {
+    Ok(txn
+        .query_row(
+            "
+            SELECT fork_seq, first_header, second_header
+            FROM ChainFork
+            WHERE author = :author
+            ORDER BY fork_seq ASC, first_header ASC, second_header ASC
+            LIMIT 1
+            ",
+            named_params! {
+                ":author": author,
+            },
+            |row| {
+                Ok(ChainFork {
+                    fork_seq: row.get("fork_seq")?,
+                    first_header: row.get("first_header")?,
+                    second_header: row.get("second_header")?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+/// Find the forks made by agent activity integrated at `when_integrated`,
+/// i.e. another header by the same author is held at the same position.
+/// If there are several, the one with the lowest hash is used so every
+/// authority records the same fork.
+pub fn get_chain_forks_integrated_at(
+    txn: &Transaction,
+    when_integrated: Timestamp,
+) -> StateQueryResult<Vec<(AgentPubKey, ChainFork)>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT Header.author, Header.seq, Header.hash,
+        (
+            SELECT Other.hash
+            FROM Header AS Other
+            WHERE
+            Other.author = Header.author
+            AND
+            Other.seq = Header.seq
+            AND
+            Other.hash != Header.hash
+            ORDER BY Other.hash ASC
+            LIMIT 1
+        ) AS conflict
+        FROM SgdOp
+        JOIN Header ON SgdOp.header_hash = Header.hash
+        WHERE
+        SgdOp.type = :register_activity
+        AND
+        SgdOp.when_integrated = :when_integrated
+        ",
+    )?;
+    let rows = stmt.query_map(
+        named_params! {
+            ":register_activity": SgdOpType::RegisterAgentActivity,
+            ":when_integrated": when_integrated,
+        },
+        |row| {
+            Ok((
+                row.get::<_, AgentPubKey>("author")?,
+                row.get::<_, u32>("seq")?,
+                row.get::<_, HeaderHash>("hash")?,
+                row.get::<_, Option<HeaderHash>>("conflict")?,
+            ))
+        },
+    )?;
+    let mut forks = Vec::new();
+    for row in rows {
+        if let (author, seq, hash, Some(conflict)) = row? {
+            forks.push((author, ChainFork::new(seq, hash, conflict)));
+        }
+    }
+    Ok(forks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use ::fixt::prelude::*;
+    use ai_hash::*;
+    use aingle_types::prelude::*;
+
+    #[test]
+    fn earliest_fork_is_returned() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let author = fixt!(AgentPubKey);
+        let hashes: Vec<HeaderHash> = HeaderHashFixturator::new(Predictable).take(4).collect();
+        let later = ChainFork::new(5, hashes[0].clone(), hashes[1].clone());
+        let earlier = ChainFork::new(2, hashes[3].clone(), hashes[2].clone());
+
+        env.conn()
+            .unwrap()
+            .with_commit_test(|txn| {
+                assert_eq!(get_chain_fork(txn, &author).unwrap(), None);
+
+                insert_chain_fork(txn, author.clone(), later.clone()).unwrap();
+                insert_chain_fork(txn, author.clone(), earlier.clone()).unwrap();
+                // Recording the same fork twice is fine.
+                insert_chain_fork(txn, author.clone(), earlier.clone()).unwrap();
+
+                assert_eq!(get_chain_fork(txn, &author).unwrap(), Some(earlier));
+                assert_eq!(get_chain_fork(txn, &fixt!(AgentPubKey)).unwrap(), None);
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn forks_are_found_when_agent_activity_is_integrated() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut existing = fixt!(CreateLink);
+        existing.header_seq = 3;
+        let mut header = fixt!(CreateLink);
+        header.author = existing.author.clone();
+        header.header_seq = 3;
+        let existing: Header = existing.into();
+        let header: Header = header.into();
+        let author = header.author().clone();
+        let when_integrated = aingle_types::timestamp::now();
+        let op = SgdOpHashed::from_content_sync(SgdOp::RegisterAgentActivity(
+            fixt!(Signature),
+            header.clone(),
+        ));
+        let op_hash = op.as_hash().clone();
+
+        env.conn()
+            .unwrap()
+            .with_commit_test(|txn| {
+                insert_op(txn, op, false).unwrap();
+                set_when_integrated(txn, op_hash, when_integrated).unwrap();
+                // Nothing else at this position so no fork.
+                assert!(get_chain_forks_integrated_at(txn, when_integrated)
+                    .unwrap()
+                    .is_empty());
+
+                let shh = SignedHeaderHashed::with_presigned(
+                    HeaderHashed::from_content_sync(existing.clone()),
+                    fixt!(Signature),
+                );
+                insert_header(txn, shh).unwrap();
+                let expected = ChainFork::new(
+                    3,
+                    HeaderHash::with_data_sync(&existing),
+                    HeaderHash::with_data_sync(&header),
+                );
+                assert_eq!(
+                    get_chain_forks_integrated_at(txn, when_integrated).unwrap(),
+                    vec![(author, expected)]
+                );
+            })
+            .unwrap();
+    }
+}
@@ -91,6 +91,11 @@ pub fn put(
     mutations::insert_entry_def(txn, key, entry_def)
 }
 
+pub fn delete(txn: &mut Transaction, key: EntryDefBufferKey) -> StateMutationResult<()> {
+    let key: EntryDefStoreKey = key.into();
+    mutations::delete_entry_def(txn, &key)
+}
+
 impl From<EntryDefBufferKey> for EntryDefStoreKey {
     fn from(a: EntryDefBufferKey) -> Self {
         Self(
@@ -34,6 +34,7 @@
 
 #![allow(deprecated)]
 
+pub mod chain_fork;
 #[allow(missing_docs)]
 pub mod saf_def;
 pub mod entry_def;
@@ -42,14 +43,17 @@ pub mod mutations;
 #[allow(missing_docs)]
 pub mod prelude;
 pub mod query;
+pub mod schedule;
 pub mod scratch;
 #[allow(missing_docs)]
 pub mod source_chain;
 pub mod validation_db;
 pub mod validation_receipts;
+pub mod warrant;
 #[allow(missing_docs)]
 pub mod wasm;
 pub mod workspace;
+pub mod x25519_key_agent;
 
 #[allow(missing_docs)]
 #[cfg(any(test, feature = "test_utils"))]
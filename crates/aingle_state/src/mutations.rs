@@ -51,6 +51,8 @@ macro_rules! sgd_op_update {
 }
 
 /// Insert a [`SgdOp`] into the [`Scratch`].
+/// The scratch only holds headers and entries, so the warrant of a
+/// [`SgdOp::RegisterWarrant`] is not kept.
 pub fn insert_op_scratch(scratch: &mut Scratch, op: SgdOpHashed) -> StateMutationResult<()> {
     let (op, _) = op.into_inner();
     let op_light = op.to_light();
@@ -91,6 +93,7 @@ pub fn insert_op(
     let header = op.header();
     let timestamp = header.timestamp();
     let signature = op.signature().clone();
+    let warrant = op.warrant().cloned();
     if let Some(entry) = op.entry() {
         let entry_hashed = EntryHashed::with_pre_hashed(
             entry.clone(),
@@ -105,7 +108,18 @@ pub fn insert_op(
     let header_hashed = SignedHeaderHashed::with_presigned(header_hashed, signature);
     let op_order = OpOrder::new(op_light.get_type(), header_hashed.header().timestamp());
     insert_header(txn, header_hashed)?;
-    insert_op_lite(txn, op_light, hash, is_authored, op_order, timestamp)?;
+    insert_op_lite(
+        txn,
+        op_light,
+        hash.clone(),
+        is_authored,
+        op_order,
+        timestamp,
+    )?;
+    // The warrant refers to its op, so it can only be inserted after it.
+    if let Some(warrant) = warrant {
+        insert_warrant(txn, hash, warrant)?;
+    }
     Ok(())
 }
 
@@ -135,6 +149,39 @@ pub fn insert_op_lite(
     Ok(())
 }
 
+/// Insert a [`SignedWarrant`] into the database.
+/// The hash is the hash of the [`SgdOp::RegisterWarrant`] carrying it.
+pub fn insert_warrant(
+    txn: &mut Transaction,
+    op_hash: SgdOpHash,
+    warrant: SignedWarrant,
+) -> StateMutationResult<()> {
+    let author = warrant.author().clone();
+    let warrantor = warrant.warrantor().clone();
+    sql_insert!(txn, Warrant, {
+        "hash": op_hash,
+        "author": author,
+        "warrantor": warrantor,
+        "blob": to_blob(warrant)?,
+    })?;
+    Ok(())
+}
+
+/// Record a fork in this agent's source chain.
+pub fn insert_chain_fork(
+    txn: &mut Transaction,
+    author: AgentPubKey,
+    fork: ChainFork,
+) -> StateMutationResult<()> {
+    sql_insert!(txn, ChainFork, {
+        "author": author,
+        "fork_seq": fork.fork_seq,
+        "first_header": fork.first_header,
+        "second_header": fork.second_header,
+    })?;
+    Ok(())
+}
+
 /// Insert a [`SignedValidationReceipt`] into the database.
 pub fn insert_validation_receipt(
     txn: &mut Transaction,
@@ -185,6 +232,39 @@ pub fn insert_entry_def(
     Ok(())
 }
 
+/// Delete a [`SafWasm`] from the database.
+pub fn delete_wasm(txn: &mut Transaction, hash: &WasmHash) -> StateMutationResult<()> {
+    txn.execute(
+        "DELETE FROM Wasm WHERE hash = :hash",
+        named_params! {
+            ":hash": hash,
+        },
+    )?;
+    Ok(())
+}
+
+/// Delete a [`SafDef`] from the database.
+pub fn delete_saf_def(txn: &mut Transaction, hash: &SafHash) -> StateMutationResult<()> {
+    txn.execute(
+        "DELETE FROM SafDef WHERE hash = :hash",
+        named_params! {
+            ":hash": hash,
+        },
+    )?;
+    Ok(())
+}
+
+/// Delete an [`EntryDef`] from the database.
+pub fn delete_entry_def(txn: &mut Transaction, key: &EntryDefStoreKey) -> StateMutationResult<()> {
+    txn.execute(
+        "DELETE FROM EntryDef WHERE key = :key",
+        named_params! {
+            ":key": key,
+        },
+    )?;
+    Ok(())
+}
+
 /// Insert [`ConductorState`] into the database.
 pub fn insert_conductor_state(
     txn: &mut Transaction,
@@ -198,6 +278,33 @@ pub fn insert_conductor_state(
     Ok(())
 }
 
+/// Record the agent an x25519 keypair was created for.
+pub fn insert_x25519_key_agent(
+    txn: &mut Transaction,
+    key: &X25519PubKey,
+    agent: &AgentPubKey,
+) -> StateMutationResult<()> {
+    sql_insert!(txn, X25519KeyAgent, {
+        "key": key.as_ref(),
+        "agent": agent,
+    })?;
+    Ok(())
+}
+
+/// Delete the records of the x25519 keypairs created for an agent.
+pub fn delete_x25519_key_agents(
+    txn: &mut Transaction,
+    agent: &AgentPubKey,
+) -> StateMutationResult<()> {
+    txn.execute(
+        "DELETE FROM X25519KeyAgent WHERE agent = :agent",
+        named_params! {
+            ":agent": agent,
+        },
+    )?;
+    Ok(())
+}
+
 /// Set the validation status of a [`SgdOp`] in the database.
 pub fn set_validation_status(
     txn: &mut Transaction,
@@ -325,6 +432,8 @@ pub fn insert_header(txn: &mut Transaction, header: SignedHeaderHashed) -> State
                 "base_hash": create_link.base_address,
                 "zome_id": create_link.zome_id.index() as u32,
                 "tag": create_link.tag,
+                "timestamp_s": create_link.timestamp.0,
+                "timestamp_ns": create_link.timestamp.1,
                 "blob": to_blob(SignedHeader::from((Header::CreateLink(create_link.clone()), signature)))?,
             })?;
         }
@@ -416,7 +525,8 @@ pub fn insert_header(txn: &mut Transaction, header: SignedHeaderHashed) -> State
                 "seq": header_seq,
                 "author": author,
                 "prev_hash": prev_hash,
-                "blob": to_blob(SignedHeader::from((Header::OpenChain(open), signature)))?,
+                "prev_saf_hash": open.prev_saf_hash,
+                "blob": to_blob(SignedHeader::from((Header::OpenChain(open.clone()), signature)))?,
             })?;
         }
         Header::CloseChain(close) => {
@@ -1,8 +1,11 @@
+pub use crate::chain_fork::*;
 pub use crate::mutations::*;
 pub use crate::query::prelude::*;
+pub use crate::schedule::*;
 pub use crate::source_chain::*;
 pub use crate::validation_db::*;
 pub use crate::validation_receipts::*;
+pub use crate::warrant::*;
 pub use crate::wasm::*;
 pub use crate::workspace::*;
 pub use crate::*;
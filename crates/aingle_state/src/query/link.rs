@@ -9,6 +9,7 @@ use super::*;
 #[derive(Debug, Clone)]
 pub struct GetLinksQuery {
     query: LinksQuery,
+    filter: GetLinksFilter,
 }
 
 #[derive(Debug, Clone)]
@@ -136,20 +137,29 @@ impl GetLinksQuery {
     pub fn new(base: EntryHash, zome_id: ZomeId, tag: Option<LinkTag>) -> Self {
         Self {
             query: LinksQuery::new(base, zome_id, tag),
+            filter: GetLinksFilter::default(),
         }
     }
 
     pub fn base(base: EntryHash, zome_id: ZomeId) -> Self {
         Self {
             query: LinksQuery::base(base, zome_id),
+            filter: GetLinksFilter::default(),
         }
     }
 
     pub fn tag(base: EntryHash, zome_id: ZomeId, tag: LinkTag) -> Self {
         Self {
             query: LinksQuery::tag(base, zome_id, tag),
+            filter: GetLinksFilter::default(),
         }
     }
+
+    /// Only return the links which pass this filter, in its order.
+    pub fn with_filter(mut self, filter: GetLinksFilter) -> Self {
+        self.filter = filter;
+        self
+    }
 }
 
 impl Query for GetLinksQuery {
@@ -205,7 +215,10 @@ impl Query for GetLinksQuery {
         let (header, hash) = header.into_inner();
         match header {
             Header::CreateLink(create_link) => {
-                if !state.deletes.contains(&hash) {
+                let included =
+                    self.filter
+                        .includes(&create_link.author, create_link.timestamp, &hash);
+                if included && !state.deletes.contains(&hash) {
                     state
                         .creates
                         .insert(hash, link_from_header(Header::CreateLink(create_link))?);
@@ -225,7 +238,7 @@ impl Query for GetLinksQuery {
         S: Store,
     {
         let mut links: Self::Output = state.creates.into_iter().map(|(_, v)| v).collect();
-        links.sort_by_key(|l| l.timestamp);
+        self.filter.sort_and_limit(&mut links);
         Ok(links)
     }
 }
@@ -59,3 +59,7 @@ pub fn contains(txn: &Transaction<'_>, hash: &SafHash) -> StateQueryResult<bool>
 pub fn put(txn: &mut Transaction, saf_def: SafDef) -> StateMutationResult<()> {
     mutations::insert_saf_def(txn, SafDefHashed::from_content_sync(saf_def))
 }
+
+pub fn delete(txn: &mut Transaction, hash: &SafHash) -> StateMutationResult<()> {
+    mutations::delete_saf_def(txn, hash)
+}
@@ -0,0 +1,262 @@
+//! Persistence for zome functions scheduled with the `schedule` host function.
+
+use std::time::Duration;
+
+use aingle_sqlite::rusqlite::named_params;
+use aingle_sqlite::rusqlite::OptionalExtension;
+use aingle_sqlite::rusqlite::Transaction;
+use aingle_zome_types::FunctionName;
+use aingle_zome_types::Schedule;
+use aingle_zome_types::ZomeName;
+
+use crate::mutations::StateMutationResult;
+use crate::prelude::StateQueryResult;
+
+/// A zome function that is scheduled to be called by the host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledFn {
+    /// The zome that scheduled the function.
+    pub zome_name: ZomeName,
+    /// The function to call.
+    pub fn_name: FunctionName,
+    /// When the function should next be called,
+    /// in milliseconds since the UNIX epoch.
+    pub next_run_ms: i64,
+    /// The interval for recurring functions.
+    pub repeat_every: Option<Duration>,
+}
+
+impl ScheduledFn {
+    /// Create a scheduled function from a [`Schedule`] requested by
+    /// `zome_name` at `now_ms`.
+    pub fn from_schedule(zome_name: ZomeName, schedule: Schedule, now_ms: i64) -> Self {
+        let Schedule {
+            fn_name,
+            execute_after,
+            repeat_every,
+        } = schedule;
+        Self {
+            zome_name,
+            fn_name,
+            next_run_ms: now_ms.saturating_add(duration_to_ms(execute_after)),
+            repeat_every,
+        }
+    }
+}
+
+/// The current time in milliseconds since the UNIX epoch,
+/// as used by the ScheduledFunctions table.
+pub fn now_ms() -> i64 {
+    let since_the_epoch = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("Time went backwards");
+    duration_to_ms(since_the_epoch)
+}
+
+fn duration_to_ms(duration: Duration) -> i64 {
+    duration.as_millis().min(i64::MAX as u128) as i64
+}
+
+/// Insert a [`ScheduledFn`] into the database,
+/// replacing any existing schedule for the same function.
+pub fn insert_scheduled_fn(
+    txn: &mut Transaction,
+    scheduled_fn: ScheduledFn,
+) -> StateMutationResult<()> {
+    let ScheduledFn {
+        zome_name,
+        fn_name,
+        next_run_ms,
+        repeat_every,
+    } = scheduled_fn;
+    crate::sql_insert!(txn, ScheduledFunctions, {
+        "zome_name": zome_name.0,
+        "fn_name": fn_name.0,
+        "next_run_ms": next_run_ms,
+        "repeat_every_ms": repeat_every.map(duration_to_ms),
+    })?;
+    Ok(())
+}
+
+/// Remove a [`ScheduledFn`] from the database.
+pub fn delete_scheduled_fn(
+    txn: &mut Transaction,
+    zome_name: &ZomeName,
+    fn_name: &FunctionName,
+) -> StateMutationResult<()> {
+    txn.execute(
+        "
+        DELETE FROM ScheduledFunctions
+        WHERE zome_name = :zome_name AND fn_name = :fn_name
+        ",
+        named_params! {
+            ":zome_name": zome_name.0,
+            ":fn_name": fn_name.0,
+        },
+    )?;
+    Ok(())
+}
+
+/// Move a recurring function on to its next run after `now_ms`
+/// or remove it if it only runs once.
+///
+/// Nothing is changed if the function was rescheduled since `scheduled_fn`
+/// was read, e.g. by the function rescheduling itself when it ran.
+pub fn reschedule_or_delete(
+    txn: &mut Transaction,
+    scheduled_fn: ScheduledFn,
+    now_ms: i64,
+) -> StateMutationResult<()> {
+    let ScheduledFn {
+        zome_name,
+        fn_name,
+        next_run_ms,
+        repeat_every,
+    } = scheduled_fn;
+    match repeat_every {
+        Some(repeat_every) => {
+            // Missed runs are skipped rather than all being run at once.
+            let new_next_run_ms = now_ms.saturating_add(duration_to_ms(repeat_every).max(1));
+            txn.execute(
+                "
+                UPDATE ScheduledFunctions
+                SET next_run_ms = :new_next_run_ms
+                WHERE zome_name = :zome_name AND fn_name = :fn_name
+                AND next_run_ms = :next_run_ms
+                ",
+                named_params! {
+                    ":new_next_run_ms": new_next_run_ms,
+                    ":zome_name": zome_name.0,
+                    ":fn_name": fn_name.0,
+                    ":next_run_ms": next_run_ms,
+                },
+            )?;
+        }
+        None => {
+            txn.execute(
+                "
+                DELETE FROM ScheduledFunctions
+                WHERE zome_name = :zome_name AND fn_name = :fn_name
+                AND next_run_ms = :next_run_ms
+                ",
+                named_params! {
+                    ":zome_name": zome_name.0,
+                    ":fn_name": fn_name.0,
+                    ":next_run_ms": next_run_ms,
+                },
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Get all the functions that are due to run at `now_ms`.
+pub fn due_scheduled_fns(txn: &Transaction, now_ms: i64) -> StateQueryResult<Vec<ScheduledFn>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT zome_name, fn_name, next_run_ms, repeat_every_ms
+        FROM ScheduledFunctions
+        WHERE next_run_ms <= :now_ms
+        ORDER BY next_run_ms ASC
+        ",
+    )?;
+    let iter = stmt.query_and_then(named_params! { ":now_ms": now_ms }, |row| {
+        let zome_name: String = row.get("zome_name")?;
+        let fn_name: String = row.get("fn_name")?;
+        let repeat_every_ms: Option<i64> = row.get("repeat_every_ms")?;
+        StateQueryResult::Ok(ScheduledFn {
+            zome_name: zome_name.into(),
+            fn_name: fn_name.into(),
+            next_run_ms: row.get("next_run_ms")?,
+            repeat_every: repeat_every_ms.map(|ms| Duration::from_millis(ms.max(0) as u64)),
+        })
+    })?;
+    iter.collect()
+}
+
+/// When the next scheduled function is due to run,
+/// in milliseconds since the UNIX epoch.
+pub fn next_scheduled_run_ms(txn: &Transaction) -> StateQueryResult<Option<i64>> {
+    Ok(txn
+        .query_row(
+            "SELECT MIN(next_run_ms) FROM ScheduledFunctions",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn scheduled(fn_name: &str, next_run_ms: i64, repeat_every: Option<Duration>) -> ScheduledFn {
+        ScheduledFn {
+            zome_name: "zome".into(),
+            fn_name: fn_name.into(),
+            next_run_ms,
+            repeat_every,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scheduled_fns_round_trip() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+
+        env.conn()
+            .unwrap()
+            .with_commit_test(|txn| {
+                insert_scheduled_fn(txn, scheduled("once", 10, None)).unwrap();
+                insert_scheduled_fn(
+                    txn,
+                    scheduled("recurring", 20, Some(Duration::from_millis(100))),
+                )
+                .unwrap();
+                insert_scheduled_fn(txn, scheduled("later", 1000, None)).unwrap();
+
+                assert_eq!(next_scheduled_run_ms(txn).unwrap(), Some(10));
+
+                let due = due_scheduled_fns(txn, 50).unwrap();
+                assert_eq!(
+                    due,
+                    vec![
+                        scheduled("once", 10, None),
+                        scheduled("recurring", 20, Some(Duration::from_millis(100))),
+                    ]
+                );
+
+                for f in due {
+                    reschedule_or_delete(txn, f, 50).unwrap();
+                }
+
+                // The one-off function is gone and the recurring one moved on.
+                assert_eq!(next_scheduled_run_ms(txn).unwrap(), Some(150));
+                assert_eq!(
+                    due_scheduled_fns(txn, 150).unwrap(),
+                    vec![scheduled(
+                        "recurring",
+                        150,
+                        Some(Duration::from_millis(100))
+                    )]
+                );
+
+                // Rescheduling replaces the existing schedule.
+                insert_scheduled_fn(txn, scheduled("recurring", 5000, None)).unwrap();
+                assert_eq!(due_scheduled_fns(txn, 2000).unwrap().len(), 0);
+
+                // A schedule that changed since it was read is left alone.
+                reschedule_or_delete(txn, scheduled("recurring", 150, None), 2000).unwrap();
+                assert_eq!(due_scheduled_fns(txn, 5000).unwrap().len(), 2);
+
+                delete_scheduled_fn(txn, &"zome".into(), &"later".into()).unwrap();
+                assert_eq!(
+                    due_scheduled_fns(txn, 5000).unwrap(),
+                    vec![scheduled("recurring", 5000, None)]
+                );
+            })
+            .unwrap();
+    }
+}
@@ -19,6 +19,7 @@ use crate::prelude::StoresIter;
 use crate::query::StateQueryResult;
 use crate::query::StmtIter;
 use crate::query::Store;
+use crate::schedule::ScheduledFn;
 
 /// The "scratch" is an in-memory space to stage Headers to be committed at the
 /// end of the CallZome workflow.
@@ -28,10 +29,14 @@ use crate::query::Store;
 /// a simple filter on the scratch space, and then chaining that iterator
 /// onto the iterators over the Headers in the database(s) produced by the
 /// Cascade.
+///
+/// Functions scheduled during the zome call are also staged here so that they
+/// are only persisted if the zome call succeeds.
 #[derive(Debug, Clone, Default)]
 pub struct Scratch {
     headers: Vec<SignedHeaderHashed>,
     entries: HashMap<EntryHash, Arc<Entry>>,
+    scheduled_fns: Vec<ScheduledFn>,
 }
 
 #[derive(Debug, Clone)]
@@ -56,6 +61,10 @@ impl Scratch {
         self.entries.insert(hash, Arc::new(entry));
     }
 
+    pub fn add_scheduled_fn(&mut self, scheduled_fn: ScheduledFn) {
+        self.scheduled_fns.push(scheduled_fn);
+    }
+
     pub fn as_filter(&self, f: impl Fn(&SignedHeaderHashed) -> bool) -> FilteredScratch {
         let headers = self.headers.iter().filter(|&t| f(t)).cloned().collect();
         FilteredScratch { headers }
@@ -91,6 +100,10 @@ impl Scratch {
         self.headers.len()
     }
 
+    pub fn has_scheduled_fns(&self) -> bool {
+        !self.scheduled_fns.is_empty()
+    }
+
     fn get_exact_element(
         &self,
         hash: &HeaderHash,
@@ -129,6 +142,11 @@ impl Scratch {
         self.headers.drain(..)
     }
 
+    /// Drain out all the scheduled functions.
+    pub fn drain_scheduled_fns(&mut self) -> impl Iterator<Item = ScheduledFn> + '_ {
+        self.scheduled_fns.drain(..)
+    }
+
     /// Drain out all the entries.
     pub fn drain_entries(&mut self) -> impl Iterator<Item = EntryHashed> + '_ {
         self.entries.drain().map(|(hash, entry)| {
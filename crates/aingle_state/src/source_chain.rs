@@ -2,7 +2,10 @@ use ai_hash::AgentPubKey;
 use ai_hash::SafHash;
 use ai_hash::HasHash;
 use ai_hash::HeaderHash;
+use aingle_sqlite::rusqlite::ToSql;
 use aingle_sqlite::rusqlite::Transaction;
+use aingle_types::chain::SourceChainArchive;
+use aingle_types::chain::SourceChainArchiveError;
 use aingle_types::sgd_op::produce_op_lights_from_elements;
 use aingle_types::sgd_op::produce_op_lights_from_iter;
 use aingle_types::sgd_op::SgdOpLight;
@@ -16,7 +19,9 @@ use aingle_types::timestamp;
 use aingle_types::EntryHashed;
 use aingle_zome_types::header;
 use aingle_zome_types::CapAccess;
+use aingle_zome_types::CapClaimEntry;
 use aingle_zome_types::CapGrant;
+use aingle_zome_types::CapGrantEntry;
 use aingle_zome_types::CapSecret;
 use aingle_zome_types::Element;
 use aingle_zome_types::Entry;
@@ -181,8 +186,84 @@ impl SourceChain {
         if author_grant.is_valid(check_function, check_agent, check_secret) {
             return Ok(Some(author_grant));
         }
+        let valid_cap_grant = self
+            .live_cap_entries()?
+            .into_iter()
+            .filter_map(|entry| entry.as_cap_grant())
+            .filter(|grant| !matches!(grant, CapGrant::ChainAuthor(_)))
+            .filter(|grant| grant.is_valid(check_function, check_agent, check_secret))
+            // if there are still multiple grants, fold them down based on specificity
+            // authorship > assigned > transferable > unrestricted
+            .fold(None, |acc: Option<CapGrant>, grant| match &grant {
+                CapGrant::RemoteAgent(zome_call_cap_grant) => match &zome_call_cap_grant.access {
+                    CapAccess::Assigned { .. } => match &acc {
+                        Some(CapGrant::RemoteAgent(acc_zome_call_cap_grant)) => {
+                            match acc_zome_call_cap_grant.access {
+                                // an assigned acc takes precedence
+                                CapAccess::Assigned { .. } => acc,
+                                // current grant takes precedence over all other accs
+                                _ => Some(grant),
+                            }
+                        }
+                        None => Some(grant),
+                        // authorship should be short circuit and filtered
+                        _ => unreachable!(),
+                    },
+                    CapAccess::Transferable { .. } => match &acc {
+                        Some(CapGrant::RemoteAgent(acc_zome_call_cap_grant)) => {
+                            match acc_zome_call_cap_grant.access {
+                                // an assigned acc takes precedence
+                                CapAccess::Assigned { .. } => acc,
+                                // transferable acc takes precedence
+                                CapAccess::Transferable { .. } => acc,
+                                // current grant takes preference over other accs
+                                _ => Some(grant),
+                            }
+                        }
+                        None => Some(grant),
+                        // authorship should be short circuited and filtered by now
+                        _ => unreachable!(),
+                    },
+                    CapAccess::Unrestricted => match acc {
+                        Some(_) => acc,
+                        None => Some(grant),
+                    },
+                },
+                // ChainAuthor should have short circuited and be filtered out already
+                _ => unreachable!(),
+            });
+        Ok(valid_cap_grant)
+    }
+
+    /// All the cap grants authored on this chain that haven't been updated or deleted.
+    pub fn live_cap_grants(&self) -> SourceChainResult<Vec<CapGrantEntry>> {
+        Ok(self
+            .live_cap_entries()?
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Entry::CapGrant(grant) => Some(grant),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// All the cap claims authored on this chain that haven't been deleted.
+    pub fn cap_claims(&self) -> SourceChainResult<Vec<CapClaimEntry>> {
+        Ok(self
+            .live_cap_entries()?
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Entry::CapClaim(claim) => Some(claim),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// The cap grant and cap claim entries authored on this chain
+    /// that haven't been updated or deleted.
+    fn live_cap_entries(&self) -> SourceChainResult<Vec<Entry>> {
         // TODO: SQL_PERF: This query could have a fast upper bound if we add indexes.
-        let valid_cap_grant = self.vault.conn()?.with_reader(|txn| {
+        let entries = self.vault.conn()?.with_reader(|txn| {
             let not_referenced_header = "
             SELECT COUNT(H_REF.hash)
             FROM Header AS H_REF
@@ -205,7 +286,7 @@ impl SourceChain {
                 WHERE
                 SgdOp.is_authored = 1
                 AND
-                Entry.access_type IS NOT NULL
+                Entry.tag IS NOT NULL
                 AND
                 ({}) = 0
                 ",
@@ -213,78 +294,18 @@ impl SourceChain {
             );
             txn.prepare(&sql)?
                 .query_and_then([], |row| from_blob(row.get("blob")?))?
-                .filter_map(|result: StateQueryResult<Entry>| match result {
-                    Ok(entry) => entry
-                        .as_cap_grant()
-                        .filter(|grant| !matches!(grant, CapGrant::ChainAuthor(_)))
-                        .filter(|grant| grant.is_valid(check_function, check_agent, check_secret))
-                        .map(|cap| Some(Ok(cap)))
-                        .unwrap_or(None),
-                    Err(e) => Some(Err(e)),
-                })
-                // if there are still multiple grants, fold them down based on specificity
-                // authorship > assigned > transferable > unrestricted
-                .fold(
-                    Ok(None),
-                    |acc: StateQueryResult<Option<CapGrant>>, grant| {
-                        let grant = grant?;
-                        let acc = acc?;
-                        let acc = match &grant {
-                            CapGrant::RemoteAgent(zome_call_cap_grant) => {
-                                match &zome_call_cap_grant.access {
-                                    CapAccess::Assigned { .. } => match &acc {
-                                        Some(CapGrant::RemoteAgent(acc_zome_call_cap_grant)) => {
-                                            match acc_zome_call_cap_grant.access {
-                                                // an assigned acc takes precedence
-                                                CapAccess::Assigned { .. } => acc,
-                                                // current grant takes precedence over all other accs
-                                                _ => Some(grant),
-                                            }
-                                        }
-                                        None => Some(grant),
-                                        // authorship should be short circuit and filtered
-                                        _ => unreachable!(),
-                                    },
-                                    CapAccess::Transferable { .. } => match &acc {
-                                        Some(CapGrant::RemoteAgent(acc_zome_call_cap_grant)) => {
-                                            match acc_zome_call_cap_grant.access {
-                                                // an assigned acc takes precedence
-                                                CapAccess::Assigned { .. } => acc,
-                                                // transferable acc takes precedence
-                                                CapAccess::Transferable { .. } => acc,
-                                                // current grant takes preference over other accs
-                                                _ => Some(grant),
-                                            }
-                                        }
-                                        None => Some(grant),
-                                        // authorship should be short circuited and filtered by now
-                                        _ => unreachable!(),
-                                    },
-                                    CapAccess::Unrestricted => match acc {
-                                        Some(_) => acc,
-                                        None => Some(grant),
-                                    },
-                                }
-                            }
-                            // ChainAuthor should have short circuited and be filtered out already
-                            _ => unreachable!(),
-                        };
-                        Ok(acc)
-                    },
-                )
+                .collect::<StateQueryResult<Vec<Entry>>>()
         })?;
-        Ok(valid_cap_grant)
+        Ok(entries)
     }
 
     /// Query Headers in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
-    /// used by the `query` host function, which crosses the wasm boundary
-    // FIXME: This query needs to be tested.
+    /// used by the `query` host function, which crosses the wasm boundary.
+    /// Every part of the filter, including the order and limit, is applied in
+    /// the database query; the uncommitted headers in the scratch are then
+    /// merged in, since they always come after the committed ones.
     pub async fn query(&self, query: QueryFilter) -> SourceChainResult<Vec<Element>> {
-        let (range_min, range_max) = match query.sequence_range.clone() {
-            Some(range) => (Some(range.start), Some(range.end)),
-            None => (None, None),
-        };
         let author = self.author.clone();
         let mut elements = self
             .vault
@@ -326,47 +347,76 @@ impl SourceChain {
                 (:range_min IS NULL OR Header.seq >= :range_min)
                 AND
                 (:range_max IS NULL OR Header.seq < :range_max)
-                AND
-                (:entry_type IS NULL OR Header.entry_type = :entry_type)
-                AND
-                (:header_type IS NULL OR Header.type = :header_type)
                 ",
                     );
+                    let mut params: Vec<(String, Box<dyn ToSql>)> = vec![
+                        (":author".into(), Box::new((*author).clone())),
+                        (":range_min".into(), Box::new(query.sequence_range.start)),
+                        (":range_max".into(), Box::new(query.sequence_range.end)),
+                    ];
+                    if let Some(entry_types) = &query.entry_types {
+                        sql.push_str(&in_clause(
+                            "Header.entry_type",
+                            "entry_type",
+                            entry_types.iter().cloned(),
+                            &mut params,
+                        ));
+                    }
+                    if let Some(header_types) = &query.header_types {
+                        sql.push_str(&in_clause(
+                            "Header.type",
+                            "header_type",
+                            header_types.iter().cloned(),
+                            &mut params,
+                        ));
+                    }
+                    if let Some(entry_hashes) = &query.entry_hashes {
+                        sql.push_str(&in_clause(
+                            "Header.entry_hash",
+                            "entry_hash",
+                            entry_hashes.iter().cloned(),
+                            &mut params,
+                        ));
+                    }
+                    sql.push_str(if query.order_descending {
+                        "ORDER BY Header.seq DESC"
+                    } else {
+                        "ORDER BY Header.seq ASC"
+                    });
+                    if let Some(limit) = query.limit {
+                        sql.push_str(" LIMIT :limit");
+                        params.push((":limit".into(), Box::new(limit)));
+                    }
+                    let params: Vec<(&str, &dyn ToSql)> = params
+                        .iter()
+                        .map(|(name, value)| (name.as_str(), value.as_ref()))
+                        .collect();
                     let mut stmt = txn.prepare(&sql)?;
                     let elements = stmt
-                        .query_and_then(
-                            named_params! {
-                                ":author": author.as_ref(),
-                                ":range_min": range_min,
-                                ":range_max": range_max,
-                                ":entry_type": query.entry_type,
-                                ":header_type": query.header_type,
-                            },
-                            |row| {
-                                let header = from_blob::<SignedHeader>(row.get("header_blob")?)?;
-                                let SignedHeader(header, signature) = header;
-                                let hash: HeaderHash = row.get("header_hash")?;
-                                let header = HeaderHashed::with_pre_hashed(header, hash);
-                                let shh = SignedHeaderHashed::with_presigned(header, signature);
-                                let entry = if query.include_entries {
-                                    let entry: Option<Vec<u8>> = row.get("entry_blob")?;
-                                    match entry {
-                                        Some(entry) => Some(from_blob::<Entry>(entry)?),
-                                        None => None,
-                                    }
-                                } else {
-                                    None
-                                };
-                                StateQueryResult::Ok(Element::new(shh, entry))
-                            },
-                        )?
+                        .query_and_then(&params[..], |row| {
+                            let header = from_blob::<SignedHeader>(row.get("header_blob")?)?;
+                            let SignedHeader(header, signature) = header;
+                            let hash: HeaderHash = row.get("header_hash")?;
+                            let header = HeaderHashed::with_pre_hashed(header, hash);
+                            let shh = SignedHeaderHashed::with_presigned(header, signature);
+                            let entry = if query.include_entries {
+                                let entry: Option<Vec<u8>> = row.get("entry_blob")?;
+                                match entry {
+                                    Some(entry) => Some(from_blob::<Entry>(entry)?),
+                                    None => None,
+                                }
+                            } else {
+                                None
+                            };
+                            StateQueryResult::Ok(Element::new(shh, entry))
+                        })?
                         .collect::<StateQueryResult<Vec<_>>>();
                     elements
                 }
             })
             .await?;
-        self.scratch.apply(|scratch| {
-            let scratch_iter = scratch
+        let scratch_elements = self.scratch.apply(|scratch| {
+            scratch
                 .headers()
                 .filter(|shh| query.check(shh.header()))
                 .filter_map(|shh| {
@@ -375,18 +425,29 @@ impl SourceChain {
                         _ => None,
                     };
                     Some(Element::new(shh.clone(), entry))
-                });
-            elements.extend(scratch_iter);
+                })
+                .collect::<Vec<_>>()
         })?;
+        if query.order_descending {
+            elements.splice(0..0, scratch_elements.into_iter().rev());
+        } else {
+            elements.extend(scratch_elements);
+        }
+        if let Some(limit) = query.limit {
+            elements.truncate(limit as usize);
+        }
         Ok(elements)
     }
 
     pub async fn flush(&self) -> SourceChainResult<()> {
         // Nothing to write
-        if self.scratch.apply(|s| s.is_empty())? {
+        if self
+            .scratch
+            .apply(|s| s.is_empty() && !s.has_scheduled_fns())?
+        {
             return Ok(());
         }
-        let (headers, ops, entries) = self.scratch.apply_and_then(|scratch| {
+        let (headers, ops, entries, scheduled_fns) = self.scratch.apply_and_then(|scratch| {
             let length = scratch.num_headers();
 
             // The op related data ends up here.
@@ -435,7 +496,9 @@ impl SourceChain {
 
             // Drain out any entries.
             let entries = scratch.drain_entries().collect::<Vec<_>>();
-            SourceChainResult::Ok((headers, ops, entries))
+            // Drain out any scheduled functions.
+            let scheduled_fns = scratch.drain_scheduled_fns().collect::<Vec<_>>();
+            SourceChainResult::Ok((headers, ops, entries, scheduled_fns))
         })?;
 
         // Write the entries, headers and ops to the database in one transaction.
@@ -443,6 +506,10 @@ impl SourceChain {
         let persisted_head = self.persisted_head.clone();
         self.vault
             .async_commit(move |txn| {
+                // Schedules don't touch the chain so don't need the as at check.
+                for scheduled_fn in scheduled_fns {
+                    insert_scheduled_fn(txn, scheduled_fn)?;
+                }
                 // As at check.
                 let (new_persisted_head, _) = chain_head_db(&txn, author)?;
                 if headers.last().is_none() {
@@ -552,6 +619,46 @@ pub async fn genesis(
         .await
 }
 
+/// Write the elements of a verified archive to the vault as authored ops.
+/// The archive must either start a new chain or begin right after the
+/// existing chain head, which is checked in the same transaction as the write.
+pub async fn import_archive(vault: EnvWrite, archive: SourceChainArchive) -> SourceChainResult<()> {
+    let SourceChainArchive {
+        cell_id,
+        from_seq,
+        elements,
+        ..
+    } = archive;
+    let author = Arc::new(cell_id.agent_pubkey().clone());
+    let elements = elements
+        .into_iter()
+        .map(|element| {
+            let ops = produce_op_lights_from_elements(vec![&element])?;
+            Ok((element, ops))
+        })
+        .collect::<SourceChainResult<Vec<_>>>()?;
+    vault
+        .async_commit(move |txn| {
+            let head = ChainHeadQuery::new(author).run(Txn::from(&*txn))?;
+            let extends = match (head, elements.first()) {
+                (None, _) => from_seq == 0,
+                (Some((head, head_seq)), Some((first, _))) => {
+                    head_seq + 1 == from_seq && first.header().prev_header() == Some(&head)
+                }
+                (Some(_), None) => false,
+            };
+            if !extends {
+                return Err(SourceChainArchiveError::DoesNotExtendChain { from_seq }.into());
+            }
+            for (element, ops) in elements {
+                let (shh, entry) = element.into_inner();
+                put_raw(txn, shh, ops, entry.into_option())?;
+            }
+            SourceChainResult::Ok(())
+        })
+        .await
+}
+
 pub fn put_raw(
     txn: &mut Transaction,
     shh: SignedHeaderHashed,
@@ -715,6 +822,26 @@ pub async fn dump_state(
         .await?)
 }
 
+/// Build an `AND column IN (...)` clause for a query, adding a named
+/// parameter for each value.
+/// An empty set of values matches nothing.
+fn in_clause<T: ToSql + 'static>(
+    column: &str,
+    name: &str,
+    values: impl Iterator<Item = T>,
+    params: &mut Vec<(String, Box<dyn ToSql>)>,
+) -> String {
+    let names: Vec<String> = values
+        .enumerate()
+        .map(|(i, value)| {
+            let param = format!(":{}_{}", name, i);
+            params.push((param.clone(), Box::new(value)));
+            param
+        })
+        .collect();
+    format!(" AND {} IN ({}) ", column, names.join(", "))
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -789,6 +916,8 @@ pub mod tests {
                 chain.valid_cap_grant(&function, &bob, secret.as_ref())?,
                 Some(grant.clone().into())
             );
+
+            assert_eq!(chain.live_cap_grants()?, vec![grant.clone()]);
         }
 
         // let's roll the secret and assign the grant to bob specifically
@@ -835,8 +964,11 @@ pub mod tests {
             );
             assert_eq!(
                 chain.valid_cap_grant(&function, &bob, updated_secret.as_ref())?,
-                Some(updated_grant.into())
+                Some(updated_grant.clone().into())
             );
+
+            // only the updated grant is live
+            assert_eq!(chain.live_cap_grants()?, vec![updated_grant]);
         }
 
         {
@@ -871,8 +1003,153 @@ pub mod tests {
                 chain.valid_cap_grant(&function, &bob, updated_secret.as_ref())?,
                 None
             );
+
+            assert!(chain.live_cap_grants()?.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cap_claims() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
+        let mut agents = AgentPubKeyFixturator::new(Predictable);
+        let alice = agents.next().unwrap();
+        let bob = agents.next().unwrap();
+        let claim = CapClaim::new("tag".into(), bob, secret);
+        source_chain::genesis(env.clone(), fake_saf_hash(1), alice.clone(), None)
+            .await
+            .unwrap();
+
+        {
+            let chain = SourceChain::new(env.clone().into(), alice.clone()).await?;
+            assert!(chain.cap_claims()?.is_empty());
+
+            let (entry, entry_hash) =
+                EntryHashed::from_content_sync(Entry::CapClaim(claim.clone())).into_inner();
+            let header_builder = builder::Create {
+                entry_type: EntryType::CapClaim,
+                entry_hash,
+            };
+            chain.put(header_builder, Some(entry)).await?;
+
+            chain.flush().await.unwrap();
+        }
+
+        let chain = SourceChain::new(env.clone().into(), alice).await?;
+        assert_eq!(chain.cap_claims()?, vec![claim]);
+        // claims are not grants
+        assert!(chain.live_cap_grants()?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_query() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut agents = AgentPubKeyFixturator::new(Predictable);
+        let alice = agents.next().unwrap();
+        let bob = agents.next().unwrap();
+        // Genesis commits headers 0 to 2.
+        source_chain::genesis(env.clone(), fake_saf_hash(1), alice.clone(), None)
+            .await
+            .unwrap();
+        let chain = SourceChain::new(env.clone().into(), alice.clone()).await?;
+
+        // Headers 3 and 4 are committed and 5 is left in the scratch.
+        let mut entry_hashes = Vec::new();
+        for i in 0..3 {
+            let secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
+            let claim = CapClaim::new(format!("tag {}", i), bob.clone(), secret);
+            let (entry, entry_hash) =
+                EntryHashed::from_content_sync(Entry::CapClaim(claim)).into_inner();
+            let header_builder = builder::Create {
+                entry_type: EntryType::CapClaim,
+                entry_hash: entry_hash.clone(),
+            };
+            chain.put(header_builder, Some(entry)).await?;
+            entry_hashes.push(entry_hash);
+            if i == 1 {
+                chain.flush().await.unwrap();
+            }
         }
 
+        let seqs = |elements: Vec<Element>| -> Vec<u32> {
+            elements.iter().map(|e| e.header().header_seq()).collect()
+        };
+
+        assert_eq!(
+            seqs(chain.query(QueryFilter::new()).await?),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            seqs(chain.query(QueryFilter::new().descending()).await?),
+            vec![5, 4, 3, 2, 1, 0]
+        );
+        assert_eq!(
+            seqs(chain.query(QueryFilter::new().limit(2)).await?),
+            vec![0, 1]
+        );
+        assert_eq!(
+            seqs(
+                chain
+                    .query(QueryFilter::new().descending().limit(2))
+                    .await?
+            ),
+            vec![5, 4]
+        );
+        assert_eq!(
+            seqs(chain.query(QueryFilter::new().sequence_range(4..)).await?),
+            vec![4, 5]
+        );
+        assert_eq!(
+            seqs(chain.query(QueryFilter::new().sequence_range(..=1)).await?),
+            vec![0, 1]
+        );
+        assert_eq!(
+            seqs(
+                chain
+                    .query(
+                        QueryFilter::new()
+                            .header_type(HeaderType::Saf)
+                            .header_type(HeaderType::AgentValidationPkg)
+                    )
+                    .await?
+            ),
+            vec![0, 1]
+        );
+        assert_eq!(
+            seqs(
+                chain
+                    .query(
+                        QueryFilter::new()
+                            .entry_type(EntryType::AgentPubKey)
+                            .entry_type(EntryType::CapClaim)
+                            .descending()
+                            .limit(3)
+                    )
+                    .await?
+            ),
+            vec![5, 4, 3]
+        );
+        let filter = QueryFilter::new()
+            .entry_hashes(
+                vec![entry_hashes[1].clone(), entry_hashes[2].clone()]
+                    .into_iter()
+                    .collect(),
+            )
+            .include_entries(true);
+        let elements = chain.query(filter).await?;
+        assert!(elements.iter().all(|e| e.entry().as_option().is_some()));
+        assert_eq!(seqs(elements), vec![4, 5]);
+        assert!(chain
+            .query(QueryFilter::new().entry_hashes(Default::default()))
+            .await?
+            .is_empty());
+
         Ok(())
     }
 
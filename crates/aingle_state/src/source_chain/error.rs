@@ -78,6 +78,9 @@ pub enum SourceChainError {
 
     #[error(transparent)]
     SyncScratchError(#[from] SyncScratchError),
+
+    #[error(transparent)]
+    SourceChainArchiveError(#[from] SourceChainArchiveError),
 }
 
 // serde_json::Error does not implement PartialEq - why is that a requirement??
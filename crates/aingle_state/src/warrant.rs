@@ -0,0 +1,120 @@
+//! Queries for warrants held by agent activity authorities.
+
+use ai_hash::AgentPubKey;
+use aingle_sqlite::rusqlite::named_params;
+use aingle_sqlite::rusqlite::Transaction;
+use aingle_zome_types::SignedWarrant;
+use aingle_zome_types::ValidationStatus;
+
+use crate::prelude::from_blob;
+use crate::prelude::StateQueryResult;
+
+/// Get all the integrated and valid warrants against this agent.
+pub fn get_warrants_for_agent(
+    txn: &Transaction,
+    author: &AgentPubKey,
+) -> StateQueryResult<Vec<SignedWarrant>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT Warrant.blob
+        FROM Warrant
+        JOIN SgdOp ON SgdOp.hash = Warrant.hash
+        WHERE
+        Warrant.author = :author
+        AND
+        SgdOp.when_integrated IS NOT NULL
+        AND
+        SgdOp.validation_status = :status
+        ",
+    )?;
+    let iter = stmt.query_and_then(
+        named_params! {
+            ":author": author,
+            ":status": ValidationStatus::Valid,
+        },
+        |row| from_blob::<SignedWarrant>(row.get("blob")?),
+    )?;
+    iter.collect()
+}
+
+/// Is there an integrated and valid warrant against this agent?
+pub fn is_warranted(txn: &Transaction, author: &AgentPubKey) -> StateQueryResult<bool> {
+    Ok(txn.query_row(
+        "
+        SELECT EXISTS(
+            SELECT 1
+            FROM Warrant
+            JOIN SgdOp ON SgdOp.hash = Warrant.hash
+            WHERE
+            Warrant.author = :author
+            AND
+            SgdOp.when_integrated IS NOT NULL
+            AND
+            SgdOp.validation_status = :status
+        )
+        ",
+        named_params! {
+            ":author": author,
+            ":status": ValidationStatus::Valid,
+        },
+        |row| row.get(0),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use ::fixt::prelude::*;
+    use ai_hash::*;
+    use aingle_types::sgd_op::SgdOp;
+    use aingle_types::sgd_op::SgdOpHashed;
+    use aingle_zome_types::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn warrants_are_found_once_integrated() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+
+        let header = Header::Create(fixt!(Create));
+        let author = header.author().clone();
+        let invalid_op = SgdOpHashed::from_content_sync(SgdOp::RegisterAgentActivity(
+            fixt!(Signature),
+            header.clone(),
+        ));
+        let warrant = SignedWarrant::new(
+            Warrant {
+                author: author.clone(),
+                header: HeaderHash::with_data_sync(&header),
+                op: invalid_op.as_hash().clone(),
+                reason: WarrantReason::SysValidation("Bad".into()),
+                warrantor: fixt!(AgentPubKey),
+                timestamp: aingle_types::timestamp::now(),
+            },
+            fixt!(Signature),
+        );
+        let op = SgdOpHashed::from_content_sync(SgdOp::RegisterWarrant(
+            fixt!(Signature),
+            header,
+            Box::new(warrant.clone()),
+        ));
+        let op_hash = op.as_hash().clone();
+
+        env.conn()
+            .unwrap()
+            .with_commit_test(|txn| {
+                insert_op(txn, op, false).unwrap();
+                // Warrants aren't trusted until they are validated and integrated.
+                assert!(!is_warranted(txn, &author).unwrap());
+                assert!(get_warrants_for_agent(txn, &author).unwrap().is_empty());
+
+                set_validation_status(txn, op_hash.clone(), ValidationStatus::Valid).unwrap();
+                set_when_integrated(txn, op_hash, aingle_types::timestamp::now()).unwrap();
+                assert!(is_warranted(txn, &author).unwrap());
+                assert_eq!(get_warrants_for_agent(txn, &author).unwrap(), vec![warrant]);
+
+                assert!(!is_warranted(txn, &fixt!(AgentPubKey)).unwrap());
+            })
+            .unwrap();
+    }
+}
@@ -43,6 +43,10 @@ pub fn put(txn: &mut Transaction, wasm: SafWasmHashed) -> StateMutationResult<()
     mutations::insert_wasm(txn, wasm)
 }
 
+pub fn delete(txn: &mut Transaction, hash: &WasmHash) -> StateMutationResult<()> {
+    mutations::delete_wasm(txn, hash)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,6 +80,14 @@ mod tests {
             assert_eq!(ret, wasm);
         });
 
+        // Delete wasm
+        env.conn()?
+            .with_commit_sync(|txn| delete(txn, wasm.as_hash()))
+            .unwrap();
+        fresh_reader_test!(env, |txn| {
+            assert!(!contains(&txn, &wasm.as_hash()).unwrap());
+        });
+
         Ok(())
     }
 }
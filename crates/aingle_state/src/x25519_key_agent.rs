@@ -0,0 +1,74 @@
+//! The agent each x25519 keypair in the keystore was created for.
+//! App clients may only use the keypairs of their App's agents.
+
+use ai_hash::AgentPubKey;
+use aingle_sqlite::rusqlite::named_params;
+use aingle_sqlite::rusqlite::OptionalExtension;
+use aingle_sqlite::rusqlite::Transaction;
+use aingle_zome_types::X25519PubKey;
+
+use crate::mutations;
+use crate::prelude::StateMutationResult;
+use crate::prelude::StateQueryResult;
+
+/// Get the agent an x25519 keypair was created for, if it was recorded.
+pub fn get(txn: &Transaction<'_>, key: &X25519PubKey) -> StateQueryResult<Option<AgentPubKey>> {
+    Ok(txn
+        .query_row(
+            "SELECT agent FROM X25519KeyAgent WHERE key = :key",
+            named_params! {
+                ":key": key.as_ref(),
+            },
+            |row| row.get("agent"),
+        )
+        .optional()?)
+}
+
+/// Record the agent an x25519 keypair was created for.
+pub fn put(
+    txn: &mut Transaction,
+    key: &X25519PubKey,
+    agent: &AgentPubKey,
+) -> StateMutationResult<()> {
+    mutations::insert_x25519_key_agent(txn, key, agent)
+}
+
+/// Forget the x25519 keypairs created for an agent.
+pub fn delete_for_agent(txn: &mut Transaction, agent: &AgentPubKey) -> StateMutationResult<()> {
+    mutations::delete_x25519_key_agents(txn, agent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use ::fixt::prelude::*;
+    use ai_hash::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn x25519_key_agents_round_trip() {
+        let test_env = test_conductor_env();
+        let env = test_env.env();
+
+        let alice = fixt!(AgentPubKey);
+        let bob = fixt!(AgentPubKey);
+        let alice_key = X25519PubKey::from([1; 32]);
+        let bob_key = X25519PubKey::from([2; 32]);
+
+        env.conn()
+            .unwrap()
+            .with_commit_test(|txn| {
+                assert_eq!(get(txn, &alice_key).unwrap(), None);
+
+                put(txn, &alice_key, &alice).unwrap();
+                put(txn, &bob_key, &bob).unwrap();
+                assert_eq!(get(txn, &alice_key).unwrap(), Some(alice.clone()));
+                assert_eq!(get(txn, &bob_key).unwrap(), Some(bob.clone()));
+
+                delete_for_agent(txn, &alice).unwrap();
+                assert_eq!(get(txn, &alice_key).unwrap(), None);
+                assert_eq!(get(txn, &bob_key).unwrap(), Some(bob));
+            })
+            .unwrap();
+    }
+}
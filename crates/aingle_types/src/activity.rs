@@ -20,6 +20,8 @@ pub struct AgentActivityResponse<T = SignedHeaderHashed> {
     /// The highest chain header that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
+    /// Warrants against this agent held by this authority.
+    pub warrants: Vec<SignedWarrant>,
 }
 
 aingle_serial!(AgentActivityResponse<HeaderHash>);
@@ -38,6 +40,7 @@ impl<A> AgentActivityResponse<A> {
             rejected_activity: convert_activity(&other.rejected_activity),
             status: ChainStatus::Empty,
             highest_observed: other.highest_observed,
+            warrants: other.warrants,
         }
     }
 
@@ -47,8 +50,9 @@ impl<A> AgentActivityResponse<A> {
             agent: other.agent,
             valid_activity: ChainItems::NotRequested,
             rejected_activity: ChainItems::NotRequested,
-            status: ChainStatus::Empty,
+            status: other.status,
             highest_observed: other.highest_observed,
+            warrants: other.warrants,
         }
     }
 
@@ -65,6 +69,7 @@ impl<A> AgentActivityResponse<A> {
             rejected_activity: convert_activity(other.rejected_activity),
             status: other.status,
             highest_observed: other.highest_observed,
+            warrants: other.warrants,
         }
     }
 }
@@ -103,7 +108,7 @@ impl From<AgentActivityResponse<Element>> for aingle_zome_types::query::AgentAct
             rejected_activity,
             status: a.status,
             highest_observed: a.highest_observed,
-            warrants: Vec::with_capacity(0),
+            warrants: a.warrants,
         }
     }
 }
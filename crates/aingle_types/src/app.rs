@@ -89,6 +89,39 @@ impl CreateCloneCellPayload {
     }
 }
 
+/// Identifies a clone cell to be archived
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ArchiveCloneCellPayload {
+    /// The App which the clone cell belongs to
+    pub installed_app_id: InstalledAppId,
+    /// The SlotId under which the clone was created
+    pub slot_id: SlotId,
+    /// The clone cell to archive
+    pub cell_id: CellId,
+}
+
+/// Identifies the slot whose archived clone cells should be deleted
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DeleteArchivedCloneCellsPayload {
+    /// The App which the clone cells belong to
+    pub installed_app_id: InstalledAppId,
+    /// The SlotId under which the clones were created
+    pub slot_id: SlotId,
+}
+
+/// Identifies the slot whose agent should migrate to a new SAF
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MigrateAgentPayload {
+    /// The App which the slot belongs to
+    pub installed_app_id: InstalledAppId,
+    /// The SlotId whose provisioned cell is migrated
+    pub slot_id: SlotId,
+    /// The already registered SAF to migrate to
+    pub saf_hash: SafHash,
+    /// Proof-of-membership, if required by the new SAF
+    pub membrane_proof: Option<MembraneProof>,
+}
+
 /// A collection of [SafHash]es paired with an [AgentPubKey] and an app id
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct InstallAppPayload {
@@ -316,6 +349,40 @@ impl ActiveApp {
         Ok(slot.clones.remove(cell_id))
     }
 
+    /// Archive a cloned cell so it no longer runs or counts towards the
+    /// clone limit, but its data is kept until the archive is deleted
+    pub fn archive_clone(&mut self, slot_id: &SlotId, cell_id: &CellId) -> AppResult<()> {
+        let slot = self.0.slot_mut(slot_id)?;
+        if slot.clones.remove(cell_id) {
+            slot.archived_clones.insert(cell_id.clone());
+            Ok(())
+        } else {
+            Err(AppError::CloneCellMissing(slot_id.clone(), cell_id.clone()))
+        }
+    }
+
+    /// Point a slot at the cell its agent migrated to, keeping the
+    /// previously provisioned cell as an archived, closed chain.
+    /// Returns the previously provisioned cell.
+    pub fn migrate_slot(&mut self, slot_id: &SlotId, cell_id: CellId) -> AppResult<CellId> {
+        let slot = self.0.slot_mut(slot_id)?;
+        if cell_id.agent_pubkey() != slot.agent_key() {
+            return Err(AppError::MigrationAgentMismatch(slot_id.clone(), cell_id));
+        }
+        if !slot.is_provisioned {
+            return Err(AppError::SlotNotProvisioned(slot_id.clone()));
+        }
+        let old_cell_id = std::mem::replace(&mut slot.base_cell_id, cell_id);
+        slot.migrated_cells.push(old_cell_id.clone());
+        Ok(old_cell_id)
+    }
+
+    /// Remove all archived clones from a slot, returning the removed cells
+    pub fn delete_archived_clones(&mut self, slot_id: &SlotId) -> AppResult<Vec<CellId>> {
+        let slot = self.0.slot_mut(slot_id)?;
+        Ok(slot.archived_clones.drain().collect())
+    }
+
     /// Move inner type out
     pub fn into_common(self) -> InstalledAppCommon {
         self.0
@@ -446,6 +513,14 @@ impl InstalledAppCommon {
         self.slots.iter().map(|(_, slot)| &slot.clones).flatten()
     }
 
+    /// Accessor
+    pub fn archived_cells(&self) -> impl Iterator<Item = &CellId> {
+        self.slots
+            .iter()
+            .map(|(_, slot)| slot.archived_clones.iter().chain(&slot.migrated_cells))
+            .flatten()
+    }
+
     /// Iterator of all cells, both provisioned and cloned
     pub fn all_cells(&self) -> impl Iterator<Item = &CellId> {
         self.provisioned_cells()
@@ -453,6 +528,21 @@ impl InstalledAppCommon {
             .chain(self.cloned_cells())
     }
 
+    /// The SAFs of every slot and every Cell, including cloned and
+    /// archived Cells
+    pub fn all_safs(&self) -> HashSet<SafHash> {
+        self.slots
+            .values()
+            .map(|slot| slot.saf_hash())
+            .chain(
+                self.all_cells()
+                    .chain(self.archived_cells())
+                    .map(|cell_id| cell_id.saf_hash()),
+            )
+            .cloned()
+            .collect()
+    }
+
     /// Accessor for particular slot
     pub fn slot(&self, slot_id: &SlotId) -> AppResult<&AppSlot> {
         self.slots
@@ -524,6 +614,7 @@ impl InstalledAppCommon {
                     base_cell_id: cell_id,
                     is_provisioned: true,
                     clones: HashSet::new(),
+                    archived_clones: HashSet::new(),
                     clone_limit: 0,
                 };
                 (cell_nick, slot)
@@ -572,6 +663,15 @@ pub struct AppSlot {
     /// Cells which were cloned at runtime. The length cannot grow beyond
     /// `clone_limit`
     clones: HashSet<CellId>,
+    /// Clones which have been archived. They are not running but their
+    /// data is kept until they are deleted.
+    #[serde(default)]
+    archived_clones: HashSet<CellId>,
+    /// Provisioned cells whose agent migrated to a new SAF, oldest first.
+    /// Their chains are closed and they no longer run, but their data is
+    /// kept until the app is uninstalled.
+    #[serde(default)]
+    migrated_cells: Vec<CellId>,
 }
 
 impl AppSlot {
@@ -582,6 +682,8 @@ impl AppSlot {
             is_provisioned,
             clone_limit,
             clones: HashSet::new(),
+            archived_clones: HashSet::new(),
+            migrated_cells: Vec::new(),
         }
     }
 
@@ -674,6 +776,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn archive_clones() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let new_clone = || CellId::new(fixt!(SafHash), agent.clone());
+        let slot1 = AppSlot::new(base_cell_id, false, 2);
+        let slot_id: SlotId = "slot_id".into();
+        let mut app: ActiveApp =
+            InstalledAppCommon::new("app", fixt!(AgentPubKey), vec![(slot_id.clone(), slot1)])
+                .into();
+
+        let clones: Vec<_> = vec![new_clone(), new_clone(), new_clone()];
+        app.add_clone(&slot_id, clones[0].clone()).unwrap();
+        app.add_clone(&slot_id, clones[1].clone()).unwrap();
+
+        // Archiving removes the clone from the running cells
+        app.archive_clone(&slot_id, &clones[0]).unwrap();
+        assert_eq!(app.cloned_cells().collect::<Vec<_>>(), vec![&clones[1]]);
+        assert_eq!(app.archived_cells().collect::<Vec<_>>(), vec![&clones[0]]);
+        assert!(!app.all_cells().any(|c| *c == clones[0]));
+
+        // Only active clones can be archived
+        matches::assert_matches!(
+            app.archive_clone(&slot_id, &clones[0]),
+            Err(AppError::CloneCellMissing(_, _))
+        );
+
+        // Archived clones don't count towards the clone limit
+        app.add_clone(&slot_id, clones[2].clone()).unwrap();
+
+        // Deleting the archive returns the archived cells
+        assert_eq!(
+            app.delete_archived_clones(&slot_id).unwrap(),
+            vec![clones[0].clone()]
+        );
+        assert_eq!(app.archived_cells().count(), 0);
+        assert_eq!(app.cloned_cells().count(), 2);
+    }
+
+    #[test]
+    fn migrate_slot() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let new_cell = || CellId::new(fixt!(SafHash), agent.clone());
+        let slot_id: SlotId = "slot_id".into();
+        let unprovisioned_id: SlotId = "unprovisioned".into();
+        let slots = vec![
+            (slot_id.clone(), AppSlot::new(base_cell_id.clone(), true, 0)),
+            (unprovisioned_id.clone(), AppSlot::new(new_cell(), false, 0)),
+        ];
+        let mut app: ActiveApp = InstalledAppCommon::new("app", agent.clone(), slots).into();
+
+        // Migrating replaces the provisioned cell and archives the old one
+        let migrated = new_cell();
+        assert_eq!(
+            app.migrate_slot(&slot_id, migrated.clone()).unwrap(),
+            base_cell_id
+        );
+        assert_eq!(
+            app.slot(&slot_id).unwrap().provisioned_cell(),
+            Some(&migrated)
+        );
+        assert_eq!(
+            app.archived_cells().collect::<Vec<_>>(),
+            vec![&base_cell_id]
+        );
+        assert!(!app.all_cells().any(|c| *c == base_cell_id));
+        assert!(app.all_safs().contains(base_cell_id.saf_hash()));
+
+        // Only provisioned slots can migrate
+        matches::assert_matches!(
+            app.migrate_slot(&unprovisioned_id, new_cell()),
+            Err(AppError::SlotNotProvisioned(_))
+        );
+
+        // An agent can only migrate to a cell with its own agent key
+        matches::assert_matches!(
+            app.migrate_slot(&slot_id, fixt!(CellId)),
+            Err(AppError::MigrationAgentMismatch(_, _))
+        );
+        assert_eq!(
+            app.slot(&slot_id).unwrap().provisioned_cell(),
+            Some(&migrated)
+        );
+    }
+
     #[test]
     fn status_serialization() {
         let status = InstalledAppStatus::Inactive {
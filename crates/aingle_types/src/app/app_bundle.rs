@@ -180,7 +180,10 @@ impl AppBundle {
         properties: Option<YamlProperties>,
     ) -> AppBundleResult<CellProvisioningOp> {
         let bytes = self.resolve(location).await?;
-        let saf_bundle: SafBundle = mr_bundle::Bundle::decode(&bytes)?.into();
+        let saf_bundle: SafBundle = mr_bundle::Bundle::decode(&bytes)?
+            .with_remote_config(self.remote_config().clone())
+            .with_content_addressed_only(self.is_content_addressed_only())
+            .into();
         let (saf_file, original_saf_hash) = saf_bundle.into_saf_file(uid, properties).await?;
         if let Some(spec) = version {
             if !spec.matches(original_saf_hash) {
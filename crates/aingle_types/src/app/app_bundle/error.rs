@@ -1,7 +1,7 @@
 use aingle_util::ffs;
 use mr_bundle::error::MrBundleError;
 
-use crate::prelude::{AppManifestError, CellNick, SafError};
+use crate::prelude::{AppManifestError, BundleSignatureError, CellNick, SafError};
 
 /// Errors occurring while installing an AppBundle
 #[derive(thiserror::Error, Debug)]
@@ -20,6 +20,12 @@ pub enum AppBundleError {
 
     #[error(transparent)]
     FfsIoError(#[from] ffs::IoError),
+
+    #[error("The app bundle is not signed by any trusted publisher")]
+    UntrustedPublisher,
+
+    #[error(transparent)]
+    BundleSignatureError(#[from] BundleSignatureError),
 }
 
 pub type AppBundleResult<T> = Result<T, AppBundleError>;
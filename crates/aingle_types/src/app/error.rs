@@ -8,6 +8,15 @@ pub enum AppError {
     #[error("Clone limit of {0} exceeded for cell: {1:?}")]
     CloneLimitExceeded(u32, AppSlot),
 
+    #[error("Slot '{0}' has no clone cell {1:?}")]
+    CloneCellMissing(SlotId, CellId),
+
+    #[error("Slot '{0}' has no provisioned cell")]
+    SlotNotProvisioned(SlotId),
+
+    #[error("Slot '{0}' can't migrate to cell {1:?}, which belongs to a different agent")]
+    MigrationAgentMismatch(SlotId, CellId),
+
     #[error("Tried to access missing slot id: '{0}'")]
     SlotIdMissing(SlotId),
 
@@ -0,0 +1,124 @@
+//! Publisher signatures on SAF and hApp bundles.
+//!
+//! A publisher signs the [`signable_bytes`](mr_bundle::Bundle::signable_bytes)
+//! of a bundle with an agent key from their keystore. The signature travels
+//! inside the bundle file, and anyone can check which publishers vouch for
+//! the bundle's content without access to a keystore.
+
+use crate::prelude::*;
+use aingle_keystore::KeystoreError;
+use mr_bundle::{error::MrBundleError, Bundle, BundleSignature, Manifest};
+
+/// Errors which can occur while signing or verifying a bundle
+#[derive(Debug, thiserror::Error)]
+pub enum BundleSignatureError {
+    /// The keystore failed to sign or verify
+    #[error(transparent)]
+    KeystoreError(#[from] KeystoreError),
+
+    /// The bundle content couldn't be encoded for signing
+    #[error(transparent)]
+    MrBundleError(#[from] MrBundleError),
+}
+
+/// Result type for [`BundleSignatureError`]
+pub type BundleSignatureResult<T> = Result<T, BundleSignatureError>;
+
+/// Sign a bundle as `publisher`, keeping any existing signatures
+pub async fn sign_bundle<M: Manifest>(
+    bundle: Bundle<M>,
+    keystore: &KeystoreSender,
+    publisher: &AgentPubKey,
+) -> BundleSignatureResult<Bundle<M>> {
+    let signature = publisher
+        .sign_raw(keystore, &bundle.signable_bytes()?)
+        .await?;
+    Ok(bundle.with_signature(BundleSignature {
+        signer: publisher.get_raw_39().to_vec(),
+        signature: signature.0.to_vec(),
+    }))
+}
+
+/// The publishers with a valid signature on this bundle.
+/// Signatures which don't verify, or don't come from an agent key,
+/// are ignored.
+pub async fn verified_publishers<M: Manifest>(
+    bundle: &Bundle<M>,
+) -> BundleSignatureResult<Vec<AgentPubKey>> {
+    let data = bundle.signable_bytes()?;
+    let mut publishers = Vec::new();
+    for BundleSignature { signer, signature } in bundle.signatures() {
+        let publisher = match AgentPubKey::from_raw_39(signer.clone()) {
+            Ok(publisher) => publisher,
+            Err(_) => continue,
+        };
+        let signature = match Signature::try_from(signature.as_slice()) {
+            Ok(signature) => signature,
+            Err(_) => continue,
+        };
+        if publisher.verify_signature_raw(&signature, &data).await? {
+            publishers.push(publisher);
+        }
+    }
+    Ok(publishers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aingle_keystore::test_keystore::spawn_test_keystore;
+    use mr_bundle::Location;
+
+    #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    struct TestManifest(Vec<Location>);
+
+    impl Manifest for TestManifest {
+        fn locations(&self) -> Vec<Location> {
+            self.0.clone()
+        }
+
+        fn path() -> std::path::PathBuf {
+            unimplemented!()
+        }
+
+        fn bundle_extension() -> &'static str {
+            unimplemented!()
+        }
+    }
+
+    fn test_bundle(thing: u8) -> Bundle<TestManifest> {
+        let manifest = TestManifest(vec![Location::Bundled("thing".into())]);
+        Bundle::new_unchecked(manifest, vec![("thing".into(), vec![thing])]).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sign_and_verify() {
+        let keystore = spawn_test_keystore().await.unwrap();
+        let publisher = AgentPubKey::new_from_pure_entropy(&keystore).await.unwrap();
+        let other = AgentPubKey::new_from_pure_entropy(&keystore).await.unwrap();
+
+        let bundle = test_bundle(1);
+        assert!(verified_publishers(&bundle).await.unwrap().is_empty());
+
+        let bundle = sign_bundle(bundle, &keystore, &publisher).await.unwrap();
+        let signature = bundle.signatures()[0].clone();
+        assert_eq!(
+            verified_publishers(&bundle).await.unwrap(),
+            vec![publisher.clone()]
+        );
+
+        // A signature claimed by the wrong key doesn't verify
+        let bundle = bundle.with_signature(BundleSignature {
+            signer: other.get_raw_39().to_vec(),
+            ..signature.clone()
+        });
+        assert_eq!(
+            verified_publishers(&bundle).await.unwrap(),
+            vec![publisher.clone()]
+        );
+
+        // The signature doesn't verify over different content
+        let bundle = test_bundle(2).with_signature(signature);
+        assert!(verified_publishers(&bundle).await.unwrap().is_empty());
+    }
+}
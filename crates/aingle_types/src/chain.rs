@@ -4,6 +4,9 @@ use crate::activity::ChainItems;
 use ai_hash::AgentPubKey;
 use aingle_zome_types::prelude::ChainStatus;
 
+mod archive;
+pub use archive::*;
+
 /// Helpers for constructing AgentActivity
 pub trait AgentActivityExt {
     /// Create an empty chain status
@@ -15,6 +18,7 @@ pub trait AgentActivityExt {
             status: ChainStatus::Empty,
             // TODO: Add the actual highest observed in a follow up PR
             highest_observed: None,
+            warrants: Vec::with_capacity(0),
         }
     }
 }
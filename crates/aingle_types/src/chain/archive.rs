@@ -0,0 +1,171 @@
+//! Portable, signed copies of a source chain, for backup and restore.
+
+use crate::prelude::*;
+use aingle_keystore::KeystoreError;
+
+/// A copy of an agent's authored source chain, or of the part of it starting
+/// at `from_seq`, signed by that agent so it can be stored and moved around
+/// outside the conductor.
+///
+/// Importing an archive which starts at sequence 0 restores a chain into an
+/// empty cell. Importing one which starts later extends a chain whose head is
+/// the header immediately before `from_seq`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct SourceChainArchive {
+    /// The cell whose chain was exported
+    pub cell_id: CellId,
+    /// The sequence number of the first element in the archive
+    pub from_seq: u32,
+    /// The elements in sequence order, including private entries
+    pub elements: Vec<Element>,
+    /// The agent's signature over the rest of the archive
+    pub signature: Signature,
+}
+
+/// Errors which make a [`SourceChainArchive`] unusable
+#[derive(Debug, thiserror::Error)]
+pub enum SourceChainArchiveError {
+    /// The archive has no elements
+    #[error("The source chain archive is empty")]
+    Empty,
+
+    /// The archive wasn't signed by the cell's agent
+    #[error("The source chain archive is not signed by its agent")]
+    InvalidSignature,
+
+    /// An element wasn't authored by the cell's agent
+    #[error("Element {0} was not authored by the archive's agent")]
+    WrongAuthor(HeaderHash),
+
+    /// An element's hash or signature doesn't match its header,
+    /// or its entry doesn't match the header's entry hash
+    #[error("Element {0} has been tampered with")]
+    InvalidElement(HeaderHash),
+
+    /// The elements don't form an unbroken chain starting at `from_seq`
+    #[error("Element {0} does not follow the previous element")]
+    BrokenChain(HeaderHash),
+
+    /// A full chain doesn't start with the genesis of the cell's SAF
+    #[error("The archive does not start with genesis for SAF {0}")]
+    NotGenesis(SafHash),
+
+    /// There are no elements to export from this sequence number on
+    #[error("The source chain ends before sequence number {0}")]
+    StartsPastHead(u32),
+
+    /// The archive doesn't start where the existing chain ends
+    #[error("The archive starts at {from_seq} but doesn't extend the existing chain")]
+    DoesNotExtendChain {
+        /// The sequence number the archive starts at
+        from_seq: u32,
+    },
+
+    /// The keystore failed to sign or verify
+    #[error(transparent)]
+    KeystoreError(#[from] KeystoreError),
+
+    /// The archive couldn't be encoded for signing
+    #[error(transparent)]
+    SerializedBytesError(#[from] SerializedBytesError),
+}
+
+/// Result type for [`SourceChainArchiveError`]
+pub type SourceChainArchiveResult<T> = Result<T, SourceChainArchiveError>;
+
+impl SourceChainArchive {
+    /// Sign an archive of these elements as the cell's agent
+    pub async fn new(
+        keystore: &KeystoreSender,
+        cell_id: CellId,
+        from_seq: u32,
+        elements: Vec<Element>,
+    ) -> SourceChainArchiveResult<Self> {
+        let data = signable_bytes(&cell_id, from_seq, &elements)?;
+        let signature = cell_id.agent_pubkey().sign_raw(keystore, &data).await?;
+        Ok(Self {
+            cell_id,
+            from_seq,
+            elements,
+            signature,
+        })
+    }
+
+    /// Check that the archive is signed by its agent and holds an unbroken
+    /// run of that agent's elements starting at `from_seq`.
+    /// A full chain must also start with the genesis of the cell's SAF.
+    /// This does not check the archive against any chain it may extend.
+    pub async fn verify(&self) -> SourceChainArchiveResult<()> {
+        let agent = self.cell_id.agent_pubkey();
+        let data = signable_bytes(&self.cell_id, self.from_seq, &self.elements)?;
+        if !agent.verify_signature_raw(&self.signature, &data).await? {
+            return Err(SourceChainArchiveError::InvalidSignature);
+        }
+
+        let first = self
+            .elements
+            .first()
+            .ok_or(SourceChainArchiveError::Empty)?;
+        if self.from_seq == 0 {
+            match first.header() {
+                Header::Saf(header::Saf { hash, .. }) if hash == self.cell_id.saf_hash() => (),
+                _ => {
+                    return Err(SourceChainArchiveError::NotGenesis(
+                        self.cell_id.saf_hash().clone(),
+                    ))
+                }
+            }
+        }
+
+        let mut prev_header = None;
+        for (seq, element) in (self.from_seq..).zip(self.elements.iter()) {
+            let header = element.header();
+            let hash = element.header_address();
+            if header.author() != agent {
+                return Err(SourceChainArchiveError::WrongAuthor(hash.clone()));
+            }
+            if HeaderHash::with_data_sync(header) != *hash
+                || !agent.verify_signature(element.signature(), header).await?
+            {
+                return Err(SourceChainArchiveError::InvalidElement(hash.clone()));
+            }
+            if let (Some(entry_hash), Some(entry)) =
+                (header.entry_hash(), element.entry().as_option())
+            {
+                if EntryHash::with_data_sync(entry) != *entry_hash {
+                    return Err(SourceChainArchiveError::InvalidElement(hash.clone()));
+                }
+            }
+            let follows = match prev_header {
+                // The first element may follow anything, which is checked on import
+                None => true,
+                Some(prev) => header.prev_header() == Some(prev),
+            };
+            if header.header_seq() != seq || !follows {
+                return Err(SourceChainArchiveError::BrokenChain(hash.clone()));
+            }
+            prev_header = Some(hash);
+        }
+        Ok(())
+    }
+
+    /// The membrane proof committed during genesis, if the archive includes it
+    pub fn membrane_proof(&self) -> Option<MembraneProof> {
+        self.elements
+            .iter()
+            .find_map(|element| match element.header() {
+                Header::AgentValidationPkg(header::AgentValidationPkg {
+                    membrane_proof, ..
+                }) => membrane_proof.clone(),
+                _ => None,
+            })
+    }
+}
+
+fn signable_bytes(
+    cell_id: &CellId,
+    from_seq: u32,
+    elements: &[Element],
+) -> Result<Vec<u8>, SerializedBytesError> {
+    aingle_middleware_bytes::encode(&(cell_id, from_seq, elements))
+}
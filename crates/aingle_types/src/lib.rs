@@ -16,6 +16,7 @@ pub mod access;
 pub mod activity;
 pub mod app;
 pub mod autonomic;
+pub mod bundle_signature;
 pub mod chain;
 pub mod db;
 pub mod sgd_op;
@@ -47,6 +47,25 @@ pub struct WireLinkKey {
     pub tag: Option<LinkTag>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// What an authority sends back in response to get links.
+pub enum GetLinksResponseType {
+    /// The condensed create and delete link ops, which the requester
+    /// can validate and cache.
+    Ops,
+    /// Only the condensed create link ops of the live links, without
+    /// any deletes or the deleted and invalid creates.
+    Live,
+    /// Only the number of live links, without any ops.
+    Count,
+}
+
+impl Default for GetLinksResponseType {
+    fn default() -> Self {
+        GetLinksResponseType::Ops
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes, Default)]
 /// Condensed link ops for sending across the wire in response to get links.
 pub struct WireLinkOps {
@@ -54,6 +73,10 @@ pub struct WireLinkOps {
     pub creates: Vec<WireCreateLink>,
     /// delete links that match this query.
     pub deletes: Vec<WireDeleteLink>,
+    /// The number of live links that match this query,
+    /// if only the count was requested.
+    #[serde(default)]
+    pub count: Option<usize>,
 }
 
 impl WireLinkOps {
@@ -63,7 +86,9 @@ impl WireLinkOps {
     }
     /// Render these ops to their full types.
     pub fn render(self, key: &WireLinkKey) -> SgdOpResult<RenderedOps> {
-        let Self { creates, deletes } = self;
+        let Self {
+            creates, deletes, ..
+        } = self;
         let mut ops = Vec::with_capacity(creates.len() + deletes.len());
         // We silently ignore ops that fail to render as they come from the network.
         ops.extend(creates.into_iter().filter_map(|op| op.render(key).ok()));
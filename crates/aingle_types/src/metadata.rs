@@ -6,6 +6,7 @@ use ai_hash::HeaderHash;
 use aingle_middleware_bytes::prelude::*;
 pub use aingle_zome_types::metadata::EntrySgdStatus;
 use aingle_zome_types::HeaderHashed;
+use aingle_zome_types::ValidationStatus;
 use std::collections::BTreeSet;
 
 /// Timestamp of when the header was created with the headers hash.
@@ -19,13 +20,13 @@ pub struct TimedHeaderHash {
 
 /// Metadata returned from a GetMeta request.
 /// The Ord derive on TimedHeaderHash means each set is ordered by time.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, SerializedBytes)]
 pub struct MetadataSet {
     /// Headers that created or updated an entry.
     /// These are the headers that show the entry exists.
     pub headers: BTreeSet<TimedHeaderHash>,
-    // TODO: Implement after validation
-    /// Placeholder
+    /// Headers that created or updated an entry
+    /// but were rejected by validation.
     pub invalid_headers: BTreeSet<TimedHeaderHash>,
     /// Deletes on a header
     pub deletes: BTreeSet<TimedHeaderHash>,
@@ -35,6 +36,39 @@ pub struct MetadataSet {
     /// This is simply a faster way of determining if
     /// there are any live headers on an entry.
     pub entry_sgd_status: Option<EntrySgdStatus>,
+    /// The number of links on this basis that haven't been deleted.
+    pub link_count: usize,
+    /// The validation status of the basis itself.
+    /// `None` if the authority doesn't hold it.
+    pub validation_status: Option<ValidationStatus>,
+}
+
+impl MetadataSet {
+    /// Merge the metadata returned by another authority into this set.
+    /// Where authorities disagree about the status of the basis
+    /// a valid or live status wins.
+    pub fn merge(&mut self, other: MetadataSet) {
+        let MetadataSet {
+            headers,
+            invalid_headers,
+            deletes,
+            updates,
+            entry_sgd_status,
+            link_count,
+            validation_status,
+        } = other;
+        self.headers.extend(headers);
+        self.invalid_headers.extend(invalid_headers);
+        self.deletes.extend(deletes);
+        self.updates.extend(updates);
+        self.link_count = self.link_count.max(link_count);
+        if self.entry_sgd_status.is_none() || entry_sgd_status == Some(EntrySgdStatus::Live) {
+            self.entry_sgd_status = entry_sgd_status.or(self.entry_sgd_status);
+        }
+        if self.validation_status.is_none() || validation_status == Some(ValidationStatus::Valid) {
+            self.validation_status = validation_status.or(self.validation_status);
+        }
+    }
 }
 
 impl From<HeaderHashed> for TimedHeaderHash {
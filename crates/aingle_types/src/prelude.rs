@@ -13,6 +13,7 @@ pub use crate::activity::*;
 pub use crate::app::error::*;
 pub use crate::app::*;
 pub use crate::autonomic::*;
+pub use crate::bundle_signature::*;
 pub use crate::chain::*;
 pub use crate::db::*;
 pub use crate::sgd_op::error::*;
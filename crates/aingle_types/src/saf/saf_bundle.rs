@@ -30,6 +30,11 @@ impl SafBundle {
         Ok(mr_bundle::Bundle::new(manifest, resources, root_dir)?.into())
     }
 
+    /// Convert to the inner Bundle
+    pub fn into_inner(self) -> mr_bundle::Bundle<SafManifest> {
+        self.0
+    }
+
     /// Convert to a SafFile, and return what the hash of the Saf *would* have
     /// been without the provided phenotype overrides
     pub async fn into_saf_file(
@@ -128,6 +133,7 @@ impl SafBundle {
                         manifest.properties.clone().unwrap_or_default(),
                     )?,
                     zomes,
+                    rate_limit: manifest.rate_limit,
                 };
 
                 if uid.is_none() && properties.is_none() {
@@ -197,6 +203,7 @@ impl SafBundle {
                 ))
             })?),
             zomes,
+            rate_limit: saf_def.rate_limit,
         }
         .into())
     }
@@ -233,6 +240,7 @@ mod tests {
                     location: mr_bundle::Location::Bundled(path2.clone()),
                 },
             ],
+            rate_limit: None,
         };
         let resources = vec![(path1, wasm1), (path2, wasm2)];
 
@@ -273,4 +281,42 @@ mod tests {
             SerializedBytes::try_from(properties).unwrap()
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn saf_bundle_rate_limit() {
+        let path = PathBuf::from("1");
+        let wasm = vec![1, 2, 3];
+        let mut manifest = SafManifestCurrent {
+            name: "name".into(),
+            uid: Some("uid".to_string()),
+            properties: None,
+            zomes: vec![ZomeManifest {
+                name: "zome1".into(),
+                hash: None,
+                location: mr_bundle::Location::Bundled(path.clone()),
+            }],
+            rate_limit: None,
+        };
+        let resources = vec![(path, wasm)];
+
+        let bundle: SafBundle =
+            mr_bundle::Bundle::new_unchecked(manifest.clone().into(), resources.clone())
+                .unwrap()
+                .into();
+        let without_limit: SafFile = bundle.into_saf_file(None, None).await.unwrap().0;
+        assert_eq!(without_limit.saf_def().rate_limit, None);
+
+        // - The rate limit is carried into the SafDef and changes the hash
+        let rate_limit = RateLimit {
+            max_headers: 10,
+            window_ms: 1000,
+        };
+        manifest.rate_limit = Some(rate_limit);
+        let bundle: SafBundle = mr_bundle::Bundle::new_unchecked(manifest.into(), resources)
+            .unwrap()
+            .into();
+        let with_limit: SafFile = bundle.into_saf_file(None, None).await.unwrap().0;
+        assert_eq!(with_limit.saf_def().rate_limit, Some(rate_limit));
+        assert_ne!(with_limit.saf_hash(), without_limit.saf_hash());
+    }
 }
@@ -42,7 +42,7 @@ impl SafManifest {
         properties: Option<YamlProperties>,
         zomes: Vec<ZomeManifest>,
     ) -> Self {
-        SafManifestCurrent::new(name, uid, properties, zomes).into()
+        SafManifestCurrent::new(name, uid, properties, zomes, None).into()
     }
 
     /// Getter for properties
@@ -65,4 +65,11 @@ impl SafManifest {
             SafManifest::V1(manifest) => manifest.name.clone(),
         }
     }
+
+    /// Getter for rate_limit
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        match self {
+            SafManifest::V1(manifest) => manifest.rate_limit,
+        }
+    }
 }
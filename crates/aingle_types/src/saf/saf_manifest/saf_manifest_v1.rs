@@ -1,5 +1,6 @@
 use crate::prelude::*;
 use ai_hash::*;
+use aingle_zome_types::RateLimit;
 use aingle_zome_types::ZomeName;
 
 /// The structure of data that goes in the SAF bundle manifest,
@@ -29,6 +30,10 @@ pub struct SafManifestV1 {
     /// An array of zomes associated with your SAF.
     /// The order is significant: it determines initialization order.
     pub(crate) zomes: Vec<ZomeManifest>,
+
+    /// Limits how many headers an author can publish within a window of time.
+    #[builder(default)]
+    pub(crate) rate_limit: Option<RateLimit>,
 }
 
 /// Manifest for an individual Zome
@@ -19,6 +19,8 @@ pub trait SafStore: Default + Send + Sync {
     fn list(&self) -> Vec<SafHash>;
     /// Get a particular SafFile
     fn get(&self, hash: &SafHash) -> Option<SafFile>;
+    /// Remove a SafFile from the store, returning it if it was present
+    fn remove_saf(&mut self, hash: &SafHash) -> Option<SafFile>;
     /// Get a particular EntryDef
     fn get_entry_def(&self, k: &EntryDefBufferKey) -> Option<EntryDef>;
 }
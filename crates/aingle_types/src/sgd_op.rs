@@ -105,6 +105,14 @@ pub enum SgdOp {
     #[display(fmt = "RegisterRemoveLink")]
     /// Op for removing a link
     RegisterRemoveLink(Signature, header::DeleteLink),
+
+    #[display(fmt = "RegisterWarrant")]
+    /// Used to notify the agent activity authorities of an agent that
+    /// one of its ops failed validation.
+    ///
+    /// The signature and header are the author's, for the invalid op,
+    /// so authorities can check the warrant against them.
+    RegisterWarrant(Signature, Header, Box<SignedWarrant>),
 }
 
 /// Show that this type is used as the basis
@@ -133,6 +141,8 @@ pub enum SgdOpLight {
     RegisterAddLink(HeaderHash, SgdBasis),
     #[display(fmt = "RegisterRemoveLink")]
     RegisterRemoveLink(HeaderHash, SgdBasis),
+    #[display(fmt = "RegisterWarrant")]
+    RegisterWarrant(HeaderHash, SgdBasis),
 }
 
 impl PartialEq for SgdOpLight {
@@ -186,6 +196,8 @@ pub enum SgdOpType {
     RegisterAddLink,
     #[display(fmt = "RegisterRemoveLink")]
     RegisterRemoveLink,
+    #[display(fmt = "RegisterWarrant")]
+    RegisterWarrant,
 }
 
 impl ToSql for SgdOpType {
@@ -227,6 +239,7 @@ impl SgdOp {
             }
             Self::RegisterAddLink(_, header) => UniqueForm::RegisterAddLink(header),
             Self::RegisterRemoveLink(_, header) => UniqueForm::RegisterRemoveLink(header),
+            Self::RegisterWarrant(_, _, warrant) => UniqueForm::RegisterWarrant(&warrant.warrant),
         }
     }
 
@@ -283,6 +296,9 @@ impl SgdOp {
                 let h = HeaderHash::with_data_sync(&Header::from(h.clone()));
                 SgdOpLight::RegisterRemoveLink(h, basis)
             }
+            SgdOp::RegisterWarrant(_, _, w) => {
+                SgdOpLight::RegisterWarrant(w.warrant.header.clone(), basis)
+            }
         }
     }
 
@@ -297,7 +313,8 @@ impl SgdOp {
             | SgdOp::RegisterDeletedBy(s, _)
             | SgdOp::RegisterDeletedEntryHeader(s, _)
             | SgdOp::RegisterAddLink(s, _)
-            | SgdOp::RegisterRemoveLink(s, _) => s,
+            | SgdOp::RegisterRemoveLink(s, _)
+            | SgdOp::RegisterWarrant(s, _, _) => s,
         }
     }
 
@@ -313,6 +330,7 @@ impl SgdOp {
             SgdOp::RegisterDeletedEntryHeader(s, h) => (s, h.into(), None),
             SgdOp::RegisterAddLink(s, h) => (s, h.into(), None),
             SgdOp::RegisterRemoveLink(s, h) => (s, h.into(), None),
+            SgdOp::RegisterWarrant(s, h, _) => (s, h, None),
         }
     }
 
@@ -330,6 +348,7 @@ impl SgdOp {
             SgdOp::RegisterDeletedEntryHeader(_, h) => h.clone().into(),
             SgdOp::RegisterAddLink(_, h) => h.clone().into(),
             SgdOp::RegisterRemoveLink(_, h) => h.clone().into(),
+            SgdOp::RegisterWarrant(_, h, _) => h.clone(),
         }
     }
 
@@ -345,6 +364,15 @@ impl SgdOp {
             SgdOp::RegisterDeletedEntryHeader(_, _) => None,
             SgdOp::RegisterAddLink(_, _) => None,
             SgdOp::RegisterRemoveLink(_, _) => None,
+            SgdOp::RegisterWarrant(_, _, _) => None,
+        }
+    }
+
+    /// Get the warrant from this op, if it is a [`SgdOp::RegisterWarrant`]
+    pub fn warrant(&self) -> Option<&SignedWarrant> {
+        match self {
+            SgdOp::RegisterWarrant(_, _, w) => Some(&**w),
+            _ => None,
         }
     }
 
@@ -360,14 +388,17 @@ impl SgdOp {
             SgdOp::RegisterDeletedEntryHeader(_, _) => SgdOpType::RegisterDeletedEntryHeader,
             SgdOp::RegisterAddLink(_, _) => SgdOpType::RegisterAddLink,
             SgdOp::RegisterRemoveLink(_, _) => SgdOpType::RegisterRemoveLink,
+            SgdOp::RegisterWarrant(_, _, _) => SgdOpType::RegisterWarrant,
         }
     }
 
-    /// From a type, header and an entry (if there is one)
+    /// From a type, header and an entry (if there is one).
+    /// [`SgdOpType::RegisterWarrant`] also requires the warrant.
     pub fn from_type(
         op_type: SgdOpType,
         header: SignedHeader,
         entry: Option<Entry>,
+        warrant: Option<SignedWarrant>,
     ) -> SgdOpResult<Self> {
         let SignedHeader(header, signature) = header;
         let r = match op_type {
@@ -396,6 +427,10 @@ impl SgdOp {
             SgdOpType::RegisterRemoveLink => {
                 SgdOp::RegisterRemoveLink(signature, header.try_into()?)
             }
+            SgdOpType::RegisterWarrant => {
+                let warrant = warrant.ok_or(SgdOpError::WarrantMissing)?;
+                SgdOp::RegisterWarrant(signature, header, Box::new(warrant))
+            }
         };
         Ok(r)
     }
@@ -413,7 +448,8 @@ impl SgdOpLight {
             | SgdOpLight::RegisterDeletedBy(_, b)
             | SgdOpLight::RegisterDeletedEntryHeader(_, b)
             | SgdOpLight::RegisterAddLink(_, b)
-            | SgdOpLight::RegisterRemoveLink(_, b) => b,
+            | SgdOpLight::RegisterRemoveLink(_, b)
+            | SgdOpLight::RegisterWarrant(_, b) => b,
         }
     }
     /// Get the header hash from this op
@@ -427,7 +463,8 @@ impl SgdOpLight {
             | SgdOpLight::RegisterDeletedBy(h, _)
             | SgdOpLight::RegisterDeletedEntryHeader(h, _)
             | SgdOpLight::RegisterAddLink(h, _)
-            | SgdOpLight::RegisterRemoveLink(h, _) => h,
+            | SgdOpLight::RegisterRemoveLink(h, _)
+            | SgdOpLight::RegisterWarrant(h, _) => h,
         }
     }
 
@@ -443,6 +480,7 @@ impl SgdOpLight {
             SgdOpLight::RegisterDeletedEntryHeader(_, _) => SgdOpType::RegisterDeletedEntryHeader,
             SgdOpLight::RegisterAddLink(_, _) => SgdOpType::RegisterAddLink,
             SgdOpLight::RegisterRemoveLink(_, _) => SgdOpType::RegisterRemoveLink,
+            SgdOpLight::RegisterWarrant(_, _) => SgdOpType::RegisterWarrant,
         }
     }
 
@@ -513,6 +551,9 @@ impl SgdOpLight {
                 };
                 Self::RegisterRemoveLink(header_hash, basis.into())
             }
+            SgdOpType::RegisterWarrant => {
+                Self::RegisterWarrant(header_hash, header.author().clone().into())
+            }
         };
         Ok(op)
     }
@@ -533,6 +574,7 @@ pub enum UniqueForm<'a> {
     RegisterDeletedEntryHeader(&'a header::Delete),
     RegisterAddLink(&'a header::CreateLink),
     RegisterRemoveLink(&'a header::DeleteLink),
+    RegisterWarrant(&'a Warrant),
 }
 
 impl<'a> UniqueForm<'a> {
@@ -553,10 +595,13 @@ impl<'a> UniqueForm<'a> {
             }
             UniqueForm::RegisterAddLink(header) => header.base_address.clone().into(),
             UniqueForm::RegisterRemoveLink(header) => header.base_address.clone().into(),
+            UniqueForm::RegisterWarrant(warrant) => warrant.author.clone().into(),
         }
     }
 
     /// Get the sgd op hash without cloning the header.
+    /// The hash of a [`SgdOpType::RegisterWarrant`] depends on the
+    /// warrant and can't be computed from the header alone.
     pub fn op_hash(op_type: SgdOpType, header: Header) -> SgdOpResult<(Header, SgdOpHash)> {
         match op_type {
             SgdOpType::StoreElement => {
@@ -603,6 +648,7 @@ impl<'a> UniqueForm<'a> {
                 let hash = SgdOpHash::with_data_sync(&UniqueForm::RegisterRemoveLink(&header));
                 Ok((header.into(), hash))
             }
+            SgdOpType::RegisterWarrant => Err(SgdOpError::WarrantMissing),
         }
     }
 }
@@ -663,6 +709,8 @@ pub fn produce_ops_from_element(element: &Element) -> SgdOpResult<Vec<SgdOp>> {
                 let link_remove = header.try_into()?;
                 SgdOp::RegisterRemoveLink(signature, link_remove)
             }
+            // Warrants are never produced from the author's own elements.
+            SgdOpLight::RegisterWarrant(_, _) => continue,
         };
         ops.push(op);
     }
@@ -913,6 +961,7 @@ pub enum OpNumericalOrder {
     RegisterDeletedEntryHeader,
     RegisterAddLink,
     RegisterRemoveLink,
+    RegisterWarrant,
 }
 
 /// This is used as an index for ordering ops in our database.
@@ -947,6 +996,7 @@ impl OpOrder {
             SgdOpType::RegisterDeletedEntryHeader => OpNumericalOrder::RegisterDeletedEntryHeader,
             SgdOpType::RegisterAddLink => OpNumericalOrder::RegisterAddLink,
             SgdOpType::RegisterRemoveLink => OpNumericalOrder::RegisterRemoveLink,
+            SgdOpType::RegisterWarrant => OpNumericalOrder::RegisterWarrant,
         };
         Self { order, timestamp }
     }
@@ -18,6 +18,8 @@ pub enum SgdOpError {
     OpHeaderMismatch(SgdOpType, HeaderType),
     #[error("Link requests without tags require a tag in the response")]
     LinkKeyTagMissing,
+    #[error("Tried to create a RegisterWarrant SgdOp without a warrant")]
+    WarrantMissing,
 }
 
 pub type SgdOpResult<T> = Result<T, SgdOpError>;
@@ -3,7 +3,9 @@
 //! - App-defined signals are produced via the `emit_signal` host function.
 //! - System-defined signals are produced in various places in the system
 
+use crate::app::{InstalledAppId, InstalledAppStatus};
 use crate::impl_from;
+use ai_hash::{AgentPubKey, SafHash, SgdOpHash};
 use aingle_middleware_bytes::prelude::*;
 use aingle_zome_types::prelude::*;
 
@@ -19,16 +21,100 @@ pub enum Signal {
 
 /// A Signal which originates from within the AIngle system, as opposed to
 /// from within a Cell
-///
-/// TODO, decide what these will be. For instance, maybe there is a
-/// DataAvailable signal for doing async network requests
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
 pub enum SystemSignal {
-    /// Since we have no real system signals, we use a test signal for testing
-    /// TODO: replace instances of this with something real
+    /// Ops authored by a Cell were published to the network.
+    /// Ops are republished until they have enough validation receipts,
+    /// so the same op can show up in more than one of these signals.
+    OpsPublished {
+        /// The Cell which published the ops
+        cell_id: CellId,
+        /// The ops which were published
+        op_hashes: Vec<SgdOpHash>,
+    },
+    /// An op authored by a Cell has received enough validation receipts
+    /// that it no longer needs to be published.
+    ReceiptThresholdReached {
+        /// The Cell which authored the op
+        cell_id: CellId,
+        /// The op which reached the threshold
+        op_hash: SgdOpHash,
+        /// The number of validation receipts the op has received
+        receipt_count: u32,
+    },
+    /// Ops held by a Cell passed validation.
+    /// Sent once per validation workflow run for all the ops it validated.
+    OpsValidated {
+        /// The Cell which validated the ops
+        cell_id: CellId,
+        /// The validated ops
+        op_hashes: Vec<SgdOpHash>,
+    },
+    /// Ops held by a Cell were rejected during validation.
+    /// Sent once per validation workflow run for all the ops it rejected.
+    OpsRejected {
+        /// The Cell which rejected the ops
+        cell_id: CellId,
+        /// The rejected ops
+        op_hashes: Vec<SgdOpHash>,
+    },
+    /// This conductor has learned of an agent it didn't know in a space
+    PeerJoined {
+        /// The space the agent joined
+        saf_hash: SafHash,
+        /// The agent which joined
+        agent: AgentPubKey,
+    },
+    /// A Cell was added to or removed from the running conductor
+    CellStatusChanged {
+        /// The Cell whose status changed
+        cell_id: CellId,
+        /// The new status of the Cell
+        status: CellStatus,
+    },
+    /// An App was activated or deactivated
+    AppStatusChanged {
+        /// The App whose status changed
+        installed_app_id: InstalledAppId,
+        /// The new status of the App
+        status: InstalledAppStatus,
+    },
+    /// An App was uninstalled
+    AppUninstalled {
+        /// The App which was uninstalled
+        installed_app_id: InstalledAppId,
+    },
+    /// A signal with arbitrary content, for testing
     Test(String),
 }
 
+impl SystemSignal {
+    /// The Cell this signal is about, if it is about a single Cell
+    pub fn cell_id(&self) -> Option<&CellId> {
+        match self {
+            SystemSignal::OpsPublished { cell_id, .. }
+            | SystemSignal::ReceiptThresholdReached { cell_id, .. }
+            | SystemSignal::OpsValidated { cell_id, .. }
+            | SystemSignal::OpsRejected { cell_id, .. }
+            | SystemSignal::CellStatusChanged { cell_id, .. } => Some(cell_id),
+            SystemSignal::PeerJoined { .. }
+            | SystemSignal::AppStatusChanged { .. }
+            | SystemSignal::AppUninstalled { .. }
+            | SystemSignal::Test(_) => None,
+        }
+    }
+}
+
+/// Whether a Cell is running in the conductor, reported by
+/// [`SystemSignal::CellStatusChanged`]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CellStatus {
+    /// The Cell is running and joined to the network
+    Active,
+    /// The Cell has been stopped
+    Inactive,
+}
+
 /// Create a test signal
 pub fn test_signal(s: &str) -> Signal {
     SystemSignal::Test(s.to_string()).into()
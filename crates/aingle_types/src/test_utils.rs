@@ -28,6 +28,7 @@ pub fn fake_saf_zomes(uid: &str, zomes: Vec<(ZomeName, SafWasm)>) -> SafFile {
             .unwrap(),
         uid: uid.to_string(),
         zomes: Vec::new(),
+        rate_limit: None,
     };
     tokio_helper::block_forever_on(async move {
         let mut wasm_code = Vec::new();
@@ -17,6 +17,9 @@
 //! You can use [`ListenerHandle::close`] to close immediately or
 //! [`ListenerHandle::close_on`] to close on a future completing.
 //!
+//! On unix, [`WebsocketListener::bind_unix_with_handle`] and [`connect_unix`]
+//! do the same over a Unix domain socket instead of TCP.
+//!
 //! # Example
 //!
 //! ```
@@ -84,7 +87,9 @@ use aingle_middleware_bytes::prelude::*;
 use stream_cancel::Valve;
 use tracing::instrument;
 use url2::Url2;
+use util::addr_to_url;
 use util::url_to_addr;
+use util::RawSocket;
 use websocket::Websocket;
 
 mod websocket_config;
@@ -119,20 +124,43 @@ pub async fn connect(
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
-    let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
-        socket,
-        Some(config.to_tungstenite()),
-    )
-    .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    let remote_addr = addr_to_url(socket.peer_addr()?, config.scheme);
+    client_handshake(url.as_str(), socket, remote_addr, config).await
+}
+
+#[cfg(unix)]
+#[instrument(skip(config))]
+/// Create a new websocket connection over the Unix domain socket at `path`,
+/// as bound by [`WebsocketListener::bind_unix_with_handle`].
+pub async fn connect_unix(
+    path: impl AsRef<std::path::Path> + std::fmt::Debug,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let path = path.as_ref();
+    let socket = tokio::net::UnixStream::connect(path).await?;
+    // The handshake needs a url, but the host in it is never resolved
+    let url = url2::url2!("{}://localhost", config.scheme);
+    client_handshake(url.as_str(), socket, util::path_to_url(path), config).await
+}
+
+/// Run the client side of the websocket handshake over a connected socket.
+async fn client_handshake<S: RawSocket>(
+    url: &str,
+    socket: S,
+    remote_addr: Url2,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let (socket, _) =
+        tokio_tungstenite::client_async_with_config(url, socket, Some(config.to_tungstenite()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
     tracing::debug!("Client connected");
 
     // Noop valve because we don't have a listener to shutdown the
     // ends when creating a client
     let (exit, valve) = Valve::new();
     exit.disable();
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -15,8 +15,9 @@ use tracing::Instrument;
 use ghost_actor::*;
 use tungstenite::protocol::frame::coding::CloseCode;
 use tungstenite::protocol::CloseFrame;
+use url2::Url2;
 
-use crate::util::addr_to_url;
+use crate::util::RawSocket;
 use crate::util::ToFromSocket;
 use crate::util::CLOSE_TIMEOUT;
 use crate::CancelResponse;
@@ -119,18 +120,15 @@ impl Task {
 }
 
 impl Websocket {
-    #[instrument(skip(config, socket, listener_shutdown))]
+    #[instrument(skip(config, socket, remote_addr, listener_shutdown))]
     /// Create the ends of this websocket channel.
-    pub fn create_ends(
+    pub(crate) fn create_ends<S: RawSocket>(
         config: Arc<WebsocketConfig>,
-        socket: ToFromSocket,
+        socket: ToFromSocket<S>,
+        remote_addr: Url2,
         listener_shutdown: Valve,
     ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
-        let remote_addr = url2::url2!(
-            "{}#{}",
-            addr_to_url(socket.get_ref().peer_addr()?, config.scheme),
-            nanoid::nanoid!(),
-        );
+        let remote_addr = url2::url2!("{}#{}", remote_addr, nanoid::nanoid!());
 
         // Channel to the websocket from the application
         let (tx_to_websocket, rx_to_websocket) = tokio::sync::mpsc::channel(config.max_send_queue);
@@ -184,8 +182,8 @@ impl Websocket {
         tx_from_websocket,
         pair_shutdown
     ))]
-    fn run(
-        socket: ToFromSocket,
+    fn run<S: RawSocket>(
+        socket: ToFromSocket<S>,
         tx_to_websocket: TxToWebsocket,
         rx_to_websocket: RxToWebsocket,
         tx_from_websocket: TxFromWebsocket,
@@ -206,9 +204,9 @@ impl Websocket {
         );
     }
 
-    fn run_socket(
+    fn run_socket<S: RawSocket>(
         self,
-        socket: ToFromSocket,
+        socket: ToFromSocket<S>,
         send_response: TxToWebsocket,
         to_websocket: RxToWebsocket,
         from_websocket: TxFromWebsocket,
@@ -4,6 +4,8 @@ use futures::TryStreamExt;
 use std::io::Error;
 use std::io::ErrorKind;
 use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::Path;
 use std::sync::Arc;
 use stream_cancel::Trigger;
 use stream_cancel::Valve;
@@ -12,7 +14,10 @@ use tracing::instrument;
 use url2::Url2;
 
 use crate::util::addr_to_url;
+#[cfg(unix)]
+use crate::util::path_to_url;
 use crate::util::url_to_addr;
+use crate::util::RawSocket;
 use crate::websocket::Websocket;
 use crate::WebsocketConfig;
 use crate::WebsocketError;
@@ -106,6 +111,26 @@ impl WebsocketListener {
     )> {
         websocket_bind(addr, config).await
     }
+
+    #[cfg(unix)]
+    #[instrument(skip(config))]
+    /// Same as [`WebsocketListener::bind_with_handle`] but listens on a
+    /// Unix domain socket at `path` instead of a TCP port.
+    ///
+    /// The socket file has the permissions `mode`, e.g. `0o600` for only
+    /// the user who bound it, so access to the listener is controlled by
+    /// that file's permissions.
+    /// A socket left at `path` by an earlier listener is replaced.
+    pub async fn bind_unix_with_handle(
+        path: impl AsRef<Path> + std::fmt::Debug,
+        mode: u32,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<(
+        ListenerHandle,
+        impl futures::stream::Stream<Item = ListenerItem>,
+    )> {
+        websocket_bind_unix(path.as_ref(), mode, config).await
+    }
     /// Shutdown the listener stream.
     pub fn close(self) {
         self.handle.close()
@@ -203,16 +228,100 @@ async fn websocket_bind(
     let local_addr = addr_to_url(socket.local_addr()?, config.scheme);
     let listener = tokio::net::TcpListener::from_std(socket)?;
     let listener_stream = tokio_stream::wrappers::TcpListenerStream::new(listener);
+    let scheme = config.scheme;
+    Ok(listen(
+        listener_stream,
+        local_addr,
+        move |socket: &tokio::net::TcpStream| Ok(addr_to_url(socket.peer_addr()?, scheme)),
+        config,
+    ))
+}
 
+#[cfg(unix)]
+async fn websocket_bind_unix(
+    path: &Path,
+    mode: u32,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+)> {
+    use std::os::unix::fs::DirBuilderExt;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    // Replace a socket left behind by a listener which wasn't closed,
+    // but never anything else which happens to be at the path.
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if metadata.file_type().is_socket() {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    // Bind inside a directory only we can enter, and only move the socket
+    // to its path once its own permissions are set, so there is no
+    // moment when anyone else could connect to it.
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let private_dir = parent.join(format!(".{}", nanoid::nanoid!()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+    let bound = (|| -> std::io::Result<std::os::unix::net::UnixListener> {
+        let private_path = private_dir.join("socket");
+        let listener = std::os::unix::net::UnixListener::bind(&private_path)?;
+        std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(mode))?;
+        std::fs::rename(&private_path, path)?;
+        Ok(listener)
+    })();
+    std::fs::remove_dir(&private_dir).ok();
+    let listener = bound?;
+    listener.set_nonblocking(true)?;
+    let listener = tokio::net::UnixListener::from_std(listener)?;
+
+    let local_addr = path_to_url(path);
+    let listener_stream = tokio_stream::wrappers::UnixListenerStream::new(listener);
+    // Clients of a Unix domain socket are unnamed, so they are known by the
+    // path they connected to.
+    let remote_addr = local_addr.clone();
+    Ok(listen(
+        listener_stream,
+        local_addr,
+        move |_: &tokio::net::UnixStream| Ok(remote_addr.clone()),
+        config,
+    ))
+}
+
+/// Accept websocket connections over every raw socket from a bound listener.
+fn listen<S, F>(
+    listener_stream: impl futures::stream::Stream<Item = std::io::Result<S>> + Send + 'static,
+    local_addr: Url2,
+    remote_addr: F,
+    config: Arc<WebsocketConfig>,
+) -> (
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+)
+where
+    S: RawSocket,
+    F: Fn(&S) -> std::io::Result<Url2> + Send + 'static,
+{
     // Setup proper shutdown
     let (shutdown, valve) = Valve::new();
 
     let buffered_listener = listener_stream
         .map_err(WebsocketError::from)
-        .map_ok({
+        .and_then({
             let config = config.clone();
             let valve = valve.clone();
-            move |socket_result| connect(config.clone(), socket_result, valve.clone())
+            move |socket| {
+                let pair = remote_addr(&socket)
+                    .map(|remote_addr| connect(config.clone(), socket, remote_addr, valve.clone()))
+                    .map_err(WebsocketError::from);
+                futures::future::ready(pair)
+            }
         })
         .try_buffer_unordered(config.max_pending_connections);
     tracing::debug!(sever_listening_on = ?local_addr);
@@ -224,13 +333,14 @@ async fn websocket_bind(
         config,
         local_addr,
     };
-    Ok((listener_handle, stream))
+    (listener_handle, stream)
 }
 
 #[instrument(skip(config, socket, valve))]
-async fn connect(
+async fn connect<S: RawSocket>(
     config: Arc<WebsocketConfig>,
-    socket: tokio::net::TcpStream,
+    socket: S,
+    remote_addr: Url2,
     valve: Valve,
 ) -> WebsocketResult<Pair> {
     // TODO: find alternative to set the keepalive
@@ -239,7 +349,7 @@ async fn connect(
     // )))?;
     tracing::debug!(
         message = "accepted incoming raw socket",
-        remote_addr = %socket.peer_addr()?,
+        %remote_addr,
     );
     let socket = tokio_tungstenite::accept_async_with_config(
         socket,
@@ -253,5 +363,5 @@ async fn connect(
     .await
     .map_err(|e| Error::new(ErrorKind::Other, e))?;
 
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
 }
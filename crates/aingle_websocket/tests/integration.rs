@@ -3,6 +3,8 @@ use std::sync::Arc;
 use futures::StreamExt;
 use aingle_middleware_bytes::prelude::*;
 use aingle_websocket::connect;
+#[cfg(unix)]
+use aingle_websocket::connect_unix;
 use aingle_websocket::ListenerHandle;
 use aingle_websocket::ListenerItem;
 use aingle_websocket::WebsocketConfig;
@@ -452,3 +454,59 @@ async fn cancel_response() {
     c_jh.await.unwrap();
     s_jh.await.unwrap();
 }
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_request_over_unix_socket() {
+    use std::os::unix::fs::PermissionsExt;
+
+    observability::test_run().ok();
+    let path = std::env::temp_dir().join(format!("{}.sock", nanoid::nanoid!()));
+    let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode();
+    let (handle, mut listener) = WebsocketListener::bind_unix_with_handle(
+        &path,
+        0o600,
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await
+    .unwrap();
+
+    // - Only the owner can connect
+    assert_eq!(mode(&path) & 0o777, 0o600);
+
+    let jh = tokio::task::spawn(async move {
+        let (_, mut receiver) = listener.next().await.unwrap().unwrap();
+        let (msg, resp) = receiver.next().await.unwrap();
+        let msg: TestString = msg.try_into().unwrap();
+        assert_eq!(msg.0, "Hey from client");
+        resp.respond(TestString("Bye from server".into()).try_into().unwrap())
+            .await
+            .unwrap();
+    });
+
+    let (mut sender, _) = connect_unix(&path, Arc::new(WebsocketConfig::default()))
+        .instrument(tracing::debug_span!("client"))
+        .await
+        .unwrap();
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "Bye from server");
+
+    jh.await.unwrap();
+    handle.close();
+
+    // - A stale socket is replaced when binding again,
+    //   with the permissions asked for
+    let (handle, _listener) = WebsocketListener::bind_unix_with_handle(
+        &path,
+        0o660,
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(mode(&path) & 0o777, 0o660);
+    handle.close();
+    std::fs::remove_file(&path).unwrap();
+}
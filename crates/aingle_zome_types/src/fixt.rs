@@ -683,6 +683,7 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limit: None,
     };
 
     curve Unpredictable SafDef {
@@ -698,6 +699,7 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Unpredictable, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limit: None,
     };
 
     curve Predictable SafDef {
@@ -713,5 +715,6 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Predictable, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limit: None,
     };
 );
@@ -331,7 +331,7 @@ pub struct DeleteLink {
 }
 
 /// When migrating to a new version of a SAF, this header is committed to the
-/// new chain to declare the migration path taken. **Currently unused**
+/// new chain to declare the migration path taken.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
 pub struct OpenChain {
     pub author: AgentPubKey,
@@ -343,7 +343,7 @@ pub struct OpenChain {
 }
 
 /// When migrating to a new version of a SAF, this header is committed to the
-/// old chain to declare the migration path taken. **Currently unused**
+/// old chain to declare the migration path taken.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
 pub struct CloseChain {
     pub author: AgentPubKey,
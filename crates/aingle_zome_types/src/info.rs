@@ -1,8 +1,13 @@
+use crate::capability::CapGrant;
+use crate::cell::CellId;
 use crate::header::ZomeId;
+use crate::zome::FunctionName;
 use crate::zome::ZomeName;
 use ai_hash::AgentPubKey;
+use ai_hash::HeaderHash;
 use ai_hash::SafHash;
 use aingle_middleware_bytes::prelude::*;
+use std::collections::BTreeMap;
 
 /// The properties of the current saf/zome being called.
 #[allow(missing_docs)]
@@ -56,11 +61,35 @@ impl AgentInfo {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AppInfo;
+/// Information about the installed app the current cell belongs to.
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq)]
+pub struct AppInfo {
+    /// The id the app was installed with.
+    pub installed_app_id: String,
+    /// The provisioned cells of the app, keyed by slot id.
+    pub cells: BTreeMap<String, CellId>,
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SafInfo;
+/// The properties of the current saf.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq)]
+pub struct SafInfo {
+    pub name: String,
+    pub hash: SafHash,
+    pub properties: SerializedBytes,
+    pub uid: String,
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CallInfo;
+/// Information about the current call.
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq)]
+pub struct CallInfo {
+    /// The agent that made the call.
+    /// For callbacks invoked by the host this is the current agent.
+    pub provenance: AgentPubKey,
+    /// The name of the function being called.
+    pub function_name: FunctionName,
+    /// The chain head hash and sequence number as the call sees them.
+    pub as_at: (HeaderHash, u32),
+    /// The capability grant that authorized the call.
+    pub cap_grant: CapGrant,
+}
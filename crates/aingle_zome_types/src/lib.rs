@@ -43,6 +43,7 @@ pub mod post_commit;
 pub mod prelude;
 pub mod query;
 pub mod request;
+pub mod schedule;
 pub mod signal;
 pub mod signature;
 pub mod timestamp;
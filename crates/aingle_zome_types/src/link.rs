@@ -1,4 +1,5 @@
 use crate::element::SignedHeaderHashed;
+use ai_hash::AgentPubKey;
 use ai_hash::HeaderHash;
 use aingle_middleware_bytes::prelude::*;
 
@@ -99,6 +100,10 @@ impl CreateLinkInput {
 pub struct GetLinksInput {
     pub base_address: ai_hash::EntryHash,
     pub tag_prefix: Option<crate::link::LinkTag>,
+    /// Narrows down and pages through the links returned by `get_links`.
+    /// `get_link_details` ignores this filter.
+    #[serde(default)]
+    pub filter: GetLinksFilter,
 }
 
 impl GetLinksInput {
@@ -109,6 +114,172 @@ impl GetLinksInput {
         Self {
             base_address,
             tag_prefix,
+            filter: GetLinksFilter::default(),
+        }
+    }
+
+    /// Only return the links which pass this filter.
+    pub fn with_filter(mut self, filter: GetLinksFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// The order links are returned in, by the time they were created.
+/// Links created at the same time are ordered by their create header hash.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LinkOrder {
+    /// Oldest links first.
+    Ascending,
+    /// Newest links first.
+    Descending,
+}
+
+impl Default for LinkOrder {
+    fn default() -> Self {
+        LinkOrder::Ascending
+    }
+}
+
+/// Marks a position in a list of links so the next page can pick up after it.
+/// Take it from the last link of a page with `LinkCursor::from(&link)`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LinkCursor {
+    pub timestamp: crate::Timestamp,
+    pub create_link_hash: HeaderHash,
+}
+
+impl LinkCursor {
+    fn key(&self) -> (crate::Timestamp, &HeaderHash) {
+        (self.timestamp, &self.create_link_hash)
+    }
+}
+
+impl From<&Link> for LinkCursor {
+    fn from(link: &Link) -> Self {
+        Self {
+            timestamp: link.timestamp,
+            create_link_hash: link.create_link_hash.clone(),
+        }
+    }
+}
+
+/// Filters, ordering and pagination for `get_links`.
+///
+/// The filter is sent along to the authorities for the base, so only the
+/// requested page of links is sent back over the network.
+///
+/// e.g. to page through the newest links 100 at a time:
+/// ```ignore
+/// let mut filter = GetLinksFilter::new().descending().limit(100);
+/// loop {
+///     let links = get_links_with_filter(base.clone(), None, filter.clone())?.into_inner();
+///     // ...
+///     match links.last() {
+///         Some(last) => filter = filter.cursor(last.into()),
+///         None => break,
+///     }
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct GetLinksFilter {
+    /// Only links created by this agent.
+    pub author: Option<AgentPubKey>,
+    /// Only links created at or after this time.
+    pub after: Option<crate::Timestamp>,
+    /// Only links created before this time.
+    pub before: Option<crate::Timestamp>,
+    /// The order the links are returned in.
+    pub order: LinkOrder,
+    /// Only links that come after this cursor in `order`.
+    pub cursor: Option<LinkCursor>,
+    /// Return at most this many links.
+    pub limit: Option<u32>,
+}
+
+impl GetLinksFilter {
+    /// A filter which lets every link through, oldest first.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only links created by this agent.
+    pub fn author(mut self, author: AgentPubKey) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Only links created at or after this time.
+    pub fn after(mut self, after: crate::Timestamp) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Only links created before this time.
+    pub fn before(mut self, before: crate::Timestamp) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// Return the newest links first.
+    pub fn descending(mut self) -> Self {
+        self.order = LinkOrder::Descending;
+        self
+    }
+
+    /// Only links that come after this cursor.
+    pub fn cursor(mut self, cursor: LinkCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Return at most this many links.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Does a link created by this author, at this time, with this create
+    /// header hash pass the author, time range and cursor filters?
+    /// The limit is applied separately, after sorting.
+    pub fn includes(
+        &self,
+        author: &AgentPubKey,
+        timestamp: crate::Timestamp,
+        create_link_hash: &HeaderHash,
+    ) -> bool {
+        self.author.as_ref().map_or(true, |a| a == author)
+            && self.after.map_or(true, |after| timestamp >= after)
+            && self.before.map_or(true, |before| timestamp < before)
+            && self.cursor.as_ref().map_or(true, |cursor| {
+                self.compare((timestamp, create_link_hash), cursor.key())
+                    == std::cmp::Ordering::Greater
+            })
+    }
+
+    /// Compare the positions of two links in this filter's order.
+    pub fn compare(
+        &self,
+        a: (crate::Timestamp, &HeaderHash),
+        b: (crate::Timestamp, &HeaderHash),
+    ) -> std::cmp::Ordering {
+        match self.order {
+            LinkOrder::Ascending => a.cmp(&b),
+            LinkOrder::Descending => b.cmp(&a),
+        }
+    }
+
+    /// Sort these links into this filter's order and cut them down to
+    /// the limit.
+    pub fn sort_and_limit(&self, links: &mut Vec<Link>) {
+        links.sort_by(|a, b| {
+            self.compare(
+                (a.timestamp, &a.create_link_hash),
+                (b.timestamp, &b.create_link_hash),
+            )
+        });
+        if let Some(limit) = self.limit {
+            links.truncate(limit as usize);
         }
     }
 }
@@ -164,3 +335,73 @@ impl rusqlite::ToSql for LinkTag {
         Ok(rusqlite::types::ToSqlOutput::Borrowed((&self.0[..]).into()))
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "fixturators")]
+mod tests {
+    use super::*;
+    use crate::fixt::*;
+    use crate::Timestamp;
+    use ::fixt::prelude::*;
+
+    fn link(secs: i64) -> Link {
+        Link {
+            target: fixt!(EntryHash),
+            timestamp: Timestamp(secs, 0),
+            tag: LinkTag::new(vec![]),
+            create_link_hash: fixt!(HeaderHash),
+        }
+    }
+
+    #[test]
+    fn get_links_filter_pages_through_links() {
+        let author = fixt!(AgentPubKey);
+        let links: Vec<Link> = (0..5).map(link).collect();
+        let page = |filter: &GetLinksFilter| {
+            let mut page: Vec<Link> = links
+                .iter()
+                .filter(|l| filter.includes(&author, l.timestamp, &l.create_link_hash))
+                .cloned()
+                .collect();
+            filter.sort_and_limit(&mut page);
+            page.into_iter().map(|l| l.timestamp.0).collect::<Vec<_>>()
+        };
+
+        let filter = GetLinksFilter::new().limit(2);
+        assert_eq!(page(&filter), vec![0, 1]);
+        let filter = filter.cursor((&links[1]).into());
+        assert_eq!(page(&filter), vec![2, 3]);
+
+        let filter = GetLinksFilter::new().descending().limit(2);
+        assert_eq!(page(&filter), vec![4, 3]);
+        let filter = filter.cursor((&links[3]).into());
+        assert_eq!(page(&filter), vec![2, 1]);
+
+        let filter = GetLinksFilter::new()
+            .after(Timestamp(1, 0))
+            .before(Timestamp(3, 0));
+        assert_eq!(page(&filter), vec![1, 2]);
+
+        let filter = GetLinksFilter::new().author(fixt!(AgentPubKey));
+        assert!(page(&filter).is_empty());
+        let filter = GetLinksFilter::new().author(author.clone());
+        assert_eq!(page(&filter).len(), 5);
+    }
+
+    #[test]
+    fn get_links_input_without_filter_decodes() {
+        #[derive(Serialize)]
+        struct OldGetLinksInput {
+            base_address: ai_hash::EntryHash,
+            tag_prefix: Option<LinkTag>,
+        }
+        let base_address = fixt!(EntryHash);
+        let old = aingle_middleware_bytes::encode(&OldGetLinksInput {
+            base_address: base_address.clone(),
+            tag_prefix: None,
+        })
+        .unwrap();
+        let input: GetLinksInput = aingle_middleware_bytes::decode(&old).unwrap();
+        assert_eq!(input, GetLinksInput::new(base_address, None));
+    }
+}
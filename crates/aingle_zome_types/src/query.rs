@@ -3,30 +3,164 @@
 use crate::header::EntryType;
 use crate::header::Header;
 use crate::header::HeaderType;
-use crate::warrant::Warrant;
+use crate::warrant::SignedWarrant;
+use ai_hash::EntryHash;
 use ai_hash::HeaderHash;
 pub use aingle_middleware_bytes::prelude::*;
+use std::collections::HashSet;
 
 /// Query arguments
 #[derive(
     serde::Serialize, serde::Deserialize, SerializedBytes, Default, PartialEq, Clone, Debug,
 )]
 #[non_exhaustive]
+#[serde(from = "ChainQueryFilterDe")]
 pub struct ChainQueryFilter {
     /// The range of source chain sequence numbers to match.
-    /// Inclusive start, exclusive end.
-    // TODO: can we generalize this over RangeBounds to allow unbounded ranges?
-    pub sequence_range: Option<std::ops::Range<u32>>,
-    /// Filter by EntryType
+    pub sequence_range: SequenceRange,
+    /// Filter by EntryType, matching any of these types
     // NB: if this filter is set, you can't verify the results, so don't
     //     use this in validation
-    pub entry_type: Option<EntryType>,
-    /// Filter by HeaderType
+    pub entry_types: Option<Vec<EntryType>>,
+    /// Filter by the hash of the entry the header creates or updates,
+    /// matching any of these hashes
     // NB: if this filter is set, you can't verify the results, so don't
     //     use this in validation
-    pub header_type: Option<HeaderType>,
+    pub entry_hashes: Option<HashSet<EntryHash>>,
+    /// Filter by HeaderType, matching any of these types
+    // NB: if this filter is set, you can't verify the results, so don't
+    //     use this in validation
+    pub header_types: Option<Vec<HeaderType>>,
     /// Include the entries in the elements
     pub include_entries: bool,
+    /// Return the newest headers first instead of the oldest
+    pub order_descending: bool,
+    /// Return at most this many elements
+    pub limit: Option<u32>,
+}
+
+/// What a [ChainQueryFilter] is decoded from.
+/// Fields added since the first version default when they're missing,
+/// and the single `entry_type` and `header_type` filters of the first
+/// version are read into the type lists, so filters serialized by older
+/// zomes still decode.
+#[derive(serde::Deserialize)]
+struct ChainQueryFilterDe {
+    #[serde(default, deserialize_with = "deserialize_sequence_range")]
+    sequence_range: SequenceRange,
+    #[serde(default)]
+    entry_types: Option<Vec<EntryType>>,
+    #[serde(default)]
+    entry_type: Option<EntryType>,
+    #[serde(default)]
+    entry_hashes: Option<HashSet<EntryHash>>,
+    #[serde(default)]
+    header_types: Option<Vec<HeaderType>>,
+    #[serde(default)]
+    header_type: Option<HeaderType>,
+    include_entries: bool,
+    #[serde(default)]
+    order_descending: bool,
+    #[serde(default)]
+    limit: Option<u32>,
+}
+
+impl From<ChainQueryFilterDe> for ChainQueryFilter {
+    fn from(de: ChainQueryFilterDe) -> Self {
+        Self {
+            sequence_range: de.sequence_range,
+            entry_types: de.entry_types.or_else(|| de.entry_type.map(|t| vec![t])),
+            entry_hashes: de.entry_hashes,
+            header_types: de.header_types.or_else(|| de.header_type.map(|t| vec![t])),
+            include_entries: de.include_entries,
+            order_descending: de.order_descending,
+            limit: de.limit,
+        }
+    }
+}
+
+/// The sequence range used to be optional, with `None` matching everything.
+fn deserialize_sequence_range<'de, D>(deserializer: D) -> Result<SequenceRange, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+    Ok(Option::<SequenceRange>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// A range of source chain sequence numbers, either end of which may be open.
+/// Build one from any of the std range types, e.g. `3..`, `..10` or `3..=9`.
+#[derive(
+    serde::Serialize, serde::Deserialize, Default, PartialEq, Eq, Hash, Clone, Copy, Debug,
+)]
+pub struct SequenceRange {
+    /// The lowest sequence number to match, inclusive.
+    pub start: Option<u32>,
+    /// The sequence number to stop before, exclusive.
+    pub end: Option<u32>,
+}
+
+impl SequenceRange {
+    /// A range which matches every sequence number
+    pub fn unbounded() -> Self {
+        Self::default()
+    }
+
+    /// Is this sequence number inside the range?
+    pub fn contains(&self, seq: u32) -> bool {
+        self.start.map_or(true, |start| seq >= start) && self.end.map_or(true, |end| seq < end)
+    }
+}
+
+impl From<std::ops::Range<u32>> for SequenceRange {
+    fn from(range: std::ops::Range<u32>) -> Self {
+        Self {
+            start: Some(range.start),
+            end: Some(range.end),
+        }
+    }
+}
+
+impl From<std::ops::RangeInclusive<u32>> for SequenceRange {
+    fn from(range: std::ops::RangeInclusive<u32>) -> Self {
+        Self {
+            start: Some(*range.start()),
+            end: range.end().checked_add(1),
+        }
+    }
+}
+
+impl From<std::ops::RangeFrom<u32>> for SequenceRange {
+    fn from(range: std::ops::RangeFrom<u32>) -> Self {
+        Self {
+            start: Some(range.start),
+            end: None,
+        }
+    }
+}
+
+impl From<std::ops::RangeTo<u32>> for SequenceRange {
+    fn from(range: std::ops::RangeTo<u32>) -> Self {
+        Self {
+            start: None,
+            end: Some(range.end),
+        }
+    }
+}
+
+impl From<std::ops::RangeToInclusive<u32>> for SequenceRange {
+    fn from(range: std::ops::RangeToInclusive<u32>) -> Self {
+        Self {
+            start: None,
+            end: range.end.checked_add(1),
+        }
+    }
+}
+
+impl From<std::ops::RangeFull> for SequenceRange {
+    fn from(_: std::ops::RangeFull) -> Self {
+        Self::unbounded()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -41,9 +175,9 @@ pub struct AgentActivity {
     /// The highest chain header that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
-    /// Warrants about this AgentActivity.
-    /// Placeholder for future.
-    pub warrants: Vec<Warrant>,
+    /// Warrants issued against the author of this activity
+    /// by authorities that found their ops to be invalid.
+    pub warrants: Vec<SignedWarrant>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -117,6 +251,20 @@ pub struct ChainFork {
     pub second_header: HeaderHash,
 }
 
+impl ChainFork {
+    /// Create a fork from two headers at the same sequence position.
+    /// The headers are ordered by hash so every authority reports the
+    /// same fork no matter which header it saw first.
+    pub fn new(fork_seq: u32, a: HeaderHash, b: HeaderHash) -> Self {
+        let (first_header, second_header) = if a <= b { (a, b) } else { (b, a) };
+        Self {
+            fork_seq,
+            first_header,
+            second_header,
+        }
+    }
+}
+
 impl ChainQueryFilter {
     /// Create a no-op ChainQueryFilter which returns everything
     pub fn new() -> Self {
@@ -126,21 +274,45 @@ impl ChainQueryFilter {
         }
     }
 
-    /// Filter on sequence range
-    pub fn sequence_range(mut self, sequence_range: std::ops::Range<u32>) -> Self {
-        self.sequence_range = Some(sequence_range);
+    /// Filter on sequence range, which may be open at either end
+    pub fn sequence_range(mut self, sequence_range: impl Into<SequenceRange>) -> Self {
+        self.sequence_range = sequence_range.into();
         self
     }
 
-    /// Filter on entry type
+    /// Filter on entry type.
+    /// Call more than once to match any of several entry types.
     pub fn entry_type(mut self, entry_type: EntryType) -> Self {
-        self.entry_type = Some(entry_type);
+        self.entry_types
+            .get_or_insert_with(Vec::new)
+            .push(entry_type);
         self
     }
 
-    /// Filter on header type
+    /// Filter on the hashes of the entries the headers create or update
+    pub fn entry_hashes(mut self, entry_hashes: HashSet<EntryHash>) -> Self {
+        self.entry_hashes = Some(entry_hashes);
+        self
+    }
+
+    /// Filter on header type.
+    /// Call more than once to match any of several header types.
     pub fn header_type(mut self, header_type: HeaderType) -> Self {
-        self.header_type = Some(header_type);
+        self.header_types
+            .get_or_insert_with(Vec::new)
+            .push(header_type);
+        self
+    }
+
+    /// Return the newest headers first
+    pub fn descending(mut self) -> Self {
+        self.order_descending = true;
+        self
+    }
+
+    /// Return at most this many elements
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
         self
     }
 
@@ -150,29 +322,36 @@ impl ChainQueryFilter {
         self
     }
 
-    /// Perform the boolean check which this filter represents
+    /// Perform the boolean check which this filter represents.
+    /// The order and limit aren't part of this check.
     pub fn check(&self, header: &Header) -> bool {
-        let check_range = self
-            .sequence_range
-            .as_ref()
-            .map(|range| range.contains(&header.header_seq()))
-            .unwrap_or(true);
+        let check_range = self.sequence_range.contains(header.header_seq());
         let check_header_type = self
-            .header_type
+            .header_types
             .as_ref()
-            .map(|header_type| header.header_type() == *header_type)
+            .map(|header_types| header_types.contains(&header.header_type()))
             .unwrap_or(true);
         let check_entry_type = self
-            .entry_type
+            .entry_types
             .as_ref()
-            .map(|entry_type| {
+            .map(|entry_types| {
                 header
                     .entry_type()
-                    .map(|header_entry_type| *header_entry_type == *entry_type)
+                    .map(|header_entry_type| entry_types.contains(header_entry_type))
                     .unwrap_or(false)
             })
             .unwrap_or(true);
-        check_range && check_header_type && check_entry_type
+        let check_entry_hash = self
+            .entry_hashes
+            .as_ref()
+            .map(|entry_hashes| {
+                header
+                    .entry_hash()
+                    .map(|entry_hash| entry_hashes.contains(entry_hash))
+                    .unwrap_or(false)
+            })
+            .unwrap_or(true);
+        check_range && check_header_type && check_entry_type && check_entry_hash
     }
 }
 
@@ -298,6 +477,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn filter_by_open_chain_sequence() {
+        let headers = fixtures();
+
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().sequence_range(3..), &headers),
+            [false, false, false, true, true, true].to_vec()
+        );
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().sequence_range(..2), &headers),
+            [true, true, false, false, false, false].to_vec()
+        );
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().sequence_range(1..=2), &headers),
+            [false, true, true, false, false, false].to_vec()
+        );
+        assert_eq!(
+            map_query(
+                &ChainQueryFilter::new().sequence_range(..=u32::MAX),
+                &headers
+            ),
+            [true; 6].to_vec()
+        );
+    }
+
+    #[test]
+    fn filter_by_several_types() {
+        let headers = fixtures();
+
+        let query = ChainQueryFilter::new()
+            .header_type(headers[1].header_type())
+            .header_type(headers[2].header_type());
+        assert_eq!(
+            map_query(&query, &headers),
+            [false, true, true, false, true, true].to_vec()
+        );
+
+        let query = ChainQueryFilter::new()
+            .entry_type(headers[0].entry_type().unwrap().clone())
+            .entry_type(headers[1].entry_type().unwrap().clone());
+        assert_eq!(
+            map_query(&query, &headers),
+            [true, true, false, true, true, false].to_vec()
+        );
+    }
+
+    #[test]
+    fn filter_by_entry_hashes() {
+        let headers = fixtures();
+
+        let entry_hashes = vec![headers[0].entry_hash().unwrap().clone()]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            map_query(
+                &ChainQueryFilter::new().entry_hashes(entry_hashes),
+                &headers
+            ),
+            [true, false, false, false, false, false].to_vec()
+        );
+    }
+
     #[test]
     fn filter_by_multi() {
         let headers = fixtures();
@@ -334,4 +575,49 @@ mod tests {
             [true, false, false, false, true, false].to_vec()
         );
     }
+
+    #[test]
+    fn filter_from_before_type_sets_decodes() {
+        #[derive(serde::Serialize)]
+        struct OldChainQueryFilter {
+            sequence_range: Option<std::ops::Range<u32>>,
+            entry_type: Option<EntryType>,
+            header_type: Option<crate::header::HeaderType>,
+            include_entries: bool,
+        }
+        let decode = |old: OldChainQueryFilter| -> ChainQueryFilter {
+            aingle_middleware_bytes::decode(&aingle_middleware_bytes::encode(&old).unwrap())
+                .unwrap()
+        };
+
+        let filter = decode(OldChainQueryFilter {
+            sequence_range: None,
+            entry_type: None,
+            header_type: None,
+            include_entries: true,
+        });
+        assert_eq!(filter, ChainQueryFilter::new().include_entries(true));
+
+        let filter = decode(OldChainQueryFilter {
+            sequence_range: Some(2..5),
+            entry_type: None,
+            header_type: None,
+            include_entries: false,
+        });
+        assert_eq!(filter, ChainQueryFilter::new().sequence_range(2..5));
+
+        let entry_type = EntryType::App(fixt!(AppEntryType));
+        let filter = decode(OldChainQueryFilter {
+            sequence_range: None,
+            entry_type: Some(entry_type.clone()),
+            header_type: Some(crate::header::HeaderType::Create),
+            include_entries: false,
+        });
+        assert_eq!(
+            filter,
+            ChainQueryFilter::new()
+                .entry_type(entry_type)
+                .header_type(crate::header::HeaderType::Create)
+        );
+    }
 }
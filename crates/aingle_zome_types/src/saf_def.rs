@@ -40,6 +40,26 @@ pub struct SafDef {
 
     /// An array of zomes associated with your SAF.
     pub zomes: Zomes,
+
+    /// Limits how quickly an author can publish headers to this SAF.
+    /// This is skipped when serializing if there is no limit so
+    /// existing SAFs keep the same hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "full-saf-def", builder(default))]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// How many headers an author can publish to a SAF within a window of time.
+/// Agent activity authorities reject any header that takes the author
+/// over this limit.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct RateLimit {
+    /// The most headers an author can publish in any window.
+    /// A limit of 0 is treated as no limit.
+    pub max_headers: u32,
+    /// The length of the window in milliseconds.
+    pub window_ms: u64,
 }
 
 #[cfg(feature = "test_utils")]
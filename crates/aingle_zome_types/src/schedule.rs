@@ -0,0 +1,49 @@
+//! Types for scheduling zome functions to be run by the host in the future.
+
+use crate::zome::FunctionName;
+use core::time::Duration;
+
+/// Input to the `schedule` host function.
+///
+/// Asks the host to call the extern `fn_name`, in the same zome that is
+/// making the request, once `execute_after` has elapsed.
+/// If `repeat_every` is set then the function will continue to be called at
+/// that interval until it is rescheduled.
+///
+/// The scheduled function is called by the host as the agent of the cell with
+/// `()` as its input.
+/// Scheduling a function that is already scheduled replaces the existing
+/// schedule, so it is always safe to (re)schedule in `init`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Schedule {
+    /// The extern to call in the current zome.
+    pub fn_name: FunctionName,
+    /// How long to wait before the first call.
+    pub execute_after: Duration,
+    /// The interval between calls for recurring schedules.
+    pub repeat_every: Option<Duration>,
+}
+
+impl Schedule {
+    /// Call `fn_name` once after `execute_after`.
+    pub fn once<F: Into<FunctionName>>(fn_name: F, execute_after: Duration) -> Self {
+        Self {
+            fn_name: fn_name.into(),
+            execute_after,
+            repeat_every: None,
+        }
+    }
+
+    /// Call `fn_name` after `execute_after` and then every `repeat_every`.
+    pub fn recurring<F: Into<FunctionName>>(
+        fn_name: F,
+        execute_after: Duration,
+        repeat_every: Duration,
+    ) -> Self {
+        Self {
+            fn_name: fn_name.into(),
+            execute_after,
+            repeat_every: Some(repeat_every),
+        }
+    }
+}
@@ -1,6 +1,80 @@
 //! Types for warrants
+//!
+//! A warrant is proof, made by a validating authority, that an agent authored
+//! an op which failed validation. Warrants are published to the agent activity
+//! authorities of the offending agent so that anyone asking about that agent's
+//! activity can see them.
+use crate::signature::Signature;
+use crate::timestamp::Timestamp;
+use ai_hash::AgentPubKey;
+use ai_hash::HeaderHash;
+use ai_hash::SgdOpHash;
 pub use aingle_middleware_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+/// The content of a warrant, which is signed by the warrantor.
+pub struct Warrant {
+    /// The agent that authored the invalid op.
+    pub author: AgentPubKey,
+    /// The header of the invalid op.
+    pub header: HeaderHash,
+    /// The invalid op.
+    pub op: SgdOpHash,
+    /// Why the op failed validation.
+    pub reason: WarrantReason,
+    /// The authority that validated the op and issued this warrant.
+    pub warrantor: AgentPubKey,
+    /// When this warrant was issued.
+    pub timestamp: Timestamp,
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+/// The validation outcome that led to a warrant being issued.
+pub enum WarrantReason {
+    /// The op was rejected by system validation.
+    SysValidation(String),
+    /// The op was rejected by the app's validation callbacks.
+    AppValidation(String),
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+/// A [`Warrant`] with the signature of its warrantor.
+pub struct SignedWarrant {
+    /// The content of the warrant.
+    pub warrant: Warrant,
+    /// The warrantor's signature of the warrant.
+    pub signature: Signature,
+}
+
+impl SignedWarrant {
+    /// Pair a warrant with its warrantor's signature.
+    pub fn new(warrant: Warrant, signature: Signature) -> Self {
+        Self { warrant, signature }
+    }
+
+    /// The content of the warrant.
+    pub fn warrant(&self) -> &Warrant {
+        &self.warrant
+    }
+
+    /// The warrantor's signature of the warrant.
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// The agent that authored the invalid op.
+    pub fn author(&self) -> &AgentPubKey {
+        &self.warrant.author
+    }
+
+    /// The authority that issued this warrant.
+    pub fn warrantor(&self) -> &AgentPubKey {
+        &self.warrant.warrantor
+    }
+}
@@ -77,6 +77,11 @@ impl InlineZome {
         }
     }
 
+    /// Whether the zome has a function or callback with this name
+    pub fn has_callback(&self, name: &FunctionName) -> bool {
+        self.callbacks.contains_key(name)
+    }
+
     /// Accessor
     pub fn uuid(&self) -> String {
         self.uuid.clone()
@@ -84,13 +84,13 @@ wasm_io_types! {
     // Info about the calling agent.
     fn agent_info (()) -> zt::info::AgentInfo;
 
-    // @todo
+    // Info about the installed app the cell belongs to.
     fn app_info (()) -> zt::info::AppInfo;
 
-    // @todo
+    // Info about the current saf.
     fn saf_info (()) -> zt::info::SafInfo;
 
-    // @todo
+    // Info about the current call.
     fn call_info (()) -> zt::info::CallInfo;
 
     // Header hash of the DeleteLink element.
@@ -98,14 +98,14 @@ wasm_io_types! {
 
     fn call (zt::call::Call) -> zt::ZomeCallResponse;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List all the local capability claims.
+    fn capability_claims (()) -> Vec<zt::capability::CapClaim>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List all the live local capability grants.
+    fn capability_grants (()) -> Vec<zt::capability::ZomeCallCapGrant>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the capability grant for the current zome call.
+    fn capability_info (()) -> zt::capability::CapGrant;
 
     // Returns HeaderHash of the newly created element.
     fn create (zt::entry::EntryWithDefId) -> ai_hash::HeaderHash;
@@ -138,6 +138,9 @@ wasm_io_types! {
     // Get links by entry hash from the cascade.
     fn get_links (zt::link::GetLinksInput) -> zt::link::Links;
 
+    // Count the live links by entry hash from the cascade.
+    fn count_links (zt::link::GetLinksInput) -> usize;
+
     // Attempt to get a live entry from the cascade.
     fn get (zt::entry::GetInput) -> Option<zt::element::Element>;
 
@@ -156,10 +159,10 @@ wasm_io_types! {
     // // @todo
     // fn send (()) -> ();
 
-    // @todo
-    fn schedule (core::time::Duration) -> ();
+    // Schedule a zome function in the current zome to be called in the future.
+    fn schedule (zt::schedule::Schedule) -> ();
 
-    // @todo
+    // Suspend the zome call for a duration.
     fn sleep (core::time::Duration) -> ();
 
     // @todo
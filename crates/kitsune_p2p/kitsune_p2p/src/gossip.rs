@@ -1 +1,71 @@
+use crate::agent_store::AgentInfoSigned;
+use crate::types::gossip::*;
+use crate::types::*;
+use kitsune_p2p_types::tx2::tx2_api::*;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+use std::sync::Arc;
+
+mod common;
+pub mod sharded_range;
 pub mod simple_bloom;
+
+/// The gossip strategies a space can be configured with through
+/// [`KitsuneP2pTuningParams::gossip_strategy`](kitsune_p2p_types::config::KitsuneP2pTuningParams).
+/// Returns `None` if the strategy is unknown.
+pub fn factory(strategy: &str) -> Option<GossipModuleFactory> {
+    match strategy {
+        "simple-bloom" => Some(simple_bloom::factory()),
+        "sharded-range" => Some(sharded_range::factory()),
+        _ => None,
+    }
+}
+
+/// max send buffer size (keep it under 16384 with a little room for overhead)
+/// (this is not a tuning_param because it must be coordinated
+/// with the constant in PoolBuf which cannot be set at runtime)
+const MAX_SEND_BUF_BYTES: usize = 16000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum MetaOpKey {
+    /// data key type
+    Op(Arc<KitsuneOpHash>),
+
+    /// agent key type
+    Agent(Arc<KitsuneAgent>, u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum MetaOpData {
+    /// data chunk type
+    Op(Arc<KitsuneOpHash>, Vec<u8>),
+
+    /// agent chunk type
+    Agent(AgentInfoSigned),
+}
+
+impl MetaOpData {
+    fn byte_count(&self) -> usize {
+        match self {
+            MetaOpData::Op(h, d) => (**h).len() + d.len(),
+            MetaOpData::Agent(a) => {
+                let h = (**a.agent).len();
+                let s = (**a.signature).len();
+                let d = a.encoded_bytes.len();
+                h + s + d
+            }
+        }
+    }
+
+    fn key(&self) -> Arc<MetaOpKey> {
+        let key = match self {
+            MetaOpData::Op(key, _) => MetaOpKey::Op(key.clone()),
+            MetaOpData::Agent(s) => MetaOpKey::Agent(s.agent.clone(), s.signed_at_ms),
+        };
+        Arc::new(key)
+    }
+}
+
+pub(crate) enum HowToConnect {
+    Con(Tx2ConHnd<wire::Wire>),
+    Url(TxUrl),
+}
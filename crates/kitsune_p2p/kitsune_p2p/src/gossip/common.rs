@@ -0,0 +1,755 @@
+//! The parts of gossip which every strategy shares.
+//!
+//! Each strategy runs the same loop: check whether a new round is due,
+//! sync the data held by our local agents, initiate with a remote node we
+//! haven't spoken to recently, send / receive gossip messages until there
+//! are none left, and record metrics for the nodes we spoke to.
+//! A [`GossipStrategy`] only provides its local sync, the message it
+//! initiates with and the handling of its incoming messages.
+
+use super::{HowToConnect, MetaOpData, MetaOpKey, MAX_SEND_BUF_BYTES};
+use crate::agent_store::AgentInfoSigned;
+use crate::event::MetricQuery;
+use crate::event::MetricQueryAnswer;
+use crate::types::event::*;
+use crate::types::gossip::*;
+use crate::types::*;
+use ghost_actor::dependencies::must_future::MustBoxFuture;
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_types::codec::*;
+use kitsune_p2p_types::config::*;
+use kitsune_p2p_types::metrics::*;
+use kitsune_p2p_types::tx2::tx2_api::*;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+use kitsune_p2p_types::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+pub(crate) type KeySet = HashSet<Arc<MetaOpKey>>;
+pub(crate) type DataMap = HashMap<Arc<MetaOpKey>, Arc<MetaOpData>>;
+
+/// Split op data into chunks which each fit within a single gossip message.
+pub(crate) fn chunk_data(data: Vec<Arc<MetaOpData>>) -> Vec<Vec<Arc<MetaOpData>>> {
+    let mut out = Vec::new();
+    let mut chunk = Vec::new();
+    let mut cur_size = 0;
+    for data in data {
+        let size = data.byte_count();
+        if !chunk.is_empty() && cur_size + size > MAX_SEND_BUF_BYTES {
+            out.push(std::mem::take(&mut chunk));
+            cur_size = 0;
+        }
+        cur_size += size;
+        chunk.push(data);
+    }
+    if !chunk.is_empty() {
+        out.push(chunk);
+    }
+    out
+}
+
+struct NodeInfo {
+    last_touch: std::time::SystemTime,
+    was_err: bool,
+}
+
+enum GossipIterationResult {
+    Close,
+    Good,
+}
+
+enum CheckResult {
+    Close,
+    NotReady,
+    SyncAndInitiate,
+    SkipSyncAndInitiate,
+}
+
+/// A gossip strategy, run by the gossip loop of a [`GossipMod`].
+pub(crate) trait GossipStrategy: 'static + Send + Sized {
+    /// The wire protocol codec of this strategy.
+    type Wire: 'static + Send + Codec;
+
+    /// The state of this strategy before the first local sync.
+    fn new() -> Self;
+
+    /// The data map holding every agent info we know about,
+    /// as of the last local sync.
+    fn agent_infos(&self) -> &DataMap;
+
+    /// Whether it's worth initiating gossip with this remote agent.
+    fn is_gossip_peer(&self, _agent_info: &AgentInfoSigned) -> bool {
+        true
+    }
+
+    /// The message initiating a round of gossip with a remote node.
+    fn initiate(&self, local_agents: &HashSet<Arc<KitsuneAgent>>) -> Self::Wire;
+
+    /// Sync the data held by our local agents.
+    /// Resolves to false if this round of gossip should be skipped.
+    fn local_sync(gossip: &GossipMod<Self>) -> MustBoxFuture<'_, KitsuneResult<bool>>;
+
+    /// Handle a gossip message from a remote node.
+    fn incoming(
+        gossip: &GossipMod<Self>,
+        con: Tx2ConHnd<wire::Wire>,
+        msg: Self::Wire,
+    ) -> MustBoxFuture<'_, KitsuneResult<()>>;
+
+    /// Forget anything kept for a local agent which has left the space.
+    fn local_agent_leave(&mut self, _agent: &Arc<KitsuneAgent>) {}
+}
+
+pub(crate) struct GossipModInner<S: GossipStrategy> {
+    pub(crate) local_agents: HashSet<Arc<KitsuneAgent>>,
+
+    /// Metrics to be recorded at the end of this round of gossip
+    pending_metrics: Vec<(Vec<Arc<KitsuneAgent>>, NodeInfo)>,
+
+    last_initiate_check_us: ProcCountMicros,
+    initiate_tgt: Option<GossipTgt>,
+
+    incoming: Vec<(Tx2ConHnd<wire::Wire>, S::Wire)>,
+
+    last_outgoing_us: ProcCountMicros,
+    outgoing: Vec<(GossipTgt, HowToConnect, S::Wire)>,
+
+    /// The state of the gossip strategy
+    pub(crate) strategy: S,
+}
+
+impl<S: GossipStrategy> GossipModInner<S> {
+    fn new() -> Self {
+        // pick an old instant for initialization
+        const ONE_DAY_MICROS: i64 = 1000 * 1000 * 60 * 60 * 24;
+        let old_us = proc_count_now_us() - ONE_DAY_MICROS;
+
+        Self {
+            local_agents: HashSet::new(),
+
+            pending_metrics: Vec::new(),
+
+            last_initiate_check_us: old_us,
+            initiate_tgt: None,
+
+            incoming: Vec::new(),
+
+            last_outgoing_us: old_us,
+            outgoing: Vec::new(),
+
+            strategy: S::new(),
+        }
+    }
+
+    /// Record a metric to be recorded at the end of this gossip round
+    // TODO: remove NodeInfo
+    fn record_pending_metric(&mut self, agents: Vec<Arc<KitsuneAgent>>, was_err: bool) {
+        let info = NodeInfo {
+            last_touch: std::time::SystemTime::now(),
+            was_err,
+        };
+        self.pending_metrics.push((agents, info))
+    }
+
+    /// Queue a gossip message to a remote node we are connected to.
+    pub(crate) fn queue(
+        &mut self,
+        con: &Tx2ConHnd<wire::Wire>,
+        remote_agents: &[Arc<KitsuneAgent>],
+        gossip: S::Wire,
+    ) {
+        let endpoint = GossipTgt::new(remote_agents.to_vec(), con.peer_cert());
+        self.outgoing
+            .push((endpoint, HowToConnect::Con(con.clone()), gossip));
+    }
+
+    /// If we initiated gossip with the node on this connection,
+    /// mark it as done.
+    pub(crate) fn finish_initiate(&mut self, peer_cert: &Tx2Cert) {
+        if let Some(tgt) = &self.initiate_tgt {
+            if tgt.cert() == peer_cert {
+                self.initiate_tgt = None;
+            }
+        }
+    }
+}
+
+pub(crate) struct GossipMod<S: GossipStrategy> {
+    tuning_params: KitsuneP2pTuningParams,
+    send_interval_ms: u64,
+    pub(crate) space: Arc<KitsuneSpace>,
+    ep_hnd: Tx2EpHnd<wire::Wire>,
+    pub(crate) evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
+    pub(crate) inner: Share<GossipModInner<S>>,
+}
+
+impl<S: GossipStrategy> GossipMod<S> {
+    pub fn new(
+        tuning_params: KitsuneP2pTuningParams,
+        space: Arc<KitsuneSpace>,
+        ep_hnd: Tx2EpHnd<wire::Wire>,
+        evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
+    ) -> Arc<Self> {
+        let inner = GossipModInner::new();
+
+        let send_interval_ms: u64 = (
+            // !*)&^$# cargo fmt...
+            16384.0    // max bytes in a gossip msg
+            * 8.0      // bits per byte
+            * 1000.0   // milliseconds
+            / 1024.0   // kbps
+            / 1024.0   // mbps
+            / tuning_params.gossip_output_target_mbps
+        ) as u64;
+
+        let this = Arc::new(Self {
+            tuning_params,
+            space,
+            ep_hnd,
+            send_interval_ms,
+            evt_sender,
+            inner: Share::new(inner),
+        });
+
+        // this value needs to be somewhat frequent to support send timing
+        let loop_check_interval_ms = std::cmp::max(send_interval_ms / 3, 100);
+
+        let gossip = this.clone();
+        metric_task(async move {
+            loop {
+                tokio::time::sleep(std::time::Duration::from_millis(
+                    loop_check_interval_ms as u64,
+                ))
+                .await;
+
+                if let GossipIterationResult::Close = gossip.run_one_iteration().await {
+                    tracing::warn!("gossip loop ending");
+                    break;
+                }
+            }
+
+            KitsuneResult::Ok(())
+        });
+
+        this
+    }
+
+    /// Get metrics data via event channel in the form of NodeInfo
+    // TODO: remove NodeInfo
+    async fn get_metric_info(
+        &self,
+        agents: Vec<Arc<KitsuneAgent>>,
+    ) -> KitsuneP2pResult<Option<NodeInfo>> {
+        // We pick an arbitrary agent for now, since in a full-sync situation,
+        // any agent should have the same data as any other agent,
+        // and sharded targets are a single remote agent.
+        let arbitrary_agent = agents
+            .first()
+            .expect("Gossip must have a least one from_agent")
+            .clone();
+        let last_touch = match self
+            .evt_sender
+            .query_metrics(MetricQuery::LastSync {
+                agent: arbitrary_agent,
+            })
+            .await?
+        {
+            MetricQueryAnswer::LastSync(time) => time,
+            _ => unreachable!(),
+        };
+        Ok(last_touch.map(|last_touch| NodeInfo {
+            last_touch,
+            was_err: false,
+        }))
+    }
+
+    /// Record a metric via event channel
+    // TODO: remove NodeInfo
+    async fn record_metric(
+        &self,
+        agents: Vec<Arc<KitsuneAgent>>,
+        info: NodeInfo,
+    ) -> KitsuneP2pResult<()> {
+        let kind = if info.was_err {
+            MetricKind::ConnectError
+        } else {
+            MetricKind::QuickGossip
+        };
+        for agent in agents {
+            self.evt_sender
+                .put_metric_datum(MetricDatum {
+                    agent,
+                    kind: kind.clone(),
+                    timestamp: info.last_touch,
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn run_one_iteration(&self) -> GossipIterationResult {
+        // # Step 1 - check state
+        //   - if closed, send GossipIterationResult::Close
+        //   - if not ready, exit early
+        let sync_and_initiate = match self.step_1_check().await {
+            CheckResult::Close => return GossipIterationResult::Close,
+            CheckResult::NotReady => return GossipIterationResult::Good,
+            CheckResult::SyncAndInitiate => true,
+            CheckResult::SkipSyncAndInitiate => false,
+        };
+
+        if sync_and_initiate {
+            // # Step 2 - run the strategy's local sync
+            match S::local_sync(self).await {
+                Err(_) => return GossipIterationResult::Close,
+                Ok(false) => return GossipIterationResult::Good,
+                Ok(true) => (),
+            }
+
+            // # Step 3 - check target for initiation
+            //   - if we don't have a current initiation target, pick one
+            //   - send the initiate message
+            match self.step_3_initiate().await {
+                Err(_) => return GossipIterationResult::Close,
+                Ok(false) => return GossipIterationResult::Good,
+                Ok(true) => (),
+            }
+        }
+
+        // # Step 4 - loop on incoming/outgoing data in parallel
+        //   - if processing incoming data is slow we want to keep
+        //     sending outgoing data at appropriate times
+        //   - if we get a "finished" chunk from our initaite target,
+        //     clear the initiate target
+        //   - if we get through all incoming/outgoing, move on
+        //   - if we take > gossip_interval, move on
+        match self.step_4_com_loop().await {
+            Err(_) => return GossipIterationResult::Close,
+            Ok(false) => return GossipIterationResult::Good,
+            Ok(true) => (),
+        }
+
+        // # Step 5 - flush all pending metrics via KitsuneP2pEvent channel
+        //   TODO: this may not be technically correct, since we may want to
+        //       record metrics from previous steps even if those other steps
+        //       short-circuited this iteration. Revisit.
+        match self.step_5_flush_metrics().await {
+            Err(_) => return GossipIterationResult::Close,
+            Ok(false) => return GossipIterationResult::Good,
+            Ok(true) => (),
+        }
+
+        GossipIterationResult::Good
+    }
+
+    async fn step_1_check(&self) -> CheckResult {
+        match self.step_1_check_inner().await {
+            Err(_) => CheckResult::Close,
+            Ok(r) => r,
+        }
+    }
+
+    async fn step_1_check_inner(&self) -> KitsuneP2pResult<CheckResult> {
+        let (not_ready, tgt) = self.inner.share_mut(|i, _| {
+            // first, if we don't have any local agents, there's
+            // no point in doing any gossip logic
+            let not_ready = i.local_agents.is_empty();
+            let tgt = i.initiate_tgt.clone();
+            Ok((not_ready, tgt))
+        })?;
+        if not_ready {
+            return Ok(CheckResult::NotReady);
+        }
+
+        // next, check to see if we should time out any current initiate_tgt
+        if let Some(initiate_tgt) = tgt {
+            if let Some(metric) = self.get_metric_info(initiate_tgt.agents().clone()).await? {
+                if metric.was_err
+                    || metric.last_touch.elapsed()?.as_millis() as u32
+                        > self.tuning_params.gossip_peer_on_success_next_gossip_delay_ms
+                        // give us a little leeway... we don't
+                        // need to be too agressive with timing out
+                        // this loop
+                        * 2
+                {
+                    tracing::warn!("gossip timeout on initiate tgt {:?}", initiate_tgt);
+
+                    self.inner.share_mut(|i, _| {
+                        i.initiate_tgt = None;
+                        Ok(())
+                    })?;
+                } else {
+                    // we're still processing the current initiate...
+                    // don't bother syncing locally
+                    return Ok(CheckResult::SkipSyncAndInitiate);
+                }
+            } else {
+                // erm... we have an initate tgt,
+                // but we've never seen them??
+                // this must be a logic error.
+                unreachable!()
+            }
+        }
+        // TODO: clean up ugly locking here
+        let needs_sync = self.inner.share_mut(|i, _| {
+            Ok(i.initiate_tgt.is_none()
+                && proc_count_us_elapsed(i.last_initiate_check_us).as_millis() as u32
+                    > self.tuning_params.gossip_loop_iteration_delay_ms)
+        })?;
+        if needs_sync {
+            Ok(CheckResult::SyncAndInitiate)
+        } else {
+            Ok(CheckResult::SkipSyncAndInitiate)
+        }
+    }
+
+    async fn step_3_initiate(&self) -> KitsuneP2pResult<bool> {
+        // we have decided to do an initiate check, mark the time
+
+        // get the remote certs we might want to speak to
+        let endpoints: HashMap<GossipTgt, TxUrl> = self.inner.share_mut(|inner, _| {
+            inner.last_initiate_check_us = proc_count_now_us();
+            // TODO: In the future we'll pull the endpoints from a p2p store query that
+            //       finds nodes which overlap our arc.
+            //       For now we use the agent infos of the last local sync.
+            Ok(inner
+                .strategy
+                .agent_infos()
+                .values()
+                .filter_map(|v| {
+                    if let MetaOpData::Agent(agent_info_signed) = &**v {
+                        // this is for remote gossip, we've already sync local agents
+                        if inner.local_agents.contains(&agent_info_signed.agent) {
+                            return None;
+                        }
+
+                        if !inner.strategy.is_gossip_peer(agent_info_signed) {
+                            return None;
+                        }
+
+                        if let Some(url) = agent_info_signed.url_list.get(0) {
+                            if let Ok(purl) = kitsune_p2p_proxy::ProxyUrl::from_full(url.as_str()) {
+                                return Some((
+                                    GossipTgt::new(
+                                        vec![agent_info_signed.agent.clone()],
+                                        Tx2Cert::from(purl.digest()),
+                                    ),
+                                    TxUrl::from(url.as_str()),
+                                ));
+                            }
+                        }
+                    }
+                    None
+                })
+                .collect())
+        })?;
+        let mut endpoints: Vec<(GossipTgt, TxUrl)> = endpoints.into_iter().collect();
+
+        let last_touch_fudge_ms: u32 = {
+            use rand::prelude::*;
+            let mut rng = thread_rng();
+            // randomize the keys
+            endpoints.shuffle(&mut rng);
+            // last_touch fudge
+            // we don't really want two nodes to both decide to initiate gossip
+            // at the same time... so let's randomize our talk window by a
+            // couple seconds
+            rng.gen_range(0, 5000)
+        };
+
+        // pick the first one that we haven't spoken to recently
+        // or just proceed without a gossip initiate.
+        let mut initiate = None;
+
+        for (endpoint, url) in endpoints {
+            if let Some(info) = self.get_metric_info(endpoint.agents().clone()).await? {
+                if self.saw_recently(&info, last_touch_fudge_ms)? {
+                    tracing::trace!(?endpoint, "saw too recently");
+                    continue;
+                }
+            }
+            self.inner.share_mut(|inner, _| {
+                inner.record_pending_metric(endpoint.agents().clone(), false);
+                inner.initiate_tgt = Some(endpoint.clone());
+                Ok(())
+            })?;
+            initiate = Some((endpoint, url));
+            break;
+        }
+
+        self.inner.share_mut(|inner, _| {
+            if let Some((endpoint, url)) = initiate {
+                tracing::info!(%url, ?endpoint, "initiating gossip");
+                let gossip = inner.strategy.initiate(&inner.local_agents);
+                inner
+                    .outgoing
+                    .push((endpoint, HowToConnect::Url(url), gossip));
+            }
+            Ok(())
+        })?;
+
+        Ok(true)
+    }
+
+    fn saw_recently(&self, info: &NodeInfo, last_touch_fudge_ms: u32) -> KitsuneP2pResult<bool> {
+        Ok(if info.was_err {
+            info.last_touch.elapsed()?.as_millis() as u32 + last_touch_fudge_ms
+                <= self.tuning_params.gossip_peer_on_error_next_gossip_delay_ms
+        } else {
+            info.last_touch.elapsed()?.as_millis() as u32 + last_touch_fudge_ms
+                <= self
+                    .tuning_params
+                    .gossip_peer_on_success_next_gossip_delay_ms
+        })
+    }
+
+    async fn step_4_com_loop(&self) -> KitsuneResult<bool> {
+        let loop_start = proc_count_now_us();
+
+        loop {
+            let (tuning_params, space, ep_hnd) = (
+                self.tuning_params.clone(),
+                self.space.clone(),
+                self.ep_hnd.clone(),
+            );
+            let (mut maybe_outgoing, mut maybe_incoming) = self.inner.share_mut(|i, _| {
+                let maybe_outgoing = if !i.outgoing.is_empty()
+                    && proc_count_us_elapsed(i.last_outgoing_us).as_millis() as u64
+                        > self.send_interval_ms
+                {
+                    let (cert, how, gossip) = i.outgoing.remove(0);
+
+                    // set this to a time in the future
+                    // so we don't accidentally double up if sending
+                    // is slow... we'll set this more reasonably
+                    // when we get a success or failure below.
+                    i.last_outgoing_us = proc_count_now_us()
+                        + (self.tuning_params.tx2_implicit_timeout_ms as i64 * 1000);
+
+                    Some((cert, how, gossip))
+                } else {
+                    None
+                };
+                let maybe_incoming = if !i.incoming.is_empty() {
+                    Some(i.incoming.remove(0))
+                } else {
+                    None
+                };
+                Ok((maybe_outgoing, maybe_incoming))
+            })?;
+
+            let will_break = (maybe_outgoing.is_none() && maybe_incoming.is_none())
+                || proc_count_us_elapsed(loop_start).as_millis() as u32
+                    > tuning_params.gossip_loop_iteration_delay_ms;
+
+            if let Some(outgoing) = maybe_outgoing.take() {
+                let (endpoint, how, gossip) = outgoing;
+                let agents = endpoint.agents().clone();
+                let was_err = if let Err(e) = step_4_com_loop_outgoing(
+                    &self.inner,
+                    tuning_params.clone(),
+                    space.clone(),
+                    ep_hnd,
+                    endpoint.cert().clone(),
+                    how,
+                    gossip,
+                )
+                .await
+                {
+                    tracing::warn!("failed to send outgoing: {:?} {:?}", endpoint, e);
+                    true
+                } else {
+                    false
+                };
+                self.inner.share_mut(move |i, _| {
+                    i.last_outgoing_us = proc_count_now_us();
+                    i.record_pending_metric(agents, was_err);
+                    Ok(())
+                })?;
+            }
+
+            if let Some(incoming) = maybe_incoming.take() {
+                let (con, gossip) = incoming;
+                if let Err(e) = S::incoming(self, con, gossip).await {
+                    tracing::warn!("failed to process incoming: {:?}", e);
+                }
+            }
+
+            if will_break {
+                break;
+            }
+        }
+        Ok(true)
+    }
+
+    async fn step_5_flush_metrics(&self) -> KitsuneP2pResult<bool> {
+        let metrics: Vec<_> = self
+            .inner
+            .share_mut(|i, _| Ok(i.pending_metrics.drain(..).collect()))?;
+        for (agents, info) in metrics {
+            self.record_metric(agents, info).await?;
+        }
+        Ok(true)
+    }
+}
+
+async fn step_4_com_loop_outgoing<S: GossipStrategy>(
+    inner: &Share<GossipModInner<S>>,
+    tuning_params: KitsuneP2pTuningParams,
+    space: Arc<KitsuneSpace>,
+    ep_hnd: Tx2EpHnd<wire::Wire>,
+    peer_cert: Tx2Cert,
+    how: HowToConnect,
+    gossip: S::Wire,
+) -> KitsuneResult<()> {
+    let gossip = gossip.encode_vec().map_err(KitsuneError::other)?;
+    let gossip = wire::Wire::gossip(space, gossip.into());
+
+    let t = tuning_params.implicit_timeout();
+
+    let con = match how {
+        HowToConnect::Con(con) => {
+            if con.is_closed() {
+                let url = pick_url_for_cert(inner, &peer_cert)?;
+                ep_hnd.get_connection(url, t).await?
+            } else {
+                con
+            }
+        }
+        HowToConnect::Url(url) => ep_hnd.get_connection(url, t).await?,
+    };
+    con.notify(&gossip, t).await?;
+
+    Ok(())
+}
+
+// if the connection is closed while awaiting an outgoing send,
+// we'll need to establish a new one...
+fn pick_url_for_cert<S: GossipStrategy>(
+    inner: &Share<GossipModInner<S>>,
+    cert: &Tx2Cert,
+) -> KitsuneResult<TxUrl> {
+    // this is a bit computationally intensive...
+    // but, in case there is a split in data recency, safer as a starting point
+
+    // first, gather the most recent agent_infos we have for this cert,
+    // then, see if the urls match - outputting a warning if they don't
+    // and pick one..
+
+    inner.share_mut(|i, _| {
+        let mut most_recent = 0;
+        let mut out_url = None;
+        for data in i.strategy.agent_infos().values() {
+            if let MetaOpData::Agent(agent_info_signed) = &**data {
+                if let Some(url) = agent_info_signed.url_list.get(0) {
+                    if let Ok(purl) = kitsune_p2p_proxy::ProxyUrl::from_full(url.as_str()) {
+                        if &Tx2Cert::from(purl.digest()) != cert {
+                            continue;
+                        }
+
+                        if agent_info_signed.signed_at_ms < most_recent {
+                            continue;
+                        }
+                        most_recent = agent_info_signed.signed_at_ms;
+
+                        let url = TxUrl::from(url.as_str());
+
+                        if let Some(out_url) = out_url {
+                            if out_url != url {
+                                tracing::warn!(?cert, %out_url, %url, "url mismatch for tgt cert");
+                            }
+                        }
+
+                        out_url = Some(url);
+                    }
+                }
+            }
+        }
+        match out_url.take() {
+            Some(out_url) => Ok(out_url),
+            None => Err("failed to find url for cert".into()),
+        }
+    })
+}
+
+impl<S: GossipStrategy> AsGossipModule for GossipMod<S> {
+    fn incoming_gossip(
+        &self,
+        con: Tx2ConHnd<wire::Wire>,
+        gossip_data: Box<[u8]>,
+    ) -> KitsuneResult<()> {
+        let (_, gossip) = S::Wire::decode_ref(&gossip_data).map_err(KitsuneError::other)?;
+        self.inner.share_mut(move |i, _| {
+            i.incoming.push((con, gossip));
+            if i.incoming.len() > 20 {
+                tracing::warn!(
+                    "Overloaded with incoming gossip.. {} messages",
+                    i.incoming.len()
+                );
+            }
+            Ok(())
+        })
+    }
+
+    fn local_agent_join(&self, a: Arc<KitsuneAgent>) {
+        let _ = self.inner.share_mut(move |i, _| {
+            i.local_agents.insert(a);
+            Ok(())
+        });
+    }
+
+    fn local_agent_leave(&self, a: Arc<KitsuneAgent>) {
+        let _ = self.inner.share_mut(move |i, _| {
+            i.strategy.local_agent_leave(&a);
+            i.local_agents.remove(&a);
+            Ok(())
+        });
+    }
+}
+
+struct GossipModFactory<S>(std::marker::PhantomData<fn() -> S>);
+
+impl<S: GossipStrategy> AsGossipModuleFactory for GossipModFactory<S> {
+    fn spawn_gossip_task(
+        &self,
+        tuning_params: KitsuneP2pTuningParams,
+        space: Arc<KitsuneSpace>,
+        ep_hnd: Tx2EpHnd<wire::Wire>,
+        evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
+    ) -> GossipModule {
+        GossipModule(GossipMod::<S>::new(
+            tuning_params,
+            space,
+            ep_hnd,
+            evt_sender,
+        ))
+    }
+}
+
+/// The factory for the gossip module running strategy `S`.
+pub(crate) fn factory<S: GossipStrategy>() -> GossipModuleFactory {
+    GossipModuleFactory(Arc::new(GossipModFactory::<S>(std::marker::PhantomData)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunked_data_fits_in_messages() {
+        assert!(chunk_data(Vec::new()).is_empty());
+
+        let data: Vec<_> = (0..10u8)
+            .map(|i| {
+                let op_hash = Arc::new(KitsuneOpHash::new(vec![i; 36]));
+                Arc::new(MetaOpData::Op(op_hash, vec![i; 4000]))
+            })
+            .collect();
+        let chunks = chunk_data(data.clone());
+        assert!(chunks.len() > 1);
+        for chunk in chunks.iter() {
+            assert!(chunk.iter().map(|d| d.byte_count()).sum::<usize>() <= MAX_SEND_BUF_BYTES);
+        }
+        assert_eq!(chunks.into_iter().flatten().collect::<Vec<_>>(), data);
+    }
+}
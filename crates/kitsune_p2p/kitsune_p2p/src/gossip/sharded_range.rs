@@ -0,0 +1,626 @@
+//! Sharded range gossip.
+//!
+//! Rather than exchanging a bloom filter of the whole space, nodes only
+//! reconcile the parts of the ring which both of them hold.
+//! The ring is divided into fixed segments, and the ops whose basis is
+//! located in each segment are further split into windows of the time they
+//! were authored, so that reconciling recent ops doesn't require re-sending
+//! the hashes of old ones.
+//! Each (segment, window) pair is a [`Bucket`]:
+//! - The initiator sends the storage arcs of its local agents.
+//! - The acceptor replies with a [`BucketDigest`] for every bucket
+//!   held by both nodes. Where the arcs only hold part of a segment,
+//!   the bucket is narrowed to the locations both nodes hold.
+//! - For buckets whose digests differ, both nodes exchange the op hashes
+//!   they hold and send each other the ops the other is missing.
+//!   An op hash doesn't carry the location of the op's basis, so incoming ops
+//!   are only accepted if the remote announced them in one of these buckets.
+//!
+//! Agent infos aren't sharded: the keys of every agent info each node
+//! knows about are sent along with the initiate / accept messages.
+
+use super::common::*;
+use super::{MetaOpData, MetaOpKey};
+use crate::agent_store::AgentInfoSigned;
+use crate::types::event::*;
+use crate::types::gossip::*;
+use crate::types::*;
+use futures::future::FutureExt;
+use ghost_actor::dependencies::must_future::MustBoxFuture;
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_types::sgd_arc::SgdArc;
+use kitsune_p2p_types::tx2::tx2_api::*;
+use kitsune_p2p_types::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::num::Wrapping;
+use std::sync::Arc;
+
+mod step_2_local_sync_inner;
+mod step_4_com_loop_inner;
+
+type OpHashSet = HashSet<Arc<KitsuneOpHash>>;
+type BucketMap = BTreeMap<Bucket, OpHashSet>;
+type OpRangeMap = HashMap<Arc<KitsuneOpHash>, LocRange>;
+
+/// An inclusive range of locations within a single ring segment.
+pub(crate) type LocRange = (u32, u32);
+
+/// The ring is divided into `2 ^ SEGMENT_BITS` segments of equal length.
+const SEGMENT_BITS: u32 = 4;
+
+/// The number of ring segments.
+const SEGMENT_COUNT: u32 = 1 << SEGMENT_BITS;
+
+/// Ops authored within this many seconds are reconciled
+/// separately from older ones.
+const RECENT_WINDOW_S: i64 = 60 * 60;
+
+/// Ops authored within this many seconds, but before the recent window,
+/// are reconciled separately from the oldest ones.
+const DAY_WINDOW_S: i64 = 60 * 60 * 24;
+
+/// A part of the space which is reconciled as a unit:
+/// the ops whose basis is located in a range of one ring segment
+/// which were authored within a time window.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct Bucket {
+    /// The first location of the range (INCLUSIVE).
+    pub start_loc: u32,
+    /// The last location of the range (INCLUSIVE).
+    pub end_loc: u32,
+    /// Only ops authored since this time (INCLUSIVE).
+    pub since_utc_epoch_s: i64,
+    /// Only ops authored until this time (EXCLUSIVE).
+    pub until_utc_epoch_s: i64,
+}
+
+/// An order independent digest of the op hashes in a [`Bucket`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BucketDigest {
+    /// The number of op hashes.
+    pub count: u32,
+    /// The op hashes folded together with xor.
+    pub xor: [u8; 32],
+}
+
+impl BucketDigest {
+    /// Digest a set of op hashes.
+    pub fn new<'a>(op_hashes: impl IntoIterator<Item = &'a Arc<KitsuneOpHash>>) -> Self {
+        let mut out = Self::default();
+        for op_hash in op_hashes {
+            out.count += 1;
+            for (i, b) in op_hash.get_bytes().iter().enumerate() {
+                out.xor[i % 32] ^= b;
+            }
+        }
+        out
+    }
+}
+
+/// The ring segment a location falls into.
+pub(crate) fn segment_of(loc: u32) -> u32 {
+    loc >> (32 - SEGMENT_BITS)
+}
+
+/// The first and last location of a ring segment.
+pub(crate) fn segment_bounds(segment: u32) -> (u32, u32) {
+    let start = segment << (32 - SEGMENT_BITS);
+    (start, start + (u32::MAX >> SEGMENT_BITS))
+}
+
+/// Arcs which together hold exactly the locations in a range.
+/// An arc holds an odd number of locations, so if the range holds
+/// an even number the first location gets an arc of its own.
+fn range_arcs((start, end): LocRange) -> Vec<SgdArc> {
+    let mut out = Vec::new();
+    let mut start = start;
+    if (end - start) % 2 == 1 {
+        out.push(SgdArc::new(start, 1));
+        start += 1;
+    }
+    let half_length = (end - start) / 2 + 1;
+    out.push(SgdArc::new(start + half_length - 1, half_length));
+    out
+}
+
+/// Does this arc hold every location in the ring segment?
+fn arc_covers_segment(arc: &SgdArc, segment: u32) -> bool {
+    let (start, end) = segment_bounds(segment);
+    // An arc holds the locations closest to its center, so within the segment
+    // the one least likely to be held is either end, or the location furthest
+    // from the center if that falls inside the segment.
+    let furthest = (arc.center_loc.0 + Wrapping(1 << 31)).0;
+    arc.contains(start)
+        && arc.contains(end)
+        && (segment_of(furthest) != segment || arc.contains(furthest))
+}
+
+/// The locations both ranges hold.
+fn intersect((a_start, a_end): LocRange, (b_start, b_end): LocRange) -> Option<LocRange> {
+    let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+    if start <= end {
+        Some((start, end))
+    } else {
+        None
+    }
+}
+
+/// The locations of a ring segment held by an arc.
+/// If the arc leaves a gap within the segment only the longer side is kept.
+fn arc_segment_range(arc: &SgdArc, segment: u32) -> Option<LocRange> {
+    let bounds = segment_bounds(segment);
+    if arc_covers_segment(arc, segment) {
+        return Some(bounds);
+    }
+    if arc.half_length == 0 {
+        return None;
+    }
+    // An arc which doesn't cover the segment holds less than the whole ring,
+    // so its ends are exactly `half_length - 1` from its center.
+    let reach = Wrapping(arc.half_length - 1);
+    let (start, end) = ((arc.center_loc.0 - reach).0, (arc.center_loc.0 + reach).0);
+    let pieces = if start <= end {
+        vec![(start, end)]
+    } else {
+        vec![(start, u32::MAX), (0, end)]
+    };
+    pieces
+        .into_iter()
+        .filter_map(|piece| intersect(piece, bounds))
+        .max_by_key(|(start, end)| end - start)
+}
+
+/// Does this arc hold any location in the range?
+fn arc_overlaps_range(arc: &SgdArc, range: LocRange) -> bool {
+    arc_segment_range(arc, segment_of(range.0))
+        .and_then(|held| intersect(held, range))
+        .is_some()
+}
+
+/// The locations of a ring segment held by any of the arcs.
+/// If the arcs leave gaps within the segment only the longest run is kept.
+fn held_range(arcs: &[SgdArc], segment: u32) -> Option<LocRange> {
+    let mut ranges: Vec<_> = arcs
+        .iter()
+        .filter_map(|arc| arc_segment_range(arc, segment))
+        .collect();
+    ranges.sort_unstable();
+    let mut runs: Vec<LocRange> = Vec::new();
+    for (start, end) in ranges {
+        match runs.last_mut() {
+            Some(run) if start <= run.1.saturating_add(1) => run.1 = run.1.max(end),
+            _ => runs.push((start, end)),
+        }
+    }
+    runs.into_iter().max_by_key(|(start, end)| end - start)
+}
+
+/// The locations of each ring segment held by at least one arc on each side.
+pub(crate) fn common_ranges(local_arcs: &[SgdArc], remote_arcs: &[SgdArc]) -> Vec<LocRange> {
+    (0..SEGMENT_COUNT)
+        .filter_map(|segment| {
+            intersect(
+                held_range(local_arcs, segment)?,
+                held_range(remote_arcs, segment)?,
+            )
+        })
+        .collect()
+}
+
+/// The time windows to reconcile, as of `now_s`.
+/// The windows are aligned to the length of the recent window,
+/// so they only move once per recent window.
+fn time_windows(now_s: i64) -> Vec<(i64, i64)> {
+    let recent = now_s - now_s.rem_euclid(RECENT_WINDOW_S);
+    let day = recent - DAY_WINDOW_S;
+    vec![(i64::MIN, day), (day, recent), (recent, i64::MAX)]
+}
+
+/// Every bucket in the given ranges, as of `now_s`.
+fn buckets(ranges: &[LocRange], now_s: i64) -> Vec<Bucket> {
+    let windows = time_windows(now_s);
+    ranges
+        .iter()
+        .flat_map(|(start, end)| {
+            windows.iter().map(move |(since, until)| Bucket {
+                start_loc: *start,
+                end_loc: *end,
+                since_utc_epoch_s: *since,
+                until_utc_epoch_s: *until,
+            })
+        })
+        .collect()
+}
+
+fn now_s() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+kitsune_p2p_types::write_codec_enum! {
+    /// ShardedRange Gossip Wire Protocol Codec
+    codec GossipWire {
+        /// Initiate a round of gossip with a remote node
+        Initiate(0x10) {
+            agents.0: HashSet<Arc<KitsuneAgent>>,
+            arcs.1: Vec<SgdArc>,
+            agent_keys.2: Vec<Arc<MetaOpKey>>,
+        },
+
+        /// Accept an incoming round of gossip from a remote node,
+        /// with digests of the buckets held by both nodes
+        Accept(0x20) {
+            agents.0: HashSet<Arc<KitsuneAgent>>,
+            agent_keys.1: Vec<Arc<MetaOpKey>>,
+            digests.2: Vec<(Bucket, BucketDigest)>,
+        },
+
+        /// The op hashes held in buckets whose digests differed,
+        /// if "reply" the remote should respond with its own
+        /// op hashes for the same buckets.
+        OpHashes(0x30) {
+            agents.0: HashSet<Arc<KitsuneAgent>>,
+            reply.1: bool,
+            op_hashes.2: Vec<(Bucket, Vec<Arc<KitsuneOpHash>>)>,
+        },
+
+        /// Send a chunks of gossip meta op data,
+        /// if "finished" this will be the final chunk.
+        Chunk(0x40) {
+            agents.0: HashSet<Arc<KitsuneAgent>>,
+            finished.1: bool,
+            chunks.2: Vec<Arc<MetaOpData>>,
+        },
+    }
+}
+
+pub(crate) type ShardedRangeMod = GossipMod<ShardedRange>;
+
+pub(crate) struct ShardedRange {
+    /// The storage arcs of our local agents, as of the last local sync
+    local_arcs: HashMap<Arc<KitsuneAgent>, SgdArc>,
+
+    /// Every agent info in the space, as of the last local sync
+    local_agent_infos: DataMap,
+
+    /// The location ranges of the ops each remote node announced
+    /// it will send us, until it sends its final chunk
+    incoming_op_ranges: HashMap<Tx2Cert, OpRangeMap>,
+}
+
+impl ShardedRange {
+    /// The storage arcs of our local agents.
+    fn arcs(&self) -> Vec<SgdArc> {
+        self.local_arcs.values().cloned().collect()
+    }
+
+    /// The keys of every agent info we know about.
+    fn agent_keys(&self) -> Vec<Arc<MetaOpKey>> {
+        self.local_agent_infos.keys().cloned().collect()
+    }
+
+    /// The agent infos we know about which are not in `remote_keys`.
+    fn agent_infos_missing_from(&self, remote_keys: &[Arc<MetaOpKey>]) -> Vec<Arc<MetaOpData>> {
+        let remote_keys: KeySet = remote_keys.iter().cloned().collect();
+        self.local_agent_infos
+            .iter()
+            .filter(|(key, _)| !remote_keys.contains(*key))
+            .map(|(_, data)| data.clone())
+            .collect()
+    }
+}
+
+impl GossipModInner<ShardedRange> {
+    /// Queue op data to a remote node we are connected to.
+    /// If "finished" the final chunk, which may be empty,
+    /// tells the remote we have nothing more to send.
+    fn queue_chunks(
+        &mut self,
+        con: &Tx2ConHnd<wire::Wire>,
+        remote_agents: &[Arc<KitsuneAgent>],
+        data: Vec<Arc<MetaOpData>>,
+        finished: bool,
+    ) {
+        let mut chunks = chunk_data(data);
+        if finished && chunks.is_empty() {
+            chunks.push(Vec::new());
+        }
+        let last_idx = chunks.len().saturating_sub(1);
+        for (idx, chunks) in chunks.into_iter().enumerate() {
+            let gossip = GossipWire::chunk(
+                self.local_agents.clone(),
+                finished && idx == last_idx,
+                chunks,
+            );
+            self.queue(con, remote_agents, gossip);
+        }
+    }
+}
+
+impl ShardedRangeMod {
+    /// Collect the op hashes our local agents hold in each of the buckets.
+    /// Ops are queried by the location of their basis, one range at a time,
+    /// since their hashes don't tell where their basis is.
+    /// Each agent is only asked for the part of a bucket its arc holds,
+    /// and buckets our local agents don't hold all of are left out.
+    async fn local_bucket_hashes(&self, buckets: &[Bucket]) -> KitsuneResult<BucketMap> {
+        let (arcs, local_arcs) = self
+            .inner
+            .share_mut(|i, _| Ok((i.strategy.arcs(), i.strategy.local_arcs.clone())))?;
+
+        let mut out = BucketMap::new();
+        for bucket in buckets {
+            let range = (bucket.start_loc, bucket.end_loc);
+            let segment = segment_of(range.0);
+            match held_range(&arcs, segment) {
+                Some(held) if intersect(held, range) == Some(range) => (),
+                _ => continue,
+            }
+            let bucket_hashes = out.entry(*bucket).or_default();
+            for (agent, arc) in local_arcs.iter() {
+                let agent_range =
+                    match arc_segment_range(arc, segment).and_then(|held| intersect(held, range)) {
+                        Some(agent_range) => agent_range,
+                        None => continue,
+                    };
+                for range_arc in range_arcs(agent_range) {
+                    let op_hashes = self
+                        .evt_sender
+                        .fetch_op_hashes_for_constraints(FetchOpHashesForConstraintsEvt {
+                            space: self.space.clone(),
+                            agent: agent.clone(),
+                            sgd_arc: range_arc,
+                            since_utc_epoch_s: bucket.since_utc_epoch_s,
+                            until_utc_epoch_s: bucket.until_utc_epoch_s,
+                        })
+                        .await
+                        .map_err(KitsuneError::other)?;
+                    bucket_hashes.extend(op_hashes);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl GossipStrategy for ShardedRange {
+    type Wire = GossipWire;
+
+    fn new() -> Self {
+        Self {
+            local_arcs: HashMap::new(),
+            local_agent_infos: HashMap::new(),
+            incoming_op_ranges: HashMap::new(),
+        }
+    }
+
+    fn agent_infos(&self) -> &DataMap {
+        &self.local_agent_infos
+    }
+
+    fn is_gossip_peer(&self, agent_info: &AgentInfoSigned) -> bool {
+        // only nodes holding some of the same locations as us are of any use
+        !common_ranges(&self.arcs(), &[agent_info.storage_arc]).is_empty()
+    }
+
+    fn initiate(&self, local_agents: &HashSet<Arc<KitsuneAgent>>) -> GossipWire {
+        let arcs = self.arcs();
+        tracing::debug!(?arcs, "initiate with our arcs");
+        GossipWire::initiate(local_agents.clone(), arcs, self.agent_keys())
+    }
+
+    fn local_sync(gossip: &ShardedRangeMod) -> MustBoxFuture<'_, KitsuneResult<bool>> {
+        async move {
+            let local_agents = gossip.inner.share_mut(|i, _| Ok(i.local_agents.clone()))?;
+
+            let (local_arcs, local_agent_infos) =
+                match gossip.step_2_local_sync_inner(local_agents).await {
+                    Err(e) => {
+                        tracing::warn!("gossip error: {:?}", e);
+                        return Ok(false);
+                    }
+                    Ok(r) => r,
+                };
+
+            gossip.inner.share_mut(move |i, _| {
+                i.strategy.local_arcs = local_arcs;
+                i.strategy.local_agent_infos = local_agent_infos;
+                Ok(())
+            })?;
+
+            Ok(true)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn incoming(
+        gossip: &ShardedRangeMod,
+        con: Tx2ConHnd<wire::Wire>,
+        msg: GossipWire,
+    ) -> MustBoxFuture<'_, KitsuneResult<()>> {
+        step_4_com_loop_inner::step_4_com_loop_inner_incoming(gossip, con, msg)
+            .boxed()
+            .into()
+    }
+
+    fn local_agent_leave(&mut self, agent: &Arc<KitsuneAgent>) {
+        self.local_arcs.remove(agent);
+    }
+}
+
+pub fn factory() -> GossipModuleFactory {
+    super::common::factory::<ShardedRange>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kitsune_p2p_types::sgd_arc::MAX_HALF_LENGTH;
+
+    fn op_hash(byte: u8, loc: u32) -> Arc<KitsuneOpHash> {
+        let mut bytes = vec![byte; 32];
+        bytes.extend_from_slice(&loc.to_le_bytes());
+        Arc::new(KitsuneOpHash::new(bytes))
+    }
+
+    #[test]
+    fn segments_cover_the_ring() {
+        assert_eq!(segment_bounds(0).0, 0);
+        assert_eq!(segment_bounds(SEGMENT_COUNT - 1).1, u32::MAX);
+        for segment in 0..SEGMENT_COUNT {
+            let (start, end) = segment_bounds(segment);
+            assert_eq!(segment_of(start), segment);
+            assert_eq!(segment_of(end), segment);
+            if segment > 0 {
+                assert_eq!(segment_bounds(segment - 1).1 + 1, start);
+            }
+        }
+    }
+
+    #[test]
+    fn range_arcs_hold_the_range() {
+        let (start, end) = segment_bounds(5);
+        let ranges = [(start, end), (start + 7, end - 20), (start + 3, start + 3)];
+        for (start, end) in ranges.iter().copied() {
+            let arcs = range_arcs((start, end));
+            let held = |loc: u32| arcs.iter().any(|arc| arc.contains(loc));
+            for loc in [start, (start + end) / 2, end].iter() {
+                assert!(held(*loc));
+            }
+            assert!(!held(start - 1) && !held(end + 1));
+        }
+        assert_eq!(range_arcs((start, end)).len(), 2);
+        assert_eq!(range_arcs((start + 3, start + 3)).len(), 1);
+    }
+
+    #[test]
+    fn arcs_cover_segments() {
+        let (start, end) = segment_bounds(3);
+        let half_segment = (end - start) / 2 + 1;
+
+        // full arcs, wherever they are centered, hold everything
+        for center in [0, start, end, u32::MAX].iter() {
+            let full = SgdArc::new(*center, u32::MAX);
+            let common = common_ranges(&[full], &[full]);
+            assert_eq!(common.len(), SEGMENT_COUNT as usize);
+            for (segment, range) in common.into_iter().enumerate() {
+                assert_eq!(range, segment_bounds(segment as u32));
+            }
+        }
+
+        // an empty arc holds nothing
+        assert!(!arc_covers_segment(&SgdArc::new(start, 0), 3));
+
+        // an arc exactly as long as a segment only holds the segment it is centered on
+        let exact = SgdArc::new(start + half_segment, half_segment + 1);
+        assert!(arc_covers_segment(&exact, 3));
+        assert!(!arc_covers_segment(&exact, 2));
+        assert!(!arc_covers_segment(&exact, 4));
+
+        // an arc slightly too short holds no segment at all
+        let short = SgdArc::new(start + half_segment, half_segment - 1);
+        assert!((0..SEGMENT_COUNT).all(|s| !arc_covers_segment(&short, s)));
+
+        // an arc holding all but a sliver of the ring
+        // only holds the longer side of the segment the sliver is in
+        let almost = SgdArc::new(start + half_segment, MAX_HALF_LENGTH - 3);
+        let sliver = start.wrapping_add(half_segment).wrapping_add(1 << 31);
+        let opposite = segment_of(sliver);
+        assert!(!arc_covers_segment(&almost, opposite));
+        let (opposite_start, opposite_end) = segment_bounds(opposite);
+        assert_eq!(
+            arc_segment_range(&almost, opposite),
+            Some((opposite_start, sliver - 3))
+        );
+        let common = common_ranges(&[almost], &[SgdArc::new(0, u32::MAX)]);
+        assert_eq!(common.len(), SEGMENT_COUNT as usize);
+        assert!(!common.contains(&(opposite_start, opposite_end)));
+    }
+
+    #[test]
+    fn common_ranges_of_partial_arcs() {
+        let (start, _) = segment_bounds(0);
+        let local = SgdArc::new(start, u32::MAX / 4);
+        let remote = SgdArc::new(start.wrapping_add(u32::MAX / 8), u32::MAX / 4);
+        let common = common_ranges(&[local], &[remote]);
+        assert!(!common.is_empty());
+        for (start, end) in common.iter() {
+            assert_eq!(segment_of(*start), segment_of(*end));
+            for loc in [start, end].iter() {
+                assert!(local.contains(**loc));
+                assert!(remote.contains(**loc));
+            }
+        }
+
+        // the ranges reach the ends of the overlap of the arcs,
+        // even though those segments are only partly held
+        let overlap_end = start.wrapping_add(u32::MAX / 4 - 1);
+        assert!(!arc_covers_segment(&local, segment_of(overlap_end)));
+        assert!(common
+            .iter()
+            .any(|(start, end)| *end == overlap_end && *start < *end));
+
+        // adjacent arcs of local agents are held together
+        let (seg_start, seg_end) = segment_bounds(2);
+        let quarter = (seg_end - seg_start) / 4;
+        let first = SgdArc::new(seg_start + quarter, quarter);
+        let second = SgdArc::new(seg_start + 3 * quarter, quarter + 1);
+        assert_eq!(
+            held_range(&[first, second], 2),
+            Some((seg_start + 1, seg_start + 4 * quarter))
+        );
+
+        // arcs on opposite sides of the ring share nothing
+        let opposite = SgdArc::new(start.wrapping_add(1 << 31), u32::MAX / 8);
+        assert!(common_ranges(&[local], &[opposite]).is_empty());
+    }
+
+    #[test]
+    fn time_windows_are_aligned() {
+        let now = 1_000 * RECENT_WINDOW_S + 17;
+        let windows = time_windows(now);
+        assert_eq!(windows, time_windows(1_000 * RECENT_WINDOW_S));
+        assert_ne!(windows, time_windows(1_001 * RECENT_WINDOW_S));
+
+        // the windows are contiguous and cover all time
+        assert_eq!(windows.first().unwrap().0, i64::MIN);
+        assert_eq!(windows.last().unwrap().1, i64::MAX);
+        for pair in windows.windows(2) {
+            assert_eq!(pair[0].1, pair[1].0);
+        }
+
+        assert_eq!(
+            buckets(&[segment_bounds(1), segment_bounds(2)], now).len(),
+            2 * windows.len()
+        );
+    }
+
+    #[test]
+    fn bucket_digests() {
+        let a = op_hash(1, 10);
+        let b = op_hash(2, 20);
+        let c = op_hash(4, 30);
+
+        assert_eq!(
+            BucketDigest::new(&OpHashSet::new()),
+            BucketDigest::default()
+        );
+        assert_eq!(
+            BucketDigest::new(&[a.clone(), b.clone()]),
+            BucketDigest::new(&[b.clone(), a.clone()])
+        );
+        assert_ne!(
+            BucketDigest::new(&[a.clone(), b.clone()]),
+            BucketDigest::new(&[a.clone(), c.clone()])
+        );
+        assert_ne!(
+            BucketDigest::new(&[a.clone(), b.clone()]),
+            BucketDigest::new(&[a, b, c])
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use super::*;
+
+type ArcMap = HashMap<Arc<KitsuneAgent>, SgdArc>;
+
+impl ShardedRangeMod {
+    pub(crate) async fn step_2_local_sync_inner(
+        &self,
+        local_agents: HashSet<Arc<KitsuneAgent>>,
+    ) -> KitsuneResult<(ArcMap, DataMap)> {
+        let mut local_arcs = HashMap::new();
+        let mut local_agent_infos = HashMap::new();
+
+        // agent store is shared between agents in one space
+        // we only have to query it once for all local_agents
+        if let Some(agent) = local_agents.iter().next() {
+            let agent_infos = self
+                .evt_sender
+                .query_agent_info_signed(QueryAgentInfoSignedEvt {
+                    space: self.space.clone(),
+                    agent: agent.clone(),
+                })
+                .await
+                .map_err(KitsuneError::other)?;
+            for agent_info in agent_infos {
+                if local_agents.contains(&agent_info.agent) {
+                    local_arcs.insert(agent_info.agent.clone(), agent_info.storage_arc);
+                }
+                let data = Arc::new(MetaOpData::Agent(agent_info));
+                local_agent_infos.insert(data.key(), data);
+            }
+        }
+
+        self.local_op_sync(&local_arcs).await?;
+
+        Ok((local_arcs, local_agent_infos))
+    }
+
+    /// Give each local agent the ops held by our other local agents
+    /// which fall within its arc.
+    async fn local_op_sync(&self, local_arcs: &ArcMap) -> KitsuneResult<()> {
+        if local_arcs.len() < 2 {
+            return Ok(());
+        }
+
+        let mut has_map: HashMap<Arc<KitsuneAgent>, OpHashSet> = HashMap::new();
+        for (agent, arc) in local_arcs.iter() {
+            let op_hashes = self
+                .evt_sender
+                .fetch_op_hashes_for_constraints(FetchOpHashesForConstraintsEvt {
+                    space: self.space.clone(),
+                    agent: agent.clone(),
+                    sgd_arc: *arc,
+                    since_utc_epoch_s: i64::MIN,
+                    until_utc_epoch_s: i64::MAX,
+                })
+                .await
+                .map_err(KitsuneError::other)?;
+            has_map.insert(agent.clone(), op_hashes.into_iter().collect());
+        }
+
+        let mut local_synced_ops = 0;
+        for from_agent in has_map.keys() {
+            for (to_agent, to_set) in has_map.iter() {
+                if from_agent == to_agent {
+                    continue;
+                }
+                // ask for the ops whose basis falls within the receiver's arc
+                let op_hashes: Vec<_> = self
+                    .evt_sender
+                    .fetch_op_hashes_for_constraints(FetchOpHashesForConstraintsEvt {
+                        space: self.space.clone(),
+                        agent: from_agent.clone(),
+                        sgd_arc: local_arcs[to_agent],
+                        since_utc_epoch_s: i64::MIN,
+                        until_utc_epoch_s: i64::MAX,
+                    })
+                    .await
+                    .map_err(KitsuneError::other)?
+                    .into_iter()
+                    .filter(|op_hash| !to_set.contains(op_hash))
+                    .collect();
+                if op_hashes.is_empty() {
+                    continue;
+                }
+
+                let ops = self
+                    .evt_sender
+                    .fetch_op_hash_data(FetchOpHashDataEvt {
+                        space: self.space.clone(),
+                        agent: from_agent.clone(),
+                        op_hashes,
+                    })
+                    .await
+                    .map_err(KitsuneError::other)?;
+                for (op_hash, op_data) in ops {
+                    local_synced_ops += 1;
+                    self.evt_sender
+                        .gossip(
+                            self.space.clone(),
+                            to_agent.clone(),
+                            from_agent.clone(),
+                            op_hash,
+                            op_data,
+                        )
+                        .await
+                        .map_err(KitsuneError::other)?;
+                }
+            }
+        }
+
+        if local_synced_ops > 0 {
+            tracing::debug!(
+                %local_synced_ops,
+                "local sync",
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+use super::*;
+
+pub(crate) async fn step_4_com_loop_inner_incoming(
+    sharded: &ShardedRangeMod,
+    con: Tx2ConHnd<wire::Wire>,
+    gossip: GossipWire,
+) -> KitsuneResult<()> {
+    match gossip {
+        GossipWire::Initiate(Initiate {
+            agents,
+            arcs,
+            agent_keys,
+        }) => {
+            let remote_agents: Vec<_> = agents.into_iter().collect();
+
+            // digest every bucket we both hold
+            let local_arcs = sharded.inner.share_mut(|i, _| Ok(i.strategy.arcs()))?;
+            let buckets = buckets(&common_ranges(&local_arcs, &arcs), now_s());
+            let local_hashes = sharded.local_bucket_hashes(&buckets).await?;
+            let digests: Vec<_> = local_hashes
+                .iter()
+                .map(|(bucket, op_hashes)| (*bucket, BucketDigest::new(op_hashes)))
+                .collect();
+
+            let bucket_count = digests.len();
+            tracing::debug!(%bucket_count, "incoming 'Initiate'");
+
+            // for now, just always accept gossip initiates
+            sharded.inner.share_mut(move |i, _| {
+                let agent_infos = i.strategy.agent_infos_missing_from(&agent_keys);
+                let gossip =
+                    GossipWire::accept(i.local_agents.clone(), i.strategy.agent_keys(), digests);
+                i.queue(&con, &remote_agents, gossip);
+                i.queue_chunks(&con, &remote_agents, agent_infos, false);
+                Ok(())
+            })?;
+        }
+        GossipWire::Accept(Accept {
+            agents,
+            agent_keys,
+            digests,
+        }) => {
+            let remote_agents: Vec<_> = agents.into_iter().collect();
+
+            // find the buckets whose digests differ from ours
+            let buckets: Vec<_> = digests.iter().map(|(bucket, _)| *bucket).collect();
+            let local_hashes = sharded.local_bucket_hashes(&buckets).await?;
+            let op_hashes: Vec<(Bucket, Vec<Arc<KitsuneOpHash>>)> = digests
+                .into_iter()
+                .filter_map(|(bucket, digest)| {
+                    let op_hashes = local_hashes.get(&bucket)?;
+                    if BucketDigest::new(op_hashes) == digest {
+                        None
+                    } else {
+                        Some((bucket, op_hashes.iter().cloned().collect()))
+                    }
+                })
+                .collect();
+
+            let bucket_count = buckets.len();
+            let mismatched_bucket_count = op_hashes.len();
+            tracing::debug!(
+                %bucket_count,
+                %mismatched_bucket_count,
+                "incoming 'Accept'",
+            );
+
+            sharded.inner.share_mut(move |i, _| {
+                let agent_infos = i.strategy.agent_infos_missing_from(&agent_keys);
+                i.queue_chunks(&con, &remote_agents, agent_infos, false);
+
+                if op_hashes.is_empty() {
+                    // we're already in sync,
+                    // ... if we initiated this gossip, mark it as done.
+                    i.finish_initiate(&con.peer_cert());
+                } else {
+                    let gossip = GossipWire::op_hashes(i.local_agents.clone(), true, op_hashes);
+                    i.queue(&con, &remote_agents, gossip);
+                }
+                Ok(())
+            })?;
+        }
+        GossipWire::OpHashes(OpHashes {
+            agents,
+            reply,
+            op_hashes,
+        }) => {
+            let remote_agents: Vec<_> = agents.into_iter().collect();
+
+            // find the ops each of us doesn't have,
+            // remembering which range their basis is located in
+            let buckets: Vec<_> = op_hashes.iter().map(|(bucket, _)| *bucket).collect();
+            let local_hashes = sharded.local_bucket_hashes(&buckets).await?;
+            let mut out_hashes = OpRangeMap::new();
+            let mut in_hashes = OpRangeMap::new();
+            let mut reply_hashes: Vec<(Bucket, Vec<Arc<KitsuneOpHash>>)> = Vec::new();
+            for (bucket, remote_hashes) in op_hashes {
+                let local_hashes = match local_hashes.get(&bucket) {
+                    Some(local_hashes) => local_hashes,
+                    None => continue,
+                };
+                let range = (bucket.start_loc, bucket.end_loc);
+                let remote_hashes: OpHashSet = remote_hashes.into_iter().collect();
+                for op_hash in local_hashes.difference(&remote_hashes) {
+                    out_hashes.insert(op_hash.clone(), range);
+                }
+                for op_hash in remote_hashes.difference(local_hashes) {
+                    in_hashes.insert(op_hash.clone(), range);
+                }
+                if reply {
+                    reply_hashes.push((bucket, local_hashes.iter().cloned().collect()));
+                }
+            }
+
+            let remote_needs_op_count = out_hashes.len();
+            tracing::debug!(%reply, %remote_needs_op_count, "incoming 'OpHashes'");
+
+            let out_data = fetch_ops(sharded, out_hashes).await?;
+
+            sharded.inner.share_mut(move |i, _| {
+                i.strategy
+                    .incoming_op_ranges
+                    .entry(con.peer_cert())
+                    .or_default()
+                    .extend(in_hashes);
+                if reply {
+                    let gossip = GossipWire::op_hashes(i.local_agents.clone(), false, reply_hashes);
+                    i.queue(&con, &remote_agents, gossip);
+                }
+                i.queue_chunks(&con, &remote_agents, out_data, true);
+                Ok(())
+            })?;
+        }
+        GossipWire::Chunk(Chunk {
+            agents: remote_agents,
+            finished,
+            chunks,
+        }) => {
+            let chunk_count = chunks.len();
+            tracing::info!(
+                %finished,
+                %chunk_count,
+                "incoming 'Chunk'",
+            );
+
+            // parse/integrate the chunks
+            let futs = sharded.inner.share_mut(move |i, _| {
+                let peer_cert = con.peer_cert();
+                if finished {
+                    i.finish_initiate(&peer_cert);
+                }
+
+                let mut futs = Vec::new();
+
+                for chunk in chunks {
+                    match &*chunk {
+                        MetaOpData::Op(key, data) => {
+                            // we can only tell who holds an op
+                            // if the remote announced its range
+                            let range = match i
+                                .strategy
+                                .incoming_op_ranges
+                                .get_mut(&peer_cert)
+                                .and_then(|ranges| ranges.remove(key))
+                            {
+                                Some(range) => range,
+                                None => {
+                                    tracing::debug!(?key, "dropping unannounced op");
+                                    continue;
+                                }
+                            };
+
+                            // only hand the op to the local agents who hold its range
+                            for (agent, arc) in i.strategy.local_arcs.iter() {
+                                if !arc_overlaps_range(arc, range) {
+                                    continue;
+                                }
+                                let from_agent = remote_agents.iter().next().unwrap_or(agent);
+                                futs.push(sharded.evt_sender.gossip(
+                                    sharded.space.clone(),
+                                    agent.clone(),
+                                    from_agent.clone(),
+                                    key.clone(),
+                                    data.clone(),
+                                ));
+                            }
+                        }
+                        MetaOpData::Agent(agent_info_signed) => {
+                            // the agent store is shared,
+                            // so we only need to put it once
+                            if let Some(agent) = i.local_agents.iter().next() {
+                                futs.push(sharded.evt_sender.put_agent_info_signed(
+                                    PutAgentInfoSignedEvt {
+                                        space: sharded.space.clone(),
+                                        agent: agent.clone(),
+                                        agent_info_signed: agent_info_signed.clone(),
+                                    },
+                                ));
+                            }
+                            i.strategy
+                                .local_agent_infos
+                                .insert(chunk.key(), chunk.clone());
+                        }
+                    }
+                }
+
+                if finished {
+                    i.strategy.incoming_op_ranges.remove(&peer_cert);
+                }
+
+                Ok(futs)
+            })?;
+
+            if !futs.is_empty() {
+                futures::future::try_join_all(futs)
+                    .await
+                    .map_err(KitsuneError::other)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch the data for ops from the local agents which hold
+/// the ranges their basis is located in.
+/// A range may be split between several local agents,
+/// so each of them is asked in turn for the ops still missing.
+async fn fetch_ops(
+    sharded: &ShardedRangeMod,
+    op_hashes: OpRangeMap,
+) -> KitsuneResult<Vec<Arc<MetaOpData>>> {
+    let local_arcs = sharded
+        .inner
+        .share_mut(|i, _| Ok(i.strategy.local_arcs.clone()))?;
+
+    let mut by_range: HashMap<LocRange, Vec<Arc<KitsuneOpHash>>> = HashMap::new();
+    for (op_hash, range) in op_hashes {
+        by_range.entry(range).or_default().push(op_hash);
+    }
+
+    let mut out = Vec::new();
+    for (range, mut op_hashes) in by_range {
+        for (agent, arc) in local_arcs.iter() {
+            if op_hashes.is_empty() {
+                break;
+            }
+            if !arc_overlaps_range(arc, range) {
+                continue;
+            }
+            let ops = match sharded
+                .evt_sender
+                .fetch_op_hash_data(FetchOpHashDataEvt {
+                    space: sharded.space.clone(),
+                    agent: agent.clone(),
+                    op_hashes: op_hashes.clone(),
+                })
+                .await
+            {
+                Err(e) => {
+                    tracing::warn!("failed to fetch ops for gossip: {:?}", e);
+                    continue;
+                }
+                Ok(ops) => ops,
+            };
+            op_hashes.retain(|op_hash| !ops.iter().any(|(found, _)| found == op_hash));
+            out.extend(
+                ops.into_iter()
+                    .map(|(op_hash, data)| Arc::new(MetaOpData::Op(op_hash, data))),
+            );
+        }
+    }
+    Ok(out)
+}
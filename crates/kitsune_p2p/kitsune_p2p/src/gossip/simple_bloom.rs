@@ -1,65 +1,17 @@
-use crate::agent_store::AgentInfoSigned;
-use crate::event::MetricQuery;
-use crate::event::MetricQueryAnswer;
+use super::common::*;
+use super::{MetaOpData, MetaOpKey};
 use crate::types::event::*;
 use crate::types::gossip::*;
 use crate::types::*;
+use futures::future::FutureExt;
+use ghost_actor::dependencies::must_future::MustBoxFuture;
 use ghost_actor::dependencies::tracing;
-use kitsune_p2p_types::config::*;
-use kitsune_p2p_types::metrics::*;
 use kitsune_p2p_types::tx2::tx2_api::*;
 use kitsune_p2p_types::tx2::tx2_utils::*;
 use kitsune_p2p_types::*;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-/// max send buffer size (keep it under 16384 with a little room for overhead)
-/// (this is not a tuning_param because it must be coordinated
-/// with the constant in PoolBuf which cannot be set at runtime)
-const MAX_SEND_BUF_BYTES: usize = 16000;
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
-pub enum MetaOpKey {
-    /// data key type
-    Op(Arc<KitsuneOpHash>),
-
-    /// agent key type
-    Agent(Arc<KitsuneAgent>, u64),
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-pub enum MetaOpData {
-    /// data chunk type
-    Op(Arc<KitsuneOpHash>, Vec<u8>),
-
-    /// agent chunk type
-    Agent(AgentInfoSigned),
-}
-
-impl MetaOpData {
-    fn byte_count(&self) -> usize {
-        match self {
-            MetaOpData::Op(h, d) => (**h).len() + d.len(),
-            MetaOpData::Agent(a) => {
-                let h = (**a.agent).len();
-                let s = (**a.signature).len();
-                let d = a.encoded_bytes.len();
-                h + s + d
-            }
-        }
-    }
-
-    fn key(&self) -> Arc<MetaOpKey> {
-        let key = match self {
-            MetaOpData::Op(key, _) => MetaOpKey::Op(key.clone()),
-            MetaOpData::Agent(s) => MetaOpKey::Agent(s.agent.clone(), s.signed_at_ms),
-        };
-        Arc::new(key)
-    }
-}
-
-type KeySet = HashSet<Arc<MetaOpKey>>;
-type DataMap = HashMap<Arc<MetaOpKey>, Arc<MetaOpData>>;
 type BloomFilter = bloomfilter::Bloom<Arc<MetaOpKey>>;
 
 pub(crate) fn encode_bloom_filter(bloom: &BloomFilter) -> PoolBuf {
@@ -103,9 +55,7 @@ pub(crate) fn decode_bloom_filter(bloom: &[u8]) -> BloomFilter {
     bloomfilter::Bloom::from_existing(&bloom[44..], bitmap_bits, k_num, sip_keys)
 }
 
-mod step_1_check_inner;
 mod step_2_local_sync_inner;
-mod step_3_initiate_inner;
 mod step_4_com_loop_inner;
 
 kitsune_p2p_types::write_codec_enum! {
@@ -133,359 +83,73 @@ kitsune_p2p_types::write_codec_enum! {
     }
 }
 
-struct NodeInfo {
-    last_touch: std::time::SystemTime,
-    was_err: bool,
-}
+pub(crate) type SimpleBloomMod = GossipMod<SimpleBloom>;
 
-pub(crate) enum HowToConnect {
-    Con(Tx2ConHnd<wire::Wire>),
-    Url(TxUrl),
-}
-
-pub(crate) struct SimpleBloomModInner {
-    local_agents: HashSet<Arc<KitsuneAgent>>,
+pub(crate) struct SimpleBloom {
     local_bloom: BloomFilter,
     local_data_map: DataMap,
     local_key_set: KeySet,
-
-    /// Metrics to be recorded at the end of this round of gossip
-    pending_metrics: Vec<(Vec<Arc<KitsuneAgent>>, NodeInfo)>,
-
-    last_initiate_check_us: ProcCountMicros,
-    initiate_tgt: Option<GossipTgt>,
-
-    incoming: Vec<(Tx2ConHnd<wire::Wire>, GossipWire)>,
-
-    last_outgoing_us: ProcCountMicros,
-    outgoing: Vec<(GossipTgt, HowToConnect, GossipWire)>,
 }
 
-impl SimpleBloomModInner {
-    pub fn new() -> Self {
-        // pick an old instant for initialization
-        const ONE_DAY_MICROS: i64 = 1000 * 1000 * 60 * 60 * 24;
-        let old_us = proc_count_now_us() - ONE_DAY_MICROS;
+impl GossipStrategy for SimpleBloom {
+    type Wire = GossipWire;
 
+    fn new() -> Self {
         Self {
-            local_agents: HashSet::new(),
             local_bloom: bloomfilter::Bloom::new(1, 1),
             local_data_map: HashMap::new(),
             local_key_set: HashSet::new(),
-
-            pending_metrics: Vec::new(),
-
-            last_initiate_check_us: old_us,
-            initiate_tgt: None,
-
-            incoming: Vec::new(),
-
-            last_outgoing_us: old_us,
-            outgoing: Vec::new(),
         }
     }
 
-    /// Record a metric to be recorded at the end of this gossip round
-    // TODO: remove NodeInfo
-    fn record_pending_metric(&mut self, agents: Vec<Arc<KitsuneAgent>>, was_err: bool) {
-        let info = NodeInfo {
-            last_touch: std::time::SystemTime::now(),
-            was_err,
-        };
-        self.pending_metrics.push((agents, info))
+    fn agent_infos(&self) -> &DataMap {
+        &self.local_data_map
     }
-}
-
-enum GossipIterationResult {
-    Close,
-    Good,
-}
-
-enum CheckResult {
-    Close,
-    NotReady,
-    SyncAndInitiate,
-    SkipSyncAndInitiate,
-}
-
-pub(crate) struct SimpleBloomMod {
-    tuning_params: KitsuneP2pTuningParams,
-    send_interval_ms: u64,
-    space: Arc<KitsuneSpace>,
-    ep_hnd: Tx2EpHnd<wire::Wire>,
-    evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
-    inner: Share<SimpleBloomModInner>,
-}
-
-impl SimpleBloomMod {
-    pub fn new(
-        tuning_params: KitsuneP2pTuningParams,
-        space: Arc<KitsuneSpace>,
-        ep_hnd: Tx2EpHnd<wire::Wire>,
-        evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
-    ) -> Arc<Self> {
-        let inner = SimpleBloomModInner::new();
-
-        let send_interval_ms: u64 = (
-            // !*)&^$# cargo fmt...
-            16384.0    // max bytes in a gossip msg
-            * 8.0      // bits per byte
-            * 1000.0   // milliseconds
-            / 1024.0   // kbps
-            / 1024.0   // mbps
-            / tuning_params.gossip_output_target_mbps
-        ) as u64;
-
-        let this = Arc::new(Self {
-            tuning_params,
-            space,
-            ep_hnd,
-            send_interval_ms,
-            evt_sender,
-            inner: Share::new(inner),
-        });
-
-        // this value needs to be somewhat frequent to support send timing
-        let loop_check_interval_ms = std::cmp::max(send_interval_ms / 3, 100);
-
-        let gossip = this.clone();
-        metric_task(async move {
-            loop {
-                tokio::time::sleep(std::time::Duration::from_millis(
-                    loop_check_interval_ms as u64,
-                ))
-                .await;
 
-                if let GossipIterationResult::Close = gossip.run_one_iteration().await {
-                    tracing::warn!("gossip loop ending");
-                    break;
-                }
-            }
-
-            KitsuneResult::Ok(())
-        });
-
-        this
+    fn initiate(&self, local_agents: &HashSet<Arc<KitsuneAgent>>) -> GossipWire {
+        let gossip = encode_bloom_filter(&self.local_bloom);
+        let bloom_byte_count = gossip.len();
+        tracing::debug!(%bloom_byte_count, "initiate with our bloom");
+        GossipWire::initiate(local_agents.clone(), gossip)
     }
 
-    /// Get metrics data via event channel in the form of NodeInfo
-    // TODO: remove NodeInfo
-    async fn get_metric_info(
-        &self,
-        agents: Vec<Arc<KitsuneAgent>>,
-    ) -> KitsuneP2pResult<Option<NodeInfo>> {
-        // We pick an arbitrary agent for now, since in a full-sync situation,
-        // any agent should have the same data as any other agent.
-        // TODO: this will naturally change after sharding.
-        let arbitrary_agent = agents
-            .first()
-            .expect("Gossip must have a least one from_agent")
-            .clone();
-        let last_touch = match self
-            .evt_sender
-            .query_metrics(MetricQuery::LastSync {
-                agent: arbitrary_agent,
-            })
-            .await?
-        {
-            MetricQueryAnswer::LastSync(time) => time,
-            _ => unreachable!(),
-        };
-        Ok(last_touch.map(|last_touch| NodeInfo {
-            last_touch,
-            was_err: false,
-        }))
-    }
-
-    /// Record a metric via event channel
-    // TODO: remove NodeInfo
-    async fn record_metric(
-        &self,
-        agents: Vec<Arc<KitsuneAgent>>,
-        info: NodeInfo,
-    ) -> KitsuneP2pResult<()> {
-        if info.was_err {
-            for agent in agents {
-                self.evt_sender
-                    .put_metric_datum(MetricDatum {
-                        agent,
-                        kind: MetricKind::ConnectError,
-                        timestamp: info.last_touch,
-                    })
-                    .await?;
-            }
-        } else {
-            for agent in agents {
-                self.evt_sender
-                    .put_metric_datum(MetricDatum {
-                        agent,
-                        kind: MetricKind::QuickGossip,
-                        timestamp: info.last_touch,
-                    })
-                    .await?;
-            }
-        }
-        Ok(())
-    }
-
-    async fn run_one_iteration(&self) -> GossipIterationResult {
-        // # Step 1 - check state
-        //   - if closed, send GossipIterationResult::Close
-        //   - if not ready, exit early
-        let sync_and_initiate = match self.step_1_check().await {
-            CheckResult::Close => return GossipIterationResult::Close,
-            CheckResult::NotReady => return GossipIterationResult::Good,
-            CheckResult::SyncAndInitiate => true,
-            CheckResult::SkipSyncAndInitiate => false,
-        };
-
-        if sync_and_initiate {
-            // # Step 2 - run a local sync, updating bloom / data_map / key_set
-            match self.step_2_local_sync().await {
-                Err(_) => return GossipIterationResult::Close,
-                Ok(false) => return GossipIterationResult::Good,
-                Ok(true) => (),
-            }
-
-            // # Step 3 - check target for initiation
-            //   - if we don't have a current initiation target, pick one
-            //   - send the initiate message
-            match self.step_3_initiate().await {
-                Err(_) => return GossipIterationResult::Close,
-                Ok(false) => return GossipIterationResult::Good,
-                Ok(true) => (),
-            }
-        }
+    fn local_sync(gossip: &SimpleBloomMod) -> MustBoxFuture<'_, KitsuneResult<bool>> {
+        async move {
+            let local_agents = gossip.inner.share_mut(|i, _| Ok(i.local_agents.clone()))?;
 
-        // # Step 4 - loop on incoming/outgoing data in parallel
-        //   - if processing incoming data is slow we want to keep
-        //     sending outgoing data at appropriate times
-        //   - if we get a "finished" chunk from our initaite target,
-        //     clear the initiate target
-        //   - if we get through all incoming/outgoing, move on
-        //   - if we take > gossip_interval, move on
-        match self.step_4_com_loop().await {
-            Err(_) => return GossipIterationResult::Close,
-            Ok(false) => return GossipIterationResult::Good,
-            Ok(true) => (),
-        }
+            let (data_map, key_set, bloom) =
+                match gossip.step_2_local_sync_inner(local_agents).await {
+                    Err(e) => {
+                        tracing::warn!("gossip error: {:?}", e);
+                        return Ok(false);
+                    }
+                    Ok(r) => r,
+                };
 
-        // # Step 5 - flush all pending metrics via KitsuneP2pEvent channel
-        //   TODO: this may not be technically correct, since we may want to
-        //       record metrics from previous steps even if those other steps
-        //       short-circuited this iteration. Revisit.
-        match self.step_5_flush_metrics().await {
-            Err(_) => return GossipIterationResult::Close,
-            Ok(false) => return GossipIterationResult::Good,
-            Ok(true) => (),
-        }
-
-        GossipIterationResult::Good
-    }
+            gossip.inner.share_mut(move |i, _| {
+                i.strategy.local_data_map = data_map;
+                i.strategy.local_key_set = key_set;
+                i.strategy.local_bloom = bloom;
+                Ok(())
+            })?;
 
-    async fn step_1_check(&self) -> CheckResult {
-        match self.step_1_check_inner().await {
-            Err(_) => CheckResult::Close,
-            Ok(r) => r,
+            Ok(true)
         }
+        .boxed()
+        .into()
     }
 
-    async fn step_2_local_sync(&self) -> KitsuneResult<bool> {
-        let local_agents = self.inner.share_mut(|i, _| Ok(i.local_agents.clone()))?;
-
-        let (data_map, key_set, bloom) = match self.step_2_local_sync_inner(local_agents).await {
-            Err(e) => {
-                tracing::warn!("gossip error: {:?}", e);
-                return Ok(false);
-            }
-            Ok(r) => r,
-        };
-
-        self.inner.share_mut(move |i, _| {
-            i.local_data_map = data_map;
-            i.local_key_set = key_set;
-            i.local_bloom = bloom;
-            Ok(())
-        })?;
-
-        Ok(true)
-    }
-
-    async fn step_3_initiate(&self) -> KitsuneP2pResult<bool> {
-        self.step_3_initiate_inner().await?;
-        Ok(true)
-    }
-
-    async fn step_4_com_loop(&self) -> KitsuneResult<bool> {
-        self.step_4_com_loop_inner().await?;
-        Ok(true)
-    }
-
-    async fn step_5_flush_metrics(&self) -> KitsuneP2pResult<bool> {
-        let metrics: Vec<_> = self
-            .inner
-            .share_mut(|i, _| Ok(i.pending_metrics.drain(..).collect()))?;
-        for (agents, info) in metrics {
-            self.record_metric(agents, info).await?;
-        }
-        Ok(true)
-    }
-}
-
-impl AsGossipModule for SimpleBloomMod {
-    fn incoming_gossip(
-        &self,
+    fn incoming(
+        gossip: &SimpleBloomMod,
         con: Tx2ConHnd<wire::Wire>,
-        gossip_data: Box<[u8]>,
-    ) -> KitsuneResult<()> {
-        use kitsune_p2p_types::codec::*;
-        let (_, gossip) = GossipWire::decode_ref(&gossip_data).map_err(KitsuneError::other)?;
-        self.inner.share_mut(move |i, _| {
-            i.incoming.push((con, gossip));
-            if i.incoming.len() > 20 {
-                tracing::warn!(
-                    "Overloaded with incoming gossip.. {} messages",
-                    i.incoming.len()
-                );
-            }
-            Ok(())
-        })
-    }
-
-    fn local_agent_join(&self, a: Arc<KitsuneAgent>) {
-        let _ = self.inner.share_mut(move |i, _| {
-            i.local_agents.insert(a);
-            Ok(())
-        });
-    }
-
-    fn local_agent_leave(&self, a: Arc<KitsuneAgent>) {
-        let _ = self.inner.share_mut(move |i, _| {
-            i.local_agents.remove(&a);
-            Ok(())
-        });
-    }
-}
-
-struct SimpleBloomModFactory;
-
-impl AsGossipModuleFactory for SimpleBloomModFactory {
-    fn spawn_gossip_task(
-        &self,
-        tuning_params: KitsuneP2pTuningParams,
-        space: Arc<KitsuneSpace>,
-        ep_hnd: Tx2EpHnd<wire::Wire>,
-        evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
-    ) -> GossipModule {
-        GossipModule(SimpleBloomMod::new(
-            tuning_params,
-            space,
-            ep_hnd,
-            evt_sender,
-        ))
+        msg: GossipWire,
+    ) -> MustBoxFuture<'_, KitsuneResult<()>> {
+        step_4_com_loop_inner::step_4_com_loop_inner_incoming(gossip, con, msg)
+            .boxed()
+            .into()
     }
 }
 
 pub fn factory() -> GossipModuleFactory {
-    GossipModuleFactory(Arc::new(SimpleBloomModFactory))
+    super::common::factory::<SimpleBloom>()
 }
@@ -101,6 +101,7 @@ impl SpaceInternalHandler for Space {
         let evt_sender = self.evt_sender.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
         let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
+        let storage_arc_half_length = self.config.tuning_params.agent_storage_arc_half_length;
         Ok(async move {
             let urls = vec![bound_url.into()];
             for agent in agent_list {
@@ -111,7 +112,7 @@ impl SpaceInternalHandler for Space {
                 let agent_info_signed = AgentInfoSigned::sign(
                     space.clone(),
                     agent.clone(),
-                    u32::MAX,
+                    storage_arc_half_length,
                     urls.clone(),
                     signed_at_ms,
                     expires_at_ms,
@@ -442,14 +443,13 @@ impl Space {
         ep_hnd: Tx2EpHnd<wire::Wire>,
         config: Arc<KitsuneP2pConfig>,
     ) -> Self {
-        let gossip_mod_fact = if &config.tuning_params.gossip_strategy == "simple-bloom" {
-            crate::gossip::simple_bloom::factory()
-        } else {
-            panic!(
-                "unknown gossip strategy: {}",
-                config.tuning_params.gossip_strategy
-            );
-        };
+        let gossip_mod_fact = crate::gossip::factory(&config.tuning_params.gossip_strategy)
+            .unwrap_or_else(|| {
+                panic!(
+                    "unknown gossip strategy: {}",
+                    config.tuning_params.gossip_strategy
+                )
+            });
         let gossip_mod = gossip_mod_fact.spawn_gossip_task(
             config.tuning_params.clone(),
             space.clone(),
@@ -461,4 +461,143 @@ mod tests {
         harness.ghost_actor_shutdown().await?;
         Ok(())
     }
+
+    /// Test that sharded range gossip syncs the ops whose basis
+    /// both agents hold, and only hands agents ops within their arcs.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sharded_range_gossip_partial_arcs() -> Result<(), KitsuneP2pError> {
+        use crate::gossip::sharded_range::common_ranges;
+        use kitsune_p2p_types::config::tuning_params_struct;
+        use kitsune_p2p_types::sgd_arc::MAX_HALF_LENGTH;
+
+        observability::test_run().ok();
+
+        // - Each agent holds three quarters of the space,
+        //   so the arcs always overlap
+        let mut tuning_params = tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_strategy = "sharded-range".to_string();
+        tuning_params.agent_storage_arc_half_length = MAX_HALF_LENGTH / 4 * 3;
+        let tuning_params = Arc::new(tuning_params);
+        let (harness, _evt) =
+            spawn_test_harness_with_tuning_params(TransportConfig::Mem {}, tuning_params).await?;
+
+        harness.add_space().await?;
+        let (a1, _) = harness.add_direct_agent("one".into()).await?;
+        let (a2, _) = harness.add_direct_agent("two".into()).await?;
+
+        // - Insert data located all around the ring for both agents
+        let mut injected = Vec::new();
+        for (idx, agent) in [a1.clone(), a2.clone()].iter().enumerate() {
+            for n in 0..64 {
+                let data = format!("agent-{}-data-{}", idx + 1, n);
+                let op_hash = harness
+                    .inject_gossip_data(agent.clone(), data.clone())
+                    .await?;
+                injected.push((agent.clone(), op_hash, data));
+            }
+        }
+
+        // TODO: remove when we have bootstrapping for tests
+        // needed until we have some way of bootstrapping
+        harness.magic_peer_info_exchange().await?;
+
+        // TODO - a better way to await gossip??
+        tokio::time::sleep(std::time::Duration::from_millis(3000)).await;
+
+        let peer_data = harness.dump_local_peer_data(a1.clone()).await?;
+        let arc1 = peer_data.get(&a1).unwrap().storage_arc;
+        let arc2 = peer_data.get(&a2).unwrap().storage_arc;
+        let common = common_ranges(&[arc1], &[arc2]);
+        assert!(!common.is_empty());
+
+        let checks = [
+            (a1.clone(), arc1, a2.clone()),
+            (a2.clone(), arc2, a1.clone()),
+        ];
+        for (agent, arc, other) in checks.iter() {
+            let res = harness.dump_local_gossip_data(agent.clone()).await?;
+
+            // - Check the agent was only given ops within its arc
+            for (op_hash, data) in res.iter() {
+                let loc = harness_op_basis_loc(data);
+                let own = injected.iter().any(|(a, h, _)| a == agent && h == op_hash);
+                assert!(own || arc.contains(loc), "{} is outside the arc", data);
+            }
+
+            // - Check the agent has all the other agent's ops
+            //   located where both of them hold
+            for (_, op_hash, data) in injected.iter().filter(|(a, _, _)| a == other) {
+                let loc = harness_op_basis_loc(data);
+                if common
+                    .iter()
+                    .any(|(start, end)| (*start..=*end).contains(&loc))
+                {
+                    assert_eq!(Some(data), res.get(op_hash));
+                }
+            }
+        }
+
+        harness.ghost_actor_shutdown().await?;
+        Ok(())
+    }
+
+    /// Test that sharded range gossip syncs an op located
+    /// in a ring segment neither agent holds all of.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sharded_range_gossip_partly_held_segment() -> Result<(), KitsuneP2pError> {
+        use crate::gossip::sharded_range::{common_ranges, segment_bounds, segment_of};
+        use kitsune_p2p_types::config::tuning_params_struct;
+        use kitsune_p2p_types::sgd_arc::MAX_HALF_LENGTH;
+
+        observability::test_run().ok();
+
+        let mut tuning_params = tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_strategy = "sharded-range".to_string();
+        tuning_params.agent_storage_arc_half_length = MAX_HALF_LENGTH / 4 * 3;
+        let tuning_params = Arc::new(tuning_params);
+        let (harness, _evt) =
+            spawn_test_harness_with_tuning_params(TransportConfig::Mem {}, tuning_params).await?;
+
+        harness.add_space().await?;
+        let (a1, _) = harness.add_direct_agent("one".into()).await?;
+        let (a2, _) = harness.add_direct_agent("two".into()).await?;
+
+        // TODO: remove when we have bootstrapping for tests
+        // needed until we have some way of bootstrapping
+        harness.magic_peer_info_exchange().await?;
+
+        let peer_data = harness.dump_local_peer_data(a1.clone()).await?;
+        let arc1 = peer_data.get(&a1).unwrap().storage_arc;
+        let arc2 = peer_data.get(&a2).unwrap().storage_arc;
+
+        // - Find data both agents hold,
+        //   located in a segment the first agent only holds part of
+        let partly_held = |loc: u32| {
+            let (start, end) = segment_bounds(segment_of(loc));
+            arc1.contains(loc)
+                && arc2.contains(loc)
+                && !(arc1.contains(start) && arc1.contains(end))
+        };
+        let data = (0..)
+            .map(|n| format!("agent-1-shared-data-{}", n))
+            .find(|data| partly_held(harness_op_basis_loc(data)))
+            .unwrap();
+        let loc = harness_op_basis_loc(&data);
+        assert!(common_ranges(&[arc1], &[arc2])
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&loc)));
+
+        // - It is the only op either agent has
+        let op_hash = harness.inject_gossip_data(a1.clone(), data.clone()).await?;
+
+        // TODO - a better way to await gossip??
+        tokio::time::sleep(std::time::Duration::from_millis(3000)).await;
+
+        let res = harness.dump_local_gossip_data(a2.clone()).await?;
+        assert_eq!(Some(&data), res.get(&op_hash));
+        assert_eq!(res.len(), 1);
+
+        harness.ghost_actor_shutdown().await?;
+        Ok(())
+    }
 }
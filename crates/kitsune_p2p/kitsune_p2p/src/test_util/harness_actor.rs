@@ -1,4 +1,5 @@
 use super::*;
+use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 
 ghost_actor::ghost_chan! {
     /// The api for the test harness controller
@@ -77,6 +78,21 @@ pub async fn spawn_test_harness(
         HarnessEventChannel,
     ),
     KitsuneP2pError,
+> {
+    spawn_test_harness_with_tuning_params(sub_config, KitsuneP2pTuningParams::default()).await
+}
+
+/// construct a test suite around a sub transport config concept,
+/// with every agent using the given tuning params
+pub async fn spawn_test_harness_with_tuning_params(
+    sub_config: TransportConfig,
+    tuning_params: KitsuneP2pTuningParams,
+) -> Result<
+    (
+        ghost_actor::GhostSender<HarnessControlApi>,
+        HarnessEventChannel,
+    ),
+    KitsuneP2pError,
 > {
     let harness_chan = HarnessEventChannel::new("");
 
@@ -92,7 +108,12 @@ pub async fn spawn_test_harness(
         .create_channel::<HarnessInner>()
         .await?;
 
-    tokio::task::spawn(builder.spawn(HarnessActor::new(i_s, harness_chan.clone(), sub_config)));
+    tokio::task::spawn(builder.spawn(HarnessActor::new(
+        i_s,
+        harness_chan.clone(),
+        sub_config,
+        tuning_params,
+    )));
 
     Ok((controller, harness_chan))
 }
@@ -112,6 +133,7 @@ struct HarnessActor {
     i_s: ghost_actor::GhostSender<HarnessInner>,
     harness_chan: HarnessEventChannel,
     sub_config: TransportConfig,
+    tuning_params: KitsuneP2pTuningParams,
     space_list: Vec<Arc<KitsuneSpace>>,
     agents: HashMap<
         Arc<KitsuneAgent>,
@@ -127,11 +149,13 @@ impl HarnessActor {
         i_s: ghost_actor::GhostSender<HarnessInner>,
         harness_chan: HarnessEventChannel,
         sub_config: TransportConfig,
+        tuning_params: KitsuneP2pTuningParams,
     ) -> Self {
         Self {
             i_s,
             harness_chan,
             sub_config,
+            tuning_params,
             space_list: Vec::new(),
             agents: HashMap::new(),
         }
@@ -208,6 +232,7 @@ impl HarnessControlApiHandler for HarnessActor {
     ) -> HarnessControlApiHandlerResult<(Arc<KitsuneAgent>, ghost_actor::GhostSender<KitsuneP2p>)>
     {
         let mut proxy_agent_config = KitsuneP2pConfig::default();
+        proxy_agent_config.tuning_params = self.tuning_params.clone();
         proxy_agent_config
             .transport_pool
             .push(TransportConfig::Proxy {
@@ -236,6 +261,7 @@ impl HarnessControlApiHandler for HarnessActor {
     ) -> HarnessControlApiHandlerResult<(Arc<KitsuneAgent>, ghost_actor::GhostSender<KitsuneP2p>)>
     {
         let mut direct_agent_config = KitsuneP2pConfig::default();
+        direct_agent_config.tuning_params = self.tuning_params.clone();
         direct_agent_config
             .transport_pool
             .push(TransportConfig::Proxy {
@@ -265,6 +291,7 @@ impl HarnessControlApiHandler for HarnessActor {
     ) -> HarnessControlApiHandlerResult<(Arc<KitsuneAgent>, ghost_actor::GhostSender<KitsuneP2p>)>
     {
         let mut nat_agent_config = KitsuneP2pConfig::default();
+        nat_agent_config.tuning_params = self.tuning_params.clone();
         nat_agent_config
             .transport_pool
             .push(TransportConfig::Proxy {
@@ -54,6 +54,20 @@ pub(crate) async fn spawn_test_agent(
     Ok((agent, p2p, control))
 }
 
+/// The location of the basis of a harness op.
+/// Harness ops are located by their data rather than by their hash,
+/// so gossip which confuses the two doesn't go unnoticed.
+pub(crate) fn harness_op_basis_loc(data: &str) -> u32 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish() as u32
+}
+
+/// Harness ops carry no timestamp,
+/// so they are all treated as authored at the UNIX epoch.
+const HARNESS_OP_AUTHORED_S: i64 = 0;
+
 use lair_keystore_api::entry::EntrySignEd25519;
 use lair_keystore_api::internal::sign_ed25519::*;
 
@@ -228,9 +242,19 @@ impl KitsuneP2pEventHandler for AgentHarness {
 
     fn handle_fetch_op_hashes_for_constraints(
         &mut self,
-        _input: FetchOpHashesForConstraintsEvt,
+        input: FetchOpHashesForConstraintsEvt,
     ) -> KitsuneP2pEventHandlerResult<Vec<Arc<super::KitsuneOpHash>>> {
-        let hashes: Vec<Arc<super::KitsuneOpHash>> = self.gossip_store.keys().cloned().collect();
+        if HARNESS_OP_AUTHORED_S < input.since_utc_epoch_s
+            || HARNESS_OP_AUTHORED_S >= input.until_utc_epoch_s
+        {
+            return Ok(async move { Ok(Vec::new()) }.boxed().into());
+        }
+        let hashes: Vec<Arc<super::KitsuneOpHash>> = self
+            .gossip_store
+            .iter()
+            .filter(|(_, data)| input.sgd_arc.contains(harness_op_basis_loc(data)))
+            .map(|(op_hash, _)| op_hash.clone())
+            .collect();
         let slug_hashes: Vec<Slug> = hashes.iter().map(|h| h.into()).collect();
         tracing::trace!(?slug_hashes, "FETCH_OP_HASHES");
         Ok(async move { Ok(hashes) }.boxed().into())
@@ -77,7 +77,11 @@ pub mod tuning_params_struct {
     }
 
     mk_tune! {
-        /// Gossip strategy to use. [Default: simple-bloom]
+        /// Gossip strategy to use, one of:
+        /// - `simple-bloom`: full sync of the space via bloom filters
+        /// - `sharded-range`: reconcile only the parts of the space
+        ///   held by both nodes, by comparing per-range digests
+        /// [Default: simple-bloom]
         gossip_strategy: String = "simple-bloom".to_string(),
 
         /// Delay between gossip loop iteration. [Default: 1s]
@@ -115,6 +119,12 @@ pub mod tuning_params_struct {
         /// Default agent expires after milliseconds. [Default: 20 minutes]
         agent_info_expires_after_ms: u32 = 1000 * 60 * 20,
 
+        /// Half-length of the storage arc our local agents publish,
+        /// i.e. how much of the space they hold. Only the
+        /// `sharded-range` gossip strategy syncs partial arcs.
+        /// [Default: u32::MAX, the whole space]
+        agent_storage_arc_half_length: u32 = u32::MAX,
+
         /// Tls in-memory session storage capacity. [Default: 512]
         tls_in_mem_session_storage: u32 = 512,
 
@@ -2,13 +2,15 @@ use crate::{
     error::{BundleError, MrBundleResult},
     location::Location,
     manifest::Manifest,
+    remote::RemoteConfig,
     resource::ResourceBytes,
+    signature::BundleSignature,
 };
 use aingle_util::ffs;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
@@ -46,6 +48,19 @@ where
     //       struct into two versions for each case.
     #[serde(skip)]
     root_dir: Option<PathBuf>,
+
+    /// How resources at a `Location::Url` are fetched and cached.
+    #[serde(skip)]
+    remote: RemoteConfig,
+
+    /// Whether to refuse resolving resources whose content isn't covered by
+    /// the manifest, i.e. anything but bundled resources and pinned URLs.
+    #[serde(skip)]
+    content_addressed_only: bool,
+
+    /// Publisher signatures over the manifest and resources.
+    #[serde(default)]
+    signatures: Vec<BundleSignature>,
 }
 
 impl<M> Bundle<M>
@@ -104,18 +119,68 @@ where
             manifest,
             resources,
             root_dir,
+            remote: RemoteConfig::default(),
+            content_addressed_only: false,
+            signatures: Vec::new(),
         })
     }
 
+    /// Set how resources at a `Location::Url` are fetched and cached
+    pub fn with_remote_config(mut self, remote: RemoteConfig) -> Self {
+        self.remote = remote;
+        self
+    }
+
+    /// How resources at a `Location::Url` are fetched and cached
+    pub fn remote_config(&self) -> &RemoteConfig {
+        &self.remote
+    }
+
+    /// Set whether to only resolve resources whose content is fixed by the
+    /// manifest: bundled resources and URLs pinned to a hash. Signatures only
+    /// vouch for such resources.
+    pub fn with_content_addressed_only(mut self, content_addressed_only: bool) -> Self {
+        self.content_addressed_only = content_addressed_only;
+        self
+    }
+
+    /// Whether only resources whose content is fixed by the manifest are resolved
+    pub fn is_content_addressed_only(&self) -> bool {
+        self.content_addressed_only
+    }
+
     /// Accessor for the Manifest
     pub fn manifest(&self) -> &M {
         &self.manifest
     }
 
+    /// The bytes a publisher signs: a deterministic encoding of the
+    /// manifest and the bundled resources, excluding any signatures.
+    pub fn signable_bytes(&self) -> MrBundleResult<Vec<u8>> {
+        let resources: BTreeMap<_, _> = self.resources.iter().collect();
+        Ok(rmp_serde::to_vec_named(&(&self.manifest, resources))?)
+    }
+
+    /// Add a publisher's signature over the [`signable_bytes`](Bundle::signable_bytes)
+    pub fn with_signature(mut self, signature: BundleSignature) -> Self {
+        self.signatures.push(signature);
+        self
+    }
+
+    /// Accessor for the publisher signatures.
+    /// These are not verified: use the signable bytes to check them.
+    pub fn signatures(&self) -> &[BundleSignature] {
+        &self.signatures
+    }
+
     /// Return a new Bundle with an updated manifest, subject to the same
     /// validation constraints as creating a new Bundle from scratch.
+    ///
+    /// Signatures are dropped, since they don't cover the new manifest.
     pub fn update_manifest(self, manifest: M) -> MrBundleResult<Self> {
-        Self::from_parts(manifest, self.resources, self.root_dir)
+        Ok(Self::from_parts(manifest, self.resources, self.root_dir)?
+            .with_remote_config(self.remote)
+            .with_content_addressed_only(self.content_addressed_only))
     }
 
     /// Load a Bundle into memory from a file
@@ -129,8 +194,12 @@ where
     }
 
     /// Retrieve the bytes for a resource at a Location, downloading it if
-    /// necessary
+    /// necessary. Downloads are checked against the hash pinned in the
+    /// Location, if any, and go through the cache set by `with_remote_config`.
     pub async fn resolve(&self, location: &Location) -> MrBundleResult<Cow<'_, ResourceBytes>> {
+        if self.content_addressed_only && !location.is_content_addressed() {
+            return Err(BundleError::NotContentAddressed(location.clone()).into());
+        }
         let bytes = match &location.normalize(self.root_dir.as_ref())? {
             Location::Bundled(path) => Cow::Borrowed(
                 self.resources
@@ -138,7 +207,9 @@ where
                     .ok_or_else(|| BundleError::BundledResourceMissing(path.clone()))?,
             ),
             Location::Path(path) => Cow::Owned(crate::location::resolve_local(path).await?),
-            Location::Url(url) => Cow::Owned(crate::location::resolve_remote(url).await?),
+            Location::Url(url) => {
+                Cow::Owned(crate::remote::resolve_remote(url, &self.remote).await?)
+            }
         };
         Ok(bytes)
     }
@@ -243,4 +314,50 @@ mod tests {
             Err(MrBundleError::BundleError(BundleError::BundledPathNotInManifest(path))) if path == PathBuf::from("3.thing")
         );
     }
+
+    #[tokio::test]
+    async fn content_addressed_only() {
+        let bundled = Location::Bundled("1.thing".into());
+        let path = Location::Path("/nowhere/2.thing".into());
+        let url = Location::Url("https://example.com/3.thing".into());
+        let manifest = TestManifest(vec![bundled.clone(), path.clone(), url.clone()]);
+        let bundle = Bundle::new_unchecked(manifest, vec![("1.thing".into(), vec![1])])
+            .unwrap()
+            .with_content_addressed_only(true);
+
+        assert_eq!(bundle.resolve(&bundled).await.unwrap().as_ref(), &vec![1]);
+        for location in &[path, url] {
+            matches::assert_matches!(
+                bundle.resolve(location).await,
+                Err(MrBundleError::BundleError(BundleError::NotContentAddressed(l))) if &l == location
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn signatures_roundtrip() {
+        let manifest = TestManifest(vec![
+            Location::Bundled("1.thing".into()),
+            Location::Bundled("2.thing".into()),
+        ]);
+        let bundle = Bundle::new_unchecked(
+            manifest,
+            vec![("1.thing".into(), vec![1]), ("2.thing".into(), vec![2])],
+        )
+        .unwrap();
+        let signable = bundle.signable_bytes().unwrap();
+        let signature = BundleSignature {
+            signer: vec![1, 2, 3],
+            signature: vec![4, 5, 6],
+        };
+        let bundle = bundle.with_signature(signature.clone());
+
+        // Signing doesn't change what is signed
+        assert_eq!(bundle.signable_bytes().unwrap(), signable);
+
+        // Signatures and signable bytes survive encoding
+        let decoded: Bundle<TestManifest> = Bundle::decode(&bundle.encode().unwrap()).unwrap();
+        assert_eq!(decoded.signatures(), &[signature][..]);
+        assert_eq!(decoded.signable_bytes().unwrap(), signable);
+    }
 }
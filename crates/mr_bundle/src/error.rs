@@ -49,6 +49,22 @@ pub enum BundleError {
         "Cannot use relative paths for local locations. The following local path is relative: {0}"
     )]
     RelativeLocalPath(std::path::PathBuf),
+
+    #[error("The resource at '{url}' has hash {actual}, but the manifest expects hash {expected}")]
+    ResourceHashMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("The resource at '{url}' is pinned to '{hash}', which is not a hex-encoded 32 byte hash")]
+    InvalidResourceHash { url: String, hash: String },
+
+    #[error("The resource at {0:?} is neither bundled nor pinned to a hash, so its content is not covered by the bundle")]
+    NotContentAddressed(crate::Location),
+
+    #[error("Cannot fetch the resource at '{0}' in offline mode because it is not cached. Only resources pinned to a hash are cached.")]
+    RemoteResourceNotCached(String),
 }
 pub type BundleResult<T> = Result<T, BundleError>;
 
@@ -11,6 +11,13 @@
 //! Manifest as "Bundled", and pulling them into the Bundle that way.
 //! Unpacking is done by specifying a target directory and creating a new file
 //! for each resource at a relative path specified by the Manifest.
+//!
+//! Resources at a [`Location::Url`](crate::Location::Url) can be pinned to the
+//! hash of their content, and cached locally by that hash, as configured by a
+//! [`RemoteConfig`](crate::RemoteConfig).
+//!
+//! A Bundle can carry any number of detached
+//! [`BundleSignature`](crate::BundleSignature)s by its publishers.
 
 #![warn(missing_docs)]
 
@@ -19,7 +26,9 @@ mod encoding;
 pub mod error;
 mod location;
 mod manifest;
+mod remote;
 mod resource;
+mod signature;
 pub(crate) mod util;
 
 #[cfg(feature = "packing")]
@@ -27,6 +36,8 @@ mod packing;
 
 pub use bundle::Bundle;
 pub use encoding::{decode, encode};
-pub use location::Location;
+pub use location::{Location, UrlLocation};
 pub use manifest::Manifest;
+pub use remote::{resource_hash, RemoteConfig};
 pub use resource::ResourceBytes;
+pub use signature::BundleSignature;
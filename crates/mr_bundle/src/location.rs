@@ -1,8 +1,10 @@
 use crate::{
-    error::{BundleError, MrBundleResult},
+    error::{BundleError, BundleResult, MrBundleResult},
+    remote::RESOURCE_HASH_LEN,
     ResourceBytes,
 };
 use aingle_util::ffs;
+use std::convert::TryFrom;
 use std::path::{Path, PathBuf};
 
 /// Where to find a Resource.
@@ -22,10 +24,110 @@ pub enum Location {
     Path(PathBuf),
 
     /// Get file from URL
-    Url(String),
+    Url(UrlLocation),
+}
+
+/// A resource fetched from a URL, optionally pinned to the hash of its
+/// expected content (see [`resource_hash`](crate::resource_hash)).
+///
+/// In a manifest this can be written either as a plain URL:
+/// ```yaml
+/// url: "https://example.com/thing.wasm"
+/// ```
+/// or, to pin the content, as:
+/// ```yaml
+/// url:
+///   url: "https://example.com/thing.wasm"
+///   hash: "<hex-encoded blake2b-256 hash>"
+/// ```
+///
+/// Only pinned resources can be stored in, and resolved from, the cache
+/// set by [`RemoteConfig`](crate::RemoteConfig).
+#[derive(Clone, Debug, Hash, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "UrlLocationRepr", into = "UrlLocationRepr")]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct UrlLocation {
+    url: String,
+    hash: Option<String>,
+}
+
+impl UrlLocation {
+    /// A URL resource whose content is pinned to the given hash, which must
+    /// be hex-encoded like the output of [`resource_hash`](crate::resource_hash)
+    pub fn pinned(url: String, hash: String) -> BundleResult<Self> {
+        if hash.len() != RESOURCE_HASH_LEN * 2 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(BundleError::InvalidResourceHash { url, hash });
+        }
+        Ok(Self {
+            url,
+            hash: Some(hash),
+        })
+    }
+
+    /// Accessor
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Accessor
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+}
+
+impl From<String> for UrlLocation {
+    fn from(url: String) -> Self {
+        Self { url, hash: None }
+    }
+}
+
+impl From<&str> for UrlLocation {
+    fn from(url: &str) -> Self {
+        url.to_string().into()
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum UrlLocationRepr {
+    Plain(String),
+    Pinned { url: String, hash: String },
+}
+
+impl TryFrom<UrlLocationRepr> for UrlLocation {
+    type Error = BundleError;
+
+    fn try_from(repr: UrlLocationRepr) -> BundleResult<Self> {
+        match repr {
+            UrlLocationRepr::Plain(url) => Ok(url.into()),
+            UrlLocationRepr::Pinned { url, hash } => Self::pinned(url, hash),
+        }
+    }
+}
+
+impl From<UrlLocation> for UrlLocationRepr {
+    fn from(location: UrlLocation) -> Self {
+        match location.hash {
+            None => Self::Plain(location.url),
+            Some(hash) => Self::Pinned {
+                url: location.url,
+                hash,
+            },
+        }
+    }
 }
 
 impl Location {
+    /// Whether the content at this location is fixed by the manifest, i.e.
+    /// it is bundled or it is a URL pinned to a hash
+    pub fn is_content_addressed(&self) -> bool {
+        match self {
+            Location::Bundled(_) => true,
+            Location::Path(_) => false,
+            Location::Url(url) => url.hash().is_some(),
+        }
+    }
+
     /// Make a relative Path absolute if possible, given the `root_dir`
     pub fn normalize(&self, root_dir: Option<&PathBuf>) -> MrBundleResult<Location> {
         if let Location::Path(path) = self {
@@ -48,22 +150,13 @@ pub(crate) async fn resolve_local(path: &Path) -> MrBundleResult<ResourceBytes>
     Ok(ffs::read(path).await?)
 }
 
-pub(crate) async fn resolve_remote(url: &str) -> MrBundleResult<ResourceBytes> {
-    Ok(reqwest::get(url)
-        .await?
-        .bytes()
-        .await?
-        .into_iter()
-        .collect())
-}
-
 #[cfg(test)]
 mod tests {
 
     use super::*;
     use serde::{Deserialize, Serialize};
 
-    #[derive(Serialize, Deserialize)]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
     struct TunaSalad {
         celery: Vec<Location>,
 
@@ -95,4 +188,40 @@ mod tests {
         assert_eq!(val["celery"][1]["path"], Value::from("p"));
         assert_eq!(val["url"], Value::from("http://r.co"));
     }
+
+    /// Test that a pinned Url nests its hash under the "url" key,
+    /// and that both forms roundtrip
+    #[test]
+    fn pinned_url_location() {
+        use serde_yaml::Value;
+
+        let hash = "ab".repeat(32);
+        let tuna = TunaSalad {
+            celery: vec![Location::Url("http://r.co".into())],
+            mayo: Location::Url(UrlLocation::pinned("http://p.co".into(), hash.clone()).unwrap()),
+        };
+        let yaml = serde_yaml::to_string(&tuna).unwrap();
+        let val: Value = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(val["celery"][0]["url"], Value::from("http://r.co"));
+        assert_eq!(val["url"]["url"], Value::from("http://p.co"));
+        assert_eq!(val["url"]["hash"], Value::from(hash.as_str()));
+        assert_eq!(serde_yaml::from_str::<TunaSalad>(&yaml).unwrap(), tuna);
+    }
+
+    /// Test that only hex-encoded hashes of the right length can be pinned
+    #[test]
+    fn invalid_pinned_hash() {
+        for hash in &["abcd".to_string(), "zz".repeat(32), "ab".repeat(33)] {
+            assert_eq!(
+                UrlLocation::pinned("http://p.co".into(), hash.clone()),
+                Err(BundleError::InvalidResourceHash {
+                    url: "http://p.co".into(),
+                    hash: hash.clone(),
+                })
+            );
+            let yaml = format!("url:\n  url: http://p.co\n  hash: {}\n", hash);
+            assert!(serde_yaml::from_str::<Location>(&yaml).is_err());
+        }
+    }
 }
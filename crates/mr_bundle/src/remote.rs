@@ -0,0 +1,97 @@
+//! Resolution of [`Location::Url`](crate::Location::Url) resources, with
+//! integrity checking and an optional content-addressed cache.
+
+use crate::{
+    error::{BundleError, MrBundleResult},
+    location::UrlLocation,
+    ResourceBytes,
+};
+use aingle_util::ffs;
+use std::path::PathBuf;
+
+/// How a [`Bundle`](crate::Bundle) resolves resources at a
+/// [`Location::Url`](crate::Location::Url).
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RemoteConfig {
+    /// Directory in which fetched resources are stored by the hash of their
+    /// content. Only resources pinned to a hash are read from or written to
+    /// the cache.
+    #[serde(default)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Never touch the network: remote resources can only be resolved
+    /// from the cache.
+    #[serde(default)]
+    pub offline: bool,
+}
+
+/// The number of bytes in a [`resource_hash`], before hex encoding
+pub(crate) const RESOURCE_HASH_LEN: usize = 32;
+
+/// The hash used to pin and cache remote resources:
+/// a hex-encoded 32 byte blake2b digest of the content.
+pub fn resource_hash(bytes: &[u8]) -> String {
+    hex::encode(
+        blake2b_simd::Params::new()
+            .hash_length(RESOURCE_HASH_LEN)
+            .hash(bytes)
+            .as_bytes(),
+    )
+}
+
+pub(crate) async fn resolve_remote(
+    location: &UrlLocation,
+    config: &RemoteConfig,
+) -> MrBundleResult<ResourceBytes> {
+    let cache_path = match (&config.cache_dir, location.hash()) {
+        (Some(dir), Some(hash)) => Some(dir.join(hash)),
+        _ => None,
+    };
+
+    if let Some(path) = &cache_path {
+        // A missing or corrupted cache entry is treated as a cache miss
+        if let Ok(bytes) = ffs::read(path).await {
+            if matches_hash(location, &bytes) {
+                return Ok(bytes);
+            }
+        }
+    }
+
+    if config.offline {
+        return Err(BundleError::RemoteResourceNotCached(location.url().to_string()).into());
+    }
+
+    let bytes: ResourceBytes = reqwest::get(location.url())
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?
+        .into_iter()
+        .collect();
+
+    if let Some(expected) = location.hash() {
+        if !matches_hash(location, &bytes) {
+            return Err(BundleError::ResourceHashMismatch {
+                url: location.url().to_string(),
+                expected: expected.to_string(),
+                actual: resource_hash(&bytes),
+            }
+            .into());
+        }
+    }
+
+    if let (Some(dir), Some(path)) = (&config.cache_dir, &cache_path) {
+        ffs::create_dir_all(dir).await?;
+        ffs::write(path, &bytes).await?;
+    }
+
+    Ok(bytes)
+}
+
+fn matches_hash(location: &UrlLocation, bytes: &[u8]) -> bool {
+    location
+        .hash()
+        .map(|hash| hash.eq_ignore_ascii_case(&resource_hash(bytes)))
+        .unwrap_or(false)
+}
@@ -0,0 +1,19 @@
+/// A detached signature by a publisher over a [`Bundle`](crate::Bundle)'s
+/// manifest and resources, as returned by
+/// [`signable_bytes`](crate::Bundle::signable_bytes).
+///
+/// The signature is carried inside the bundle but does not cover the other
+/// signatures, so a bundle can be signed by any number of publishers.
+/// This crate doesn't interpret the key or signature bytes: producing and
+/// verifying them is left to the user of the bundle.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct BundleSignature {
+    /// The public key of the publisher
+    #[serde(with = "serde_bytes")]
+    pub signer: Vec<u8>,
+
+    /// The publisher's signature over the signable bytes
+    #[serde(with = "serde_bytes")]
+    pub signature: Vec<u8>,
+}
@@ -1,5 +1,16 @@
-use mr_bundle::{Bundle, Location, Manifest};
-use std::{collections::HashSet, path::PathBuf};
+use mr_bundle::{
+    error::{BundleError, MrBundleError},
+    resource_hash, Bundle, Location, Manifest, RemoteConfig, UrlLocation,
+};
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "manifest_version")]
@@ -120,6 +131,120 @@ async fn resource_resolution() {
     assert_eq!(bundle, bundle_file);
 }
 
+/// Serve `body` to every request on a local port, as a stand-in for a remote
+/// host. Returns the base url and a count of the requests served.
+async fn serve_http(body: Vec<u8>) -> (String, Arc<AtomicUsize>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            socket.write_all(header.as_bytes()).await.unwrap();
+            socket.write_all(&body).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+    });
+    (url, hits)
+}
+
+#[tokio::test]
+async fn remote_resource_resolution() {
+    let dir = tempdir::TempDir::new("mr_bundle").unwrap();
+    let cache_dir = dir.path().join("cache");
+
+    let remote_thing_encoded = mr_bundle::encode(&Thing("remote".into())).unwrap();
+    let hash = resource_hash(&remote_thing_encoded);
+    let (base_url, hits) = serve_http(remote_thing_encoded.clone()).await;
+    let url = format!("{}/remote.thing", base_url);
+
+    let pinned_location = Location::Url(UrlLocation::pinned(url.clone(), hash.clone()).unwrap());
+    let unpinned_location = Location::Url(url.clone().into());
+    let bad_location =
+        Location::Url(UrlLocation::pinned(url.clone(), resource_hash(b"bad")).unwrap());
+    let manifest = TestManifest::V1(ManifestV1 {
+        name: "name".to_string(),
+        things: vec![ThingManifest {
+            location: pinned_location.clone(),
+        }],
+    });
+    let online = RemoteConfig {
+        cache_dir: Some(cache_dir.clone()),
+        offline: false,
+    };
+    let offline = RemoteConfig {
+        cache_dir: Some(cache_dir.clone()),
+        offline: true,
+    };
+
+    // Nothing is cached yet, so offline resolution fails
+    let bundle = Bundle::new_unchecked(manifest.clone(), vec![])
+        .unwrap()
+        .with_remote_config(offline.clone());
+    matches::assert_matches!(
+        bundle.resolve(&pinned_location).await,
+        Err(MrBundleError::BundleError(BundleError::RemoteResourceNotCached(u))) if u == url
+    );
+    assert_eq!(hits.load(Ordering::SeqCst), 0);
+
+    // Fetch the pinned resource, which stores it in the cache
+    let bundle = Bundle::new_unchecked(manifest.clone(), vec![])
+        .unwrap()
+        .with_remote_config(online);
+    assert_eq!(
+        bundle.resolve(&pinned_location).await.unwrap().into_owned(),
+        remote_thing_encoded
+    );
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        std::fs::read(cache_dir.join(&hash)).unwrap(),
+        remote_thing_encoded
+    );
+
+    // A second resolution is served from the cache
+    bundle.resolve(&pinned_location).await.unwrap();
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // Content which doesn't match the pinned hash is rejected
+    matches::assert_matches!(
+        bundle.resolve(&bad_location).await,
+        Err(MrBundleError::BundleError(BundleError::ResourceHashMismatch { actual, .. })) if actual == hash
+    );
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+    // Offline, the pinned resource comes from the cache
+    // but unpinned resources can't be resolved at all
+    let bundle = Bundle::new_unchecked(manifest, vec![])
+        .unwrap()
+        .with_remote_config(offline);
+    assert_eq!(
+        bundle.resolve(&pinned_location).await.unwrap().into_owned(),
+        remote_thing_encoded
+    );
+    matches::assert_matches!(
+        bundle.resolve(&unpinned_location).await,
+        Err(MrBundleError::BundleError(
+            BundleError::RemoteResourceNotCached(_)
+        ))
+    );
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
 #[cfg(feature = "packing")]
 #[tokio::test]
 async fn unpack_roundtrip() {
@@ -68,6 +68,21 @@ fn needs_cap_claim(_: ()) -> ExternResult<()> {
     Ok(())
 }
 
+#[adk_extern]
+fn capability_grants(_: ()) -> ExternResult<Vec<CapGrantEntry>> {
+    adk::prelude::capability_grants()
+}
+
+#[adk_extern]
+fn capability_claims(_: ()) -> ExternResult<Vec<CapClaimEntry>> {
+    adk::prelude::capability_claims()
+}
+
+#[adk_extern]
+fn capability_info(_: ()) -> ExternResult<CapGrant> {
+    adk::prelude::capability_info()
+}
+
 #[adk_extern]
 fn try_cap_claim(cap_for: crate::CapFor) -> ExternResult<ZomeCallResponse> {
     call_remote(
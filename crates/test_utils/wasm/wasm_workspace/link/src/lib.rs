@@ -31,6 +31,11 @@ fn get_links(_: ()) -> ExternResult<Links> {
     adk::prelude::get_links(base()?, None)
 }
 
+#[adk_extern]
+fn count_links(_: ()) -> ExternResult<usize> {
+    adk::prelude::count_links(base()?, None)
+}
+
 #[adk_extern]
 fn delete_all_links(_: ()) -> ExternResult<()> {
     for link in adk::prelude::get_links(base()?, None)?.into_inner() {
@@ -5,6 +5,21 @@ fn zome_info(_: ()) -> ExternResult<ZomeInfo> {
     adk::prelude::zome_info()
 }
 
+#[adk_extern]
+fn saf_info(_: ()) -> ExternResult<SafInfo> {
+    adk::prelude::saf_info()
+}
+
+#[adk_extern]
+fn app_info(_: ()) -> ExternResult<AppInfo> {
+    adk::prelude::app_info()
+}
+
+#[adk_extern]
+fn call_info(_: ()) -> ExternResult<CallInfo> {
+    adk::prelude::call_info()
+}
+
 #[cfg(test)]
 pub mod tests {
     use adk::prelude::*;